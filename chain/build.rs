@@ -0,0 +1,24 @@
+use std::process::Command;
+
+/// Inject the git commit the node was built from as `MEMECHAIN_GIT_HASH`
+fn main() {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|stdout| stdout.trim().to_string())
+    };
+
+    let hash = git(&["rev-parse", "--short=12", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=MEMECHAIN_GIT_HASH={}", hash);
+
+    // Rebuild when HEAD moves so the hash stays current
+    if let Some(git_dir) = git(&["rev-parse", "--git-dir"]) {
+        println!("cargo:rerun-if-changed={}/HEAD", git_dir);
+        println!("cargo:rerun-if-changed={}/refs", git_dir);
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -1,21 +1,47 @@
-use crate::config::Config;
-use crate::error::{MemeChainError, Result};
+use crate::clock::{system_clock, SharedClock};
+use crate::config::{ApiConfig, Config, GenesisConfig};
+use crate::error::{CommonError, ConfigError, MemeChainError, MemeError, ModuleError, NftError, Result, StorageError};
 use crate::modules::{nft::NftModule, meme::MemeModule, common::CommonModule};
-use crate::storage::Storage;
-use crate::types::{Address, Block, Transaction, TransactionResult};
+use crate::network::PeerBook;
+use crate::rpc;
+use crate::storage::{Storage, StorageStats};
+use crate::types::{Address, AddressCheck, AntiRugSettings as TokenAntiRugSettings, Balance, Block, BlockSummary, Collection, IdempotencyRecord, LockStatus, MempoolEntry, MempoolEvent, Nft, OwnershipRecord, Peer, PooledTransaction, Receipt, Redemption, Token, TokenSupply, TradeQuote, TradeSide, Transaction, TransactionResult, DEFAULT_TOKEN_DECIMALS, SIG_SCHEME_ED25519};
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::Json,
-    routing::{get, post},
+    error_handling::HandleErrorLayer,
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        DefaultBodyLimit, FromRequest, Path, Query, Request, State,
+    },
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+    routing::{delete, get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
+use tower::limit::GlobalConcurrencyLimitLayer;
+use tower::load_shed::LoadShedLayer;
+use tower::ServiceBuilder;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tower_http::limit::RequestBodyLimitLayer;
 use tracing::{debug, error, info, warn};
 
+/// Events buffered per `mempool` subscriber before the slowest one lags
+const MEMPOOL_FEED_CAPACITY: usize = 1024;
+
+/// Receipts buffered per `receipt` subscriber before the slowest one lags
+const RECEIPT_FEED_CAPACITY: usize = 1024;
+
+/// Most keys `apply_genesis` sends to the backend in a single `batch_write`
+const GENESIS_MAX_BATCH_KEYS: usize = 1000;
+
+/// Blocks between sweeps of expired idempotency records
+const IDEMPOTENCY_PRUNE_INTERVAL_BLOCKS: u64 = 100;
+
 /// Main blockchain application
 pub struct MemeChainApp {
     /// Application configuration
@@ -30,10 +56,28 @@ pub struct MemeChainApp {
     common_module: CommonModule,
     /// Current block height
     block_height: u64,
-    /// Transaction pool
+    /// App hash of the latest block
+    app_hash: String,
+    /// Hash of the latest block
+    last_block_hash: String,
+    /// Transaction pool; a sender's nonced transactions in it are contiguous
     tx_pool: Arc<RwLock<Vec<Transaction>>>,
-    /// Rate limiting
-    rate_limiter: Arc<RwLock<HashMap<String, u64>>>,
+    /// Transactions waiting for an earlier nonce: sender -> nonce -> (arrival time, tx)
+    future_pool: Arc<RwLock<HashMap<Address, BTreeMap<u64, (i64, Transaction)>>>>,
+    /// Rate limiting: address -> (window start, transactions in window)
+    rate_limiter: Arc<RwLock<HashMap<String, (u64, u32)>>>,
+    /// Maintenance mode: transactions are rejected while set
+    halted: AtomicBool,
+    /// Known network peers
+    peer_book: RwLock<PeerBook>,
+    /// Feed of transactions admitted to the pool
+    mempool_feed: broadcast::Sender<MempoolEvent>,
+    /// Feed of receipts for transactions in committed blocks
+    receipt_feed: broadcast::Sender<Receipt>,
+    /// Time source
+    clock: SharedClock,
+    /// Stable identity of this node
+    node_id: String,
 }
 
 impl MemeChainApp {
@@ -44,25 +88,108 @@ impl MemeChainApp {
         // Initialize storage
         let storage = Storage::new(&config.storage).await?;
 
+        Self::with_storage(config, storage).await
+    }
+
+    /// Create a new MemeChain application over an existing storage instance
+    pub async fn with_storage(config: Config, storage: Storage) -> Result<Self> {
         // Initialize modules
-        let nft_module = NftModule::new(storage.clone()).await?;
-        let meme_module = MemeModule::new(storage.clone()).await?;
+        let mut nft_module = NftModule::new(storage.clone(), config.chain.clone()).await?;
+        let mut meme_module = MemeModule::new(storage.clone(), config.chain.clone()).await?;
         let common_module = CommonModule::new(storage.clone()).await?;
 
         // Initialize transaction pool
         let tx_pool = Arc::new(RwLock::new(Vec::new()));
         let rate_limiter = Arc::new(RwLock::new(HashMap::new()));
+        let peer_book = RwLock::new(PeerBook::from_config(&config.network)?);
+        let (mempool_feed, _) = broadcast::channel(MEMPOOL_FEED_CAPACITY);
+        let (receipt_feed, _) = broadcast::channel(RECEIPT_FEED_CAPACITY);
+        let node_id = Self::load_node_id(&config, &storage).await?;
+
+        if let Some(total) = storage.backfill_token_supply_total().await? {
+            info!("Backfilled the aggregate token supply: {}", total);
+        }
+        let ranked = storage.backfill_holder_ranks().await?;
+        if ranked > 0 {
+            info!("Built the holder rank index with {} entries", ranked);
+        }
+        let traits = storage.backfill_trait_index().await?;
+        if traits > 0 {
+            info!("Built the trait index with {} entries", traits);
+        }
+        if let Some(indexed) = storage.rebuild_metadata_index().await? {
+            info!("Rebuilt the metadata index with {} entries", indexed);
+        }
+
+        // Resume from the stored tip so block links and `block_rng` seeds continue
+        let tip = storage.get_latest_block().await?;
+        let block_height = tip.as_ref().map_or(0, |block| block.height);
+        if let Some(tip) = &tip {
+            info!("Resuming at block {} ({})", tip.height, tip.hash);
+        }
+        nft_module.update_block_height(block_height);
+        meme_module.update_block_height(block_height);
 
-        Ok(Self {
+        let app = Self {
             config,
             storage,
             nft_module,
             meme_module,
             common_module,
-            block_height: 0,
+            block_height,
+            app_hash: tip.as_ref().map(|block| block.app_hash.clone()).unwrap_or_default(),
+            last_block_hash: tip.map(|block| block.hash).unwrap_or_default(),
             tx_pool,
+            future_pool: Arc::new(RwLock::new(HashMap::new())),
             rate_limiter,
-        })
+            halted: AtomicBool::new(false),
+            peer_book,
+            mempool_feed,
+            receipt_feed,
+            clock: system_clock(),
+            node_id,
+        };
+        if app.config.chain.persist_mempool {
+            app.restore_mempool().await?;
+        }
+
+        Ok(app)
+    }
+
+    /// The configured node ID, else the persisted one, else a fresh one;
+    /// whichever is used is persisted so it survives restarts
+    async fn load_node_id(config: &Config, storage: &Storage) -> Result<String> {
+        let stored = storage.get_node_id().await?;
+        let node_id = match (&config.network.node_id, stored) {
+            (Some(configured), _) => configured.clone(),
+            (None, Some(stored)) => return Ok(stored),
+            (None, None) => uuid::Uuid::new_v4().to_string(),
+        };
+        storage.set_node_id(&node_id).await?;
+        info!("Node ID: {}", node_id);
+        Ok(node_id)
+    }
+
+    /// Re-admit persisted pool transactions, dropping any that have expired,
+    /// timed out or whose nonce has been used since
+    async fn restore_mempool(&self) -> Result<()> {
+        let mut entries = self.storage.pooled_transactions().await?;
+        if entries.is_empty() {
+            return Ok(());
+        }
+        entries.sort_by_key(|(_, entry)| (entry.admitted_at, entry.tx.nonce));
+
+        let mut restored = 0;
+        for (hash, entry) in entries {
+            // Re-admission persists the entry again under the same hash
+            self.storage.remove_pooled_transactions(&[hash.clone()]).await?;
+            match self.submit_transaction(entry.tx).await {
+                Ok(_) => restored += 1,
+                Err(e) => debug!("Dropping persisted pool transaction {}: {}", hash, e),
+            }
+        }
+        info!("Restored {} transactions to the pool", restored);
+        Ok(())
     }
 
     /// Initialize storage
@@ -72,39 +199,506 @@ impl MemeChainApp {
         Ok(())
     }
 
+    /// Apply genesis state: seed the native token, initial account balances,
+    /// and the tokens and collections declared in `app_state`.
+    ///
+    /// Writes go to the backend in chunks of at most `GENESIS_MAX_BATCH_KEYS`
+    /// keys, so a failure part way through can leave earlier chunks applied.
+    /// The native token and the genesis hash go in the last chunk, so such a
+    /// database still counts as uninitialized and genesis is applied again.
+    pub async fn apply_genesis(&self, genesis: &GenesisConfig) -> Result<()> {
+        info!("Applying genesis for chain {}", genesis.chain_id);
+
+        genesis.validate()?;
+        genesis.validate_genesis_time(self.clock.now(), self.config.chain.genesis_time_max_skew_secs)?;
+        let native_symbol = &self.config.chain.native_symbol;
+        if genesis.app_state.meme.tokens.iter().any(|token| &token.symbol == native_symbol) {
+            return Err(ConfigError::InvalidGenesis(format!(
+                "Genesis token {} clashes with the native token", native_symbol
+            )).into());
+        }
+        let total_supply = genesis.accounts.iter()
+            .try_fold(0u64, |total, account| total.checked_add(account.balance))
+            .ok_or_else(|| ConfigError::InvalidGenesis(
+                "Genesis account balances overflow the native token supply".to_string()
+            ))?;
+
+        self.storage.begin_batch();
+        match self.write_genesis_state(genesis, total_supply).await {
+            Ok(()) => self.storage.commit_batch().await,
+            Err(e) => {
+                self.storage.discard_batch();
+                Err(e)
+            }
+        }
+    }
+
+    /// Store the genesis balances, tokens and collections, then the native
+    /// token, flushing every `GENESIS_MAX_BATCH_KEYS` buffered keys
+    async fn write_genesis_state(&self, genesis: &GenesisConfig, total_supply: u64) -> Result<()> {
+        let native_symbol = &self.config.chain.native_symbol;
+        // Genesis state is stamped with the genesis time so every node writes the same records
+        let genesis_time = genesis.parsed_genesis_time()?.timestamp();
+
+        for account in &genesis.accounts {
+            let balance = Balance::new(
+                Address::new(account.address.clone()),
+                native_symbol.clone(),
+                account.balance,
+                genesis_time,
+            );
+            self.storage.store_balance(&balance).await?;
+            self.flush_genesis_batch().await?;
+        }
+
+        for genesis_token in &genesis.app_state.meme.tokens {
+            let creator = Address::new(genesis_token.creator.clone());
+            let token = Token::new(
+                genesis_token.symbol.clone(),
+                genesis_token.name.clone(),
+                genesis_token.total_supply,
+                creator.clone(),
+                genesis_token.anti_rug.clone().into(),
+                genesis_time,
+            );
+            self.storage.store_token(&token).await?;
+
+            let balance = Balance::new(creator, genesis_token.symbol.clone(), genesis_token.total_supply, genesis_time);
+            self.storage.store_balance(&balance).await?;
+            self.flush_genesis_batch().await?;
+        }
+
+        for genesis_collection in &genesis.app_state.nft.collections {
+            let collection = Collection::new(
+                genesis_collection.id.clone(),
+                genesis_collection.name.clone(),
+                Address::new(genesis_collection.creator.clone()),
+                genesis_collection.description.clone(),
+                genesis_time,
+            );
+            self.storage.store_collection(&collection).await?;
+            self.flush_genesis_batch().await?;
+        }
+
+        // The native token has no creator account, so no one can administer it
+        let mut native_token = Token::new(
+            native_symbol.clone(),
+            native_symbol.clone(),
+            total_supply,
+            Address::new(String::new()),
+            TokenAntiRugSettings {
+                max_wallet_percentage: 100,
+                buy_tax_percentage: 0,
+                sell_tax_percentage: 0,
+                transfer_tax_percentage: 0,
+                liquidity_locked_percentage: 0,
+                lock_duration_blocks: 0,
+                lock_start_block: None,
+            },
+            genesis_time,
+        );
+        native_token.decimals = self.config.chain.native_decimals;
+        self.storage.store_token(&native_token).await?;
+
+        // Block rewards go to the most powerful genesis validator (first on ties)
+        let proposer = genesis.validators.iter().fold(None, |best: Option<&crate::config::Validator>, validator| {
+            match best {
+                Some(best) if best.power >= validator.power => Some(best),
+                _ => Some(validator),
+            }
+        });
+        if let Some(proposer) = proposer {
+            self.storage.store_proposer(&Address::new(proposer.address.clone())).await?;
+        }
+
+        // Written last, so it marks a completely applied genesis
+        self.storage.set_genesis_hash(&genesis.hash()).await?;
+        Ok(())
+    }
+
+    /// Write out the genesis batch and start another once it holds
+    /// `GENESIS_MAX_BATCH_KEYS` keys
+    async fn flush_genesis_batch(&self) -> Result<()> {
+        if self.storage.batch_len() < GENESIS_MAX_BATCH_KEYS {
+            return Ok(());
+        }
+        let result = self.storage.commit_batch().await;
+        self.storage.begin_batch();
+        result
+    }
+
+    /// Check that `genesis` is the one this database was initialized from.
+    /// A database from before genesis hashes were recorded adopts its hash.
+    pub async fn verify_genesis(&self, genesis: &GenesisConfig) -> Result<()> {
+        let hash = genesis.hash();
+        match self.storage.get_genesis_hash().await? {
+            Some(stored) if stored == hash => Ok(()),
+            Some(stored) => Err(ConfigError::InvalidGenesis(format!(
+                "Database was initialized from genesis {}, but the loaded genesis hashes to {}", stored, hash
+            )).into()),
+            None => {
+                warn!("No genesis hash recorded; recording {} for chain {}", hash, genesis.chain_id);
+                self.storage.set_genesis_hash(&hash).await
+            }
+        }
+    }
+
+    /// Ensure the configured native token exists in state
+    pub async fn validate_native_token(&self) -> Result<()> {
+        let native_symbol = &self.config.chain.native_symbol;
+        if self.storage.get_token(native_symbol).await?.is_none() {
+            return Err(ConfigError::InvalidGenesis(format!(
+                "Native token {} not found in state", native_symbol
+            )).into());
+        }
+        Ok(())
+    }
+
     /// Process a transaction
     pub async fn process_transaction(&mut self, tx: Transaction) -> Result<TransactionResult> {
+        self.execute_transaction(tx, false).await
+    }
+
+    /// Process a transaction, skipping the signature check when the caller
+    /// has already verified it
+    async fn execute_transaction(&mut self, tx: Transaction, signature_verified: bool) -> Result<TransactionResult> {
+        self.admit_transaction(&tx, signature_verified).await?;
+        self.dispatch_transaction(tx).await
+    }
+
+    /// Validate `tx`, consume its nonce and charge its fee. A block keeps
+    /// these even if the action then fails, so the sender's later nonces
+    /// still apply.
+    async fn admit_transaction(&self, tx: &Transaction, signature_verified: bool) -> Result<()> {
         debug!("Processing transaction: {:?}", tx);
 
+        self.ensure_not_halted()?;
+
         // Validate transaction
-        self.validate_transaction(&tx).await?;
+        self.validate_transaction(tx, !signature_verified).await?;
 
         // Apply rate limiting
         self.check_rate_limit(&tx.from).await?;
 
+        // Enforce nonce order and consume the nonce
+        if let Some(nonce) = tx.nonce {
+            let expected = self.storage.get_nonce(&tx.from).await?;
+            if nonce != expected {
+                return Err(MemeChainError::Validation(format!(
+                    "Invalid nonce {} for {}; expected {}", nonce, tx.from, expected
+                )));
+            }
+            self.storage.set_nonce(&tx.from, nonce + 1).await?;
+        }
+
+        // Charge the transaction fee
+        self.charge_fee(&tx.from, self.fee_for(tx)).await
+    }
+
+    /// Run the action of an admitted transaction
+    async fn dispatch_transaction(&mut self, tx: Transaction) -> Result<TransactionResult> {
         // Route transaction to appropriate module
-        let result = match tx.module {
+        let result = match tx.module.as_str() {
             "nft" => self.nft_module.process_transaction(tx).await?,
             "meme" => self.meme_module.process_transaction(tx).await?,
             "common" => self.common_module.process_transaction(tx).await?,
             _ => return Err(MemeChainError::Validation(format!("Unknown module: {}", tx.module))),
         };
 
-        // Update rate limiter
-        self.update_rate_limiter(&tx.from).await?;
+        Ok(result)
+    }
+
+    /// Process a transaction at most once per sender and idempotency key.
+    ///
+    /// Repeating a key within `api.idempotency_ttl_secs` returns the original
+    /// result without executing again; reusing it for a different request is
+    /// rejected. Rejected transactions are not remembered, so retrying after
+    /// an error executes normally. Replays omit `SENSITIVE_RESULT_FIELDS`,
+    /// which are never stored.
+    pub async fn process_transaction_idempotent(&mut self, key: &str, tx: Transaction) -> Result<TransactionResult> {
+        let now = self.clock.now();
+        let key = format!("{}:{}", tx.from, key);
+        let request_hash = tx.request_hash();
+        if let Some(record) = self.storage.get_idempotency_record(&key).await? {
+            if record.expires_at > now {
+                if record.request_hash != request_hash {
+                    return Err(MemeChainError::IdempotencyKeyReused(format!(
+                        "{} was already used for a different request", key
+                    )));
+                }
+                debug!("Replaying result for idempotency key {}", key);
+                return Ok(record.result);
+            }
+        }
+
+        let result = self.process_transaction(tx).await?;
+        let record = IdempotencyRecord {
+            result: result.clone().without_secrets(),
+            request_hash,
+            expires_at: now + self.config.api.idempotency_ttl_secs as i64,
+        };
+        self.storage.store_idempotency_record(&key, &record).await?;
 
         Ok(result)
     }
 
+    /// Validate a transaction and add it to the pool for the next block.
+    ///
+    /// A transaction whose nonce is ahead of the sender's next one waits in a
+    /// side buffer and joins the pool once the gap is filled.
+    pub async fn submit_transaction(&self, tx: Transaction) -> Result<String> {
+        self.ensure_not_halted()?;
+        self.validate_transaction(&tx, true).await?;
+
+        let hash = tx.hash();
+        let mut tx_pool = self.tx_pool.write().await;
+        let Some(nonce) = tx.nonce else {
+            self.admit(&mut tx_pool, tx).await?;
+            return Ok(hash);
+        };
+
+        let sender = tx.from.clone();
+        let mut expected = self.next_pool_nonce(&tx_pool, &sender).await?;
+        if nonce < expected {
+            return Err(MemeChainError::Validation(format!(
+                "Nonce {} for {} is already used; next is {}", nonce, sender, expected
+            )));
+        }
+
+        let mut future_pool = self.future_pool.write().await;
+        if nonce > expected {
+            self.prune_queued(&mut future_pool);
+            let chain = &self.config.chain;
+            let queued = future_pool.get(&sender).map_or(0, BTreeMap::len);
+            let replacing = future_pool.get(&sender).is_some_and(|waiting| waiting.contains_key(&nonce));
+            if !replacing && queued >= chain.max_queued_per_sender {
+                return Err(MemeChainError::Validation(format!(
+                    "{} already has {} transactions waiting for nonce {}", sender, queued, expected
+                )));
+            }
+            if !replacing && future_pool.values().map(BTreeMap::len).sum::<usize>() >= chain.max_queued_transactions {
+                return Err(MemeChainError::ServiceUnavailable(format!(
+                    "{} transactions are already waiting for nonce gaps", chain.max_queued_transactions
+                )));
+            }
+            debug!("Holding transaction {} until nonce {} from {} arrives", hash, expected, sender);
+            future_pool.entry(sender).or_default().insert(nonce, (self.clock.now(), tx));
+            return Ok(hash);
+        }
+
+        self.admit(&mut tx_pool, tx).await?;
+        expected += 1;
+
+        // Promote waiting transactions whose gap is now filled
+        if let Some(waiting) = future_pool.get_mut(&sender) {
+            while let Some((_, tx)) = waiting.remove(&expected) {
+                self.admit(&mut tx_pool, tx).await?;
+                expected += 1;
+            }
+            if waiting.is_empty() {
+                future_pool.remove(&sender);
+            }
+        }
+
+        Ok(hash)
+    }
+
+    /// Add a validated transaction to the pool (persisting it if configured)
+    /// and announce it
+    async fn admit(&self, tx_pool: &mut Vec<Transaction>, tx: Transaction) -> Result<()> {
+        let event = MempoolEvent {
+            hash: tx.hash(),
+            module: tx.module.clone(),
+            action: tx.action.clone(),
+            from: tx.from.clone(),
+        };
+        if self.config.chain.persist_mempool {
+            let entry = PooledTransaction {
+                admitted_at: self.clock.now(),
+                tx,
+            };
+            self.storage.store_pooled_transaction(&event.hash, &entry).await?;
+            tx_pool.push(entry.tx);
+        } else {
+            tx_pool.push(tx);
+        }
+        debug!("Admitted transaction {} to the pool", event.hash);
+
+        // Sending only fails when nobody is subscribed
+        let _ = self.mempool_feed.send(event);
+        Ok(())
+    }
+
+    /// Every pooled and queued transaction, pooled ones in block order
+    pub async fn mempool_entries(&self) -> Vec<MempoolEntry> {
+        let tx_pool = self.tx_pool.read().await;
+        let future_pool = self.future_pool.read().await;
+        let pooled = tx_pool.iter().map(|tx| (false, tx));
+        let queued = future_pool.values().flat_map(|waiting| waiting.values()).map(|(_, tx)| (true, tx));
+        pooled
+            .chain(queued)
+            .map(|(queued, tx)| MempoolEntry {
+                hash: tx.hash(),
+                queued,
+                tx: tx.clone(),
+            })
+            .collect()
+    }
+
+    /// Drop one transaction from the mempool; returns whether it was there.
+    /// The sender's later pooled transactions go back to waiting for the
+    /// evicted nonce, so a replacement can still unblock them.
+    pub async fn evict_transaction(&self, hash: &str) -> Result<bool> {
+        let mut tx_pool = self.tx_pool.write().await;
+        let mut future_pool = self.future_pool.write().await;
+
+        let queued = future_pool.iter().find_map(|(sender, waiting)| {
+            waiting.iter().find(|(_, (_, tx))| tx.hash() == hash).map(|(nonce, _)| (sender.clone(), *nonce))
+        });
+        if let Some((sender, nonce)) = queued {
+            if let Some(waiting) = future_pool.get_mut(&sender) {
+                waiting.remove(&nonce);
+                if waiting.is_empty() {
+                    future_pool.remove(&sender);
+                }
+            }
+            info!("Evicted queued transaction {}", hash);
+            return Ok(true);
+        }
+
+        let Some(position) = tx_pool.iter().position(|tx| tx.hash() == hash) else {
+            return Ok(false);
+        };
+        let evicted = tx_pool.remove(position);
+        let mut removed = vec![hash.to_string()];
+        if let Some(nonce) = evicted.nonce {
+            let now = self.clock.now();
+            let (later, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut *tx_pool)
+                .into_iter()
+                .partition(|tx| tx.from == evicted.from && tx.nonce.is_some_and(|n| n > nonce));
+            *tx_pool = kept;
+            for tx in later {
+                removed.push(tx.hash());
+                future_pool.entry(tx.from.clone()).or_default().insert(tx.nonce.unwrap_or_default(), (now, tx));
+            }
+        }
+        self.storage.remove_pooled_transactions(&removed).await?;
+        info!("Evicted pooled transaction {}", hash);
+        Ok(true)
+    }
+
+    /// Drop every pooled and queued transaction; returns how many were dropped
+    pub async fn clear_mempool(&self) -> Result<usize> {
+        let mut tx_pool = self.tx_pool.write().await;
+        let mut future_pool = self.future_pool.write().await;
+        let hashes: Vec<String> = tx_pool.iter().map(Transaction::hash).collect();
+        let cleared = hashes.len() + future_pool.values().map(BTreeMap::len).sum::<usize>();
+
+        self.storage.remove_pooled_transactions(&hashes).await?;
+        tx_pool.clear();
+        future_pool.clear();
+        warn!("Cleared {} transactions from the mempool", cleared);
+        Ok(cleared)
+    }
+
+    /// Nonce that `sender`'s next pooled transaction must carry
+    async fn next_pool_nonce(&self, tx_pool: &[Transaction], sender: &Address) -> Result<u64> {
+        let state_nonce = self.storage.get_nonce(sender).await?;
+        let pooled = tx_pool
+            .iter()
+            .filter(|tx| &tx.from == sender)
+            .filter_map(|tx| tx.nonce)
+            .max();
+        Ok(pooled.map_or(state_nonce, |nonce| state_nonce.max(nonce + 1)))
+    }
+
+    /// Drop waiting transactions that have been stuck longer than a
+    /// transaction may live
+    async fn prune_future_pool(&self) {
+        let mut future_pool = self.future_pool.write().await;
+        self.prune_queued(&mut future_pool);
+    }
+
+    fn prune_queued(&self, future_pool: &mut HashMap<Address, BTreeMap<u64, (i64, Transaction)>>) {
+        let cutoff = self.clock.now() - self.config.chain.tx_max_age_secs as i64;
+        future_pool.retain(|sender, waiting| {
+            waiting.retain(|nonce, (arrived, _)| {
+                let keep = *arrived >= cutoff;
+                if !keep {
+                    warn!("Dropping transaction with nonce {} from {}: gap never filled", nonce, sender);
+                }
+                keep
+            });
+            !waiting.is_empty()
+        });
+    }
+
+    /// Stop accepting transactions (maintenance mode); reads stay available
+    pub fn halt(&self) {
+        self.halted.store(true, Ordering::SeqCst);
+        warn!("Chain halted: transactions are rejected until resumed");
+    }
+
+    /// Resume accepting transactions
+    pub fn resume(&self) {
+        self.halted.store(false, Ordering::SeqCst);
+        info!("Chain resumed");
+    }
+
+    /// Whether the chain is in maintenance mode
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::SeqCst)
+    }
+
+    fn ensure_not_halted(&self) -> Result<()> {
+        if self.is_halted() {
+            return Err(MemeChainError::ServiceUnavailable("Chain is halted for maintenance".to_string()));
+        }
+        Ok(())
+    }
+
     /// Validate a transaction
-    async fn validate_transaction(&self, tx: &Transaction) -> Result<()> {
+    async fn validate_transaction(&self, tx: &Transaction, check_signature: bool) -> Result<()> {
+        if !self.config.chain.is_action_enabled(&tx.module, &tx.action) {
+            return Err(MemeChainError::ActionDisabled(format!(
+                "{}/{} is not enabled on this chain", tx.module, tx.action
+            )));
+        }
+
         // Check if transaction is not expired
-        if tx.timestamp + self.config.chain.block_time * 10 < chrono::Utc::now().timestamp() {
+        if tx.timestamp + (self.config.chain.tx_max_age_secs as i64) < self.clock.now() {
             return Err(MemeChainError::Validation("Transaction expired".to_string()));
         }
 
+        // The earliest block this transaction can land in is the next one
+        if let Some(timeout_height) = tx.timeout_height {
+            if self.block_height + 1 > timeout_height {
+                return Err(MemeChainError::Validation(format!(
+                    "Transaction timed out at height {}", timeout_height
+                )));
+            }
+        }
+
+        let fee = self.fee_for(tx);
+        if fee < self.config.chain.min_fee {
+            return Err(MemeChainError::Validation(format!(
+                "Fee {} is below the minimum of {} {}", fee, self.config.chain.min_fee, self.config.chain.native_symbol
+            )));
+        }
+
+        if let Some(memo) = &tx.memo {
+            let memo_len = memo.chars().count();
+            if memo_len > self.config.chain.max_memo_len {
+                return Err(MemeChainError::Validation(format!(
+                    "Memo is {} characters, at most {} allowed", memo_len, self.config.chain.max_memo_len
+                )));
+            }
+        }
+
         // Validate signature
-        self.common_module.validate_signature(tx).await?;
+        if check_signature {
+            self.common_module.validate_signature(tx, &self.config.chain.chain_id).await?;
+        }
 
         // Validate address format
         self.common_module.validate_address(&tx.from).await?;
@@ -112,42 +706,171 @@ impl MemeChainApp {
         Ok(())
     }
 
-    /// Check rate limiting
-    async fn check_rate_limit(&self, address: &Address) -> Result<()> {
-        let mut rate_limiter = self.rate_limiter.write().await;
-        let current_time = chrono::Utc::now().timestamp() as u64;
-        let window = 60; // 1 minute window
+    /// Fee charged for `tx`: its offered fee, but at least `chain.tx_fee`
+    fn fee_for(&self, tx: &Transaction) -> u64 {
+        tx.fee.unwrap_or(0).max(self.config.chain.tx_fee)
+    }
 
-        if let Some(last_time) = rate_limiter.get(&address.to_string()) {
-            if current_time - last_time < window {
-                return Err(MemeChainError::RateLimitExceeded);
-            }
+    /// Debit `fee` from the sender in the native token, burning
+    /// `fee_burn_bps` of it and paying the rest to the treasury
+    async fn charge_fee(&self, address: &Address, fee: u64) -> Result<()> {
+        if fee == 0 {
+            return Ok(());
+        }
+
+        let native_symbol = &self.config.chain.native_symbol;
+        let mut balance = self.storage.get_balance(address, native_symbol).await?
+            .ok_or_else(|| MemeChainError::InsufficientBalance(format!(
+                "No {} balance to pay fee for {}", native_symbol, address
+            )))?;
+        let now = self.clock.now();
+        balance.subtract(fee, now)?;
+        self.storage.store_balance(&balance).await?;
+
+        let burned = (fee as u128 * self.config.chain.fee_burn_bps as u128 / 10_000) as u64;
+        if burned > 0 {
+            let mut native_token = self.storage.get_token(native_symbol).await?
+                .ok_or_else(|| ConfigError::InvalidGenesis(format!(
+                    "Native token {} not found in state", native_symbol
+                )))?;
+            native_token.total_supply = native_token.total_supply.saturating_sub(burned);
+            native_token.burned = native_token.burned.saturating_add(burned);
+            self.storage.store_token(&native_token).await?;
+        }
+
+        let collected = fee - burned;
+        if collected > 0 {
+            let treasury = Address::new(self.config.chain.treasury_address.clone());
+            self.storage.update_balance(&treasury, native_symbol, collected as i64, now).await?;
         }
 
-        rate_limiter.insert(address.to_string(), current_time);
         Ok(())
     }
 
-    /// Update rate limiter
-    async fn update_rate_limiter(&self, address: &Address) -> Result<()> {
+    /// Check rate limiting: at most `api.rate_limit` transactions per address per window
+    async fn check_rate_limit(&self, address: &Address) -> Result<()> {
+        if self.config.api.rate_limit_exempt.iter().any(|exempt| Address::new(exempt.clone()) == *address) {
+            return Ok(());
+        }
+
         let mut rate_limiter = self.rate_limiter.write().await;
-        rate_limiter.insert(address.to_string(), chrono::Utc::now().timestamp() as u64);
+        let current_time = self.clock.now() as u64;
+        let window = 60; // 1 minute window
+
+        let entry = rate_limiter.entry(address.to_string()).or_insert((current_time, 0));
+        if current_time.saturating_sub(entry.0) >= window {
+            *entry = (current_time, 0);
+        }
+
+        if entry.1 >= self.config.api.rate_limit {
+            return Err(MemeChainError::RateLimitExceeded);
+        }
+
+        entry.1 += 1;
         Ok(())
     }
 
     /// Create a new block
     pub async fn create_block(&mut self) -> Result<Block> {
+        self.create_block_at(self.clock.now()).await
+    }
+
+    /// Create a block for a scheduled slot, unless the pool is empty and
+    /// `consensus.produce_empty_blocks` is off
+    pub async fn produce_block_at(&mut self, timestamp: i64) -> Result<Option<Block>> {
+        if !self.config.consensus.produce_empty_blocks && self.tx_pool.read().await.is_empty() {
+            debug!("Skipping empty block for slot {}", timestamp);
+            return Ok(None);
+        }
+        self.create_block_at(timestamp).await.map(Some)
+    }
+
+    /// Create a new block for the given slot timestamp (clamped to the current time)
+    pub async fn create_block_at(&mut self, timestamp: i64) -> Result<Block> {
         info!("Creating new block at height {}", self.block_height + 1);
+        let timestamp = timestamp.min(self.clock.now());
 
         // Get transactions from pool
-        let mut tx_pool = self.tx_pool.write().await;
-        let transactions = tx_pool.drain(..).collect::<Vec<_>>();
+        self.prune_future_pool().await;
+        let transactions = self.tx_pool.write().await.drain(..).collect::<Vec<_>>();
+        self.meme_module.update_block_height(self.block_height + 1);
+        self.nft_module.update_block_height(self.block_height + 1);
+
+        // Buffer every state change in the block so it commits atomically with
+        // the block record; each transaction gets its own nested batch so a
+        // failed transaction leaves no partial writes behind.
+        self.storage.begin_batch();
+        let committed = match self.execute_block(&transactions, timestamp).await {
+            Ok(built) => self.storage.commit_batch().await.map(|()| built),
+            Err(e) => {
+                self.storage.discard_batch();
+                Err(e)
+            }
+        };
+        let (block, receipts) = match committed {
+            Ok(built) => built,
+            Err(e) => {
+                error!("Failed to create block {}: {}", self.block_height + 1, e);
+                // Nothing was persisted; put the transactions back for the next attempt
+                let mut tx_pool = self.tx_pool.write().await;
+                let pending = tx_pool.drain(..).collect::<Vec<_>>();
+                tx_pool.extend(transactions);
+                tx_pool.extend(pending);
+                return Err(e);
+            }
+        };
+
+        // The block is committed either way; a failed flush is retried by the next one
+        if let Err(e) = self.storage.flush().await {
+            error!("Failed to flush block {} to disk: {}", block.height, e);
+        }
+
+        // Update block height
+        self.block_height += 1;
+        self.app_hash = block.app_hash.clone();
+        self.last_block_hash = block.hash.clone();
+
+        // Only published once committed; no subscribers is not an error
+        for receipt in receipts {
+            let _ = self.receipt_feed.send(receipt);
+        }
+
+        if self.block_height % IDEMPOTENCY_PRUNE_INTERVAL_BLOCKS == 0 {
+            match self.storage.prune_idempotency_records(self.clock.now()).await {
+                Ok(0) => {}
+                Ok(pruned) => debug!("Pruned {} expired idempotency records", pruned),
+                Err(e) => warn!("Failed to prune idempotency records: {}", e),
+            }
+        }
+
+        info!("Block {} created with {} transactions", block.height, block.transactions.len());
+        Ok(block)
+    }
+
+    /// Execute `transactions` and store the resulting block in the open block
+    /// batch, leaving the commit to the caller
+    async fn execute_block(&mut self, transactions: &[Transaction], timestamp: i64) -> Result<(Block, Vec<Receipt>)> {
+        if self.config.chain.persist_mempool {
+            let hashes: Vec<String> = transactions.iter().map(Transaction::hash).collect();
+            self.storage.remove_pooled_transactions(&hashes).await?;
+        }
+
+        // Signatures that fail the batch are checked again while executing,
+        // which rejects their transactions with the usual error
+        let verified = if self.config.consensus.batch_verify_signatures {
+            self.common_module.batch_verify_signatures(transactions, &self.config.chain.chain_id)
+        } else {
+            vec![false; transactions.len()]
+        };
 
         // Process transactions
         let mut results = Vec::new();
-        for tx in transactions {
-            match self.process_transaction(tx.clone()).await {
-                Ok(result) => results.push(result),
+        for (tx, signature_verified) in transactions.iter().zip(verified) {
+            match self.execute_block_transaction(tx, signature_verified).await? {
+                Ok(result) => {
+                    // Results are replicated in the block and its receipts
+                    results.push(result.without_secrets());
+                }
                 Err(e) => {
                     warn!("Transaction failed: {}", e);
                     results.push(TransactionResult {
@@ -159,24 +882,117 @@ impl MemeChainApp {
             }
         }
 
+        self.mint_block_reward().await?;
+
+        // Commit to the post-block state
+        let app_hash = self.storage.app_hash().await?;
+
+        let receipts: Vec<Receipt> = transactions
+            .iter()
+            .zip(&results)
+            .enumerate()
+            .map(|(index, (tx, result))| Receipt {
+                tx_hash: tx.hash(),
+                height: self.block_height + 1,
+                index,
+                result: result.clone(),
+                memo: tx.memo.clone(),
+            })
+            .collect();
+        for receipt in &receipts {
+            self.storage.store_receipt(receipt).await?;
+        }
+
+        let interval = self.config.chain.holder_snapshot_interval;
+        if interval > 0 && (self.block_height + 1) % interval == 0 {
+            let chain = &self.config.chain;
+            self.storage
+                .store_holder_snapshot(self.block_height + 1, &chain.holder_snapshot_tokens, chain.holder_snapshot_retention)
+                .await?;
+        }
+
         // Create block
-        let block = Block {
+        let mut block = Block {
             height: self.block_height + 1,
-            timestamp: chrono::Utc::now().timestamp(),
-            transactions,
+            timestamp,
+            transactions: transactions.to_vec(),
             results,
-            hash: "".to_string(), // Will be calculated
-            previous_hash: "".to_string(), // Will be set
+            hash: String::new(),
+            previous_hash: self.last_block_hash.clone(),
+            app_hash,
         };
+        block.calculate_hash();
 
-        // Update block height
-        self.block_height += 1;
-
-        // Store block
         self.storage.store_block(&block).await?;
 
-        info!("Block {} created with {} transactions", block.height, block.transactions.len());
-        Ok(block)
+        Ok((block, receipts))
+    }
+
+    /// Execute one of a block's transactions in batches of its own. Admission
+    /// (nonce and fee) is committed to the block before the action runs, so
+    /// a failed action discards only its own writes. The outer error is a
+    /// storage failure that fails the whole block.
+    async fn execute_block_transaction(
+        &mut self,
+        tx: &Transaction,
+        signature_verified: bool,
+    ) -> Result<Result<TransactionResult>> {
+        self.storage.begin_batch();
+        if let Err(e) = self.admit_transaction(tx, signature_verified).await {
+            self.storage.discard_batch();
+            return Ok(Err(e));
+        }
+        self.storage.commit_batch().await?;
+
+        self.storage.begin_batch();
+        match self.dispatch_transaction(tx.clone()).await {
+            Ok(result) => {
+                self.storage.commit_batch().await?;
+                Ok(Ok(result))
+            }
+            Err(e) => {
+                self.storage.discard_batch();
+                Ok(Err(e))
+            }
+        }
+    }
+
+    /// Mint the block reward to the proposer, limited by the global supply cap.
+    /// Returns the amount minted.
+    async fn mint_block_reward(&self) -> Result<u64> {
+        let chain = &self.config.chain;
+        let Some(proposer) = self.storage.get_proposer().await? else {
+            return Ok(0);
+        };
+        let mut native_token = self.storage.get_token(&chain.native_symbol).await?
+            .ok_or_else(|| ConfigError::InvalidGenesis(format!(
+                "Native token {} not found in state", chain.native_symbol
+            )))?;
+
+        let mut reward = chain
+            .block_reward_for(native_token.total_supply)
+            .min(u64::MAX - native_token.total_supply)
+            .min(i64::MAX as u64);
+        if let Some(cap) = chain.total_token_cap {
+            let headroom = (cap as u128).saturating_sub(self.storage.total_token_supply().await?);
+            reward = reward.min(headroom.min(u64::MAX as u128) as u64);
+        }
+        if reward == 0 {
+            return Ok(0);
+        }
+
+        native_token.total_supply += reward;
+        self.storage.store_token(&native_token).await?;
+        self.storage.update_balance(&proposer, &chain.native_symbol, reward as i64, self.clock.now()).await?;
+        self.storage.record_inflation(reward).await?;
+
+        debug!("Minted block reward of {} {} to {}", reward, chain.native_symbol, proposer);
+        Ok(reward)
+    }
+
+    /// Consensus-safe RNG for transaction `tx_index` of the block being built
+    pub fn tx_rng(&self, tx_index: u64) -> rand_chacha::ChaCha20Rng {
+        crate::consensus::block_rng(&self.last_block_hash, self.block_height + 1, tx_index)
     }
 
     /// Get current block height
@@ -184,11 +1000,39 @@ impl MemeChainApp {
         self.block_height
     }
 
+    /// Get the app hash of the latest block
+    pub fn app_hash(&self) -> &str {
+        &self.app_hash
+    }
+
+    /// Stable identity of this node
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Node status summary, as served by `/status`
+    pub async fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "node_id": self.node_id(),
+            "block_height": self.block_height(),
+            "app_hash": self.app_hash(),
+            "tx_pool_size": self.tx_pool_size().await,
+            "future_pool_size": self.future_pool_size().await,
+            "halted": self.is_halted(),
+            "chain_id": self.config.chain.chain_id,
+        })
+    }
+
     /// Get transaction pool size
     pub async fn tx_pool_size(&self) -> usize {
         self.tx_pool.read().await.len()
     }
 
+    /// Number of transactions waiting for an earlier nonce
+    pub async fn future_pool_size(&self) -> usize {
+        self.future_pool.read().await.values().map(BTreeMap::len).sum()
+    }
+
     /// Get NFT module
     pub fn nft_module(&self) -> &NftModule {
         &self.nft_module
@@ -208,32 +1052,327 @@ impl MemeChainApp {
     pub fn storage(&self) -> &Storage {
         &self.storage
     }
+
+    /// Get the peer book
+    pub fn peer_book(&self) -> &RwLock<PeerBook> {
+        &self.peer_book
+    }
+
+    /// Subscribe to transactions admitted to the pool
+    pub fn subscribe_mempool(&self) -> broadcast::Receiver<MempoolEvent> {
+        self.mempool_feed.subscribe()
+    }
+
+    /// Subscribe to receipts of transactions in newly committed blocks
+    pub fn subscribe_receipts(&self) -> broadcast::Receiver<Receipt> {
+        self.receipt_feed.subscribe()
+    }
+
+    /// Time source used by the application and its modules
+    pub fn clock(&self) -> &SharedClock {
+        &self.clock
+    }
+
+    /// Replace the time source of the application and its modules
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.nft_module.set_clock(clock.clone());
+        self.meme_module.set_clock(clock.clone());
+        self.common_module.set_clock(clock.clone());
+        self.clock = clock;
+    }
+
+    /// Get configuration
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
 }
 
-/// API request types
-#[derive(Debug, Deserialize)]
-pub struct CreateTokenRequest {
-    pub name: String,
-    pub symbol: String,
-    pub supply: u64,
-    pub creator: String,
-    pub anti_rug: Option<AntiRugSettings>,
+/// An amount in base units (JSON integer) or as a decimal string such as "1.5"
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum ApiAmount {
+    Units(u64),
+    Decimal(String),
 }
 
-#[derive(Debug, Deserialize)]
-pub struct MintNftRequest {
-    pub collection: String,
-    pub name: String,
-    pub owner: String,
+impl ApiAmount {
+    /// Convert to base units for a token with `decimals` places
+    pub fn to_units(&self, common: &CommonModule, decimals: u8) -> Result<u64> {
+        match self {
+            ApiAmount::Units(amount) => Ok(*amount),
+            ApiAmount::Decimal(amount) => common.parse_amount(amount, decimals),
+        }
+    }
+}
+
+/// A single invalid or missing request field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub error: String,
+}
+
+impl FieldError {
+    fn new(field: &str, error: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            error: error.to_string(),
+        }
+    }
+}
+
+/// 400 body listing every field that failed validation
+#[derive(Debug, Serialize)]
+pub struct ValidationErrorResponse {
+    pub success: bool,
+    pub error: String,
+    pub errors: Vec<FieldError>,
+}
+
+/// Request bodies checked field by field before deserialization
+pub trait ValidateRequest: serde::de::DeserializeOwned {
+    /// Problems with the raw JSON body; empty when it is valid
+    fn validate(body: &serde_json::Value) -> Vec<FieldError>;
+}
+
+/// JSON body extractor that rejects invalid requests with field-level errors
+pub struct Validated<T>(pub T);
+
+#[axum::async_trait]
+impl<S, T> FromRequest<S> for Validated<T>
+where
+    S: Send + Sync,
+    T: ValidateRequest,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &S) -> std::result::Result<Self, Self::Rejection> {
+        let Json(body) = Json::<serde_json::Value>::from_request(request, state)
+            .await
+            .map_err(|rejection| {
+                // Keep the rejection's status so oversized bodies stay 413
+                validation_error(rejection.status(), vec![FieldError::new("body", &rejection.body_text())])
+            })?;
+        if !body.is_object() {
+            return Err(validation_error(
+                StatusCode::BAD_REQUEST,
+                vec![FieldError::new("body", "must be a JSON object")],
+            ));
+        }
+        let errors = T::validate(&body);
+        if !errors.is_empty() {
+            return Err(validation_error(StatusCode::BAD_REQUEST, errors));
+        }
+        serde_json::from_value(body).map(Validated).map_err(|e| {
+            validation_error(StatusCode::BAD_REQUEST, vec![FieldError::new("body", &e.to_string())])
+        })
+    }
+}
+
+/// A structured validation failure response
+fn validation_error(status: StatusCode, errors: Vec<FieldError>) -> Response {
+    let body = ValidationErrorResponse {
+        success: false,
+        error: "Invalid request".to_string(),
+        errors,
+    };
+    (status, Json(body)).into_response()
+}
+
+/// Check that `field` is a non-empty string
+fn require_string(body: &serde_json::Value, field: &str, errors: &mut Vec<FieldError>) {
+    match body.get(field) {
+        None | Some(serde_json::Value::Null) => errors.push(FieldError::new(field, "is required")),
+        Some(serde_json::Value::String(value)) if value.trim().is_empty() => {
+            errors.push(FieldError::new(field, "must not be empty"))
+        }
+        Some(serde_json::Value::String(_)) => {}
+        Some(_) => errors.push(FieldError::new(field, "must be a string")),
+    }
+}
+
+/// Check that `field` is a positive amount, as an integer or decimal string
+fn require_amount(body: &serde_json::Value, field: &str, errors: &mut Vec<FieldError>) {
+    let error = match body.get(field) {
+        None | Some(serde_json::Value::Null) => Some("is required"),
+        Some(serde_json::Value::Number(value)) => match value.as_u64() {
+            Some(0) => Some("must be > 0"),
+            Some(_) => None,
+            None if value.as_f64().map_or(false, |v| v <= 0.0) => Some("must be > 0"),
+            None => Some("must be a whole number of base units or a decimal string"),
+        },
+        Some(serde_json::Value::String(value)) => match value.trim().parse::<f64>() {
+            Ok(v) if v > 0.0 => None,
+            Ok(_) => Some("must be > 0"),
+            Err(_) => Some("must be a decimal number"),
+        },
+        Some(_) => Some("must be a number or decimal string"),
+    };
+    if let Some(error) = error {
+        errors.push(FieldError::new(field, error));
+    }
+}
+
+/// Check that `field`, if present, is a JSON object
+fn optional_object(body: &serde_json::Value, field: &str, errors: &mut Vec<FieldError>) {
+    match body.get(field) {
+        None | Some(serde_json::Value::Null) | Some(serde_json::Value::Object(_)) => {}
+        Some(_) => errors.push(FieldError::new(field, "must be an object")),
+    }
+}
+
+/// API request types
+#[derive(Debug, Deserialize)]
+pub struct CreateTokenRequest {
+    pub name: String,
+    pub symbol: String,
+    pub supply: ApiAmount,
+    pub creator: String,
+    pub anti_rug: Option<AntiRugSettings>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MintNftRequest {
+    pub collection: String,
+    pub name: String,
+    pub owner: String,
     pub metadata: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct TransferRequest {
     pub to: String,
-    pub amount: u64,
+    pub amount: ApiAmount,
     pub token: String,
     pub from: String,
+    pub memo: Option<String>,
+}
+
+impl ValidateRequest for CreateTokenRequest {
+    fn validate(body: &serde_json::Value) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_string(body, "name", &mut errors);
+        require_string(body, "symbol", &mut errors);
+        require_amount(body, "supply", &mut errors);
+        require_string(body, "creator", &mut errors);
+        optional_object(body, "anti_rug", &mut errors);
+        errors
+    }
+}
+
+impl ValidateRequest for MintNftRequest {
+    fn validate(body: &serde_json::Value) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_string(body, "collection", &mut errors);
+        require_string(body, "name", &mut errors);
+        require_string(body, "owner", &mut errors);
+        optional_object(body, "metadata", &mut errors);
+        errors
+    }
+}
+
+impl ValidateRequest for TransferRequest {
+    fn validate(body: &serde_json::Value) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_string(body, "to", &mut errors);
+        require_amount(body, "amount", &mut errors);
+        require_string(body, "token", &mut errors);
+        require_string(body, "from", &mut errors);
+        if !matches!(body.get("memo"), None | Some(serde_json::Value::Null) | Some(serde_json::Value::String(_))) {
+            errors.push(FieldError::new("memo", "must be a string"));
+        }
+        errors
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RichlistQuery {
+    /// Token symbol (defaults to the native token)
+    pub token: Option<String>,
+    /// Number of holders to return
+    pub limit: Option<usize>,
+    /// Leave out the treasury and burn addresses
+    #[serde(default)]
+    pub exclude_system: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BlocksQuery {
+    /// Number of blocks to return (defaults to `BLOCKS_DEFAULT_LIMIT`)
+    pub limit: Option<usize>,
+    /// Only list blocks below this height (defaults to past the tip)
+    pub before: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollectionNftsQuery {
+    /// Page size (defaults to `COLLECTION_NFTS_DEFAULT_LIMIT`)
+    pub limit: Option<usize>,
+    /// NFTs to skip
+    #[serde(default)]
+    pub offset: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CollectionNftsPage {
+    /// Total NFTs in the collection
+    pub total: usize,
+    pub limit: usize,
+    pub offset: usize,
+    /// This page of NFTs
+    pub nfts: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AwaitReceiptQuery {
+    /// How long to wait, in milliseconds (defaults to `AWAIT_RECEIPT_DEFAULT_TIMEOUT_MS`)
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MetadataSearchQuery {
+    /// Indexed top-level metadata key
+    pub key: String,
+    /// Value to match; non-string values match their JSON text (`5`, `true`)
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SnapshotQuery {
+    /// Block height of the snapshot
+    pub height: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HolderSnapshot {
+    pub token: String,
+    pub height: u64,
+    /// Non-zero balances at the end of block `height`, ordered by address
+    pub holders: Vec<Balance>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QuoteQuery {
+    /// `buy` or `sell`
+    pub side: String,
+    /// Amount paid in, in base units
+    pub amount: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyRequest {
+    /// Signed message, as plain text
+    pub message: String,
+    /// Hex-encoded ed25519 signature
+    pub signature: String,
+    /// Hex-encoded ed25519 public key
+    pub public_key: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerifyResponse {
+    pub valid: bool,
+    pub address: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -243,41 +1382,131 @@ pub struct ApiResponse<T> {
     pub error: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AntiRugSettings {
     pub max_wallet_percentage: u8,
     pub buy_tax_percentage: u8,
     pub sell_tax_percentage: u8,
+    #[serde(default)]
+    pub transfer_tax_percentage: u8,
     pub liquidity_locked_percentage: u8,
     pub lock_duration_blocks: u64,
 }
 
 /// Start the API server
-pub async fn start_api_server(app: Arc<RwLock<MemeChainApp>>, port: u16) -> Result<()> {
-    info!("Starting API server on port {}", port);
+pub async fn start_api_server(app: Arc<RwLock<MemeChainApp>>, api: &ApiConfig) -> Result<()> {
+    info!("Starting API server on port {}", api.api_port);
+
+    let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", api.api_port)).await?;
+    axum::serve(listener, api_router(app, api)).await?;
+
+    Ok(())
+}
+
+/// Build the API router with the configured middleware (body limit,
+/// concurrency limit, CORS)
+pub fn api_router(app: Arc<RwLock<MemeChainApp>>, api: &ApiConfig) -> Router {
+    // Oversized bodies are rejected with 413 before any handler runs; axum's
+    // own default limit is replaced by the configured one
+    let mut router = router(app)
+        .layer(DefaultBodyLimit::disable())
+        .layer(RequestBodyLimitLayer::new(api.max_request_body_bytes));
+    if api.max_concurrent_requests > 0 {
+        // `Router::layer` wraps every route separately, so the limit must
+        // share one semaphore to bound the whole API
+        router = router.layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(overloaded))
+                .layer(LoadShedLayer::new())
+                .layer(GlobalConcurrencyLimitLayer::new(api.max_concurrent_requests)),
+        );
+    }
+    match cors_layer(api) {
+        Some(cors) => router.layer(cors),
+        None => router,
+    }
+}
+
+/// Response to a request shed because the API is at its concurrency limit
+async fn overloaded(_: tower::BoxError) -> (StatusCode, Json<ApiResponse<()>>) {
+    error_response(StatusCode::SERVICE_UNAVAILABLE, "Too many concurrent requests".to_string())
+}
+
+/// CORS layer for the configured origins and methods; `None` when disabled
+fn cors_layer(api: &ApiConfig) -> Option<CorsLayer> {
+    if !api.enable_cors {
+        return None;
+    }
+
+    let origins = if api.allowed_origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(api.allowed_origins.iter().filter_map(|origin| origin.parse().ok()))
+    };
+    let methods: Vec<axum::http::Method> = api
+        .allowed_methods
+        .iter()
+        .filter_map(|method| method.to_ascii_uppercase().parse().ok())
+        .collect();
+
+    Some(
+        CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers([
+                axum::http::header::CONTENT_TYPE,
+                axum::http::header::AUTHORIZATION,
+                axum::http::HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
+                axum::http::HeaderName::from_static(CHAIN_ID_HEADER),
+            ])
+            .max_age(Duration::from_secs(api.max_age_seconds)),
+    )
+}
 
+/// Build the API router
+pub fn router(app: Arc<RwLock<MemeChainApp>>) -> Router {
     let app_state = AppState { app };
 
-    let router = Router::new()
+    Router::new()
         .route("/health", get(health_check))
         .route("/status", get(get_status))
+        .route("/version", get(get_version))
         .route("/tokens/create", post(create_token))
         .route("/nft/mint", post(mint_nft))
         .route("/transfer", post(transfer))
         .route("/tokens", get(list_tokens))
+        .route("/blocks", get(list_blocks))
+        .route("/tokens/:symbol/lock", get(get_token_lock))
+        .route("/tokens/:symbol/supply", get(get_token_supply))
+        .route("/tokens/:symbol/quote", get(get_token_quote))
+        .route("/tokens/:symbol/snapshot", get(get_holder_snapshot))
         .route("/nfts", get(list_nfts))
-        .with_state(app_state);
-
-    let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
-    axum::serve(listener, router).await?;
-
-    Ok(())
+        .route("/nfts/:id/history", get(get_nft_history))
+        .route("/redemptions/:nft_id", get(get_redemption))
+        .route("/collections/:id/nfts", get(get_collection_nfts))
+        .route("/collections/:id/traits", get(get_collection_traits))
+        .route("/collections/:id/search", get(search_collection))
+        .route("/tx/:hash", get(get_receipt))
+        .route("/tx/:hash/await", get(await_receipt))
+        .route("/blobs/:hash", get(get_blob))
+        .route("/peers", get(list_peers))
+        .route("/richlist", get(get_richlist))
+        .route("/rpc", post(rpc::handle_rpc))
+        .route("/ws/mempool", get(mempool_ws))
+        .route("/verify", post(verify_message))
+        .route("/address/:addr/validate", get(validate_address))
+        .route("/admin/halt", post(admin_halt))
+        .route("/admin/resume", post(admin_resume))
+        .route("/admin/storage/stats", get(admin_storage_stats))
+        .route("/admin/mempool", get(admin_mempool).delete(admin_clear_mempool))
+        .route("/admin/mempool/:hash", delete(admin_evict_transaction))
+        .with_state(app_state)
 }
 
 /// Application state for API
 #[derive(Clone)]
-struct AppState {
-    app: Arc<RwLock<MemeChainApp>>,
+pub(crate) struct AppState {
+    pub(crate) app: Arc<RwLock<MemeChainApp>>,
 }
 
 /// Health check endpoint
@@ -292,11 +1521,7 @@ async fn health_check() -> Json<ApiResponse<String>> {
 /// Get blockchain status
 async fn get_status(State(state): State<AppState>) -> Json<ApiResponse<serde_json::Value>> {
     let app = state.app.read().await;
-    let status = serde_json::json!({
-        "block_height": app.block_height(),
-        "tx_pool_size": app.tx_pool_size().await,
-        "chain_id": app.config().chain.chain_id,
-    });
+    let status = app.status().await;
 
     Json(ApiResponse {
         success: true,
@@ -305,12 +1530,33 @@ async fn get_status(State(state): State<AppState>) -> Json<ApiResponse<serde_jso
     })
 }
 
+/// Build version of this node
+async fn get_version() -> Json<ApiResponse<serde_json::Value>> {
+    Json(ApiResponse {
+        success: true,
+        data: Some(serde_json::json!({
+            "version": crate::VERSION,
+            "git_hash": crate::GIT_HASH,
+        })),
+        error: None,
+    })
+}
+
 /// Create a new token
 async fn create_token(
     State(state): State<AppState>,
-    Json(request): Json<CreateTokenRequest>,
-) -> Json<ApiResponse<String>> {
+    headers: HeaderMap,
+    Validated(request): Validated<CreateTokenRequest>,
+) -> (StatusCode, Json<ApiResponse<String>>) {
     let mut app = state.app.write().await;
+    if let Err(message) = check_chain_id(&app, &headers) {
+        return error_response(StatusCode::CONFLICT, message);
+    }
+    
+    let supply = match request.supply.to_units(app.common_module(), DEFAULT_TOKEN_DECIMALS) {
+        Ok(supply) => supply,
+        Err(e) => return api_error(e),
+    };
     
     // Create transaction
     let tx = Transaction {
@@ -321,33 +1567,43 @@ async fn create_token(
         data: serde_json::json!({
             "name": request.name,
             "symbol": request.symbol,
-            "supply": request.supply,
+            "supply": supply,
             "anti_rug": request.anti_rug,
         }),
-        timestamp: chrono::Utc::now().timestamp(),
+        timestamp: app.clock().now(),
+        timeout_height: None,
+        nonce: None,
+        memo: None,
+        fee: None,
+        sig_scheme: SIG_SCHEME_ED25519,
+        public_key: String::new(),
         signature: "".to_string(), // Will be validated
+        signatures: Vec::new(),
     };
 
-    match app.process_transaction(tx).await {
-        Ok(result) => Json(ApiResponse {
-            success: result.success,
-            data: Some(format!("Token created: {}", request.symbol)),
-            error: result.error,
-        }),
-        Err(e) => Json(ApiResponse {
-            success: false,
-            data: None,
-            error: Some(e.to_string()),
-        }),
+    match process_write(&mut app, &headers, "/tokens/create", tx).await {
+        Ok(result) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: result.success,
+                data: Some(format!("Token created: {}", request.symbol)),
+                error: result.error,
+            }),
+        ),
+        Err(e) => api_error(e),
     }
 }
 
 /// Mint an NFT
 async fn mint_nft(
     State(state): State<AppState>,
-    Json(request): Json<MintNftRequest>,
-) -> Json<ApiResponse<String>> {
+    headers: HeaderMap,
+    Validated(request): Validated<MintNftRequest>,
+) -> (StatusCode, Json<ApiResponse<String>>) {
     let mut app = state.app.write().await;
+    if let Err(message) = check_chain_id(&app, &headers) {
+        return error_response(StatusCode::CONFLICT, message);
+    }
     
     // Create transaction
     let tx = Transaction {
@@ -360,30 +1616,45 @@ async fn mint_nft(
             "name": request.name,
             "metadata": request.metadata,
         }),
-        timestamp: chrono::Utc::now().timestamp(),
+        timestamp: app.clock().now(),
+        timeout_height: None,
+        nonce: None,
+        memo: None,
+        fee: None,
+        sig_scheme: SIG_SCHEME_ED25519,
+        public_key: String::new(),
         signature: "".to_string(), // Will be validated
+        signatures: Vec::new(),
     };
 
-    match app.process_transaction(tx).await {
-        Ok(result) => Json(ApiResponse {
-            success: result.success,
-            data: Some(format!("NFT minted: {}", request.name)),
-            error: result.error,
-        }),
-        Err(e) => Json(ApiResponse {
-            success: false,
-            data: None,
-            error: Some(e.to_string()),
-        }),
+    match process_write(&mut app, &headers, "/nft/mint", tx).await {
+        Ok(result) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: result.success,
+                data: Some(format!("NFT minted: {}", request.name)),
+                error: result.error,
+            }),
+        ),
+        Err(e) => api_error(e),
     }
 }
 
 /// Transfer tokens
 async fn transfer(
     State(state): State<AppState>,
-    Json(request): Json<TransferRequest>,
-) -> Json<ApiResponse<String>> {
+    headers: HeaderMap,
+    Validated(request): Validated<TransferRequest>,
+) -> (StatusCode, Json<ApiResponse<String>>) {
     let mut app = state.app.write().await;
+    if let Err(message) = check_chain_id(&app, &headers) {
+        return error_response(StatusCode::CONFLICT, message);
+    }
+    
+    let amount = match token_amount(&app, &request.token, &request.amount).await {
+        Ok(amount) => amount,
+        Err(e) => return api_error(e),
+    };
     
     // Create transaction
     let tx = Transaction {
@@ -392,68 +1663,912 @@ async fn transfer(
         from: request.from.clone(),
         to: Some(request.to.clone()),
         data: serde_json::json!({
-            "amount": request.amount,
+            "amount": amount,
             "token": request.token,
         }),
-        timestamp: chrono::Utc::now().timestamp(),
+        timestamp: app.clock().now(),
+        timeout_height: None,
+        nonce: None,
+        memo: request.memo.clone(),
+        fee: None,
+        sig_scheme: SIG_SCHEME_ED25519,
+        public_key: String::new(),
         signature: "".to_string(), // Will be validated
+        signatures: Vec::new(),
     };
 
-    match app.process_transaction(tx).await {
-        Ok(result) => Json(ApiResponse {
-            success: result.success,
-            data: Some("Transfer completed".to_string()),
-            error: result.error,
-        }),
-        Err(e) => Json(ApiResponse {
+    match process_write(&mut app, &headers, "/transfer", tx).await {
+        Ok(result) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: result.success,
+                data: Some("Transfer completed".to_string()),
+                error: result.error,
+            }),
+        ),
+        Err(e) => api_error(e),
+    }
+}
+
+/// Resolve an API amount against the decimals of `token`
+async fn token_amount(app: &MemeChainApp, token: &str, amount: &ApiAmount) -> Result<u64> {
+    if let ApiAmount::Units(amount) = amount {
+        return Ok(*amount);
+    }
+    let token = app.storage().get_token(token).await?
+        .ok_or_else(|| MemeError::TokenNotFound(token.to_string()))?;
+    amount.to_units(app.common_module(), token.decimals)
+}
+
+/// Header letting clients retry a write without it executing twice
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Longest accepted `Idempotency-Key`
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// Header naming the chain a client expects its write to land on
+const CHAIN_ID_HEADER: &str = "x-chain-id";
+
+/// Reject a write whose `X-Chain-Id` header names another chain; writes
+/// without the header are not checked
+pub(crate) fn check_chain_id(app: &MemeChainApp, headers: &HeaderMap) -> std::result::Result<(), String> {
+    let Some(value) = headers.get(CHAIN_ID_HEADER) else {
+        return Ok(());
+    };
+    let chain_id = &app.config().chain.chain_id;
+    if value.to_str().ok() != Some(chain_id.as_str()) {
+        return Err(format!(
+            "X-Chain-Id {:?} does not match this node's chain {}",
+            String::from_utf8_lossy(value.as_bytes()), chain_id
+        ));
+    }
+    Ok(())
+}
+
+/// Execute a write, honouring an `Idempotency-Key` header scoped to `endpoint`
+async fn process_write(
+    app: &mut MemeChainApp,
+    headers: &HeaderMap,
+    endpoint: &str,
+    tx: Transaction,
+) -> Result<TransactionResult> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+        return app.process_transaction(tx).await;
+    };
+
+    let key = value
+        .to_str()
+        .map_err(|_| MemeChainError::Validation("Idempotency-Key must be visible ASCII".to_string()))?;
+    if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+        return Err(MemeChainError::Validation(format!(
+            "Idempotency-Key must be 1 to {} characters", MAX_IDEMPOTENCY_KEY_LEN
+        )));
+    }
+
+    app.process_transaction_idempotent(&format!("{}:{}", endpoint, key), tx).await
+}
+
+/// `mempool` WebSocket topic: one JSON message per admitted transaction
+async fn mempool_ws(State(state): State<AppState>, ws: WebSocketUpgrade) -> Response {
+    let events = state.app.read().await.subscribe_mempool();
+    ws.on_upgrade(move |socket| stream_mempool(socket, events))
+}
+
+/// Forward mempool events to a socket until either side closes
+async fn stream_mempool(mut socket: WebSocket, mut events: broadcast::Receiver<MempoolEvent>) {
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let Some(message) = mempool_message(event) else {
+                    break;
+                };
+                if socket.send(Message::Text(message.to_string())).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => {
+                if matches!(incoming, None | Some(Err(_)) | Some(Ok(Message::Close(_)))) {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Encode a feed item; a lagging subscriber gets a control message instead
+/// of the dropped events. `None` once the feed has shut down.
+fn mempool_message(
+    event: std::result::Result<MempoolEvent, broadcast::error::RecvError>,
+) -> Option<serde_json::Value> {
+    match event {
+        Ok(event) => Some(serde_json::json!({ "type": "transaction", "data": event })),
+        Err(broadcast::error::RecvError::Lagged(dropped)) => Some(serde_json::json!({
+            "type": "lagged",
+            "dropped": dropped,
+            "message": format!("lagged, {} dropped", dropped),
+        })),
+        Err(broadcast::error::RecvError::Closed) => None,
+    }
+}
+
+/// Verify a wallet-signed message
+async fn verify_message(
+    State(state): State<AppState>,
+    Json(request): Json<VerifyRequest>,
+) -> (StatusCode, Json<ApiResponse<VerifyResponse>>) {
+    let decoded = hex::decode(&request.signature).and_then(|signature| {
+        hex::decode(&request.public_key).map(|public_key| (signature, public_key))
+    });
+    let (signature, public_key) = match decoded {
+        Ok(decoded) => decoded,
+        Err(e) => return bad_request(format!("Malformed hex: {}", e)),
+    };
+
+    let app = state.app.read().await;
+    let common = app.common_module();
+    let verified = common
+        .verify_signature(request.message.as_bytes(), &signature, &public_key)
+        .and_then(|valid| Ok((valid, common.generate_address(&public_key)?)));
+
+    match verified {
+        Ok((valid, address)) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(VerifyResponse {
+                    valid,
+                    address: address.to_string(),
+                }),
+                error: None,
+            }),
+        ),
+        Err(e) => bad_request(e.to_string()),
+    }
+}
+
+/// Check an address without creating a transaction
+async fn validate_address(
+    State(state): State<AppState>,
+    Path(addr): Path<String>,
+) -> Json<ApiResponse<AddressCheck>> {
+    let app = state.app.read().await;
+    Json(ApiResponse {
+        success: true,
+        data: Some(app.common_module().check_address(&addr)),
+        error: None,
+    })
+}
+
+/// A 400 response carrying an error message
+fn bad_request<T>(error: String) -> (StatusCode, Json<ApiResponse<T>>) {
+    error_response(StatusCode::BAD_REQUEST, error)
+}
+
+/// Response for a failure while serving a request, with the status from
+/// [`error_status`]
+fn api_error<T>(error: MemeChainError) -> (StatusCode, Json<ApiResponse<T>>) {
+    error_response(error_status(&error), error.to_string())
+}
+
+/// HTTP status for an error, looking through `Module` to the module's own
+/// variant so e.g. an insufficient balance (422) is told apart from a
+/// malformed amount (400). Storage timeouts are 503 so clients may retry.
+fn error_status(error: &MemeChainError) -> StatusCode {
+    match error {
+        MemeChainError::Module(ModuleError::Meme(error)) => match error {
+            MemeError::TokenNotFound(_) => StatusCode::NOT_FOUND,
+            MemeError::TokenExists(_) => StatusCode::CONFLICT,
+            MemeError::Unauthorized(_) => StatusCode::FORBIDDEN,
+            MemeError::InvalidSymbol(_)
+            | MemeError::InvalidName(_)
+            | MemeError::InvalidSupply(_)
+            | MemeError::InvalidAmount(_)
+            | MemeError::InvalidAntiRugSettings(_)
+            | MemeError::InvalidTransferHook(_) => StatusCode::BAD_REQUEST,
+            MemeError::TransferFailed(_)
+            | MemeError::InsufficientBalance(_)
+            | MemeError::MaxWalletLimitExceeded(_)
+            | MemeError::TaxCalculationFailed(_)
+            | MemeError::LiquidityNotLocked(_)
+            | MemeError::LockPeriodNotExpired(_)
+            | MemeError::TokenPaused(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        },
+        MemeChainError::Module(ModuleError::Nft(error)) => match error {
+            NftError::CollectionNotFound(_) | NftError::NftNotFound(_) => StatusCode::NOT_FOUND,
+            NftError::CollectionExists(_)
+            | NftError::NftExists(_)
+            | NftError::VoucherRedeemed(_)
+            | NftError::AlreadyRedeemed(_) => StatusCode::CONFLICT,
+            NftError::Unauthorized(_) => StatusCode::FORBIDDEN,
+            NftError::InvalidMetadata(_) | NftError::InvalidCollectionId(_) | NftError::InvalidNftId(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            NftError::TransferFailed(_)
+            | NftError::MetadataCooldown(_)
+            | NftError::RevealNotReady(_)
+            | NftError::VoucherExpired(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        },
+        MemeChainError::Module(ModuleError::Common(error)) => match error {
+            CommonError::KeyNotFound(_) => StatusCode::NOT_FOUND,
+            CommonError::HashCalculationFailed(_)
+            | CommonError::EncryptionFailed(_)
+            | CommonError::DecryptionFailed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        },
+        MemeChainError::Validation(_) | MemeChainError::InvalidAddress(_) => StatusCode::BAD_REQUEST,
+        MemeChainError::TokenNotFound(_) | MemeChainError::NftNotFound(_) | MemeChainError::CollectionNotFound(_) => {
+            StatusCode::NOT_FOUND
+        }
+        MemeChainError::Unauthorized(_) | MemeChainError::ActionDisabled(_) => StatusCode::FORBIDDEN,
+        MemeChainError::InsufficientBalance(_)
+        | MemeChainError::TransactionFailed(_)
+        | MemeChainError::IdempotencyKeyReused(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        MemeChainError::RateLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
+        MemeChainError::ServiceUnavailable(_) | MemeChainError::Storage(StorageError::Timeout(_)) => {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// An error response with the given status
+fn error_response<T>(status: StatusCode, error: String) -> (StatusCode, Json<ApiResponse<T>>) {
+    (
+        status,
+        Json(ApiResponse {
             success: false,
             data: None,
-            error: Some(e.to_string()),
+            error: Some(error),
         }),
+    )
+}
+
+/// Compare secrets in constant time. Both sides are hashed first so the
+/// comparison does not leak the expected token's length either.
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    use sha2::{Digest, Sha256};
+    use subtle::ConstantTimeEq;
+    let provided = Sha256::digest(provided.as_bytes());
+    let expected = Sha256::digest(expected.as_bytes());
+    provided[..].ct_eq(&expected[..]).into()
+}
+
+/// Check the `Authorization: Bearer` header against the configured admin token
+fn authorize_admin<T>(
+    app: &MemeChainApp,
+    headers: &HeaderMap,
+) -> std::result::Result<(), (StatusCode, Json<ApiResponse<T>>)> {
+    let Some(admin_token) = app.config().api.admin_token.as_deref() else {
+        return Err(error_response(StatusCode::FORBIDDEN, "Admin API is disabled".to_string()));
+    };
+
+    let provided = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if !provided.is_some_and(|provided| tokens_match(provided, admin_token)) {
+        return Err(error_response(StatusCode::UNAUTHORIZED, "Invalid admin token".to_string()));
     }
+    Ok(())
 }
 
-/// List all tokens
-async fn list_tokens(State(state): State<AppState>) -> Json<ApiResponse<Vec<serde_json::Value>>> {
+/// Put the chain into maintenance mode
+async fn admin_halt(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<ApiResponse<bool>>) {
     let app = state.app.read().await;
-    
-    match app.meme_module().list_tokens().await {
-        Ok(tokens) => Json(ApiResponse {
+    if let Err(response) = authorize_admin(&app, &headers) {
+        return response;
+    }
+
+    app.halt();
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
             success: true,
-            data: Some(tokens),
+            data: Some(app.is_halted()),
             error: None,
         }),
-        Err(e) => Json(ApiResponse {
-            success: false,
-            data: None,
-            error: Some(e.to_string()),
-        }),
+    )
+}
+
+/// Database size and per-prefix key counts
+async fn admin_storage_stats(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<ApiResponse<StorageStats>>) {
+    let app = state.app.read().await;
+    if let Err(response) = authorize_admin(&app, &headers) {
+        return response;
+    }
+
+    match app.storage().stats().await {
+        Ok(stats) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(stats),
+                error: None,
+            }),
+        ),
+        Err(e) => api_error(e),
     }
 }
 
-/// List all NFTs
-async fn list_nfts(State(state): State<AppState>) -> Json<ApiResponse<Vec<serde_json::Value>>> {
+/// Leave maintenance mode
+async fn admin_resume(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<ApiResponse<bool>>) {
     let app = state.app.read().await;
-    
-    match app.nft_module().list_nfts().await {
-        Ok(nfts) => Json(ApiResponse {
+    if let Err(response) = authorize_admin(&app, &headers) {
+        return response;
+    }
+
+    app.resume();
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
             success: true,
-            data: Some(nfts),
+            data: Some(app.is_halted()),
             error: None,
         }),
-        Err(e) => Json(ApiResponse {
-            success: false,
-            data: None,
-            error: Some(e.to_string()),
-        }),
-    }
+    )
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Every transaction waiting in the mempool
+async fn admin_mempool(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<ApiResponse<Vec<MempoolEntry>>>) {
+    let app = state.app.read().await;
+    if let Err(response) = authorize_admin(&app, &headers) {
+        return response;
+    }
 
-    #[tokio::test]
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            success: true,
+            data: Some(app.mempool_entries().await),
+            error: None,
+        }),
+    )
+}
+
+/// Evict one transaction from the mempool
+async fn admin_evict_transaction(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<ApiResponse<bool>>) {
+    let app = state.app.read().await;
+    if let Err(response) = authorize_admin(&app, &headers) {
+        return response;
+    }
+
+    match app.evict_transaction(&hash).await {
+        Ok(true) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(true),
+                error: None,
+            }),
+        ),
+        Ok(false) => error_response(StatusCode::NOT_FOUND, format!("Transaction not in mempool: {}", hash)),
+        Err(e) => api_error(e),
+    }
+}
+
+/// Evict every transaction from the mempool
+async fn admin_clear_mempool(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<ApiResponse<usize>>) {
+    let app = state.app.read().await;
+    if let Err(response) = authorize_admin(&app, &headers) {
+        return response;
+    }
+
+    match app.clear_mempool().await {
+        Ok(cleared) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(cleared),
+                error: None,
+            }),
+        ),
+        Err(e) => api_error(e),
+    }
+}
+
+/// List all tokens
+async fn list_tokens(State(state): State<AppState>) -> Json<ApiResponse<Vec<serde_json::Value>>> {
+    let app = state.app.read().await;
+    
+    match app.meme_module().list_tokens().await {
+        Ok(tokens) => Json(ApiResponse {
+            success: true,
+            data: Some(tokens),
+            error: None,
+        }),
+        Err(e) => Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Liquidity lock status of a token at the current height
+async fn get_token_lock(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+) -> (StatusCode, Json<ApiResponse<LockStatus>>) {
+    let app = state.app.read().await;
+
+    match app.meme_module().get_token(&symbol).await {
+        Ok(Some(token)) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(token.anti_rug.lock_status(app.block_height(), app.config().chain.block_time)),
+                error: None,
+            }),
+        ),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, format!("Token not found: {}", symbol)),
+        Err(e) => api_error(e),
+    }
+}
+
+/// Total, circulating, burned and treasury-held supply of a token
+async fn get_token_supply(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+) -> (StatusCode, Json<ApiResponse<TokenSupply>>) {
+    let app = state.app.read().await;
+
+    match app.meme_module().get_supply(&symbol).await {
+        Ok(Some(supply)) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(supply),
+                error: None,
+            }),
+        ),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, format!("Token not found: {}", symbol)),
+        Err(e) => api_error(e),
+    }
+}
+
+/// Expected output and tax of a trade, without executing it
+async fn get_token_quote(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<QuoteQuery>,
+) -> (StatusCode, Json<ApiResponse<TradeQuote>>) {
+    let side = match query.side.as_str() {
+        "buy" => TradeSide::Buy,
+        "sell" => TradeSide::Sell,
+        other => return bad_request(format!("Invalid side {:?}; expected buy or sell", other)),
+    };
+    if query.amount == 0 {
+        return bad_request("Amount cannot be zero".to_string());
+    }
+    let app = state.app.read().await;
+
+    match app.meme_module().get_token(&symbol).await {
+        Ok(Some(token)) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(crate::modules::meme::quote_trade(&token, side, query.amount)),
+                error: None,
+            }),
+        ),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, format!("Token not found: {}", symbol)),
+        Err(e) => api_error(e),
+    }
+}
+
+/// Holders of a token as of a snapshot height
+async fn get_holder_snapshot(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<SnapshotQuery>,
+) -> (StatusCode, Json<ApiResponse<HolderSnapshot>>) {
+    let app = state.app.read().await;
+    let interval = app.config().chain.holder_snapshot_interval;
+    if interval == 0 {
+        return error_response(StatusCode::NOT_FOUND, "Holder snapshots are disabled".to_string());
+    }
+    if !app.config().chain.holder_snapshot_tokens.contains(&symbol) {
+        return error_response(StatusCode::NOT_FOUND, format!("Holder snapshots do not cover {}", symbol));
+    }
+    if query.height == 0 || query.height % interval != 0 {
+        return bad_request(format!("Snapshots are taken every {} blocks; {} is not one of them", interval, query.height));
+    }
+
+    match app.storage().get_token(&symbol).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, format!("Token not found: {}", symbol)),
+        Err(e) => return api_error(e),
+    }
+
+    match app.storage().get_holder_snapshot(&symbol, query.height).await {
+        Ok(Some(holders)) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(HolderSnapshot {
+                    token: symbol,
+                    height: query.height,
+                    holders,
+                }),
+                error: None,
+            }),
+        ),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, format!("No snapshot at height {}", query.height)),
+        Err(e) => api_error(e),
+    }
+}
+
+/// Receipt of an included transaction
+async fn get_receipt(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+) -> (StatusCode, Json<ApiResponse<Receipt>>) {
+    let app = state.app.read().await;
+
+    match app.storage().get_receipt(&hash).await {
+        Ok(Some(receipt)) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(receipt),
+                error: None,
+            }),
+        ),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, format!("Receipt not found: {}", hash)),
+        Err(e) => api_error(e),
+    }
+}
+
+/// Default wait of `/tx/:hash/await`
+const AWAIT_RECEIPT_DEFAULT_TIMEOUT_MS: u64 = 10_000;
+/// Longest wait a client may request from `/tx/:hash/await`
+const AWAIT_RECEIPT_MAX_TIMEOUT_MS: u64 = 60_000;
+
+/// Long-poll for a transaction's receipt: answers as soon as a block
+/// including it is committed, or 504 once the timeout passes. A timeout is
+/// not a client error: the transaction may still be included later
+async fn await_receipt(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+    Query(query): Query<AwaitReceiptQuery>,
+) -> (StatusCode, Json<ApiResponse<Receipt>>) {
+    let timeout_ms = query.timeout_ms.unwrap_or(AWAIT_RECEIPT_DEFAULT_TIMEOUT_MS).min(AWAIT_RECEIPT_MAX_TIMEOUT_MS);
+
+    let wait = next_receipt(&state, &hash);
+    match tokio::time::timeout(Duration::from_millis(timeout_ms), wait).await {
+        Ok(Ok(Some(receipt))) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(receipt),
+                error: None,
+            }),
+        ),
+        Ok(Ok(None)) => error_response(StatusCode::SERVICE_UNAVAILABLE, "Node is shutting down".to_string()),
+        Ok(Err(e)) => api_error(e),
+        Err(_) => error_response(
+            StatusCode::GATEWAY_TIMEOUT,
+            format!("Transaction {} was not included within {} ms", hash, timeout_ms),
+        ),
+    }
+}
+
+/// The stored receipt for `hash`, else the next one published for it;
+/// `None` if the feed closes first
+async fn next_receipt(state: &AppState, hash: &str) -> Result<Option<Receipt>> {
+    loop {
+        // Subscribe before the lookup so a block committed in between is not missed
+        let mut receipts = {
+            let app = state.app.read().await;
+            let receipts = app.subscribe_receipts();
+            if let Some(receipt) = app.storage().get_receipt(hash).await? {
+                return Ok(Some(receipt));
+            }
+            receipts
+        };
+        // After a lag the receipt may have been skipped; look it up again
+        loop {
+            match receipts.recv().await {
+                Ok(receipt) if receipt.tx_hash == hash => return Ok(Some(receipt)),
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(_)) => break,
+                Err(broadcast::error::RecvError::Closed) => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Redemption claimed by burning an NFT
+async fn get_redemption(
+    State(state): State<AppState>,
+    Path(nft_id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<Redemption>>) {
+    let app = state.app.read().await;
+
+    match app.storage().get_redemption(&nft_id).await {
+        Ok(Some(redemption)) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(redemption),
+                error: None,
+            }),
+        ),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, format!("No redemption for NFT {}", nft_id)),
+        Err(e) => api_error(e),
+    }
+}
+
+/// List all NFTs
+async fn list_nfts(State(state): State<AppState>) -> Json<ApiResponse<Vec<serde_json::Value>>> {
+    let app = state.app.read().await;
+    
+    match app.nft_module().list_nfts().await {
+        Ok(nfts) => Json(ApiResponse {
+            success: true,
+            data: Some(nfts),
+            error: None,
+        }),
+        Err(e) => Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Get an NFT's ownership history
+async fn get_nft_history(
+    State(state): State<AppState>,
+    Path(nft_id): Path<String>,
+) -> Json<ApiResponse<Vec<OwnershipRecord>>> {
+    let app = state.app.read().await;
+
+    match app.nft_module().get_nft_history(&nft_id).await {
+        Ok(history) => Json(ApiResponse {
+            success: true,
+            data: Some(history),
+            error: None,
+        }),
+        Err(e) => Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Default page size of `/collections/:id/nfts`
+const COLLECTION_NFTS_DEFAULT_LIMIT: usize = 50;
+/// Largest page `/collections/:id/nfts` returns
+const COLLECTION_NFTS_MAX_LIMIT: usize = 500;
+
+/// A page of a collection's NFTs, ordered by ID
+async fn get_collection_nfts(
+    State(state): State<AppState>,
+    Path(collection_id): Path<String>,
+    Query(query): Query<CollectionNftsQuery>,
+) -> (StatusCode, Json<ApiResponse<CollectionNftsPage>>) {
+    let app = state.app.read().await;
+
+    match app.storage().get_collection(&collection_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, format!("Collection not found: {}", collection_id)),
+        Err(e) => return api_error(e),
+    }
+
+    let limit = query.limit.unwrap_or(COLLECTION_NFTS_DEFAULT_LIMIT).min(COLLECTION_NFTS_MAX_LIMIT);
+    match app.nft_module().get_nfts_by_collection(&collection_id, query.offset, Some(limit)).await {
+        Ok(page) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(CollectionNftsPage {
+                    total: page.total,
+                    limit,
+                    offset: query.offset,
+                    nfts: page.items,
+                }),
+                error: None,
+            }),
+        ),
+        Err(e) => api_error(e),
+    }
+}
+
+/// Trait value distribution of a collection: trait -> value -> NFT count
+async fn get_collection_traits(
+    State(state): State<AppState>,
+    Path(collection_id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<BTreeMap<String, BTreeMap<String, u64>>>>) {
+    let app = state.app.read().await;
+
+    match app.storage().get_collection(&collection_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, format!("Collection not found: {}", collection_id)),
+        Err(e) => return api_error(e),
+    }
+
+    match app.storage().trait_distribution(&collection_id).await {
+        Ok(distribution) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(distribution),
+                error: None,
+            }),
+        ),
+        Err(e) => api_error(e),
+    }
+}
+
+/// NFTs in a collection with an indexed metadata value
+async fn search_collection(
+    State(state): State<AppState>,
+    Path(collection_id): Path<String>,
+    Query(query): Query<MetadataSearchQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<Nft>>>) {
+    let app = state.app.read().await;
+
+    if !app.storage().is_metadata_indexed(&query.key) {
+        return bad_request(format!("Metadata key {:?} is not indexed", query.key));
+    }
+    match app.storage().get_collection(&collection_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, format!("Collection not found: {}", collection_id)),
+        Err(e) => return api_error(e),
+    }
+
+    match app.storage().search_nfts_by_metadata(&collection_id, &query.key, &query.value).await {
+        Ok(nfts) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(nfts),
+                error: None,
+            }),
+        ),
+        Err(e) => api_error(e),
+    }
+}
+
+/// Get a content-addressed metadata blob
+async fn get_blob(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+) -> (StatusCode, Json<ApiResponse<serde_json::Value>>) {
+    if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return bad_request(format!("Invalid blob hash: {}", hash));
+    }
+
+    let app = state.app.read().await;
+    let blob = app
+        .storage()
+        .get_blob(&hash.to_lowercase())
+        .await
+        .and_then(|blob| blob.map(|data| serde_json::from_slice(&data)).transpose().map_err(Into::into));
+
+    match blob {
+        Ok(Some(metadata)) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                success: true,
+                data: Some(metadata),
+                error: None,
+            }),
+        ),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, format!("Blob not found: {}", hash)),
+        Err(e) => api_error(e),
+    }
+}
+
+/// Default page size of `/blocks`
+const BLOCKS_DEFAULT_LIMIT: usize = 20;
+/// Largest page `/blocks` returns
+const BLOCKS_MAX_LIMIT: usize = 100;
+
+/// Summaries of recent blocks, newest first; pass the last height as
+/// `before` to get the next page
+async fn list_blocks(
+    State(state): State<AppState>,
+    Query(query): Query<BlocksQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<BlockSummary>>>) {
+    let app = state.app.read().await;
+    let limit = query.limit.unwrap_or(BLOCKS_DEFAULT_LIMIT).min(BLOCKS_MAX_LIMIT);
+    let top = match query.before {
+        Some(before) => before.saturating_sub(1).min(app.block_height()),
+        None => app.block_height(),
+    };
+
+    let mut blocks = Vec::with_capacity(limit);
+    for height in (1..=top).rev().take(limit) {
+        match app.storage().get_block(height).await {
+            Ok(Some(block)) => blocks.push(BlockSummary::from(&block)),
+            Ok(None) => {}
+            Err(e) => return api_error(e),
+        }
+    }
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            success: true,
+            data: Some(blocks),
+            error: None,
+        }),
+    )
+}
+
+/// Maximum number of holders returned by `/richlist`
+const RICHLIST_MAX_LIMIT: usize = 1000;
+
+/// Top holders of a token by balance
+async fn get_richlist(
+    State(state): State<AppState>,
+    Query(query): Query<RichlistQuery>,
+) -> Json<ApiResponse<Vec<Balance>>> {
+    let app = state.app.read().await;
+    let chain = &app.config().chain;
+    let token = query.token.unwrap_or_else(|| chain.native_symbol.clone());
+    let limit = query.limit.unwrap_or(100).min(RICHLIST_MAX_LIMIT);
+    let exclude = if query.exclude_system {
+        vec![
+            Address::new(chain.treasury_address.clone()),
+            Address::new(chain.burn_address.clone()),
+        ]
+    } else {
+        Vec::new()
+    };
+
+    match app.storage().top_holders(&token, limit, &exclude).await {
+        Ok(holders) => Json(ApiResponse {
+            success: true,
+            data: Some(holders),
+            error: None,
+        }),
+        Err(e) => Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// List known peers
+async fn list_peers(State(state): State<AppState>) -> Json<ApiResponse<Vec<Peer>>> {
+    let app = state.app.read().await;
+    let peers = app.peer_book().read().await.list_peers();
+
+    Json(ApiResponse {
+        success: true,
+        data: Some(peers),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
     async fn test_app_creation() {
         let config = Config::default();
         let app = MemeChainApp::new(config).await;
@@ -461,10 +2576,2197 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_block_creation() {
-        let config = Config::default();
+    async fn test_trades_paid_in_native_symbol() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.storage.db_path = temp_dir.path().join("native_db").to_str().unwrap().to_string();
+        config.chain.native_symbol = "DOGE".to_string();
+
         let mut app = MemeChainApp::new(config).await.unwrap();
-        let block = app.create_block().await;
-        assert!(block.is_ok());
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[1u8; 32]);
+        let sender = app.common_module()
+            .generate_address(&signing_key.verifying_key().to_bytes())
+            .unwrap();
+
+        let mut genesis = GenesisConfig::new("test-chain".to_string(), "validator".to_string());
+        genesis.accounts = vec![crate::config::Account {
+            address: sender.to_string(),
+            balance: 1000,
+            name: "sender".to_string(),
+        }];
+        app.apply_genesis(&genesis).await.unwrap();
+        app.validate_native_token().await.unwrap();
+
+        // Seed a token to trade
+        let create = Transaction::new(
+            "meme".to_string(),
+            "create_token".to_string(),
+            Address::new("memechain1cccccccccccccccccccccccccccccccc".to_string()),
+            None,
+            serde_json::json!({"name": "Pepe", "symbol": "PEPE", "supply": 1_000_000}),
+            chrono::Utc::now().timestamp(),
+        );
+        app.meme_module().process_transaction(create).await.unwrap();
+
+        let mut tx = Transaction::new(
+            "meme".to_string(),
+            "buy".to_string(),
+            sender.clone(),
+            None,
+            serde_json::json!({"token": "PEPE", "amount": 100}),
+            chrono::Utc::now().timestamp(),
+        );
+        tx.sign(crate::config::DEFAULT_CHAIN_ID, &hex::encode(signing_key.to_bytes())).unwrap();
+        app.process_transaction(tx).await.unwrap();
+
+        // The buy is paid for in DOGE; no MEME is ever touched
+        let balance = app.storage().get_balance(&sender, "DOGE").await.unwrap().unwrap();
+        assert_eq!(balance.amount, 900);
+        assert!(app.storage().get_balance(&sender, "MEME").await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secreT", "secret"));
+        assert!(!tokens_match("secret-but-longer", "secret"));
+        assert!(!tokens_match("", "secret"));
+    }
+
+    fn memory_config() -> Config {
+        let mut config = Config::default();
+        config.storage.db_type = "memory".to_string();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_mempool_survives_restart() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.storage.db_path = temp_dir.path().join("db").to_str().unwrap().to_string();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let bob = crate::testing::TestAccount::from_seed(2);
+        let transfer = |account: &crate::testing::TestAccount, nonce: Option<u64>| {
+            let mut tx = account
+                .transaction("meme", "transfer", Some(Address::from("memechain1carol")), serde_json::json!({"token": "MEME", "amount": 1}))
+                .unwrap();
+            tx.nonce = nonce;
+            tx.sign(crate::config::DEFAULT_CHAIN_ID, &account.private_key_hex()).unwrap();
+            tx
+        };
+
+        let kept = transfer(&alice, None);
+        let stale = transfer(&bob, Some(0));
+        let mut expired = transfer(&alice, None);
+        expired.timestamp -= 3_600;
+        expired.sign(crate::config::DEFAULT_CHAIN_ID, &alice.private_key_hex()).unwrap();
+        {
+            let mut app = MemeChainApp::new(config.clone()).await.unwrap();
+            // Admit the old transaction while it is still fresh
+            let clock = Arc::new(crate::clock::MockClock::new(expired.timestamp));
+            app.set_clock(clock.clone());
+            app.submit_transaction(expired).await.unwrap();
+            clock.set(chrono::Utc::now().timestamp());
+            app.submit_transaction(kept.clone()).await.unwrap();
+            app.submit_transaction(stale).await.unwrap();
+            assert_eq!(app.tx_pool_size().await, 3);
+
+            // Bob's nonce gets used elsewhere before the restart
+            app.storage().set_nonce(bob.address(), 1).await.unwrap();
+        }
+
+        let mut app = MemeChainApp::new(config.clone()).await.unwrap();
+        assert_eq!(app.tx_pool_size().await, 1);
+        let block = app.create_block().await.unwrap();
+        assert_eq!(block.transactions.len(), 1);
+        assert_eq!(block.transactions[0].hash(), kept.hash());
+
+        // Mined transactions are gone from storage too
+        assert!(app.storage().pooled_transactions().await.unwrap().is_empty());
+        drop(app);
+        let app = MemeChainApp::new(config).await.unwrap();
+        assert_eq!(app.tx_pool_size().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_genesis_app_state() {
+        let app = MemeChainApp::new(memory_config()).await.unwrap();
+
+        let mut genesis = GenesisConfig::new("test-chain".to_string(), "validator".to_string());
+        genesis.app_state.meme.tokens.push(crate::config::Token {
+            symbol: "PEPE".to_string(),
+            name: "Pepe".to_string(),
+            total_supply: 1_000_000,
+            creator: "memechain1alice".to_string(),
+            anti_rug: crate::config::AntiRugSettings::default(),
+        });
+        genesis.app_state.nft.collections.push(crate::config::Collection {
+            id: "genesis-apes".to_string(),
+            name: "Genesis Apes".to_string(),
+            creator: "memechain1bob".to_string(),
+            description: "Launch collection".to_string(),
+        });
+        app.apply_genesis(&genesis).await.unwrap();
+
+        let token = app.storage().get_token("PEPE").await.unwrap().unwrap();
+        assert_eq!(token.total_supply, 1_000_000);
+        let creator_balance = app.storage()
+            .get_balance(&Address::new("memechain1alice".to_string()), "PEPE")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(creator_balance.amount, 1_000_000);
+
+        let collection = app.storage().get_collection("genesis-apes").await.unwrap().unwrap();
+        assert_eq!(collection.name, "Genesis Apes");
+
+        // Records are stamped with the genesis time, not the wall clock
+        let genesis_time = genesis.parsed_genesis_time().unwrap().timestamp();
+        assert_eq!(token.created_at, genesis_time);
+        assert_eq!(creator_balance.updated_at, genesis_time);
+        assert_eq!(collection.created_at, genesis_time);
+    }
+
+    #[tokio::test]
+    async fn test_genesis_duplicate_symbol_rejected() {
+        let app = MemeChainApp::new(memory_config()).await.unwrap();
+
+        let mut genesis = GenesisConfig::new("test-chain".to_string(), "validator".to_string());
+        let token = crate::config::Token {
+            symbol: "PEPE".to_string(),
+            name: "Pepe".to_string(),
+            total_supply: 1_000_000,
+            creator: "memechain1alice".to_string(),
+            anti_rug: crate::config::AntiRugSettings::default(),
+        };
+        genesis.app_state.meme.tokens = vec![token.clone(), token];
+
+        let err = app.apply_genesis(&genesis).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Config(ConfigError::InvalidGenesis(_))));
+    }
+
+    #[tokio::test]
+    async fn test_genesis_supply_overflow_rejected() {
+        let app = MemeChainApp::new(memory_config()).await.unwrap();
+
+        let mut genesis = GenesisConfig::new("test-chain".to_string(), "validator".to_string());
+        genesis.accounts = ["memechain1alice", "memechain1bob"]
+            .into_iter()
+            .map(|address| crate::config::Account {
+                address: address.to_string(),
+                balance: u64::MAX,
+                name: address.to_string(),
+            })
+            .collect();
+
+        let err = app.apply_genesis(&genesis).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Config(ConfigError::InvalidGenesis(_))));
+        assert!(app.storage().get_token("MEME").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_apply_genesis_rejects_future_time() {
+        use crate::clock::Clock;
+
+        let mut app = MemeChainApp::new(memory_config()).await.unwrap();
+        let clock = Arc::new(crate::clock::MockClock::new(1_700_000_000));
+        app.set_clock(clock.clone());
+
+        let mut genesis = GenesisConfig::new("test-chain".to_string(), "validator".to_string());
+        let skew = app.config().chain.genesis_time_max_skew_secs as i64;
+        genesis.genesis_time = chrono::DateTime::from_timestamp(clock.now() + skew + 3_600, 0)
+            .unwrap()
+            .to_rfc3339();
+
+        let err = app.apply_genesis(&genesis).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Config(ConfigError::InvalidGenesis(_))));
+
+        clock.advance(3_600);
+        app.apply_genesis(&genesis).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restart_with_changed_genesis_rejected() {
+        let storage = Storage::from_backend(Arc::new(crate::storage::MemoryBackend::new()));
+        let mut genesis = GenesisConfig::new("test-chain".to_string(), "validator".to_string());
+        genesis.accounts = vec![crate::config::Account {
+            address: "memechain1alice".to_string(),
+            balance: 1000,
+            name: "alice".to_string(),
+        }];
+        let app = MemeChainApp::with_storage(memory_config(), storage.clone()).await.unwrap();
+        app.apply_genesis(&genesis).await.unwrap();
+        assert_eq!(app.storage().get_genesis_hash().await.unwrap(), Some(genesis.hash()));
+
+        // Restarting against the same database with the same genesis is fine
+        let app = MemeChainApp::with_storage(memory_config(), storage.clone()).await.unwrap();
+        app.verify_genesis(&genesis).await.unwrap();
+
+        let mut changed = genesis.clone();
+        changed.accounts[0].balance = 1_000_000;
+        assert_ne!(changed.hash(), genesis.hash());
+        let err = app.verify_genesis(&changed).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Config(ConfigError::InvalidGenesis(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_exemption() {
+        let relayer = crate::testing::TestAccount::from_seed(1);
+        let user = crate::testing::TestAccount::from_seed(2);
+
+        let mut config = memory_config();
+        config.api.rate_limit = 2;
+        config.api.rate_limit_exempt = vec![relayer.address().to_string()];
+        assert!(config.validate().is_ok());
+        let app = MemeChainApp::new(config).await.unwrap();
+
+        for _ in 0..5 {
+            assert!(app.check_rate_limit(relayer.address()).await.is_ok());
+        }
+
+        assert!(app.check_rate_limit(user.address()).await.is_ok());
+        assert!(app.check_rate_limit(user.address()).await.is_ok());
+        assert!(matches!(
+            app.check_rate_limit(user.address()).await,
+            Err(MemeChainError::RateLimitExceeded)
+        ));
+    }
+
+    /// Memory backend whose batch writes can be made to fail and whose
+    /// reads can be made to stall; counts successful batch writes
+    struct FailingBackend {
+        inner: crate::storage::MemoryBackend,
+        fail_writes: std::sync::atomic::AtomicBool,
+        fail_scans: std::sync::atomic::AtomicBool,
+        stall_reads: std::sync::atomic::AtomicBool,
+        batch_writes: std::sync::atomic::AtomicU64,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::storage::StorageBackend for FailingBackend {
+        async fn initialize(&self) -> Result<()> {
+            self.inner.initialize().await
+        }
+
+        async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+            if self.stall_reads.load(std::sync::atomic::Ordering::SeqCst) {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            }
+            self.inner.get(key).await
+        }
+
+        async fn set(&self, key: &str, value: &[u8]) -> Result<()> {
+            self.inner.set(key, value).await
+        }
+
+        async fn delete(&self, key: &str) -> Result<()> {
+            self.inner.delete(key).await
+        }
+
+        async fn exists(&self, key: &str) -> Result<bool> {
+            self.inner.exists(key).await
+        }
+
+        async fn get_keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>> {
+            if self.fail_scans.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err(crate::error::StorageError::ReadFailed("injected failure".to_string()).into());
+            }
+            self.inner.get_keys_with_prefix(prefix).await
+        }
+
+        async fn batch_write(&self, operations: Vec<(String, Option<Vec<u8>>)>) -> Result<()> {
+            if self.fail_writes.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err(crate::error::StorageError::WriteFailed("injected failure".to_string()).into());
+            }
+            self.batch_writes.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.inner.batch_write(operations).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_block_commit_leaves_no_partial_state() {
+        let backend = Arc::new(FailingBackend {
+            inner: crate::storage::MemoryBackend::new(),
+            fail_writes: std::sync::atomic::AtomicBool::new(false),
+            fail_scans: std::sync::atomic::AtomicBool::new(false),
+            stall_reads: std::sync::atomic::AtomicBool::new(false),
+            batch_writes: std::sync::atomic::AtomicU64::new(0),
+        });
+        let config = Config::default();
+
+        let mut app = MemeChainApp::with_storage(config, Storage::from_backend(backend.clone()))
+            .await
+            .unwrap();
+        let account = crate::testing::TestAccount::from_seed(1);
+        let mut genesis = GenesisConfig::new("test-chain".to_string(), "validator".to_string());
+        genesis.accounts = vec![crate::config::Account {
+            address: account.address().to_string(),
+            balance: 1000,
+            name: "sender".to_string(),
+        }];
+        app.apply_genesis(&genesis).await.unwrap();
+
+        let tx = account
+            .transaction(
+                "meme",
+                "transfer",
+                Some(Address::from("memechain1bob")),
+                serde_json::json!({"token": "MEME", "amount": 10}),
+            )
+            .unwrap();
+        app.submit_transaction(tx).await.unwrap();
+
+        // The transfer applies mid-block, but the commit fails
+        backend.fail_writes.store(true, std::sync::atomic::Ordering::SeqCst);
+        assert!(app.create_block().await.is_err());
+
+        let balance = app.storage().get_balance(account.address(), "MEME").await.unwrap().unwrap();
+        assert_eq!(balance.amount, 1000);
+        assert!(app.storage().get_block(1).await.unwrap().is_none());
+        assert_eq!(app.block_height(), 0);
+        assert_eq!(app.tx_pool_size().await, 1);
+
+        // Once storage recovers the same block applies in full
+        backend.fail_writes.store(false, std::sync::atomic::Ordering::SeqCst);
+        let block = app.create_block().await.unwrap();
+        assert_eq!(block.height, 1);
+        let balance = app.storage().get_balance(account.address(), "MEME").await.unwrap().unwrap();
+        assert_eq!(balance.amount, 990);
+        assert!(app.storage().get_block(1).await.unwrap().is_some());
+        assert_eq!(block.app_hash, app.storage().app_hash().await.unwrap());
+        assert_eq!(app.app_hash(), block.app_hash);
+    }
+
+    #[tokio::test]
+    async fn test_failed_block_execution_discards_block_batch() {
+        let backend = Arc::new(FailingBackend {
+            inner: crate::storage::MemoryBackend::new(),
+            fail_writes: std::sync::atomic::AtomicBool::new(false),
+            fail_scans: std::sync::atomic::AtomicBool::new(false),
+            stall_reads: std::sync::atomic::AtomicBool::new(false),
+            batch_writes: std::sync::atomic::AtomicU64::new(0),
+        });
+        let mut app = MemeChainApp::with_storage(Config::default(), Storage::from_backend(backend.clone()))
+            .await
+            .unwrap();
+        let account = crate::testing::TestAccount::from_seed(1);
+        let mut genesis = GenesisConfig::new("test-chain".to_string(), "validator".to_string());
+        genesis.accounts = vec![crate::config::Account {
+            address: account.address().to_string(),
+            balance: 1000,
+            name: "sender".to_string(),
+        }];
+        app.apply_genesis(&genesis).await.unwrap();
+
+        let tx = account
+            .transaction(
+                "meme",
+                "transfer",
+                Some(Address::from("memechain1bob")),
+                serde_json::json!({"token": "MEME", "amount": 10}),
+            )
+            .unwrap();
+        app.submit_transaction(tx).await.unwrap();
+
+        // The app hash scan fails after the transfer has applied
+        backend.fail_scans.store(true, std::sync::atomic::Ordering::SeqCst);
+        assert!(app.create_block().await.is_err());
+        let balance = app.storage().get_balance(account.address(), "MEME").await.unwrap().unwrap();
+        assert_eq!(balance.amount, 1000);
+        assert_eq!(app.tx_pool_size().await, 1);
+
+        // The next block writes straight through rather than into a leaked batch
+        backend.fail_scans.store(false, std::sync::atomic::Ordering::SeqCst);
+        let writes = backend.batch_writes.load(std::sync::atomic::Ordering::SeqCst);
+        let block = app.create_block().await.unwrap();
+        assert_eq!(block.transactions.len(), 1);
+        assert!(backend.batch_writes.load(std::sync::atomic::Ordering::SeqCst) > writes);
+        let balance = app.storage().get_balance(account.address(), "MEME").await.unwrap().unwrap();
+        assert_eq!(balance.amount, 990);
+    }
+
+    #[tokio::test]
+    async fn test_block_creation() {
+        let config = Config::default();
+        let mut app = MemeChainApp::new(config).await.unwrap();
+        let block = app.create_block().await;
+        assert!(block.is_ok());
+    }
+
+    async fn send(
+        app: Arc<RwLock<MemeChainApp>>,
+        request: axum::http::Request<axum::body::Body>,
+    ) -> (StatusCode, serde_json::Value) {
+        use tower::ServiceExt;
+
+        let response = router(app).oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    async fn post_verify(body: serde_json::Value) -> (StatusCode, serde_json::Value) {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let request = axum::http::Request::post("/verify")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+        send(node.app(), request).await
+    }
+
+    #[tokio::test]
+    async fn test_verify_valid_signature() {
+        use ed25519_dalek::Signer;
+
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = signing_key.verifying_key().to_bytes();
+        let message = "Sign in to MemeChain: nonce 42";
+
+        let (status, body) = post_verify(serde_json::json!({
+            "message": message,
+            "signature": hex::encode(signing_key.sign(message.as_bytes()).to_bytes()),
+            "public_key": hex::encode(public_key),
+        })).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["valid"], true);
+        assert_eq!(body["data"]["address"], Address::from_public_key(&public_key).to_string());
+    }
+
+    #[tokio::test]
+    async fn test_verify_invalid_signature() {
+        use ed25519_dalek::Signer;
+
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+        let signature = signing_key.sign(b"original message");
+
+        let (status, body) = post_verify(serde_json::json!({
+            "message": "tampered message",
+            "signature": hex::encode(signature.to_bytes()),
+            "public_key": hex::encode(signing_key.verifying_key().to_bytes()),
+        })).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["valid"], false);
+    }
+
+    #[tokio::test]
+    async fn test_verify_malformed_hex() {
+        let (status, body) = post_verify(serde_json::json!({
+            "message": "hello",
+            "signature": "not-hex",
+            "public_key": "zz",
+        })).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["success"], false);
+    }
+
+    #[tokio::test]
+    async fn test_halt_rejects_writes_until_resumed() {
+        let mut config = Config::default();
+        config.api.admin_token = Some("secret".to_string());
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let admin = |path: &str, token: &str| {
+            axum::http::Request::post(path)
+                .header("authorization", format!("Bearer {}", token))
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let (status, _) = send(node.app(), admin("/admin/halt", "wrong")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(!node.app().read().await.is_halted());
+
+        let (status, _) = send(node.app(), admin("/admin/halt", "secret")).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let data = serde_json::json!({"data": "hello"});
+        let err = node.submit(&alice, "common", "hash_data", None, data.clone()).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::ServiceUnavailable(_)));
+        let err = node.execute(&alice, "common", "hash_data", None, data.clone()).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::ServiceUnavailable(_)));
+
+        // Reads stay available
+        let (status, body) = send(
+            node.app(),
+            axum::http::Request::get("/status").body(axum::body::Body::empty()).unwrap(),
+        ).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["halted"], true);
+
+        let (status, _) = send(node.app(), admin("/admin/resume", "secret")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(node.submit(&alice, "common", "hash_data", None, data.clone()).await.is_ok());
+        assert!(node.execute(&alice, "common", "hash_data", None, data).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_richlist_endpoint() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let treasury = Address::from("memechain1treasury");
+        node.execute(&alice, "meme", "transfer", Some(treasury), serde_json::json!({"token": "MEME", "amount": 10}))
+            .await
+            .unwrap();
+
+        let get = |uri: &str| axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+
+        let (status, body) = send(node.app(), get("/richlist?limit=2")).await;
+        assert_eq!(status, StatusCode::OK);
+        let holders = body["data"].as_array().unwrap();
+        assert_eq!(holders.len(), 2);
+        assert!(holders[0]["amount"].as_u64() >= holders[1]["amount"].as_u64());
+
+        // Alice paid the treasury, so she ranks last; the treasury holds her payment
+        let (_, body) = send(node.app(), get("/richlist")).await;
+        let holders = body["data"].as_array().unwrap();
+        assert_eq!(holders.len(), 4);
+        assert_eq!(holders[2]["address"], alice.address().to_string());
+        assert_eq!(holders[3]["address"], "memechain1treasury");
+
+        let (_, body) = send(node.app(), get("/richlist?exclude_system=true")).await;
+        let holders = body["data"].as_array().unwrap();
+        assert_eq!(holders.len(), 3);
+        assert!(holders.iter().all(|holder| holder["address"] != "memechain1treasury"));
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key_executes_once() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let bob = crate::testing::TestAccount::from_seed(2);
+        let app = node.app();
+        let mut app = app.write().await;
+
+        let transfer = || {
+            alice
+                .transaction("meme", "transfer", Some(bob.address().clone()), serde_json::json!({"token": "MEME", "amount": 100}))
+                .unwrap()
+        };
+
+        let first = app.process_transaction_idempotent("transfer:retry-1", transfer()).await.unwrap();
+        let second = app.process_transaction_idempotent("transfer:retry-1", transfer()).await.unwrap();
+        assert!(first.success);
+        assert_eq!(first.data, second.data);
+
+        let balance = app.storage().get_balance(bob.address(), "MEME").await.unwrap().unwrap();
+        assert_eq!(balance.amount, crate::testing::TEST_ACCOUNT_BALANCE + 100);
+
+        // A fresh key executes again
+        app.process_transaction_idempotent("transfer:retry-2", transfer()).await.unwrap();
+        let balance = app.storage().get_balance(bob.address(), "MEME").await.unwrap().unwrap();
+        assert_eq!(balance.amount, crate::testing::TEST_ACCOUNT_BALANCE + 200);
+
+        // Keys are scoped to the sender: another sender's request with the same key runs
+        let carol = crate::testing::TestAccount::from_seed(3);
+        let from_carol = carol
+            .transaction("meme", "transfer", Some(bob.address().clone()), serde_json::json!({"token": "MEME", "amount": 100}))
+            .unwrap();
+        app.process_transaction_idempotent("transfer:retry-1", from_carol).await.unwrap();
+        let balance = app.storage().get_balance(bob.address(), "MEME").await.unwrap().unwrap();
+        assert_eq!(balance.amount, crate::testing::TEST_ACCOUNT_BALANCE + 300);
+
+        // Reusing a key for a different request is refused, not answered from the cache
+        let different = alice
+            .transaction("meme", "transfer", Some(bob.address().clone()), serde_json::json!({"token": "MEME", "amount": 5}))
+            .unwrap();
+        let err = app.process_transaction_idempotent("transfer:retry-1", different).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::IdempotencyKeyReused(_)));
+        assert_eq!(error_status(&err), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn test_expired_idempotency_records_pruned() {
+        let storage = Storage::from_backend(Arc::new(crate::storage::MemoryBackend::new()));
+        let record = |expires_at| IdempotencyRecord {
+            result: TransactionResult::success(None),
+            request_hash: String::new(),
+            expires_at,
+        };
+        storage.store_idempotency_record("alice:transfer:old", &record(100)).await.unwrap();
+        storage.store_idempotency_record("alice:transfer:new", &record(300)).await.unwrap();
+
+        assert_eq!(storage.prune_idempotency_records(200).await.unwrap(), 1);
+        assert!(storage.get_idempotency_record("alice:transfer:old").await.unwrap().is_none());
+        assert!(storage.get_idempotency_record("alice:transfer:new").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_generated_private_key_never_stored() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let keygen = alice.transaction("common", "generate_keypair", None, serde_json::json!({"direct": true})).unwrap();
+
+        // The sender still receives the key directly, but a replay does not
+        let app = node.app();
+        let direct = app.write().await.process_transaction_idempotent("keygen-1", keygen.clone()).await.unwrap();
+        assert!(direct.data.as_ref().unwrap()["private_key"].is_string());
+        let replay = app.write().await.process_transaction_idempotent("keygen-1", keygen).await.unwrap();
+        assert!(replay.data.as_ref().unwrap().get("private_key").is_none());
+
+        let hash = node.submit(&alice, "common", "generate_keypair", None, serde_json::json!({})).await.unwrap();
+        let block = node.produce_block().await.unwrap();
+        assert!(block.results[0].data.as_ref().unwrap().get("private_key").is_none());
+
+        let get = |uri: String| axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+        for uri in [format!("/tx/{}", hash), format!("/tx/{}/await", hash)] {
+            let (status, body) = send(node.app(), get(uri)).await;
+            assert_eq!(status, StatusCode::OK);
+            assert!(body["data"]["result"]["data"]["public_key"].is_string());
+            assert!(body["data"]["result"]["data"].get("private_key").is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key_header_validated() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let request = axum::http::Request::post("/transfer")
+            .header("content-type", "application/json")
+            .header(IDEMPOTENCY_KEY_HEADER, "x".repeat(MAX_IDEMPOTENCY_KEY_LEN + 1))
+            .body(axum::body::Body::from(serde_json::json!({
+                "from": "memechain1alice",
+                "to": "memechain1bob",
+                "amount": 1,
+                "token": "MEME",
+            }).to_string()))
+            .unwrap();
+
+        let (_, body) = send(node.app(), request).await;
+        assert_eq!(body["success"], false);
+        assert!(body["error"].as_str().unwrap().contains("Idempotency-Key"));
+    }
+
+    #[tokio::test]
+    async fn test_cors_preflight() {
+        use tower::ServiceExt;
+
+        let mut config = Config::default();
+        config.api.allowed_methods = vec!["get".to_string(), "POST".to_string()];
+        config.api.max_age_seconds = 3600;
+        config.validate().unwrap();
+        let node = crate::testing::TestNode::with_config(config.clone()).await.unwrap();
+
+        let preflight = axum::http::Request::builder()
+            .method("OPTIONS")
+            .uri("/transfer")
+            .header("origin", "https://app.example")
+            .header("access-control-request-method", "POST")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = api_router(node.app(), &config.api).oneshot(preflight).await.unwrap();
+
+        let headers = response.headers();
+        assert_eq!(headers["access-control-max-age"], "3600");
+        assert_eq!(headers["access-control-allow-methods"], "GET,POST");
+        assert_eq!(headers["access-control-allow-origin"], "*");
+
+        config.api.allowed_methods.push("BAD METHOD".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_api_amount_decimal_strings() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let app = node.app();
+        let app = app.read().await;
+        let common = app.common_module();
+        let parse = |json: &str| serde_json::from_str::<ApiAmount>(json).unwrap();
+
+        assert_eq!(parse("\"1.234567\"").to_units(common, 6).unwrap(), 1_234_567);
+        assert_eq!(parse("\"1.5\"").to_units(common, 6).unwrap(), 1_500_000);
+        assert_eq!(parse("1500000").to_units(common, 6).unwrap(), 1_500_000);
+        assert!(parse("\"1.2345678\"").to_units(common, 6).is_err());
+        assert!(parse("\"1.2.3\"").to_units(common, 6).is_err());
+        assert!(parse("\"1.-5\"").to_units(common, 6).is_err());
+        assert!(parse("\"18446744073709.551616\"").to_units(common, 6).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_transfer_amount_uses_token_decimals() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let app = node.app();
+        let app = app.read().await;
+
+        let amount = ApiAmount::Decimal("2.5".to_string());
+        assert_eq!(token_amount(&app, "MEME", &amount).await.unwrap(), 2_500_000);
+        assert!(token_amount(&app, "NOPE", &amount).await.is_err());
+        assert_eq!(token_amount(&app, "NOPE", &ApiAmount::Units(7)).await.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_mempool_feed() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let mut events = node.app().read().await.subscribe_mempool();
+
+        let mut hashes = Vec::new();
+        for data in ["a", "b", "c"] {
+            let hash = node
+                .submit(&alice, "common", "hash_data", None, serde_json::json!({ "data": data }))
+                .await
+                .unwrap();
+            hashes.push(hash);
+        }
+
+        for hash in hashes {
+            let message = mempool_message(events.recv().await).unwrap();
+            assert_eq!(message["type"], "transaction");
+            assert_eq!(message["data"]["hash"], hash);
+            assert_eq!(message["data"]["module"], "common");
+            assert_eq!(message["data"]["action"], "hash_data");
+            assert_eq!(message["data"]["from"], alice.address().to_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mempool_feed_reports_lag() {
+        let (sender, mut events) = broadcast::channel(2);
+        for i in 0..5 {
+            sender
+                .send(MempoolEvent {
+                    hash: format!("hash-{}", i),
+                    module: "common".to_string(),
+                    action: "hash_data".to_string(),
+                    from: Address::from("memechain1alice"),
+                })
+                .unwrap();
+        }
+
+        let message = mempool_message(events.recv().await).unwrap();
+        assert_eq!(message["type"], "lagged");
+        assert_eq!(message["dropped"], 3);
+        assert_eq!(message["message"], "lagged, 3 dropped");
+
+        // The subscriber resumes from the oldest retained event
+        let message = mempool_message(events.recv().await).unwrap();
+        assert_eq!(message["data"]["hash"], "hash-3");
+
+        drop(sender);
+        events.recv().await.unwrap();
+        assert!(mempool_message(events.recv().await).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fee_burn_reduces_native_supply() {
+        let mut config = Config::default();
+        config.chain.tx_fee = 100;
+        config.chain.fee_burn_bps = 2_500;
+        config.validate().unwrap();
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+
+        let app = node.app();
+        let supply_before = app.read().await.storage().get_token("MEME").await.unwrap().unwrap().total_supply;
+
+        node.execute(&alice, "common", "hash_data", None, serde_json::json!({"data": "hello"}))
+            .await
+            .unwrap();
+
+        let app = app.read().await;
+        let native = app.storage().get_token("MEME").await.unwrap().unwrap();
+        assert_eq!(native.total_supply, supply_before - 25);
+        let treasury = Address::new(app.config().chain.treasury_address.clone());
+        let collected = app.storage().get_balance(&treasury, "MEME").await.unwrap().unwrap();
+        assert_eq!(collected.amount, 75);
+        let balance = app.storage().get_balance(alice.address(), "MEME").await.unwrap().unwrap();
+        assert_eq!(balance.amount, crate::testing::TEST_ACCOUNT_BALANCE - 100);
+    }
+
+    #[tokio::test]
+    async fn test_token_supply_endpoint() {
+        let mut config = Config::default();
+        config.chain.tx_fee = 100;
+        config.chain.fee_burn_bps = 2_500;
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let supply = |symbol: &str| {
+            let request = axum::http::Request::get(format!("/tokens/{}/supply", symbol))
+                .body(axum::body::Body::empty())
+                .unwrap();
+            send(node.app(), request)
+        };
+        let (_, body) = supply("MEME").await;
+        let native_before = body["data"]["total_supply"].as_u64().unwrap();
+
+        node.execute(&alice, "meme", "create_token", None, serde_json::json!({
+            "name": "Pepe",
+            "symbol": "PEPE",
+            "supply": 1_000_000,
+        }))
+        .await
+        .unwrap();
+        node.execute(&alice, "meme", "burn", None, serde_json::json!({"token": "PEPE", "amount": 250_000}))
+            .await
+            .unwrap();
+
+        let (status, body) = supply("PEPE").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"], serde_json::json!({
+            "total_supply": 750_000,
+            "circulating_supply": 750_000,
+            "burned": 250_000,
+            "treasury": 0,
+        }));
+
+        // Two fees of 100: a quarter of each burned, the rest to the treasury
+        let (_, body) = supply("MEME").await;
+        assert_eq!(body["data"]["burned"], 50);
+        assert_eq!(body["data"]["treasury"], 150);
+        assert_eq!(body["data"]["total_supply"].as_u64().unwrap(), native_before - 50);
+        assert_eq!(
+            body["data"]["circulating_supply"].as_u64().unwrap() + 150,
+            body["data"]["total_supply"].as_u64().unwrap()
+        );
+
+        let err = node
+            .execute(&alice, "meme", "burn", None, serde_json::json!({"token": "PEPE", "amount": 750_001}))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(_)));
+
+        let (status, _) = supply("NOPE").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_quote_matches_buy_fill() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let bob = crate::testing::TestAccount::from_seed(2);
+        node.execute(&alice, "meme", "create_token", None, serde_json::json!({
+            "name": "Pepe",
+            "symbol": "PEPE",
+            "supply": 1_000_000,
+        }))
+        .await
+        .unwrap();
+
+        let quote = |side: &str, amount: u64| {
+            let request = axum::http::Request::get(format!("/tokens/PEPE/quote?side={}&amount={}", side, amount))
+                .body(axum::body::Body::empty())
+                .unwrap();
+            send(node.app(), request)
+        };
+        let (status, body) = quote("buy", 10_000).await;
+        assert_eq!(status, StatusCode::OK);
+        let buy_quote = body["data"].clone();
+
+        // Quoting leaves state untouched
+        let app = node.app();
+        assert!(app.read().await.storage().get_balance(bob.address(), "PEPE").await.unwrap().is_none());
+
+        let fill = node
+            .execute(&bob, "meme", "buy", None, serde_json::json!({"token": "PEPE", "amount": 10_000}))
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(buy_quote["amount_out"], fill["amount"]);
+        assert_eq!(buy_quote["tax"], fill["tax"]);
+        assert_eq!(buy_quote["price_impact_bps"], 0);
+        let balance = app.read().await.storage().get_balance(bob.address(), "PEPE").await.unwrap().unwrap();
+        assert_eq!(balance.amount, buy_quote["amount_out"].as_u64().unwrap());
+
+        let (_, body) = quote("sell", 10_000).await;
+        assert_eq!(body["data"]["side"], "sell");
+        assert!(body["data"]["tax"].as_u64().unwrap() > 0);
+
+        let (status, _) = quote("hold", 10_000).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = quote("buy", 0).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let request = axum::http::Request::get("/tokens/NOPE/quote?side=buy&amount=1")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let (status, _) = send(node.app(), request).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_actions_rejected() {
+        let mut config = Config::default();
+        config.chain.disabled_actions.insert("meme".to_string(), vec!["buy".to_string(), "sell".to_string()]);
+        config.chain.enabled_actions.insert("common".to_string(), vec!["hash_data".to_string()]);
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+
+        for (module, action) in [("meme", "buy"), ("common", "validate_address")] {
+            let err = node
+                .execute(&alice, module, action, None, serde_json::json!({"token": "MEME", "amount": 1}))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, MemeChainError::ActionDisabled(_)));
+            let err = node.submit(&alice, module, action, None, serde_json::json!({})).await.unwrap_err();
+            assert!(matches!(err, MemeChainError::ActionDisabled(_)));
+        }
+
+        node.execute(&alice, "common", "hash_data", None, serde_json::json!({"data": "hello"}))
+            .await
+            .unwrap();
+        node.execute(&alice, "meme", "create_token", None, serde_json::json!({
+            "name": "Pepe",
+            "symbol": "PEPE",
+            "supply": 1_000,
+        }))
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_fee_burn_bps_validated() {
+        let mut config = Config::default();
+        config.chain.fee_burn_bps = 10_001;
+        assert!(config.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_timeout_height() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let timed_tx = |timeout_height: u64| {
+            let mut tx = Transaction::new(
+                "common".to_string(),
+                "hash_data".to_string(),
+                alice.address().clone(),
+                None,
+                serde_json::json!({"data": "hello"}),
+                chrono::Utc::now().timestamp(),
+            );
+            tx.timeout_height = Some(timeout_height);
+            tx.sign(crate::config::DEFAULT_CHAIN_ID, &alice.private_key_hex()).unwrap();
+            tx
+        };
+
+        // Within its timeout: the next block is height 1
+        node.app().write().await.process_transaction(timed_tx(1)).await.unwrap();
+        node.app().read().await.submit_transaction(timed_tx(1)).await.unwrap();
+        node.produce_block().await.unwrap();
+
+        let err = node.app().write().await.process_transaction(timed_tx(1)).await.unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        let err = node.app().read().await.submit_transaction(timed_tx(1)).await.unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+
+        // The timeout is covered by the signature
+        let mut tx = timed_tx(1);
+        tx.timeout_height = Some(100);
+        assert!(node.app().write().await.process_transaction(tx).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_version_endpoint() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let request = axum::http::Request::get("/version").body(axum::body::Body::empty()).unwrap();
+
+        let (status, body) = send(node.app(), request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(body["data"]["git_hash"], crate::GIT_HASH);
+    }
+
+    #[tokio::test]
+    async fn test_blob_endpoint() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let result = node
+            .execute(&alice, "nft", "create_collection", None, serde_json::json!({"name": "Apes"}))
+            .await
+            .unwrap();
+        let collection_id = result.data.unwrap()["collection_id"].as_str().unwrap().to_string();
+
+        let metadata = serde_json::json!({"description": "a".repeat(crate::storage::BLOB_THRESHOLD_BYTES * 2)});
+        let result = node
+            .execute(&alice, "nft", "mint", None, serde_json::json!({
+                "collection": collection_id,
+                "name": "Ape 1",
+                "metadata": metadata,
+            }))
+            .await
+            .unwrap();
+        let nft_id = result.data.unwrap()["nft_id"].as_str().unwrap().to_string();
+
+        let app = node.app();
+        let hash = {
+            let app = app.read().await;
+            let nft = app.storage().get_nft(&nft_id).await.unwrap().unwrap();
+            assert_eq!(nft.metadata, metadata);
+            nft.metadata_hash.unwrap()
+        };
+
+        let get = |uri: String| axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+        let (status, body) = send(app.clone(), get(format!("/blobs/{}", hash))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"], metadata);
+
+        let (status, _) = send(app.clone(), get(format!("/blobs/{}", "0".repeat(64)))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(app, get("/blobs/not-a-hash".to_string())).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_blocks_chain_hashes_and_seed_rng() {
+        use rand::Rng;
+
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let first = node.produce_block().await.unwrap();
+        let second = node.produce_block().await.unwrap();
+        assert!(first.previous_hash.is_empty());
+        assert!(!first.hash.is_empty());
+        assert_eq!(second.previous_hash, first.hash);
+
+        let app = node.app();
+        let app = app.read().await;
+        let expected = crate::consensus::block_rng(&second.hash, 3, 0).gen::<u64>();
+        assert_eq!(app.tx_rng(0).gen::<u64>(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_restart_resumes_from_stored_tip() {
+        use rand::Rng;
+
+        let node = crate::testing::TestNode::new().await.unwrap();
+        node.produce_block().await.unwrap();
+        let second = node.produce_block().await.unwrap();
+        let (config, storage) = {
+            let app = node.app();
+            let app = app.read().await;
+            (app.config().clone(), app.storage().clone())
+        };
+
+        // A node restarted over the same database continues the chain
+        let mut restarted = MemeChainApp::with_storage(config, storage).await.unwrap();
+        assert_eq!(restarted.block_height(), 2);
+        let expected = crate::consensus::block_rng(&second.hash, 3, 0).gen::<u64>();
+        assert_eq!(restarted.tx_rng(0).gen::<u64>(), expected);
+
+        let third = restarted.create_block().await.unwrap();
+        assert_eq!(third.height, 3);
+        assert_eq!(third.previous_hash, second.hash);
+        assert_eq!(restarted.storage().get_latest_block().await.unwrap().unwrap().hash, third.hash);
+    }
+
+    #[tokio::test]
+    async fn test_mock_clock_drives_transaction_expiry() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let clock = Arc::new(crate::clock::MockClock::new(1_700_000_000));
+        let app = node.app();
+        let mut app = app.write().await;
+        app.set_clock(clock.clone());
+
+        let signed_at = |timestamp: i64| {
+            let mut tx = Transaction::new(
+                "common".to_string(),
+                "hash_data".to_string(),
+                alice.address().clone(),
+                None,
+                serde_json::json!({"data": "hello"}),
+                timestamp,
+            );
+            tx.sign(crate::config::DEFAULT_CHAIN_ID, &alice.private_key_hex()).unwrap();
+            tx
+        };
+
+        let max_age = app.config().chain.tx_max_age_secs as i64;
+        app.process_transaction(signed_at(clock.now())).await.unwrap();
+
+        let tx = signed_at(clock.now());
+        clock.advance(max_age + 1);
+        let err = app.process_transaction(tx).await.unwrap_err();
+        assert!(err.to_string().contains("expired"));
+
+        // Blocks are stamped with the mock time too
+        let block = app.create_block().await.unwrap();
+        assert_eq!(block.timestamp, clock.now());
+    }
+
+    #[tokio::test]
+    async fn test_token_lock_endpoint() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        for symbol in ["PEPE", "DOGE"] {
+            node.execute(&alice, "meme", "create_token", None, serde_json::json!({
+                "name": symbol,
+                "symbol": symbol,
+                "supply": 1_000_000,
+            }))
+            .await
+            .unwrap();
+        }
+        node.execute(&alice, "meme", "lock_liquidity", None, serde_json::json!({
+            "token": "PEPE",
+            "duration_blocks": 10,
+        }))
+        .await
+        .unwrap();
+        node.produce_block().await.unwrap();
+        node.produce_block().await.unwrap();
+
+        let get = |uri: &str| axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+        let (status, body) = send(node.app(), get("/tokens/PEPE/lock")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"], serde_json::json!({
+            "locked": true,
+            "lock_start_block": 0,
+            "unlock_block": 10,
+            "remaining_blocks": 8,
+            "remaining_seconds": 48,
+        }));
+
+        let (status, body) = send(node.app(), get("/tokens/DOGE/lock")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"], serde_json::json!({
+            "locked": false,
+            "lock_start_block": null,
+            "unlock_block": null,
+            "remaining_blocks": 0,
+            "remaining_seconds": 0,
+        }));
+
+        let (status, _) = send(node.app(), get("/tokens/NOPE/lock")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_admin_storage_stats() {
+        let mut config = Config::default();
+        config.api.admin_token = Some("secret".to_string());
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+        let request = |token: &str| {
+            axum::http::Request::get("/admin/storage/stats")
+                .header("authorization", format!("Bearer {}", token))
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let (status, _) = send(node.app(), request("wrong")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, body) = send(node.app(), request("secret")).await;
+        assert_eq!(status, StatusCode::OK);
+        // Genesis seeds the native token and three funded balances
+        assert_eq!(body["data"]["keys_by_prefix"]["token"], 1);
+        assert_eq!(body["data"]["keys_by_prefix"]["balance"], 3);
+        assert!(body["data"]["total_keys"].as_u64().unwrap() >= 4);
+    }
+
+    #[tokio::test]
+    async fn test_block_reward_paid_to_proposer() {
+        let mut config = Config::default();
+        config.chain.block_reward = 50;
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+        let app = node.app();
+        let proposer = app.read().await.storage().get_proposer().await.unwrap().unwrap();
+        let native = app.read().await.config().chain.native_symbol.clone();
+
+        for height in 1..=3u64 {
+            node.produce_block().await.unwrap();
+            let app = app.read().await;
+            let balance = app.storage().get_balance(&proposer, &native).await.unwrap().unwrap();
+            assert_eq!(balance.amount, 50 * height);
+            assert_eq!(app.storage().total_inflation().await.unwrap(), 50 * height as u128);
+        }
+
+        let app = app.read().await;
+        let supply = app.storage().get_token(&native).await.unwrap().unwrap().total_supply;
+        assert_eq!(supply, 3 * crate::testing::TEST_ACCOUNT_BALANCE + 150);
+    }
+
+    #[tokio::test]
+    async fn test_block_reward_respects_supply_cap() {
+        let mut config = Config::default();
+        config.chain.block_reward = 50;
+        config.chain.total_token_cap = Some(3 * crate::testing::TEST_ACCOUNT_BALANCE + 70);
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+
+        for _ in 0..3 {
+            node.produce_block().await.unwrap();
+        }
+        let app = node.app();
+        let app = app.read().await;
+        assert_eq!(app.storage().total_inflation().await.unwrap(), 70);
+    }
+
+    #[tokio::test]
+    async fn test_out_of_order_nonces_wait_for_gap() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let app = node.app();
+        let nonced = |nonce: u64| {
+            let mut tx = Transaction::new(
+                "common".to_string(),
+                "hash_data".to_string(),
+                alice.address().clone(),
+                None,
+                serde_json::json!({"data": format!("nonce {}", nonce)}),
+                chrono::Utc::now().timestamp(),
+            );
+            tx.nonce = Some(nonce);
+            tx.sign(crate::config::DEFAULT_CHAIN_ID, &alice.private_key_hex()).unwrap();
+            tx
+        };
+
+        for nonce in 0..2 {
+            app.read().await.submit_transaction(nonced(nonce)).await.unwrap();
+        }
+        node.produce_block().await.unwrap();
+
+        // Nonce 3 waits for 2 instead of failing
+        app.read().await.submit_transaction(nonced(3)).await.unwrap();
+        assert_eq!(app.read().await.tx_pool_size().await, 0);
+        assert_eq!(app.read().await.future_pool_size().await, 1);
+
+        app.read().await.submit_transaction(nonced(2)).await.unwrap();
+        assert_eq!(app.read().await.tx_pool_size().await, 2);
+        assert_eq!(app.read().await.future_pool_size().await, 0);
+
+        let block = node.produce_block().await.unwrap();
+        let nonces: Vec<_> = block.transactions.iter().map(|tx| tx.nonce).collect();
+        assert_eq!(nonces, vec![Some(2), Some(3)]);
+        assert!(block.results.iter().all(|result| result.success));
+        assert_eq!(app.read().await.storage().get_nonce(alice.address()).await.unwrap(), 4);
+
+        // Used nonces are rejected outright
+        assert!(app.read().await.submit_transaction(nonced(1)).await.is_err());
+
+        // A gap that never fills is eventually dropped
+        let clock = Arc::new(crate::clock::MockClock::new(chrono::Utc::now().timestamp()));
+        app.write().await.set_clock(clock.clone());
+        app.read().await.submit_transaction(nonced(9)).await.unwrap();
+        clock.advance(app.read().await.config().chain.tx_max_age_secs as i64 + 1);
+        node.produce_block().await.unwrap();
+        assert_eq!(app.read().await.future_pool_size().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_action_still_consumes_nonce() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let app = node.app();
+        let nonced = |action: &str, nonce: u64| {
+            let mut tx = Transaction::new(
+                "common".to_string(),
+                action.to_string(),
+                alice.address().clone(),
+                None,
+                serde_json::json!({"data": format!("nonce {}", nonce)}),
+                chrono::Utc::now().timestamp(),
+            );
+            tx.nonce = Some(nonce);
+            tx.sign(crate::config::DEFAULT_CHAIN_ID, &alice.private_key_hex()).unwrap();
+            tx
+        };
+
+        app.read().await.submit_transaction(nonced("no_such_action", 0)).await.unwrap();
+        app.read().await.submit_transaction(nonced("hash_data", 1)).await.unwrap();
+        let block = node.produce_block().await.unwrap();
+
+        // The failed action keeps its nonce, so the next one still lands
+        assert_eq!(block.transactions.len(), 2);
+        assert!(!block.results[0].success);
+        assert!(block.results[1].success);
+        assert_eq!(app.read().await.storage().get_nonce(alice.address()).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_future_pool_bounded() {
+        use crate::clock::Clock;
+
+        let mut config = Config::default();
+        config.chain.max_queued_per_sender = 2;
+        config.chain.max_queued_transactions = 3;
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+        let clock = Arc::new(crate::clock::MockClock::new(chrono::Utc::now().timestamp()));
+        let app = node.app();
+        app.write().await.set_clock(clock.clone());
+        let nonced = |account: &crate::testing::TestAccount, nonce: u64| {
+            let mut tx = Transaction::new(
+                "common".to_string(),
+                "hash_data".to_string(),
+                account.address().clone(),
+                None,
+                serde_json::json!({"data": format!("nonce {}", nonce)}),
+                clock.now(),
+            );
+            tx.nonce = Some(nonce);
+            tx.sign(crate::config::DEFAULT_CHAIN_ID, &account.private_key_hex()).unwrap();
+            tx
+        };
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let bob = crate::testing::TestAccount::from_seed(2);
+        let carol = crate::testing::TestAccount::from_seed(3);
+
+        // One sender cannot queue more than its share
+        app.read().await.submit_transaction(nonced(&alice, 5)).await.unwrap();
+        app.read().await.submit_transaction(nonced(&alice, 6)).await.unwrap();
+        let err = app.read().await.submit_transaction(nonced(&alice, 7)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Validation(_)));
+        // Replacing a queued nonce does not count against the cap
+        app.read().await.submit_transaction(nonced(&alice, 6)).await.unwrap();
+
+        // Nor can all senders together exceed the global cap
+        app.read().await.submit_transaction(nonced(&bob, 5)).await.unwrap();
+        let err = app.read().await.submit_transaction(nonced(&carol, 5)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::ServiceUnavailable(_)));
+        assert_eq!(app.read().await.future_pool_size().await, 3);
+
+        // Stale entries are evicted on submission, freeing room
+        clock.advance(app.read().await.config().chain.tx_max_age_secs as i64 + 1);
+        app.read().await.submit_transaction(nonced(&carol, 5)).await.unwrap();
+        assert_eq!(app.read().await.future_pool_size().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body_rejected() {
+        use tower::ServiceExt;
+
+        let mut config = Config::default();
+        config.api.max_request_body_bytes = 1024;
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+        let api = node.app().read().await.config().api.clone();
+        let mint = |metadata: String, content_length: bool| {
+            let body = serde_json::json!({
+                "collection": "apes",
+                "name": "Ape 1",
+                "owner": "memechain1alice",
+                "metadata": {"description": metadata},
+            })
+            .to_string();
+            let mut request = axum::http::Request::post("/nft/mint").header("content-type", "application/json");
+            if content_length {
+                request = request.header("content-length", body.len());
+            }
+            request.body(axum::body::Body::from(body)).unwrap()
+        };
+
+        // Rejected up front from Content-Length, and while reading without it
+        for content_length in [true, false] {
+            let response = api_router(node.app(), &api)
+                .oneshot(mint("a".repeat(2048), content_length))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        }
+
+        let response = api_router(node.app(), &api).oneshot(mint("small".to_string(), true)).await.unwrap();
+        assert_ne!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_collection_trait_distribution() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let result = node
+            .execute(&alice, "nft", "create_collection", None, serde_json::json!({"name": "Apes"}))
+            .await
+            .unwrap();
+        let collection_id = result.data.unwrap()["collection_id"].as_str().unwrap().to_string();
+
+        let mut nft_ids = Vec::new();
+        for metadata in [
+            serde_json::json!({"traits": [{"trait_type": "hat", "value": "red"}, {"trait_type": "eyes", "value": "laser"}]}),
+            serde_json::json!({"traits": [{"trait_type": "hat", "value": "red"}, {"trait_type": "level", "value": 3}]}),
+            serde_json::json!({"traits": [{"trait_type": "hat", "value": "blue"}, {"value": "orphan"}]}),
+            serde_json::json!({"rarity": "common"}),
+        ] {
+            let result = node
+                .execute(&alice, "nft", "mint", None, serde_json::json!({
+                    "collection": collection_id,
+                    "name": "Ape",
+                    "metadata": metadata,
+                }))
+                .await
+                .unwrap();
+            nft_ids.push(result.data.unwrap()["nft_id"].as_str().unwrap().to_string());
+        }
+
+        let get = |uri: String| axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+        let uri = format!("/collections/{}/traits", collection_id);
+        let (status, body) = send(node.app(), get(uri.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"], serde_json::json!({
+            "hat": {"red": 2, "blue": 1},
+            "eyes": {"laser": 1},
+            "level": {"3": 1},
+        }));
+
+        // Metadata updates and burns keep the counts in step
+        node.execute(&alice, "nft", "update_metadata", None, serde_json::json!({
+            "nft_id": nft_ids[0],
+            "metadata": {"traits": [{"trait_type": "hat", "value": "blue"}]},
+        }))
+        .await
+        .unwrap();
+        node.execute(&alice, "nft", "burn", None, serde_json::json!({"nft_id": nft_ids[1]}))
+            .await
+            .unwrap();
+        let (_, body) = send(node.app(), get(uri)).await;
+        assert_eq!(body["data"], serde_json::json!({"hat": {"blue": 2}}));
+
+        let (status, _) = send(node.app(), get("/collections/missing/traits".to_string())).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_collection_nfts_pagination() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let result = node
+            .execute(&alice, "nft", "create_collection", None, serde_json::json!({"name": "Apes"}))
+            .await
+            .unwrap();
+        let collection_id = result.data.unwrap()["collection_id"].as_str().unwrap().to_string();
+        for i in 0..5 {
+            node.execute(&alice, "nft", "mint", None, serde_json::json!({
+                "collection": collection_id,
+                "name": format!("Ape {}", i),
+            }))
+            .await
+            .unwrap();
+        }
+
+        let get = |uri: String| axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+        let mut seen = Vec::new();
+        for (offset, expected) in [(0, 2), (2, 2), (4, 1)] {
+            let uri = format!("/collections/{}/nfts?limit=2&offset={}", collection_id, offset);
+            let (status, body) = send(node.app(), get(uri)).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(body["data"]["total"], 5);
+            let nfts = body["data"]["nfts"].as_array().unwrap();
+            assert_eq!(nfts.len(), expected);
+            seen.extend(nfts.iter().map(|nft| nft["id"].as_str().unwrap().to_string()));
+        }
+        let mut sorted = seen.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted, seen);
+        assert_eq!(seen.len(), 5);
+
+        let (status, _) = send(node.app(), get("/collections/missing/nfts".to_string())).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_memo_round_trip() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let bob = crate::testing::TestAccount::from_seed(2);
+        let native = node.app().read().await.config().chain.native_symbol.clone();
+        let transfer = |memo: String| {
+            let mut tx = alice
+                .transaction("meme", "transfer", Some(bob.address().clone()), serde_json::json!({"token": native, "amount": 10}))
+                .unwrap();
+            tx.memo = Some(memo);
+            tx.sign(crate::config::DEFAULT_CHAIN_ID, &alice.private_key_hex()).unwrap();
+            tx
+        };
+
+        // The memo is signed: changing it invalidates the signature
+        let mut tampered = transfer("deposit 42".to_string());
+        tampered.memo = Some("deposit 43".to_string());
+        assert!(node.app().read().await.submit_transaction(tampered).await.is_err());
+
+        let max_len = node.app().read().await.config().chain.max_memo_len;
+        let err = node.app().read().await.submit_transaction(transfer("x".repeat(max_len + 1))).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Validation(_)));
+
+        let hash = node.app().read().await.submit_transaction(transfer("deposit 42".to_string())).await.unwrap();
+        node.produce_block().await.unwrap();
+
+        let request = axum::http::Request::get(format!("/tx/{}", hash)).body(axum::body::Body::empty()).unwrap();
+        let (status, body) = send(node.app(), request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["memo"], "deposit 42");
+        assert_eq!(body["data"]["height"], 1);
+        assert_eq!(body["data"]["result"]["success"], true);
+        assert_eq!(body["data"]["result"]["data"]["memo"], "deposit 42");
+
+        let request = axum::http::Request::get("/tx/unknown").body(axum::body::Body::empty()).unwrap();
+        let (status, _) = send(node.app(), request).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    async fn post_create_token(body: serde_json::Value) -> (StatusCode, serde_json::Value) {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let request = axum::http::Request::post("/tokens/create")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+        send(node.app(), request).await
+    }
+
+    #[tokio::test]
+    async fn test_create_token_missing_symbol_reports_field() {
+        let (status, body) = post_create_token(serde_json::json!({
+            "name": "Doge",
+            "supply": 1000,
+            "creator": "memechain1alice",
+        }))
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["success"], false);
+        assert_eq!(body["errors"], serde_json::json!([{"field": "symbol", "error": "is required"}]));
+    }
+
+    #[tokio::test]
+    async fn test_create_token_rejects_non_positive_supply() {
+        for supply in [serde_json::json!(-5), serde_json::json!(0), serde_json::json!("-1.5")] {
+            let (status, body) = post_create_token(serde_json::json!({
+                "name": "Doge",
+                "symbol": "DOGE",
+                "supply": supply,
+                "creator": "memechain1alice",
+            }))
+            .await;
+
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["errors"], serde_json::json!([{"field": "supply", "error": "must be > 0"}]));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_node_id_stable_across_restarts() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.storage.db_path = temp_dir.path().join("db").to_str().unwrap().to_string();
+        let status = || axum::http::Request::get("/status").body(axum::body::Body::empty()).unwrap();
+
+        let first = {
+            let app = Arc::new(RwLock::new(MemeChainApp::new(config.clone()).await.unwrap()));
+            let (code, body) = send(app, status()).await;
+            assert_eq!(code, StatusCode::OK);
+            body["data"]["node_id"].as_str().unwrap().to_string()
+        };
+        assert!(!first.is_empty());
+
+        let app = Arc::new(RwLock::new(MemeChainApp::new(config.clone()).await.unwrap()));
+        let (_, body) = send(app, status()).await;
+        assert_eq!(body["data"]["node_id"], first);
+
+        // A configured ID takes precedence
+        config.network.node_id = Some("validator-1".to_string());
+        let app = MemeChainApp::new(config).await.unwrap();
+        assert_eq!(app.node_id(), "validator-1");
+    }
+
+    #[tokio::test]
+    async fn test_admin_mempool_evict_and_clear() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.storage.db_path = temp_dir.path().join("db").to_str().unwrap().to_string();
+        config.api.admin_token = Some("secret".to_string());
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let transfer = |nonce: u64| {
+            let mut tx = alice
+                .transaction("meme", "transfer", Some(Address::from("memechain1carol")), serde_json::json!({"token": "MEME", "amount": 1}))
+                .unwrap();
+            tx.nonce = Some(nonce);
+            tx.sign(crate::config::DEFAULT_CHAIN_ID, &alice.private_key_hex()).unwrap();
+            tx
+        };
+        let admin = |method: &str, path: &str| {
+            axum::http::Request::builder()
+                .method(method)
+                .uri(path)
+                .header("authorization", "Bearer secret")
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let app = Arc::new(RwLock::new(MemeChainApp::new(config.clone()).await.unwrap()));
+        let first = app.read().await.submit_transaction(transfer(0)).await.unwrap();
+        let second = app.read().await.submit_transaction(transfer(1)).await.unwrap();
+
+        let unauthorized = axum::http::Request::get("/admin/mempool").body(axum::body::Body::empty()).unwrap();
+        let (status, _) = send(app.clone(), unauthorized).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, body) = send(app.clone(), admin("GET", "/admin/mempool")).await;
+        assert_eq!(status, StatusCode::OK);
+        let hashes: Vec<&str> = body["data"].as_array().unwrap().iter().map(|e| e["hash"].as_str().unwrap()).collect();
+        assert_eq!(hashes, vec![first.as_str(), second.as_str()]);
+
+        // Evicting the first parks the second until nonce 0 is resubmitted
+        let (status, _) = send(app.clone(), admin("DELETE", &format!("/admin/mempool/{}", first))).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(app.clone(), admin("DELETE", &format!("/admin/mempool/{}", first))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (_, body) = send(app.clone(), admin("GET", "/admin/mempool")).await;
+        assert_eq!(body["data"].as_array().unwrap().len(), 1);
+        assert_eq!(body["data"][0]["hash"], second);
+        assert_eq!(body["data"][0]["queued"], true);
+        assert_eq!(app.read().await.tx_pool_size().await, 0);
+        assert!(app.read().await.storage().pooled_transactions().await.unwrap().is_empty());
+
+        let (status, body) = send(app.clone(), admin("DELETE", "/admin/mempool")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"], 1);
+        assert!(app.read().await.mempool_entries().await.is_empty());
+
+        // Nothing comes back after a restart
+        drop(app);
+        let app = MemeChainApp::new(config).await.unwrap();
+        assert_eq!(app.tx_pool_size().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_slow_storage_times_out_with_503() {
+        let backend = Arc::new(FailingBackend {
+            inner: crate::storage::MemoryBackend::new(),
+            fail_writes: std::sync::atomic::AtomicBool::new(false),
+            fail_scans: std::sync::atomic::AtomicBool::new(false),
+            stall_reads: std::sync::atomic::AtomicBool::new(false),
+            batch_writes: std::sync::atomic::AtomicU64::new(0),
+        });
+        let storage = Storage::from_backend(backend.clone()).with_limits(50, 4);
+        let app = MemeChainApp::with_storage(memory_config(), storage).await.unwrap();
+        let app = Arc::new(RwLock::new(app));
+        let lock = || axum::http::Request::get("/tokens/MEME/lock").body(axum::body::Body::empty()).unwrap();
+
+        let (status, _) = send(app.clone(), lock()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        backend.stall_reads.store(true, std::sync::atomic::Ordering::SeqCst);
+        let started = std::time::Instant::now();
+        let (status, body) = send(app.clone(), lock()).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body["error"].as_str().unwrap().contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_validate_address_endpoint() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let check = |addr: String| {
+            let app = node.app();
+            async move {
+                let request = axum::http::Request::get(format!("/address/{}/validate", addr))
+                    .body(axum::body::Body::empty())
+                    .unwrap();
+                let (status, body) = send(app, request).await;
+                assert_eq!(status, StatusCode::OK);
+                body["data"].clone()
+            }
+        };
+        let valid = Address::from_public_key(&[7u8; 32]).to_string();
+
+        let data = check(valid.to_uppercase()).await;
+        assert_eq!(data["is_valid"], true);
+        assert_eq!(data["checksum_ok"], true);
+        assert_eq!(data["normalized"], valid);
+
+        let data = check("memechain1short".to_string()).await;
+        assert_eq!(data["is_valid"], false);
+        assert!(data["normalized"].is_null());
+
+        // Right length and prefix, but not a hex key hash, or in mixed case
+        let mangled = format!("{}zz", &valid[..40]);
+        let data = check(mangled).await;
+        assert_eq!(data["is_valid"], false);
+        assert_eq!(data["checksum_ok"], false);
+        let mixed = format!("{}{}", &valid[..20], valid[20..].to_uppercase());
+        assert_eq!(check(mixed).await["checksum_ok"], false);
+    }
+
+    #[tokio::test]
+    async fn test_create_token_invalidates_cached_token_list() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let list = || axum::http::Request::get("/tokens").body(axum::body::Body::empty()).unwrap();
+
+        let (_, body) = send(node.app(), list()).await;
+        let before = body["data"].as_array().unwrap().len();
+        let (_, body) = send(node.app(), list()).await;
+        assert_eq!(body["data"].as_array().unwrap().len(), before);
+
+        node.execute(&alice, "meme", "create_token", None, serde_json::json!({
+            "name": "Pepe",
+            "symbol": "PEPE",
+            "supply": 1_000_000,
+        }))
+        .await
+        .unwrap();
+        let (_, body) = send(node.app(), list()).await;
+        let tokens = body["data"].as_array().unwrap();
+        assert_eq!(tokens.len(), before + 1);
+        assert!(tokens.iter().any(|token| token["symbol"] == "PEPE"));
+    }
+
+    #[test]
+    fn test_error_status_distinguishes_module_errors() {
+        let status = |err: MemeChainError| error_status(&err);
+        assert_eq!(status(MemeError::InsufficientBalance("x".into()).into()), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(status(CommonError::InvalidAmount("x".into()).into()), StatusCode::BAD_REQUEST);
+        assert_eq!(status(MemeError::TokenNotFound("x".into()).into()), StatusCode::NOT_FOUND);
+        assert_eq!(status(NftError::CollectionExists("x".into()).into()), StatusCode::CONFLICT);
+        assert_eq!(status(NftError::Unauthorized("x".into()).into()), StatusCode::FORBIDDEN);
+        assert_eq!(status(StorageError::Timeout("x".into()).into()), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(status(StorageError::WriteFailed("x".into()).into()), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_write_errors_map_to_status() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let transfer = |token: &str| {
+            axum::http::Request::post("/transfer")
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(serde_json::json!({
+                    "from": "memechain1alice",
+                    "to": "memechain1bob",
+                    "amount": "1.5",
+                    "token": token,
+                }).to_string()))
+                .unwrap()
+        };
+
+        // The decimal amount is resolved against a token that doesn't exist
+        let (status, body) = send(node.app(), transfer("NOPE")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["error"].as_str().unwrap().contains("NOPE"));
+
+        // Unsigned API writes are rejected as bad input rather than a server error
+        let (status, body) = send(node.app(), transfer("MEME")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["success"], false);
+    }
+
+    #[tokio::test]
+    async fn test_large_genesis_is_written_in_chunks() {
+        use std::sync::atomic::Ordering;
+
+        let backend = Arc::new(FailingBackend {
+            inner: crate::storage::MemoryBackend::new(),
+            fail_writes: std::sync::atomic::AtomicBool::new(false),
+            fail_scans: std::sync::atomic::AtomicBool::new(false),
+            stall_reads: std::sync::atomic::AtomicBool::new(false),
+            batch_writes: std::sync::atomic::AtomicU64::new(0),
+        });
+        let app = MemeChainApp::with_storage(memory_config(), Storage::from_backend(backend.clone()))
+            .await
+            .unwrap();
+        let mut genesis = GenesisConfig::new("test-chain".to_string(), "validator".to_string());
+        genesis.accounts = (0..1000)
+            .map(|i| crate::config::Account {
+                address: format!("memechain1account{}", i),
+                balance: i + 1,
+                name: format!("account {}", i),
+            })
+            .collect();
+
+        let before = backend.batch_writes.load(Ordering::SeqCst);
+        app.apply_genesis(&genesis).await.unwrap();
+        let writes = backend.batch_writes.load(Ordering::SeqCst) - before;
+
+        // Two keys per balance, flushed every GENESIS_MAX_BATCH_KEYS keys
+        assert!(writes <= 3, "{} batch writes", writes);
+        for i in 0..1000u64 {
+            let address = Address::new(format!("memechain1account{}", i));
+            let balance = app.storage().get_balance(&address, "MEME").await.unwrap().unwrap();
+            assert_eq!(balance.amount, i + 1);
+        }
+        let token = app.storage().get_token("MEME").await.unwrap().unwrap();
+        assert_eq!(token.total_supply, 1000 * 1001 / 2);
+    }
+
+    #[tokio::test]
+    async fn test_min_fee_floor() {
+        let mut config = Config::default();
+        config.chain.min_fee = 5;
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let app = node.app();
+        let data = serde_json::json!({"data": "hello"});
+
+        let free = alice.transaction("common", "hash_data", None, data.clone()).unwrap();
+        let err = app.read().await.submit_transaction(free).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Validation(_)));
+        assert!(err.to_string().contains("below the minimum of 5 MEME"));
+
+        let mut paid = Transaction::new("common".to_string(), "hash_data".to_string(), alice.address().clone(), None, data, chrono::Utc::now().timestamp());
+        paid.fee = Some(5);
+        paid.sign(crate::config::DEFAULT_CHAIN_ID, &alice.private_key_hex()).unwrap();
+        app.read().await.submit_transaction(paid).await.unwrap();
+        node.produce_block().await.unwrap();
+
+        let app = app.read().await;
+        let balance = app.storage().get_balance(alice.address(), "MEME").await.unwrap().unwrap();
+        assert_eq!(balance.amount, crate::testing::TEST_ACCOUNT_BALANCE - 5);
+    }
+
+    #[tokio::test]
+    async fn test_empty_blocks_suppressed() {
+        let mut config = Config::default();
+        config.consensus.produce_empty_blocks = false;
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let app = node.app();
+        let now = app.read().await.clock().now();
+
+        assert!(app.write().await.produce_block_at(now).await.unwrap().is_none());
+        assert_eq!(app.read().await.block_height(), 0);
+
+        node.submit(&alice, "common", "hash_data", None, serde_json::json!({"data": "hello"}))
+            .await
+            .unwrap();
+        let block = app.write().await.produce_block_at(now).await.unwrap().unwrap();
+        assert_eq!(block.height, 1);
+        assert_eq!(block.transactions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_burn_to_redeem() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let mut collections = Vec::new();
+        for redeemable in [true, false] {
+            let result = node
+                .execute(&alice, "nft", "create_collection", None, serde_json::json!({
+                    "name": "Sneakers",
+                    "redeemable": redeemable,
+                }))
+                .await
+                .unwrap();
+            collections.push(result.data.unwrap()["collection_id"].as_str().unwrap().to_string());
+        }
+        let mut nft_ids = Vec::new();
+        for collection_id in &collections {
+            let result = node
+                .execute(&alice, "nft", "mint", None, serde_json::json!({"collection": collection_id, "name": "Pair #1"}))
+                .await
+                .unwrap();
+            nft_ids.push(result.data.unwrap()["nft_id"].as_str().unwrap().to_string());
+        }
+        let burn = |nft_id: &str| serde_json::json!({
+            "nft_id": nft_id,
+            "redemption_data": {"size": 42, "ship_to": "ref-123"},
+        });
+
+        // Redemption data is held to the metadata size limit
+        let max_size = Config::default().chain.max_metadata_size;
+        let oversized = serde_json::json!({
+            "nft_id": nft_ids[0],
+            "redemption_data": {"ship_to": "x".repeat(max_size)},
+        });
+        let err = node.execute(&alice, "nft", "burn", None, oversized).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::InvalidMetadata(_)))));
+
+        let result = node.execute(&alice, "nft", "burn", None, burn(&nft_ids[0])).await.unwrap();
+        assert_eq!(result.data.unwrap()["redeemed"], true);
+
+        let get = |uri: String| axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+        let (status, body) = send(node.app(), get(format!("/redemptions/{}", nft_ids[0]))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["redeemed_by"], alice.address().to_string());
+        assert_eq!(body["data"]["collection_id"], collections[0]);
+        assert_eq!(body["data"]["redemption_data"]["ship_to"], "ref-123");
+
+        // The NFT is gone, so it cannot be redeemed twice
+        let err = node.execute(&alice, "nft", "burn", None, burn(&nft_ids[0])).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::NftNotFound(_)))));
+
+        // Collections must opt in
+        let err = node.execute(&alice, "nft", "burn", None, burn(&nft_ids[1])).await.unwrap_err();
+        assert!(err.to_string().contains("does not offer redemptions"));
+        let (status, _) = send(node.app(), get(format!("/redemptions/{}", nft_ids[1]))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_tx_signed_for_other_chain_rejected() {
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let tx = alice
+            .transaction_on("chain-a", "common", "hash_data", None, serde_json::json!({"data": "hello"}))
+            .unwrap();
+
+        let mut config = Config::default();
+        config.chain.chain_id = "chain-b".to_string();
+        let other = crate::testing::TestNode::with_config(config.clone()).await.unwrap();
+        let err = other.app().read().await.submit_transaction(tx.clone()).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MemeChainError::Module(ModuleError::Common(CommonError::InvalidSignature(_)))
+        ));
+
+        config.chain.chain_id = "chain-a".to_string();
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+        node.app().read().await.submit_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_await_receipt() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let get = |uri: String| axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+        let hash = node
+            .submit(&alice, "common", "hash_data", None, serde_json::json!({"data": "hello"}))
+            .await
+            .unwrap();
+
+        // The long poll is answered by the block that includes the transaction
+        let waiter = tokio::spawn(send(node.app(), get(format!("/tx/{}/await?timeout_ms=5000", hash))));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+        node.produce_block().await.unwrap();
+        let (status, body) = waiter.await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["tx_hash"], hash);
+        assert_eq!(body["data"]["height"], 1);
+        assert_eq!(body["data"]["result"]["success"], true);
+
+        // Already mined: answered straight from storage
+        let (status, body) = send(node.app(), get(format!("/tx/{}/await", hash))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["tx_hash"], hash);
+
+        let (status, body) = send(node.app(), get("/tx/unknown/await?timeout_ms=20".to_string())).await;
+        assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+        assert!(body["error"].as_str().unwrap().contains("not included within 20 ms"));
+    }
+
+    #[tokio::test]
+    async fn test_search_collection_by_metadata() {
+        let mut config = Config::default();
+        config.storage.indexed_metadata_keys = vec!["rarity".to_string(), "level".to_string()];
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let result = node
+            .execute(&alice, "nft", "create_collection", None, serde_json::json!({"name": "Frogs"}))
+            .await
+            .unwrap();
+        let collection_id = result.data.unwrap()["collection_id"].as_str().unwrap().to_string();
+        let mut nft_ids = Vec::new();
+        for (rarity, level) in [("rare", 1), ("common", 1), ("rare", 2)] {
+            let result = node
+                .execute(&alice, "nft", "mint", None, serde_json::json!({
+                    "collection": collection_id,
+                    "name": "Frog",
+                    "metadata": {"rarity": rarity, "level": level, "color": "green"},
+                }))
+                .await
+                .unwrap();
+            nft_ids.push(result.data.unwrap()["nft_id"].as_str().unwrap().to_string());
+        }
+
+        let get = |uri: String| axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+        let search = |key: &str, value: &str| get(format!("/collections/{}/search?key={}&value={}", collection_id, key, value));
+        let found = |body: &serde_json::Value| -> Vec<String> {
+            body["data"].as_array().unwrap().iter().map(|nft| nft["id"].as_str().unwrap().to_string()).collect()
+        };
+
+        let (status, body) = send(node.app(), search("rarity", "rare")).await;
+        assert_eq!(status, StatusCode::OK);
+        let mut expected = vec![nft_ids[0].clone(), nft_ids[2].clone()];
+        expected.sort();
+        assert_eq!(found(&body), expected);
+        // Numbers are matched by their JSON text
+        let (_, body) = send(node.app(), search("level", "2")).await;
+        assert_eq!(found(&body), vec![nft_ids[2].clone()]);
+
+        // Updates and burns keep the index in sync
+        node.execute(&alice, "nft", "update_metadata", None, serde_json::json!({
+            "nft_id": nft_ids[0],
+            "metadata": {"rarity": "common"},
+        }))
+        .await
+        .unwrap();
+        node.execute(&alice, "nft", "burn", None, serde_json::json!({"nft_id": nft_ids[2]})).await.unwrap();
+        let (_, body) = send(node.app(), search("rarity", "rare")).await;
+        assert!(found(&body).is_empty());
+        let (_, body) = send(node.app(), search("rarity", "common")).await;
+        assert_eq!(found(&body).len(), 2);
+        let (_, body) = send(node.app(), search("level", "1")).await;
+        assert_eq!(found(&body), vec![nft_ids[1].clone()]);
+
+        let (status, _) = send(node.app(), search("color", "green")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(node.app(), get("/collections/missing/search?key=rarity&value=rare".to_string())).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_chain_id_header_guards_writes() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let tx = alice
+            .transaction("common", "hash_data", None, serde_json::json!({"data": "hello"}))
+            .unwrap();
+        let submit = |chain_id: &str| {
+            axum::http::Request::post("/rpc")
+                .header("content-type", "application/json")
+                .header(CHAIN_ID_HEADER, chain_id)
+                .body(axum::body::Body::from(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "tx_submit",
+                    "params": [tx],
+                }).to_string()))
+                .unwrap()
+        };
+
+        let (status, body) = send(node.app(), submit("other-chain")).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(body["error"]["message"].as_str().unwrap().contains("other-chain"));
+        assert_eq!(node.app().read().await.tx_pool_size().await, 0);
+
+        let (status, body) = send(node.app(), submit(crate::config::DEFAULT_CHAIN_ID)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["result"]["hash"], tx.hash());
+
+        // REST writes are guarded the same way
+        let transfer = |chain_id: &str| {
+            axum::http::Request::post("/transfer")
+                .header("content-type", "application/json")
+                .header(CHAIN_ID_HEADER, chain_id)
+                .body(axum::body::Body::from(serde_json::json!({
+                    "from": alice.address().to_string(),
+                    "to": "memechain1bob",
+                    "amount": 1,
+                    "token": "MEME",
+                }).to_string()))
+                .unwrap()
+        };
+        let (status, _) = send(node.app(), transfer("other-chain")).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        // A matching header passes the guard; the unsigned write then fails validation
+        let (status, _) = send(node.app(), transfer(crate::config::DEFAULT_CHAIN_ID)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_list_blocks_paginates_from_tip() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let mut hashes = Vec::new();
+        for i in 0..5 {
+            node.submit(&alice, "common", "hash_data", None, serde_json::json!({"data": i})).await.unwrap();
+            hashes.push(node.produce_block().await.unwrap().hash);
+        }
+        let get = |uri: &str| axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+        let heights = |body: &serde_json::Value| -> Vec<u64> {
+            body["data"].as_array().unwrap().iter().map(|block| block["height"].as_u64().unwrap()).collect()
+        };
+
+        let (status, body) = send(node.app(), get("/blocks?limit=2")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(heights(&body), vec![5, 4]);
+        assert_eq!(body["data"][0]["hash"], hashes[4]);
+        assert_eq!(body["data"][0]["tx_count"], 1);
+        assert!(body["data"][0]["timestamp"].as_i64().unwrap() > 0);
+
+        let (_, body) = send(node.app(), get("/blocks?limit=2&before=4")).await;
+        assert_eq!(heights(&body), vec![3, 2]);
+        let (_, body) = send(node.app(), get("/blocks?limit=2&before=2")).await;
+        assert_eq!(heights(&body), vec![1]);
+        let (_, body) = send(node.app(), get("/blocks?before=1")).await;
+        assert!(heights(&body).is_empty());
+
+        // `before` past the tip starts at the tip; the limit is capped
+        let (_, body) = send(node.app(), get("/blocks?before=100&limit=100000")).await;
+        assert_eq!(heights(&body), vec![5, 4, 3, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_holder_snapshot_at_past_height() {
+        let mut config = Config::default();
+        config.chain.holder_snapshot_interval = 2;
+        config.chain.holder_snapshot_tokens = vec!["MEME".to_string()];
+        config.chain.holder_snapshot_retention = 2;
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let bob = crate::testing::TestAccount::from_seed(2);
+        let carol = Address::new("memechain1carol".to_string());
+        let transfer = |amount: u64| serde_json::json!({"token": "MEME", "amount": amount});
+
+        node.submit(&alice, "meme", "transfer", Some(bob.address().clone()), transfer(100)).await.unwrap();
+        node.produce_block().await.unwrap();
+        node.submit(&alice, "meme", "transfer", Some(carol.clone()), transfer(50)).await.unwrap();
+        node.produce_block().await.unwrap();
+        // Moves after the snapshot do not change it
+        node.submit(&bob, "meme", "transfer", Some(carol.clone()), transfer(30)).await.unwrap();
+        node.produce_block().await.unwrap();
+
+        let get = |uri: &str| axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+        let (status, body) = send(node.app(), get("/tokens/MEME/snapshot?height=2")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["height"], 2);
+        let holders = body["data"]["holders"].as_array().unwrap();
+        let amount_of = |address: &Address| {
+            holders.iter().find(|holder| holder["address"] == address.to_string()).map(|holder| holder["amount"].as_u64().unwrap())
+        };
+        assert_eq!(amount_of(bob.address()), Some(crate::testing::TEST_ACCOUNT_BALANCE + 100));
+        assert_eq!(amount_of(&carol), Some(50));
+
+        let app = node.app();
+        let current = app.read().await.storage().get_balance(&carol, "MEME").await.unwrap().unwrap();
+        assert_eq!(current.amount, 80);
+
+        let (status, _) = send(node.app(), get("/tokens/MEME/snapshot?height=3")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, body) = send(node.app(), get("/tokens/MEME/snapshot?height=4")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["error"].as_str().unwrap().contains("No snapshot at height 4"));
+        let (status, _) = send(node.app(), get("/tokens/NOPE/snapshot?height=2")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // Only the two most recent snapshots are kept
+        for _ in 0..3 {
+            node.produce_block().await.unwrap();
+        }
+        let (status, _) = send(node.app(), get("/tokens/MEME/snapshot?height=2")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        for height in [4, 6] {
+            let (status, _) = send(node.app(), get(&format!("/tokens/MEME/snapshot?height={}", height))).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_sheds_excess_requests() {
+        use tower::ServiceExt;
+
+        let mut config = Config::default();
+        config.api.max_concurrent_requests = 2;
+        let node = crate::testing::TestNode::with_config(config).await.unwrap();
+        let api = node.app().read().await.config().api.clone();
+        let router = api_router(node.app(), &api);
+        let get = |uri: &str| axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+
+        // Long polls hold their slots until they time out
+        let requests: Vec<_> = (0..5)
+            .map(|_| tokio::spawn(router.clone().oneshot(get("/tx/unknown/await?timeout_ms=300"))))
+            .collect();
+        let mut statuses = Vec::new();
+        for request in requests {
+            statuses.push(request.await.unwrap().unwrap().status());
+        }
+        let count = |status: StatusCode| statuses.iter().filter(|s| **s == status).count();
+        assert_eq!(count(StatusCode::GATEWAY_TIMEOUT), 2);
+        assert_eq!(count(StatusCode::SERVICE_UNAVAILABLE), 3);
+
+        // Slots are released once requests finish
+        let response = router.oneshot(get("/health")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_block_rejects_bad_signature_found_by_batch() {
+        let node = crate::testing::TestNode::new().await.unwrap();
+        let alice = crate::testing::TestAccount::from_seed(1);
+        let mut txs: Vec<Transaction> = (0..20)
+            .map(|i| alice.transaction("common", "hash_data", None, serde_json::json!({"data": i})).unwrap())
+            .collect();
+        // Slipped past admission, e.g. relayed by a faulty peer
+        txs[7].data = serde_json::json!({"data": "tampered"});
+        let app = node.app();
+        app.read().await.tx_pool.write().await.extend(txs);
+
+        let block = node.produce_block().await.unwrap();
+        let failed: Vec<usize> = (0..block.results.len()).filter(|i| !block.results[*i].success).collect();
+        assert_eq!(failed, vec![7]);
+        assert!(block.results[7].error.as_ref().unwrap().contains("Signature verification failed"));
     }
 } 
\ No newline at end of file
@@ -0,0 +1,555 @@
+//! Binary encoding used by the bincode storage codec.
+//!
+//! bincode cannot decode types that need `deserialize_any` (such as the
+//! `serde_json::Value` in NFT metadata) or that skip `None` fields, so
+//! values are converted to `BinValue`, a self-describing mirror of the JSON
+//! data model, which bincode then encodes. The conversion goes directly
+//! between a value and its `BinValue` through the serializer and
+//! deserializer below.
+
+use bincode::Options;
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::{ser, Deserialize, Serialize};
+use std::fmt;
+
+/// Error converting a value to or from its `BinValue`
+#[derive(Debug)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// Self-describing value tree that bincode can round-trip
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BinValue {
+    Null,
+    Bool(bool),
+    U64(u64),
+    I64(i64),
+    F64(f64),
+    String(String),
+    Array(Vec<BinValue>),
+    Object(Vec<(String, BinValue)>),
+}
+
+/// Encode `value` as bincode
+pub fn to_bytes<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    let tree = value.serialize(BinValueSerializer)?;
+    bincode::serialize(&tree).map_err(|e| Error(e.to_string()))
+}
+
+/// Decode a value written by `to_bytes`. Trailing bytes are rejected, so
+/// data in another format does not decode by accident.
+pub fn from_slice<T: DeserializeOwned>(data: &[u8]) -> Result<T, Error> {
+    let tree: BinValue = bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .reject_trailing_bytes()
+        .deserialize(data)
+        .map_err(|e| Error(e.to_string()))?;
+    T::deserialize(tree)
+}
+
+impl BinValue {
+    fn unexpected(&self) -> de::Unexpected<'_> {
+        match self {
+            Self::Null => de::Unexpected::Unit,
+            Self::Bool(b) => de::Unexpected::Bool(*b),
+            Self::U64(u) => de::Unexpected::Unsigned(*u),
+            Self::I64(i) => de::Unexpected::Signed(*i),
+            Self::F64(f) => de::Unexpected::Float(*f),
+            Self::String(s) => de::Unexpected::Str(s),
+            Self::Array(_) => de::Unexpected::Seq,
+            Self::Object(_) => de::Unexpected::Map,
+        }
+    }
+}
+
+/// Builds the `BinValue` of a value, with the same shapes `serde_json`
+/// gives it: non-negative integers are `U64`, non-finite floats are `Null`,
+/// enum variants are a string or a single-entry object
+struct BinValueSerializer;
+
+impl ser::Serializer for BinValueSerializer {
+    type Ok = BinValue;
+    type Error = Error;
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeVariant<SerializeArray>;
+    type SerializeMap = SerializeObject;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeVariant<SerializeObject>;
+
+    fn serialize_bool(self, v: bool) -> Result<BinValue, Error> {
+        Ok(BinValue::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<BinValue, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<BinValue, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<BinValue, Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<BinValue, Error> {
+        Ok(match u64::try_from(v) {
+            Ok(u) => BinValue::U64(u),
+            Err(_) => BinValue::I64(v),
+        })
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<BinValue, Error> {
+        match i64::try_from(v) {
+            Ok(i) => self.serialize_i64(i),
+            Err(_) => self.serialize_u128(u128::try_from(v).map_err(|_| Error(format!("{} is out of range", v)))?),
+        }
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<BinValue, Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<BinValue, Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<BinValue, Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<BinValue, Error> {
+        Ok(BinValue::U64(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<BinValue, Error> {
+        u64::try_from(v)
+            .map(BinValue::U64)
+            .map_err(|_| Error(format!("{} is out of range", v)))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<BinValue, Error> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<BinValue, Error> {
+        Ok(if v.is_finite() { BinValue::F64(v) } else { BinValue::Null })
+    }
+
+    fn serialize_char(self, v: char) -> Result<BinValue, Error> {
+        Ok(BinValue::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<BinValue, Error> {
+        Ok(BinValue::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<BinValue, Error> {
+        Ok(BinValue::Array(v.iter().map(|&b| BinValue::U64(b.into())).collect()))
+    }
+
+    fn serialize_none(self) -> Result<BinValue, Error> {
+        Ok(BinValue::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<BinValue, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<BinValue, Error> {
+        Ok(BinValue::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<BinValue, Error> {
+        Ok(BinValue::Null)
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<BinValue, Error> {
+        Ok(BinValue::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<BinValue, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<BinValue, Error> {
+        Ok(BinValue::Object(vec![(variant.to_string(), value.serialize(self)?)]))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, Error> {
+        Ok(SerializeArray(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SerializeArray, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeArray>, Error> {
+        Ok(SerializeVariant { variant, inner: self.serialize_seq(Some(len))? })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeObject, Error> {
+        Ok(SerializeObject { entries: Vec::with_capacity(len.unwrap_or(0)), next_key: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeObject, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeObject>, Error> {
+        Ok(SerializeVariant { variant, inner: self.serialize_map(Some(len))? })
+    }
+}
+
+struct SerializeArray(Vec<BinValue>);
+
+impl SerializeArray {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.0.push(value.serialize(BinValueSerializer)?);
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = BinValue;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<BinValue, Error> {
+        Ok(BinValue::Array(self.0))
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = BinValue;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<BinValue, Error> {
+        Ok(BinValue::Array(self.0))
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = BinValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<BinValue, Error> {
+        Ok(BinValue::Array(self.0))
+    }
+}
+
+struct SerializeObject {
+    entries: Vec<(String, BinValue)>,
+    next_key: Option<String>,
+}
+
+impl SerializeObject {
+    fn insert<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), Error> {
+        self.entries.push((key, value.serialize(BinValueSerializer)?));
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for SerializeObject {
+    type Ok = BinValue;
+    type Error = Error;
+
+    /// Keys become strings, as in JSON objects
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.next_key = Some(match key.serialize(BinValueSerializer)? {
+            BinValue::String(s) => s,
+            BinValue::U64(u) => u.to_string(),
+            BinValue::I64(i) => i.to_string(),
+            BinValue::Bool(b) => b.to_string(),
+            other => return Err(Error(format!("map key must be a string, got {}", other.unexpected()))),
+        });
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self.next_key.take().ok_or_else(|| Error("map value without a key".to_string()))?;
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<BinValue, Error> {
+        Ok(BinValue::Object(self.entries))
+    }
+}
+
+impl ser::SerializeStruct for SerializeObject {
+    type Ok = BinValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<BinValue, Error> {
+        Ok(BinValue::Object(self.entries))
+    }
+}
+
+/// A tuple or struct variant: its fields wrapped in a single-entry object
+/// keyed by the variant name
+struct SerializeVariant<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl SerializeVariant<SerializeArray> {
+    fn wrap(self) -> BinValue {
+        BinValue::Object(vec![(self.variant.to_string(), BinValue::Array(self.inner.0))])
+    }
+}
+
+impl SerializeVariant<SerializeObject> {
+    fn wrap(self) -> BinValue {
+        BinValue::Object(vec![(self.variant.to_string(), BinValue::Object(self.inner.entries))])
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeArray> {
+    type Ok = BinValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.inner.push(value)
+    }
+
+    fn end(self) -> Result<BinValue, Error> {
+        Ok(self.wrap())
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeObject> {
+    type Ok = BinValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.inner.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<BinValue, Error> {
+        Ok(self.wrap())
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for BinValue {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for BinValue {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Self::Null => visitor.visit_unit(),
+            Self::Bool(b) => visitor.visit_bool(b),
+            Self::U64(u) => visitor.visit_u64(u),
+            Self::I64(i) => visitor.visit_i64(i),
+            Self::F64(f) => visitor.visit_f64(f),
+            Self::String(s) => visitor.visit_string(s),
+            Self::Array(items) => {
+                let mut seq = de::value::SeqDeserializer::new(items.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Self::Object(entries) => {
+                let mut map = de::value::MapDeserializer::new(entries.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Self::Null => visitor.visit_none(),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self {
+            Self::String(variant) => visitor.visit_enum(variant_name(variant)),
+            Self::Object(mut entries) if entries.len() == 1 => {
+                let (variant, value) = entries.remove(0);
+                visitor.visit_enum(EnumDeserializer { variant, value })
+            }
+            other => Err(de::Error::invalid_type(other.unexpected(), &"an enum variant")),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+fn variant_name(variant: String) -> de::value::StringDeserializer<Error> {
+    variant.into_deserializer()
+}
+
+/// A variant with data: its name and the value it carries
+struct EnumDeserializer {
+    variant: String,
+    value: BinValue,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = BinValue;
+
+    fn variant_seed<S: de::DeserializeSeed<'de>>(self, seed: S) -> Result<(S::Value, BinValue), Error> {
+        Ok((seed.deserialize(variant_name(self.variant))?, self.value))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for BinValue {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self {
+            Self::Null => Ok(()),
+            other => Err(de::Error::invalid_type(other.unexpected(), &"a unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<S: de::DeserializeSeed<'de>>(self, seed: S) -> Result<S::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Point(i32, i32),
+        Rect { width: u32, height: u32 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        name: String,
+        count: u8,
+        delta: i64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        note: Option<String>,
+        shapes: Vec<Shape>,
+        extra: serde_json::Value,
+    }
+
+    #[test]
+    fn test_round_trip_matches_json_shape() {
+        let sample = Sample {
+            name: "ape".to_string(),
+            count: 7,
+            delta: -3,
+            note: None,
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(1.5),
+                Shape::Point(1, -1),
+                Shape::Rect { width: 2, height: 3 },
+            ],
+            extra: serde_json::json!({"attributes": [{"trait_type": "hat"}], "score": 2.5, "none": null}),
+        };
+
+        let bytes = to_bytes(&sample).unwrap();
+        assert_eq!(from_slice::<Sample>(&bytes).unwrap(), sample);
+        // Decoding as JSON data gives what `serde_json` would have produced
+        assert_eq!(from_slice::<serde_json::Value>(&bytes).unwrap(), serde_json::to_value(&sample).unwrap());
+    }
+
+    #[test]
+    fn test_rejects_other_formats() {
+        let json = serde_json::to_vec(&serde_json::json!({"name": "ape"})).unwrap();
+        assert!(from_slice::<serde_json::Value>(&json).is_err());
+
+        let mut bytes = to_bytes(&7u64).unwrap();
+        bytes.push(0);
+        assert!(from_slice::<u64>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+//! Time source used by the application and modules.
+//!
+//! Everything that reads the current time goes through a `Clock` so that
+//! expiry, rate limiting and timestamps can be driven deterministically in
+//! tests with a `MockClock`.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+/// Source of the current time
+pub trait Clock: Send + Sync {
+    /// Current Unix timestamp in seconds
+    fn now(&self) -> i64;
+}
+
+/// Shared handle to a clock
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Wall-clock time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        chrono::Utc::now().timestamp()
+    }
+}
+
+/// The default clock: wall-clock time
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// A clock that only moves when told to
+#[derive(Debug, Default)]
+pub struct MockClock {
+    now: AtomicI64,
+}
+
+impl MockClock {
+    /// Create a clock stopped at `now`
+    pub fn new(now: i64) -> Self {
+        Self {
+            now: AtomicI64::new(now),
+        }
+    }
+
+    /// Jump to `now`
+    pub fn set(&self, now: i64) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+
+    /// Move forward by `seconds`
+    pub fn advance(&self, seconds: i64) {
+        self.now.fetch_add(seconds, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> i64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock() {
+        let clock = MockClock::new(1_000);
+        assert_eq!(clock.now(), 1_000);
+        clock.advance(60);
+        assert_eq!(clock.now(), 1_060);
+        clock.set(5);
+        assert_eq!(clock.now(), 5);
+    }
+}
@@ -0,0 +1,235 @@
+//! Client-side support for the `memechain` CLI: loading signing keys and
+//! submitting signed transactions to a node.
+//!
+//! A key comes either from a key file (`--keyfile`) or from a keyring
+//! directory (`--keyring-dir`) holding one `<name>.json` file per key. Both use
+//! the same JSON format: `{"private_key": "<hex>"}`, optionally with the
+//! `address` it is expected to derive.
+
+use crate::error::{CommonError, NetworkError, Result};
+use crate::modules::common::Keypair;
+use crate::types::{Address, Transaction};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Key name used when `--key` is not given
+pub const DEFAULT_KEY_NAME: &str = "default";
+
+/// On-disk key format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyFile {
+    /// ed25519 private key (hex)
+    pub private_key: String,
+    /// Address the key should derive, checked when present
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+}
+
+/// Signs outgoing transactions with a loaded key
+pub struct Signer {
+    keypair: Keypair,
+}
+
+impl Signer {
+    /// Load the key selected by the CLI flags: `keyfile` wins, otherwise
+    /// `name` (or `default`) is looked up in `keyring_dir`
+    pub fn load(keyfile: Option<&Path>, keyring_dir: Option<&Path>, name: Option<&str>) -> Result<Self> {
+        match (keyfile, keyring_dir) {
+            (Some(path), _) => Self::from_keyfile(path),
+            (None, Some(dir)) => Self::from_keyring(dir, name.unwrap_or(DEFAULT_KEY_NAME)),
+            (None, None) => Err(CommonError::KeyNotFound(
+                "No signing key; pass --keyfile or --keyring-dir".to_string(),
+            ).into()),
+        }
+    }
+
+    /// Load a key from a JSON key file
+    pub fn from_keyfile(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            CommonError::KeyNotFound(format!("Cannot read key file {}: {}", path.display(), e))
+        })?;
+        let keyfile: KeyFile = serde_json::from_str(&content).map_err(|e| {
+            CommonError::InvalidPrivateKey(format!("Malformed key file {}: {}", path.display(), e))
+        })?;
+
+        let keypair = Keypair::from_private_key(&keyfile.private_key).map_err(|e| {
+            CommonError::InvalidPrivateKey(format!("Key file {}: {}", path.display(), e))
+        })?;
+        if let Some(address) = keyfile.address {
+            if address != keypair.address {
+                return Err(CommonError::InvalidPrivateKey(format!(
+                    "Key file {} declares address {} but its key derives {}",
+                    path.display(), address, keypair.address
+                )).into());
+            }
+        }
+
+        Ok(Self { keypair })
+    }
+
+    /// Load the key called `name` from a keyring directory
+    pub fn from_keyring(dir: &Path, name: &str) -> Result<Self> {
+        Self::from_keyfile(&keyring_path(dir, name)?)
+    }
+
+    /// Address transactions are sent from
+    pub fn address(&self) -> &Address {
+        &self.keypair.address
+    }
+
+    /// Build a transaction from this key's address and sign it for `chain_id`
+    pub fn transaction(
+        &self,
+        chain_id: &str,
+        module: &str,
+        action: &str,
+        to: Option<Address>,
+        data: serde_json::Value,
+    ) -> Result<Transaction> {
+        let mut tx = Transaction::new(
+            module.to_string(),
+            action.to_string(),
+            self.keypair.address.clone(),
+            to,
+            data,
+            chrono::Utc::now().timestamp(),
+        );
+        tx.sign(chain_id, &self.keypair.private_key)?;
+        Ok(tx)
+    }
+}
+
+/// Path of the key called `name` inside a keyring directory
+pub fn keyring_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    let valid = !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(CommonError::KeyNotFound(format!(
+            "Invalid key name {:?}; use letters, digits, '-' and '_'", name
+        )).into());
+    }
+    Ok(dir.join(format!("{}.json", name)))
+}
+
+/// Submit a signed transaction to a node's JSON-RPC endpoint and return its hash
+pub async fn submit_transaction(node_url: &str, tx: &Transaction) -> Result<String> {
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tx_submit",
+        "params": {"transaction": tx},
+    });
+    let url = format!("{}/rpc", node_url.trim_end_matches('/'));
+
+    let response: serde_json::Value = reqwest::Client::new()
+        .post(&url)
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| NetworkError::ConnectionFailed(format!("{}: {}", url, e)))?
+        .json()
+        .await
+        .map_err(|e| NetworkError::ConnectionFailed(format!("Invalid response from {}: {}", url, e)))?;
+
+    if let Some(error) = response.get("error") {
+        return Err(NetworkError::ConnectionFailed(format!(
+            "Node rejected transaction: {}", error["message"].as_str().unwrap_or("unknown error")
+        )).into());
+    }
+    response["result"]["hash"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| NetworkError::ConnectionFailed(format!("Missing transaction hash in response from {}", url)).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::MemeChainError;
+    use crate::error::ModuleError;
+    use crate::testing::{TestAccount, TestNode, TEST_ACCOUNT_BALANCE};
+    use tempfile::tempdir;
+
+    fn write_key(path: &Path, account: &TestAccount) {
+        let keyfile = KeyFile {
+            private_key: account.private_key_hex(),
+            address: Some(account.address().clone()),
+        };
+        std::fs::write(path, serde_json::to_string(&keyfile).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_load_keys() {
+        let dir = tempdir().unwrap();
+        let alice = TestAccount::from_seed(1);
+        write_key(&dir.path().join("alice.json"), &alice);
+
+        let signer = Signer::load(None, Some(dir.path()), Some("alice")).unwrap();
+        assert_eq!(signer.address(), alice.address());
+        let signer = Signer::load(Some(&dir.path().join("alice.json")), None, None).unwrap();
+        assert_eq!(signer.address(), alice.address());
+
+        let key_error = |result: Result<Signer>| match result {
+            Err(MemeChainError::Module(ModuleError::Common(err))) => err,
+            Err(other) => panic!("unexpected error: {}", other),
+            Ok(_) => panic!("expected an error"),
+        };
+        assert!(matches!(key_error(Signer::load(None, None, None)), CommonError::KeyNotFound(_)));
+        assert!(matches!(key_error(Signer::load(None, Some(dir.path()), None)), CommonError::KeyNotFound(_)));
+        assert!(matches!(key_error(Signer::from_keyring(dir.path(), "../alice")), CommonError::KeyNotFound(_)));
+
+        std::fs::write(dir.path().join("bad.json"), "not json").unwrap();
+        assert!(matches!(key_error(Signer::from_keyring(dir.path(), "bad")), CommonError::InvalidPrivateKey(_)));
+        std::fs::write(dir.path().join("short.json"), r#"{"private_key": "abcd"}"#).unwrap();
+        assert!(matches!(key_error(Signer::from_keyring(dir.path(), "short")), CommonError::InvalidPrivateKey(_)));
+
+        // A key file whose address does not match its key is rejected
+        let bob = TestAccount::from_seed(2);
+        let mismatched = KeyFile {
+            private_key: alice.private_key_hex(),
+            address: Some(bob.address().clone()),
+        };
+        std::fs::write(dir.path().join("mismatch.json"), serde_json::to_string(&mismatched).unwrap()).unwrap();
+        assert!(matches!(key_error(Signer::from_keyring(dir.path(), "mismatch")), CommonError::InvalidPrivateKey(_)));
+    }
+
+    #[tokio::test]
+    async fn test_signed_transfer_submitted_to_node() {
+        let node = TestNode::new().await.unwrap();
+        let port = node.spawn_api().await.unwrap();
+        let dir = tempdir().unwrap();
+        let alice = TestAccount::from_seed(1);
+        let bob = TestAccount::from_seed(2);
+        write_key(&dir.path().join("alice.json"), &alice);
+
+        let signer = Signer::from_keyring(dir.path(), "alice").unwrap();
+        let chain = node.app().read().await.config().chain.clone();
+        let (chain_id, native) = (chain.chain_id, chain.native_symbol);
+        let tx = signer
+            .transaction(&chain_id, "meme", "transfer", Some(bob.address().clone()), serde_json::json!({
+                "token": native,
+                "amount": 1000,
+            }))
+            .unwrap();
+        assert_eq!(&tx.from, alice.address());
+
+        let hash = submit_transaction(&format!("http://127.0.0.1:{}", port), &tx).await.unwrap();
+        assert_eq!(hash, tx.hash());
+
+        node.produce_block().await.unwrap();
+        let app = node.app();
+        let app = app.read().await;
+        let balance = app.storage().get_balance(bob.address(), &native).await.unwrap().unwrap();
+        assert_eq!(balance.amount, TEST_ACCOUNT_BALANCE + 1000);
+
+        // A tampered transaction fails signature verification on the node
+        let mut tampered = signer
+            .transaction(&chain_id, "meme", "transfer", Some(bob.address().clone()), serde_json::json!({
+                "token": native,
+                "amount": 1,
+            }))
+            .unwrap();
+        tampered.data["amount"] = serde_json::json!(1_000_000);
+        assert!(submit_transaction(&format!("http://127.0.0.1:{}", port), &tampered).await.is_err());
+    }
+}
@@ -1,4 +1,6 @@
+use crate::error::ConfigError;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
@@ -17,8 +19,18 @@ pub struct Config {
     pub consensus: ConsensusConfig,
 }
 
+/// Seconds in a (365-day) year, used to spread annual inflation over blocks
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+/// Default allowance for a genesis time ahead of the local clock
+pub const DEFAULT_GENESIS_TIME_MAX_SKEW_SECS: u64 = 300;
+
+/// Chain ID used when none is configured
+pub const DEFAULT_CHAIN_ID: &str = "memechain-dev";
+
 /// Chain-specific configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ChainConfig {
     /// Chain ID
     pub chain_id: String,
@@ -28,6 +40,75 @@ pub struct ChainConfig {
     pub max_block_size: u64,
     /// Gas limit per block
     pub gas_limit: u64,
+    /// Native token symbol used for fees and trading
+    pub native_symbol: String,
+    /// Native token decimals
+    pub native_decimals: u8,
+    /// Flat fee charged per transaction, in native token base units
+    pub tx_fee: u64,
+    /// Smallest fee a transaction may pay, in native token base units; 0
+    /// admits free transactions
+    pub min_fee: u64,
+    /// Share of each fee burned, in basis points; the rest goes to the treasury
+    pub fee_burn_bps: u16,
+    /// Address that collects transaction fees
+    pub treasury_address: String,
+    /// Address that holds burned tokens
+    pub burn_address: String,
+    /// A sender left holding less than this many base units after a transfer
+    /// has the remainder swept to the treasury; 0 disables sweeping
+    pub dust_threshold: u64,
+    /// Smallest total supply a new token may have
+    pub min_supply: u64,
+    /// Largest total supply a new token may have
+    pub max_supply: u64,
+    /// Ceiling on a token's combined buy and sell tax percentage
+    pub max_total_tax_percentage: u8,
+    /// Bridge addresses allowed to mark any token as wrapped
+    pub bridge_addresses: Vec<String>,
+    /// Cap on the sum of all token supplies, native token included
+    pub total_token_cap: Option<u64>,
+    /// Anti-rug settings for tokens created without explicit settings or a
+    /// preset; the built-in defaults apply when unset
+    pub anti_rug_defaults: Option<AntiRugSettings>,
+    /// Native tokens minted to the block proposer for every block
+    pub block_reward: u64,
+    /// Annual inflation of the native supply, in basis points, paid to the
+    /// proposer on top of `block_reward` and spread evenly over the year's blocks
+    pub inflation_bps: Option<u16>,
+    /// Blocks an NFT's metadata must wait between updates; 0 disables the cooldown
+    pub metadata_update_cooldown_blocks: u64,
+    /// How far in the future, in seconds, a genesis time may be
+    pub genesis_time_max_skew_secs: u64,
+    /// Longest NFT name, in characters
+    pub max_nft_name_len: usize,
+    /// Largest NFT metadata document or redemption claim, in bytes of JSON
+    pub max_metadata_size: usize,
+    /// Longest transaction memo, in characters
+    pub max_memo_len: usize,
+    /// Seconds after its timestamp that a transaction expires; transactions
+    /// waiting for a nonce gap to fill are dropped after as long
+    pub tx_max_age_secs: u64,
+    /// Symbols no token may be created with (case-insensitive); the native
+    /// symbol is always reserved
+    pub reserved_symbols: Vec<String>,
+    /// Keep admitted pool transactions in storage so they survive a restart
+    pub persist_mempool: bool,
+    /// Most transactions one sender may have waiting for a nonce gap to fill
+    pub max_queued_per_sender: usize,
+    /// Most transactions waiting for nonce gaps across all senders
+    pub max_queued_transactions: usize,
+    /// Per module, the only actions accepted; modules not listed allow every action
+    pub enabled_actions: BTreeMap<String, Vec<String>>,
+    /// Per module, actions rejected even if enabled
+    pub disabled_actions: BTreeMap<String, Vec<String>>,
+    /// Record the holders of `holder_snapshot_tokens` at each height
+    /// divisible by this, for `/tokens/:symbol/snapshot`; 0 disables snapshots
+    pub holder_snapshot_interval: u64,
+    /// Tokens whose holders are snapshotted
+    pub holder_snapshot_tokens: Vec<String>,
+    /// Number of most recent holder snapshots kept; older ones are deleted
+    pub holder_snapshot_retention: usize,
 }
 
 /// Network configuration
@@ -43,10 +124,21 @@ pub struct NetworkConfig {
     pub persistent_peers: Vec<String>,
     /// Maximum number of peers
     pub max_peers: u32,
+    /// Stable node identity; generated and persisted on first start if unset
+    #[serde(default)]
+    pub node_id: Option<String>,
+    /// Oldest peer protocol version accepted in a handshake
+    #[serde(default = "default_min_protocol_version")]
+    pub min_protocol_version: u32,
+}
+
+fn default_min_protocol_version() -> u32 {
+    crate::network::PROTOCOL_VERSION
 }
 
 /// API configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ApiConfig {
     /// API server port
     pub api_port: u16,
@@ -54,25 +146,90 @@ pub struct ApiConfig {
     pub enable_cors: bool,
     /// Allowed origins
     pub allowed_origins: Vec<String>,
+    /// HTTP methods allowed by CORS
+    pub allowed_methods: Vec<String>,
+    /// How long browsers may cache a CORS preflight response, in seconds
+    pub max_age_seconds: u64,
     /// Rate limiting
     pub rate_limit: u32,
+    /// Addresses exempt from rate limiting (relayers, internal services)
+    pub rate_limit_exempt: Vec<String>,
+    /// Bearer token for `/admin` endpoints; admin endpoints are disabled when unset
+    pub admin_token: Option<String>,
+    /// How long a processed `Idempotency-Key` is remembered, in seconds
+    pub idempotency_ttl_secs: u64,
+    /// Largest request body accepted, in bytes; larger requests get 413
+    pub max_request_body_bytes: usize,
+    /// Most requests handled at once; further requests get 503 instead of
+    /// queueing on the application lock (0 = unbounded)
+    pub max_concurrent_requests: usize,
 }
 
 /// Storage configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StorageConfig {
     /// Database path
     pub db_path: String,
-    /// Database type (rocksdb, sled)
+    /// Database type (rocksdb, sled, memory)
     pub db_type: String,
     /// Cache size in MB
     pub cache_size: u64,
-    /// Enable compression
+    /// Enable compression (legacy alias for `compression = { algorithm = "lz4" }`)
     pub enable_compression: bool,
+    /// Compression algorithm and level; overrides `enable_compression` when set
+    pub compression: Option<CompressionConfig>,
+    /// fsync every write to disk (RocksDB only).
+    ///
+    /// Disabling this trades durability for throughput: a power loss or
+    /// kernel crash may lose the most recent writes. Only turn it off for
+    /// benchmarks and throwaway dev nodes.
+    pub fsync: bool,
+    /// Incrementally sync written data to disk every N bytes (RocksDB only,
+    /// 0 disables incremental syncing)
+    pub bytes_per_sync: u64,
+    /// Flush buffered writes to disk every N milliseconds (Sled only, 0
+    /// disables the periodic flush; block commits are always flushed)
+    pub flush_every_ms: u64,
+    /// Run a full manual compaction every N seconds (RocksDB only, 0
+    /// disables scheduled compaction)
+    pub compaction_interval_secs: u64,
+    /// Encoding of stored values (json, bincode). The formats are not
+    /// interchangeable: the database records the codec it was written
+    /// with and refuses to open under another, so changing this requires
+    /// re-syncing or exporting and re-importing the state. The app hash
+    /// does not depend on the codec.
+    pub codec: String,
+    /// Fail a storage request that takes longer than this many
+    /// milliseconds, including time spent waiting for a slot (0 waits
+    /// forever). A write that times out may still land later. Requests
+    /// made while executing genesis or a block, including batch commits and
+    /// the app hash, are never timed out.
+    pub request_timeout_ms: u64,
+    /// Most storage requests in flight at once, which bounds how much of
+    /// the blocking thread pool storage can occupy (0 = unbounded). A timed
+    /// out request holds its slot until the backend finishes it.
+    pub max_concurrent_requests: usize,
+    /// Number of list and detail query results kept in an LRU cache;
+    /// writes evict the entries they affect (0 disables the cache)
+    pub query_cache_size: usize,
+    /// Top-level NFT metadata keys indexed for `/collections/:id/search`.
+    /// Changing the list rebuilds the index on the next start.
+    pub indexed_metadata_keys: Vec<String>,
+}
+
+/// Storage compression configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    /// Compression algorithm (none, lz4, zstd)
+    pub algorithm: String,
+    /// Compression level (only used by zstd)
+    pub level: i32,
 }
 
 /// Consensus configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ConsensusConfig {
     /// Validator moniker
     pub moniker: String,
@@ -82,6 +239,15 @@ pub struct ConsensusConfig {
     pub timeout_commit: u64,
     /// Block size limit
     pub max_block_size_txs: u32,
+    /// Maximum number of missed blocks produced per tick when catching up
+    pub max_catchup_blocks: u32,
+    /// Lateness in milliseconds tolerated before block production counts as behind
+    pub block_time_jitter_ms: u64,
+    /// Produce a block every slot even when the pool is empty
+    pub produce_empty_blocks: bool,
+    /// Verify the single-key signatures of a block's transactions as one
+    /// ed25519 batch instead of one at a time
+    pub batch_verify_signatures: bool,
 }
 
 impl Default for Config {
@@ -99,10 +265,41 @@ impl Default for Config {
 impl Default for ChainConfig {
     fn default() -> Self {
         Self {
-            chain_id: "memechain-dev".to_string(),
+            chain_id: DEFAULT_CHAIN_ID.to_string(),
             block_time: 6,
             max_block_size: 1024 * 1024, // 1MB
             gas_limit: 10_000_000,
+            native_symbol: "MEME".to_string(),
+            native_decimals: 6,
+            tx_fee: 0,
+            min_fee: 0,
+            fee_burn_bps: 0,
+            treasury_address: "memechain1treasury".to_string(),
+            burn_address: "memechain1burn".to_string(),
+            dust_threshold: 0,
+            min_supply: 1,
+            max_supply: 1_000_000_000_000_000_000,
+            max_total_tax_percentage: 25,
+            bridge_addresses: vec![],
+            total_token_cap: None,
+            anti_rug_defaults: None,
+            block_reward: 0,
+            inflation_bps: None,
+            metadata_update_cooldown_blocks: 0,
+            genesis_time_max_skew_secs: DEFAULT_GENESIS_TIME_MAX_SKEW_SECS,
+            max_nft_name_len: 128,
+            max_metadata_size: 16 * 1024,
+            max_memo_len: 256,
+            tx_max_age_secs: 60,
+            reserved_symbols: vec![],
+            persist_mempool: true,
+            max_queued_per_sender: 64,
+            max_queued_transactions: 4096,
+            enabled_actions: BTreeMap::new(),
+            disabled_actions: BTreeMap::new(),
+            holder_snapshot_interval: 0,
+            holder_snapshot_tokens: Vec::new(),
+            holder_snapshot_retention: 10,
         }
     }
 }
@@ -115,6 +312,8 @@ impl Default for NetworkConfig {
             seeds: vec![],
             persistent_peers: vec![],
             max_peers: 50,
+            node_id: None,
+            min_protocol_version: default_min_protocol_version(),
         }
     }
 }
@@ -125,7 +324,14 @@ impl Default for ApiConfig {
             api_port: 8080,
             enable_cors: true,
             allowed_origins: vec!["*".to_string()],
+            allowed_methods: vec!["GET".to_string(), "POST".to_string()],
+            max_age_seconds: 600,
             rate_limit: 1000,
+            rate_limit_exempt: vec![],
+            admin_token: None,
+            idempotency_ttl_secs: 86_400,
+            max_request_body_bytes: 2 * 1024 * 1024, // 2MB
+            max_concurrent_requests: 512,
         }
     }
 }
@@ -137,6 +343,16 @@ impl Default for StorageConfig {
             db_type: "rocksdb".to_string(),
             cache_size: 512, // 512MB
             enable_compression: true,
+            compression: None,
+            fsync: true,
+            bytes_per_sync: 1024 * 1024, // 1MB
+            flush_every_ms: 500,
+            compaction_interval_secs: 0,
+            codec: "json".to_string(),
+            request_timeout_ms: 10_000,
+            max_concurrent_requests: 64,
+            query_cache_size: 256,
+            indexed_metadata_keys: Vec::new(),
         }
     }
 }
@@ -148,7 +364,168 @@ impl Default for ConsensusConfig {
             validator_key_path: "./config/priv_validator_key.json".to_string(),
             timeout_commit: 5000, // 5 seconds
             max_block_size_txs: 10000,
+            max_catchup_blocks: 10,
+            block_time_jitter_ms: 500,
+            produce_empty_blocks: true,
+            batch_verify_signatures: true,
+        }
+    }
+}
+
+impl StorageConfig {
+    /// Compression settings in effect, resolving the legacy `enable_compression` flag
+    pub fn effective_compression(&self) -> CompressionConfig {
+        match &self.compression {
+            Some(compression) => compression.clone(),
+            None if self.enable_compression => CompressionConfig {
+                algorithm: "lz4".to_string(),
+                level: 0,
+            },
+            None => CompressionConfig {
+                algorithm: "none".to_string(),
+                level: 0,
+            },
+        }
+    }
+
+    /// Validate storage configuration
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if let Some(compression) = &self.compression {
+            match compression.algorithm.as_str() {
+                "none" | "lz4" | "zstd" => {}
+                other => {
+                    return Err(ConfigError::Invalid(format!(
+                        "Unknown compression algorithm: {}", other
+                    )).into())
+                }
+            }
+        }
+        crate::storage::StorageCodec::from_name(&self.codec)?;
+        Ok(())
+    }
+}
+
+impl ChainConfig {
+    /// Whether transactions may call `module`/`action` on this chain
+    pub fn is_action_enabled(&self, module: &str, action: &str) -> bool {
+        let listed = |lists: &BTreeMap<String, Vec<String>>| {
+            lists.get(module).map(|actions| actions.iter().any(|listed| listed == action))
+        };
+        listed(&self.enabled_actions).unwrap_or(true) && !listed(&self.disabled_actions).unwrap_or(false)
+    }
+
+    /// Whether `symbol` is reserved, ignoring case
+    pub fn is_reserved_symbol(&self, symbol: &str) -> bool {
+        std::iter::once(&self.native_symbol)
+            .chain(&self.reserved_symbols)
+            .any(|reserved| reserved.eq_ignore_ascii_case(symbol))
+    }
+
+    /// Validate chain configuration
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if self.min_supply == 0 || self.min_supply > self.max_supply {
+            return Err(ConfigError::Invalid(format!(
+                "Invalid token supply bounds: min {} max {}", self.min_supply, self.max_supply
+            )).into());
+        }
+        if self.fee_burn_bps > 10_000 {
+            return Err(ConfigError::Invalid(format!(
+                "fee_burn_bps must be at most 10000, got {}", self.fee_burn_bps
+            )).into());
+        }
+        for address in &self.bridge_addresses {
+            if !crate::types::Address::new(address.clone()).is_valid() {
+                return Err(ConfigError::Invalid(format!("Invalid bridge address: {}", address)).into());
+            }
         }
+        if self.max_total_tax_percentage > 100 {
+            return Err(ConfigError::Invalid(format!(
+                "max_total_tax_percentage must be at most 100, got {}", self.max_total_tax_percentage
+            )).into());
+        }
+        if self.max_nft_name_len == 0 {
+            return Err(ConfigError::Invalid("max_nft_name_len must be at least 1".to_string()).into());
+        }
+        if let Some(inflation_bps) = self.inflation_bps {
+            if inflation_bps > 10_000 {
+                return Err(ConfigError::Invalid(format!(
+                    "inflation_bps must be at most 10000, got {}", inflation_bps
+                )).into());
+            }
+        }
+        if self.holder_snapshot_interval > 0 {
+            if self.holder_snapshot_tokens.is_empty() {
+                return Err(ConfigError::Invalid(
+                    "holder_snapshot_tokens must name at least one token when snapshots are enabled".to_string()
+                ).into());
+            }
+            if self.holder_snapshot_retention == 0 {
+                return Err(ConfigError::Invalid("holder_snapshot_retention must be at least 1".to_string()).into());
+            }
+        }
+        if self.anti_rug_defaults.is_some() {
+            self.default_anti_rug()
+                .validate(self.max_total_tax_percentage)
+                .map_err(|e| ConfigError::Invalid(format!("Invalid anti_rug_defaults: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Reward for one block given the current native supply, before any cap
+    pub fn block_reward_for(&self, native_supply: u64) -> u64 {
+        let inflation = match self.inflation_bps {
+            Some(bps) if self.block_time > 0 => {
+                let blocks_per_year = (SECONDS_PER_YEAR / self.block_time).max(1) as u128;
+                (native_supply as u128 * bps as u128 / 10_000 / blocks_per_year) as u64
+            }
+            _ => 0,
+        };
+        self.block_reward.saturating_add(inflation)
+    }
+
+    /// Anti-rug settings applied when a token is created without any
+    pub fn default_anti_rug(&self) -> crate::types::AntiRugSettings {
+        match &self.anti_rug_defaults {
+            Some(settings) => settings.clone().into(),
+            None => crate::types::AntiRugSettings::default(),
+        }
+    }
+}
+
+impl ApiConfig {
+    /// Validate API configuration
+    pub fn validate(&self) -> crate::error::Result<()> {
+        for address in &self.rate_limit_exempt {
+            if !crate::types::Address::new(address.clone()).is_valid() {
+                return Err(ConfigError::Invalid(format!(
+                    "Invalid rate limit exempt address: {}", address
+                )).into());
+            }
+        }
+        if self.max_request_body_bytes == 0 {
+            return Err(ConfigError::Invalid("max_request_body_bytes must be positive".to_string()).into());
+        }
+        for method in &self.allowed_methods {
+            if method.to_ascii_uppercase().parse::<axum::http::Method>().is_err() {
+                return Err(ConfigError::Invalid(format!("Invalid CORS method: {}", method)).into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl NetworkConfig {
+    /// Validate network configuration
+    pub fn validate(&self) -> crate::error::Result<()> {
+        // Otherwise this node would refuse peers running its own version
+        if self.min_protocol_version > crate::network::PROTOCOL_VERSION {
+            return Err(ConfigError::Invalid(format!(
+                "min_protocol_version {} is newer than this node's protocol version {}",
+                self.min_protocol_version,
+                crate::network::PROTOCOL_VERSION
+            )).into());
+        }
+        Ok(())
     }
 }
 
@@ -157,9 +534,19 @@ impl Config {
     pub fn from_file<P: AsRef<Path>>(path: P) -> crate::error::Result<Self> {
         let content = fs::read_to_string(path)?;
         let config: Config = toml::from_str(&content)?;
+        config.validate()?;
         Ok(config)
     }
 
+    /// Validate configuration
+    pub fn validate(&self) -> crate::error::Result<()> {
+        self.chain.validate()?;
+        self.network.validate()?;
+        self.storage.validate()?;
+        self.api.validate()?;
+        Ok(())
+    }
+
     /// Save configuration to file
     pub fn save<P: AsRef<Path>>(&self, path: P) -> crate::error::Result<()> {
         let content = toml::to_string_pretty(self)?;
@@ -181,6 +568,9 @@ pub struct GenesisConfig {
     pub accounts: Vec<Account>,
     /// App state
     pub app_state: AppState,
+    /// The genesis file as parsed, when loaded with `from_file`
+    #[serde(skip)]
+    source: Option<serde_json::Value>,
 }
 
 /// Validator configuration
@@ -267,6 +657,9 @@ pub struct AntiRugSettings {
     pub buy_tax_percentage: u8,
     /// Sell tax percentage
     pub sell_tax_percentage: u8,
+    /// Percentage of every transfer sent to the treasury
+    #[serde(default)]
+    pub transfer_tax_percentage: u8,
     /// Liquidity locked percentage
     pub liquidity_locked_percentage: u8,
     /// Lock duration in blocks
@@ -309,7 +702,82 @@ impl GenesisConfig {
                     tokens: vec![],
                 },
             },
+            source: None,
+        }
+    }
+
+    /// Validate genesis configuration
+    pub fn validate(&self) -> crate::error::Result<()> {
+        self.parsed_genesis_time()?;
+
+        let mut symbols = std::collections::HashSet::new();
+        for token in &self.app_state.meme.tokens {
+            if !symbols.insert(token.symbol.as_str()) {
+                return Err(ConfigError::InvalidGenesis(format!(
+                    "Duplicate token symbol: {}", token.symbol
+                )).into());
+            }
+        }
+
+        let mut collection_ids = std::collections::HashSet::new();
+        for collection in &self.app_state.nft.collections {
+            if !collection_ids.insert(collection.id.as_str()) {
+                return Err(ConfigError::InvalidGenesis(format!(
+                    "Duplicate collection ID: {}", collection.id
+                )).into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Canonical hash of the genesis: SHA-256 (hex) of its JSON with object
+    /// keys sorted, so formatting and field order in the file do not matter.
+    ///
+    /// A genesis loaded with `from_file` hashes the file as written, not the
+    /// parsed struct, so fields later added with defaults leave the hash of
+    /// an existing genesis unchanged. Changes made to it after loading are
+    /// therefore not reflected.
+    pub fn hash(&self) -> String {
+        use sha2::{Digest, Sha256};
+        // `serde_json::Value` maps keep their keys sorted
+        let canonical = match &self.source {
+            Some(source) => source.to_string(),
+            None => serde_json::to_value(self).unwrap_or_default().to_string(),
+        };
+        format!("{:x}", Sha256::digest(canonical.as_bytes()))
+    }
+
+    /// Genesis time parsed as RFC3339
+    pub fn parsed_genesis_time(&self) -> crate::error::Result<chrono::DateTime<chrono::Utc>> {
+        chrono::DateTime::parse_from_rfc3339(&self.genesis_time)
+            .map(|time| time.with_timezone(&chrono::Utc))
+            .map_err(|e| ConfigError::InvalidGenesis(format!(
+                "Invalid genesis_time {:?}: {}", self.genesis_time, e
+            )).into())
+    }
+
+    /// Reject a genesis time more than `max_skew_secs` after `now` (unix seconds)
+    pub fn validate_genesis_time(&self, now: i64, max_skew_secs: u64) -> crate::error::Result<()> {
+        let genesis_time = self.parsed_genesis_time()?.timestamp();
+        let latest = now.saturating_add(i64::try_from(max_skew_secs).unwrap_or(i64::MAX));
+        if genesis_time > latest {
+            return Err(ConfigError::InvalidGenesis(format!(
+                "genesis_time {} is {}s in the future, more than the allowed {}s",
+                self.genesis_time, genesis_time - now, max_skew_secs
+            )).into());
         }
+        Ok(())
+    }
+
+    /// Load genesis configuration from file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> crate::error::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let source: serde_json::Value = serde_json::from_str(&content)?;
+        let mut genesis: GenesisConfig = serde_json::from_value(source.clone())?;
+        genesis.source = Some(source);
+        genesis.validate()?;
+        Ok(genesis)
     }
 
     /// Save genesis configuration to file
@@ -320,12 +788,27 @@ impl GenesisConfig {
     }
 }
 
+impl From<AntiRugSettings> for crate::types::AntiRugSettings {
+    fn from(settings: AntiRugSettings) -> Self {
+        Self {
+            max_wallet_percentage: settings.max_wallet_percentage,
+            buy_tax_percentage: settings.buy_tax_percentage,
+            sell_tax_percentage: settings.sell_tax_percentage,
+            transfer_tax_percentage: settings.transfer_tax_percentage,
+            liquidity_locked_percentage: settings.liquidity_locked_percentage,
+            lock_duration_blocks: settings.lock_duration_blocks,
+            lock_start_block: None,
+        }
+    }
+}
+
 impl Default for AntiRugSettings {
     fn default() -> Self {
         Self {
             max_wallet_percentage: 5, // 5% max per wallet
             buy_tax_percentage: 2,     // 2% buy tax
             sell_tax_percentage: 3,    // 3% sell tax
+            transfer_tax_percentage: 0, // no transfer tax
             liquidity_locked_percentage: 80, // 80% locked
             lock_duration_blocks: 1000, // ~100 minutes
         }
@@ -344,6 +827,25 @@ mod tests {
         assert_eq!(config.chain.chain_id, parsed_config.chain.chain_id);
     }
 
+    #[test]
+    fn test_compression_config() {
+        let mut storage = StorageConfig::default();
+        assert_eq!(storage.effective_compression().algorithm, "lz4");
+
+        storage.compression = Some(CompressionConfig {
+            algorithm: "zstd".to_string(),
+            level: 3,
+        });
+        assert!(storage.validate().is_ok());
+        assert_eq!(storage.effective_compression().level, 3);
+
+        storage.compression = Some(CompressionConfig {
+            algorithm: "brotli".to_string(),
+            level: 0,
+        });
+        assert!(storage.validate().is_err());
+    }
+
     #[test]
     fn test_genesis_creation() {
         let genesis = GenesisConfig::new("test-chain".to_string(), "test-validator".to_string());
@@ -351,4 +853,115 @@ mod tests {
         assert_eq!(genesis.validators.len(), 1);
         assert_eq!(genesis.accounts.len(), 2);
     }
+
+    #[test]
+    fn test_holder_snapshot_validation() {
+        let mut chain = ChainConfig {
+            holder_snapshot_interval: 10,
+            ..ChainConfig::default()
+        };
+        assert!(chain.validate().is_err());
+        chain.holder_snapshot_tokens = vec!["MEME".to_string()];
+        assert!(chain.validate().is_ok());
+        chain.holder_snapshot_retention = 0;
+        assert!(chain.validate().is_err());
+    }
+
+    #[test]
+    fn test_anti_rug_defaults_validation() {
+        let mut chain = ChainConfig::default();
+        assert_eq!(chain.default_anti_rug().max_wallet_percentage, 5);
+
+        chain.anti_rug_defaults = Some(AntiRugSettings {
+            max_wallet_percentage: 2,
+            ..AntiRugSettings::default()
+        });
+        assert!(chain.validate().is_ok());
+        assert_eq!(chain.default_anti_rug().max_wallet_percentage, 2);
+
+        chain.anti_rug_defaults = Some(AntiRugSettings {
+            max_wallet_percentage: 0,
+            ..AntiRugSettings::default()
+        });
+        assert!(chain.validate().is_err());
+    }
+
+    #[test]
+    fn test_block_reward_for() {
+        let mut chain = ChainConfig {
+            block_reward: 10,
+            block_time: 1,
+            ..ChainConfig::default()
+        };
+        assert_eq!(chain.block_reward_for(1_000_000), 10);
+
+        // 10% a year with one block per second: supply / blocks_per_year is 100, 10% of that is 10
+        chain.inflation_bps = Some(1_000);
+        assert_eq!(chain.block_reward_for(SECONDS_PER_YEAR * 100), 20);
+
+        chain.inflation_bps = Some(10_001);
+        assert!(chain.validate().is_err());
+    }
+
+    #[test]
+    fn test_genesis_time_validation() {
+        let mut genesis = GenesisConfig::new("test-chain".to_string(), "test-validator".to_string());
+        let now = chrono::Utc::now().timestamp();
+        assert!(genesis.validate().is_ok());
+        assert!(genesis.validate_genesis_time(now, DEFAULT_GENESIS_TIME_MAX_SKEW_SECS).is_ok());
+
+        genesis.genesis_time = "yesterday".to_string();
+        let err = genesis.validate().unwrap_err();
+        assert!(matches!(err, crate::MemeChainError::Config(ConfigError::InvalidGenesis(_))));
+
+        genesis.genesis_time = (chrono::Utc::now() + chrono::Duration::days(365)).to_rfc3339();
+        assert!(genesis.validate().is_ok());
+        let err = genesis.validate_genesis_time(now, DEFAULT_GENESIS_TIME_MAX_SKEW_SECS).unwrap_err();
+        assert!(matches!(err, crate::MemeChainError::Config(ConfigError::InvalidGenesis(_))));
+    }
+
+    #[test]
+    fn test_genesis_from_file_rejects_malformed_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("genesis.json");
+        let mut genesis = GenesisConfig::new("test-chain".to_string(), "test-validator".to_string());
+
+        genesis.save(&path).unwrap();
+        assert!(GenesisConfig::from_file(&path).is_ok());
+
+        genesis.genesis_time = "2024-13-01 25:00".to_string();
+        genesis.save(&path).unwrap();
+        let err = GenesisConfig::from_file(&path).unwrap_err();
+        assert!(matches!(err, crate::MemeChainError::Config(ConfigError::InvalidGenesis(_))));
+    }
+
+    #[test]
+    fn test_genesis_hash_ignores_defaulted_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("genesis.json");
+        let mut file = serde_json::to_value(GenesisConfig::new("test-chain".to_string(), "v".to_string())).unwrap();
+        // Written before transfer taxes existed
+        file["app_state"]["meme"]["tokens"] = serde_json::json!([{
+            "symbol": "PEPE",
+            "name": "Pepe",
+            "total_supply": 1000,
+            "creator": "memechain1alice",
+            "anti_rug": {
+                "max_wallet_percentage": 5,
+                "buy_tax_percentage": 2,
+                "sell_tax_percentage": 3,
+                "liquidity_locked_percentage": 80,
+                "lock_duration_blocks": 1000
+            }
+        }]);
+        fs::write(&path, serde_json::to_string_pretty(&file).unwrap()).unwrap();
+
+        let genesis = GenesisConfig::from_file(&path).unwrap();
+        let expected = format!("{:x}", <sha2::Sha256 as sha2::Digest>::digest(file.to_string().as_bytes()));
+        assert_eq!(genesis.hash(), expected);
+
+        // Formatting does not matter
+        fs::write(&path, file.to_string()).unwrap();
+        assert_eq!(GenesisConfig::from_file(&path).unwrap().hash(), expected);
+    }
 } 
\ No newline at end of file
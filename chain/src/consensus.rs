@@ -0,0 +1,157 @@
+use crate::app::MemeChainApp;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+
+/// Schedules block production on fixed slots of `block_time` seconds.
+///
+/// When the node falls behind (e.g. after the host was suspended) it catches
+/// up on missed slots, at most `max_catchup_blocks` per tick, instead of
+/// drifting. Slots are only ever produced once their time has passed, so
+/// blocks never carry a future timestamp.
+#[derive(Debug, Clone)]
+pub struct BlockScheduler {
+    /// Slot length in seconds
+    interval: i64,
+    /// Timestamp of the next slot to produce
+    next_slot: i64,
+    /// Maximum number of blocks produced per tick
+    max_catchup_blocks: u32,
+    /// Lateness tolerated before we consider ourselves behind, in milliseconds
+    jitter_tolerance_ms: u64,
+}
+
+impl BlockScheduler {
+    /// Create a scheduler whose first slot is at `start`
+    pub fn new(block_time: u64, start: i64, max_catchup_blocks: u32, jitter_tolerance_ms: u64) -> Self {
+        Self {
+            interval: block_time.max(1) as i64,
+            next_slot: start,
+            max_catchup_blocks: max_catchup_blocks.max(1),
+            jitter_tolerance_ms,
+        }
+    }
+
+    /// Timestamp of the next slot
+    pub fn next_slot(&self) -> i64 {
+        self.next_slot
+    }
+
+    /// Whether the schedule is more than one interval (plus jitter) behind `now`
+    pub fn is_behind(&self, now: i64) -> bool {
+        let lag_ms = (now - self.next_slot) * 1000;
+        lag_ms > self.interval * 1000 + self.jitter_tolerance_ms as i64
+    }
+
+    /// Return the timestamps of the blocks due at `now`, advancing the schedule
+    pub fn due_slots(&mut self, now: i64) -> Vec<i64> {
+        let mut slots = Vec::new();
+        while self.next_slot <= now && slots.len() < self.max_catchup_blocks as usize {
+            slots.push(self.next_slot);
+            self.next_slot += self.interval;
+        }
+        slots
+    }
+}
+
+/// Produce blocks on schedule until the task is dropped
+pub async fn run_block_production(app: Arc<RwLock<MemeChainApp>>, mut scheduler: BlockScheduler) {
+    let mut ticker = tokio::time::interval(Duration::from_secs(scheduler.interval as u64));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        ticker.tick().await;
+
+        let now = app.read().await.clock().now();
+        if scheduler.is_behind(now) {
+            warn!(
+                "Block production is {}s behind schedule, catching up",
+                now - scheduler.next_slot()
+            );
+        }
+
+        for slot in scheduler.due_slots(now) {
+            let mut app = app.write().await;
+            match app.produce_block_at(slot).await {
+                Ok(Some(block)) => info!("Produced block {} for slot {}", block.height, slot),
+                Ok(None) => {}
+                Err(e) => error!("Block production failed: {}", e),
+            }
+        }
+    }
+}
+
+/// Deterministic RNG for transaction `tx_index` of block `block_height`.
+///
+/// Seeded from a SHA-256 of the previous block hash and both indices, so every
+/// validator replaying the block draws the same values. This must be the only
+/// source of randomness on consensus paths: `thread_rng`, `Uuid::new_v4` and
+/// wall-clock time differ between nodes and would fork the state.
+pub fn block_rng(previous_hash: &str, block_height: u64, tx_index: u64) -> ChaCha20Rng {
+    let mut hasher = Sha256::new();
+    hasher.update(previous_hash.as_bytes());
+    hasher.update(block_height.to_be_bytes());
+    hasher.update(tx_index.to_be_bytes());
+    ChaCha20Rng::from_seed(hasher.finalize().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_on_schedule_produces_one_block() {
+        let mut scheduler = BlockScheduler::new(6, 1000, 5, 500);
+        assert!(scheduler.due_slots(999).is_empty());
+        assert_eq!(scheduler.due_slots(1000), vec![1000]);
+        assert!(scheduler.due_slots(1005).is_empty());
+        assert_eq!(scheduler.due_slots(1006), vec![1006]);
+        assert!(!scheduler.is_behind(1012));
+    }
+
+    #[test]
+    fn test_stalled_clock_catches_up_bounded() {
+        let mut scheduler = BlockScheduler::new(6, 1000, 5, 500);
+        assert_eq!(scheduler.due_slots(1000), vec![1000]);
+
+        // The host was suspended for 100 seconds
+        let now = 1100;
+        assert!(scheduler.is_behind(now));
+
+        let mut produced = Vec::new();
+        loop {
+            let slots = scheduler.due_slots(now);
+            assert!(slots.len() <= 5);
+            if slots.is_empty() {
+                break;
+            }
+            produced.extend(slots);
+        }
+
+        // Every missed slot is produced once, in order, never in the future
+        assert_eq!(produced.first(), Some(&1006));
+        assert_eq!(produced.last(), Some(&1096));
+        assert!(produced.windows(2).all(|pair| pair[1] == pair[0] + 6));
+        assert!(produced.iter().all(|&slot| slot <= now));
+        assert!(!scheduler.is_behind(now));
+    }
+
+    #[test]
+    fn test_block_rng_is_deterministic() {
+        use rand::Rng;
+
+        let draw = |previous_hash: &str, height: u64, tx_index: u64| {
+            let mut rng = block_rng(previous_hash, height, tx_index);
+            (0..8).map(|_| rng.gen::<u64>()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(draw("abc", 7, 0), draw("abc", 7, 0));
+        assert_ne!(draw("abc", 7, 0), draw("abc", 7, 1));
+        assert_ne!(draw("abc", 7, 0), draw("abc", 8, 0));
+        assert_ne!(draw("abc", 7, 0), draw("abd", 7, 0));
+    }
+}
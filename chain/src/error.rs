@@ -48,9 +48,18 @@ pub enum MemeChainError {
     #[error("Rate limit exceeded")]
     RateLimitExceeded,
 
+    #[error("Action disabled: {0}")]
+    ActionDisabled(String),
+
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
     #[error("Transaction failed: {0}")]
     TransactionFailed(String),
 
+    #[error("Idempotency key reused: {0}")]
+    IdempotencyKeyReused(String),
+
     #[error("Blockchain error: {0}")]
     Blockchain(String),
 }
@@ -77,6 +86,15 @@ pub enum StorageError {
     #[error("Database connection failed: {0}")]
     ConnectionFailed(String),
 
+    #[error("Database is locked: {0}")]
+    Locked(String),
+
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("Invalid database path: {0}")]
+    InvalidPath(String),
+
     #[error("Key not found: {0}")]
     KeyNotFound(String),
 
@@ -91,6 +109,9 @@ pub enum StorageError {
 
     #[error("Corrupted data: {0}")]
     CorruptedData(String),
+
+    #[error("Storage timed out: {0}")]
+    Timeout(String),
 }
 
 /// Module-related errors
@@ -135,6 +156,21 @@ pub enum NftError {
 
     #[error("Invalid NFT ID: {0}")]
     InvalidNftId(String),
+
+    #[error("Metadata update cooldown: {0}")]
+    MetadataCooldown(String),
+
+    #[error("Reveal not possible: {0}")]
+    RevealNotReady(String),
+
+    #[error("Voucher already redeemed: {0}")]
+    VoucherRedeemed(String),
+
+    #[error("Voucher expired: {0}")]
+    VoucherExpired(String),
+
+    #[error("NFT already redeemed: {0}")]
+    AlreadyRedeemed(String),
 }
 
 /// Meme token module errors
@@ -155,6 +191,9 @@ pub enum MemeError {
     #[error("Invalid supply: {0}")]
     InvalidSupply(String),
 
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+
     #[error("Transfer failed: {0}")]
     TransferFailed(String),
 
@@ -175,6 +214,15 @@ pub enum MemeError {
 
     #[error("Invalid anti-rug settings: {0}")]
     InvalidAntiRugSettings(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Token paused: {0}")]
+    TokenPaused(String),
+
+    #[error("Invalid transfer hook: {0}")]
+    InvalidTransferHook(String),
 }
 
 /// Common module errors
@@ -203,6 +251,18 @@ pub enum CommonError {
 
     #[error("Decryption failed: {0}")]
     DecryptionFailed(String),
+
+    #[error("Unsupported signature scheme: {0}")]
+    UnsupportedSignatureScheme(u8),
+
+    #[error("Invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
+
+    #[error("Invalid derivation path: {0}")]
+    InvalidDerivationPath(String),
+
+    #[error("Key not found: {0}")]
+    KeyNotFound(String),
 }
 
 /// Network-related errors
@@ -225,6 +285,12 @@ pub enum NetworkError {
 
     #[error("Protocol error: {0}")]
     ProtocolError(String),
+
+    #[error("Peer limit reached: {0}")]
+    PeerLimitReached(usize),
+
+    #[error("Invalid peer address: {0}")]
+    InvalidPeerAddress(String),
 }
 
 // Type alias for Result
@@ -242,6 +308,24 @@ impl From<toml::ser::Error> for MemeChainError {
     }
 }
 
+impl From<NftError> for MemeChainError {
+    fn from(err: NftError) -> Self {
+        MemeChainError::Module(ModuleError::Nft(err))
+    }
+}
+
+impl From<MemeError> for MemeChainError {
+    fn from(err: MemeError) -> Self {
+        MemeChainError::Module(ModuleError::Meme(err))
+    }
+}
+
+impl From<CommonError> for MemeChainError {
+    fn from(err: CommonError) -> Self {
+        MemeChainError::Module(ModuleError::Common(err))
+    }
+}
+
 impl From<rocksdb::Error> for MemeChainError {
     fn from(err: rocksdb::Error) -> Self {
         MemeChainError::Database(err.to_string())
@@ -272,4 +356,24 @@ mod tests {
         let memechain_error = MemeChainError::Module(module_error);
         assert!(matches!(memechain_error, MemeChainError::Module(_)));
     }
+
+    #[test]
+    fn test_source_chain_preserves_module_variant() {
+        use std::error::Error as _;
+
+        let err: MemeChainError = MemeError::InsufficientBalance("alice".to_string()).into();
+        let module = err.source().and_then(|e| e.downcast_ref::<ModuleError>()).unwrap();
+        assert!(matches!(module, ModuleError::Meme(_)));
+        let meme = module.source().and_then(|e| e.downcast_ref::<MemeError>()).unwrap();
+        assert!(matches!(meme, MemeError::InsufficientBalance(_)));
+
+        let err: MemeChainError = CommonError::InvalidAmount("0".to_string()).into();
+        let common = err
+            .source()
+            .and_then(|e| e.source())
+            .and_then(|e| e.downcast_ref::<CommonError>())
+            .unwrap();
+        assert!(matches!(common, CommonError::InvalidAmount(_)));
+        assert!(err.source().and_then(|e| e.source()).unwrap().downcast_ref::<MemeError>().is_none());
+    }
 } 
\ No newline at end of file
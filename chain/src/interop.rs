@@ -0,0 +1,398 @@
+use crate::config::ChainConfig;
+use crate::error::{MemeChainError, Result};
+use crate::modules::meme::validate_new_token;
+use crate::modules::nft::{normalize_name, validate_collection_description, validate_metadata_size, MAX_COLLECTION_NAME_LEN};
+use crate::storage::Storage;
+use crate::types::{Address, AntiRugSettings, Balance, Collection, Nft, OwnershipRecord, Token};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Most decimals an imported ERC20 token may declare
+pub const MAX_IMPORT_DECIMALS: u8 = 18;
+
+/// Longest symbol accepted from an ERC20 token list
+pub const MAX_IMPORT_SYMBOL_LEN: usize = 11;
+
+/// Metadata formats that can be imported from EVM chains
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportFormat {
+    /// A token list: `{"tokens": [...]}` or a bare array of
+    /// `{symbol, name, decimals, totalSupply}` entries
+    Erc20,
+    /// A collection: `{name, description, id?, tokens: [...]}` where each
+    /// token is standard ERC721 metadata plus `token_id` and optional `owner`
+    Erc721,
+}
+
+/// An entry that was not imported, and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedEntry {
+    /// Position of the entry in the input list
+    pub index: usize,
+    pub reason: String,
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// IDs of the created records (token symbols, or collection and NFT IDs)
+    pub imported: Vec<String>,
+    pub skipped: Vec<SkippedEntry>,
+}
+
+/// Read `path` and import its records into storage, owned by `creator` and
+/// created at `now` (unix seconds).
+///
+/// Imported records are chain state, so they change the app hash: an import
+/// is part of genesis and must run, with the same file, on every node before
+/// the first block. Imports into a chain that has produced blocks are refused.
+pub async fn import_file(
+    storage: &Storage,
+    config: &ChainConfig,
+    format: ImportFormat,
+    path: &Path,
+    creator: &Address,
+    now: i64,
+) -> Result<ImportReport> {
+    let data: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    let report = match format {
+        ImportFormat::Erc20 => import_erc20(storage, config, &data, creator, now).await?,
+        ImportFormat::Erc721 => import_erc721(storage, config, &data, creator, now).await?,
+    };
+    for skipped in &report.skipped {
+        warn!("Skipped entry {} of {}: {}", skipped.index, path.display(), skipped.reason);
+    }
+    info!("Imported {} records from {}", report.imported.len(), path.display());
+    Ok(report)
+}
+
+/// Refuse to import into a chain that already has blocks
+async fn ensure_genesis(storage: &Storage) -> Result<()> {
+    if let Some(block) = storage.get_latest_block().await? {
+        return Err(MemeChainError::Validation(format!(
+            "Imports change the app hash and must run before the first block; the chain is at height {}",
+            block.height
+        )));
+    }
+    Ok(())
+}
+
+/// Run an import in a storage batch, so one that fails part way writes
+/// nothing
+async fn batched(storage: &Storage, import: impl std::future::Future<Output = Result<ImportReport>>) -> Result<ImportReport> {
+    storage.begin_batch();
+    match import.await {
+        Ok(report) => {
+            storage.commit_batch().await?;
+            Ok(report)
+        }
+        Err(e) => {
+            storage.discard_batch();
+            Err(e)
+        }
+    }
+}
+
+/// Whether an error rejects an entry rather than failing the import
+fn rejects_entry(error: &MemeChainError) -> bool {
+    matches!(error, MemeChainError::Module(_))
+}
+
+/// Create a token, credited in full to `creator`, for each token list entry
+/// that `create_token` would accept
+pub async fn import_erc20(storage: &Storage, config: &ChainConfig, data: &Value, creator: &Address, now: i64) -> Result<ImportReport> {
+    ensure_genesis(storage).await?;
+    batched(storage, write_erc20(storage, config, data, creator, now)).await
+}
+
+async fn write_erc20(storage: &Storage, config: &ChainConfig, data: &Value, creator: &Address, now: i64) -> Result<ImportReport> {
+    let entries = data
+        .get("tokens")
+        .unwrap_or(data)
+        .as_array()
+        .ok_or_else(|| MemeChainError::Validation("Expected a token list or an array of tokens".to_string()))?;
+
+    let mut report = ImportReport::default();
+    for (index, entry) in entries.iter().enumerate() {
+        let token = match parse_erc20(entry, creator, now) {
+            Ok(token) => token,
+            Err(reason) => {
+                report.skipped.push(SkippedEntry { index, reason });
+                continue;
+            }
+        };
+        match validate_new_token(config, storage, &token.symbol, token.total_supply).await {
+            Ok(()) => {}
+            Err(e) if rejects_entry(&e) => {
+                report.skipped.push(SkippedEntry { index, reason: e.to_string() });
+                continue;
+            }
+            Err(e) => return Err(e),
+        }
+
+        storage.store_token(&token).await?;
+        storage.store_balance(&Balance::new(creator.clone(), token.symbol.clone(), token.total_supply, now)).await?;
+        report.imported.push(token.symbol);
+    }
+    Ok(report)
+}
+
+/// Map one token list entry to a token
+fn parse_erc20(entry: &Value, creator: &Address, now: i64) -> std::result::Result<Token, String> {
+    let symbol = required_str(entry, "symbol")?.to_uppercase();
+    if symbol.len() > MAX_IMPORT_SYMBOL_LEN || !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("Invalid symbol: {}", symbol));
+    }
+    let name = required_str(entry, "name")?;
+
+    let decimals = match entry.get("decimals") {
+        None => crate::types::DEFAULT_TOKEN_DECIMALS,
+        Some(decimals) => decimals
+            .as_u64()
+            .and_then(|decimals| u8::try_from(decimals).ok())
+            .filter(|decimals| *decimals <= MAX_IMPORT_DECIMALS)
+            .ok_or_else(|| format!("decimals must be at most {}", MAX_IMPORT_DECIMALS))?,
+    };
+
+    // EVM tools usually write the supply as a decimal string of base units
+    let supply = entry.get("totalSupply").or_else(|| entry.get("total_supply"));
+    let total_supply = match supply {
+        Some(Value::Number(supply)) => supply.as_u64(),
+        Some(Value::String(supply)) => supply.parse().ok(),
+        _ => return Err("totalSupply is required".to_string()),
+    }
+    .filter(|supply| *supply > 0)
+    .ok_or_else(|| "totalSupply must be a positive integer that fits in 64 bits".to_string())?;
+
+    let mut token = Token::new(symbol, name.to_string(), total_supply, creator.clone(), AntiRugSettings::default(), now);
+    token.decimals = decimals;
+    Ok(token)
+}
+
+/// Create the collection and its NFTs. NFTs go to their `owner`, or to
+/// `creator` when none is given, under the ID `{collection}-{token_id}`.
+/// Names and metadata are held to the same limits as `create_collection`
+/// and `mint`.
+pub async fn import_erc721(storage: &Storage, config: &ChainConfig, data: &Value, creator: &Address, now: i64) -> Result<ImportReport> {
+    ensure_genesis(storage).await?;
+    batched(storage, write_erc721(storage, config, data, creator, now)).await
+}
+
+async fn write_erc721(storage: &Storage, config: &ChainConfig, data: &Value, creator: &Address, now: i64) -> Result<ImportReport> {
+    let invalid = |reason: String| MemeChainError::Validation(format!("Invalid ERC721 collection: {}", reason));
+    let name = required_str(data, "name").map_err(invalid)?;
+    let name = normalize_name("Collection name", name, MAX_COLLECTION_NAME_LEN).map_err(|e| invalid(e.to_string()))?;
+    let name = name.as_str();
+    let tokens = data
+        .get("tokens")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("tokens must be an array".to_string()))?;
+    let collection_id = match data.get("id") {
+        Some(id) => id.as_str().map(str::to_string).ok_or_else(|| invalid("id must be a string".to_string()))?,
+        None => slug(name),
+    };
+    if collection_id.is_empty() {
+        return Err(invalid(format!("cannot derive a collection ID from {:?}", name)));
+    }
+
+    let mut report = ImportReport::default();
+    match storage.get_collection(&collection_id).await? {
+        Some(existing) if existing.creator != *creator => {
+            return Err(invalid(format!("collection {} belongs to {}", collection_id, existing.creator)));
+        }
+        Some(_) => {}
+        None => {
+            let description = data.get("description").and_then(Value::as_str).unwrap_or_default();
+            validate_collection_description(description).map_err(|e| invalid(e.to_string()))?;
+            let collection = Collection::new(collection_id.clone(), name.to_string(), creator.clone(), description.to_string(), now);
+            storage.store_collection(&collection).await?;
+            report.imported.push(collection_id.clone());
+        }
+    }
+
+    for (index, entry) in tokens.iter().enumerate() {
+        let nft = match parse_erc721(config, entry, &collection_id, creator, now) {
+            Ok(nft) => nft,
+            Err(reason) => {
+                report.skipped.push(SkippedEntry { index, reason });
+                continue;
+            }
+        };
+        if storage.get_nft(&nft.id).await?.is_some() {
+            report.skipped.push(SkippedEntry { index, reason: format!("NFT {} already exists", nft.id) });
+            continue;
+        }
+
+        storage.store_nft(&nft).await?;
+        // Imports run before the first block
+        let record = OwnershipRecord { from: None, to: nft.owner.clone(), block_height: 0, timestamp: now };
+        storage.append_nft_history(&nft.id, &record).await?;
+        report.imported.push(nft.id);
+    }
+    Ok(report)
+}
+
+/// Map one ERC721 metadata document to an NFT; `attributes` become `traits`
+fn parse_erc721(config: &ChainConfig, entry: &Value, collection_id: &str, creator: &Address, now: i64) -> std::result::Result<Nft, String> {
+    let mut metadata = entry.as_object().cloned().ok_or_else(|| "Expected a metadata object".to_string())?;
+    let name = normalize_name("NFT name", required_str(entry, "name")?, config.max_nft_name_len).map_err(|e| e.to_string())?;
+    let token_id = match metadata.remove("token_id").or_else(|| metadata.remove("id")) {
+        Some(Value::Number(id)) => id.to_string(),
+        Some(Value::String(id)) if !id.trim().is_empty() => id.trim().to_string(),
+        _ => return Err("token_id is required".to_string()),
+    };
+    let owner = match metadata.remove("owner") {
+        None => creator.clone(),
+        Some(Value::String(owner)) if Address::new(owner.clone()).is_valid() => Address::new(owner),
+        Some(owner) => return Err(format!("Invalid owner: {}", owner)),
+    };
+    metadata.remove("name");
+    if let Some(attributes) = metadata.remove("attributes") {
+        metadata.insert("traits".to_string(), attributes);
+    }
+    let metadata = Value::Object(metadata);
+    validate_metadata_size("NFT metadata", &metadata, config.max_metadata_size).map_err(|e| e.to_string())?;
+
+    Ok(Nft::new(
+        format!("{}-{}", collection_id, token_id),
+        collection_id.to_string(),
+        name,
+        owner,
+        metadata,
+        now,
+    ))
+}
+
+/// A non-empty string field
+fn required_str<'a>(value: &'a Value, field: &str) -> std::result::Result<&'a str, String> {
+    match value.get(field).and_then(Value::as_str).map(str::trim) {
+        Some(s) if !s.is_empty() => Ok(s),
+        _ => Err(format!("{} is required", field)),
+    }
+}
+
+/// Lowercase ID from a display name: alphanumeric runs joined by `-`
+fn slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{MemoryBackend, QueryNfts};
+    use std::sync::Arc;
+
+    fn creator() -> Address {
+        Address::from_public_key(&[1u8; 32])
+    }
+
+    #[tokio::test]
+    async fn test_import_erc721_file() {
+        let storage = Storage::from_backend(Arc::new(MemoryBackend::new()));
+        let holder = Address::from_public_key(&[2u8; 32]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("apes.json");
+        std::fs::write(&path, serde_json::json!({
+            "name": "Bored Apes",
+            "description": "Imported from Ethereum",
+            "tokens": [
+                {
+                    "token_id": 1,
+                    "name": "Ape #1",
+                    "image": "ipfs://ape1",
+                    "attributes": [{"trait_type": "hat", "value": "cap"}],
+                },
+                {"token_id": "2", "name": "Ape #2", "image": "ipfs://ape2", "owner": holder.to_string()},
+                {"name": "No ID"},
+                {"token_id": 1, "name": "Duplicate"},
+                {"token_id": 3, "name": "a".repeat(200)},
+                {"token_id": 4, "name": "Ape #4", "image": "x".repeat(20_000)},
+            ],
+        }).to_string()).unwrap();
+
+        let config = ChainConfig::default();
+        let report = import_file(&storage, &config, ImportFormat::Erc721, &path, &creator(), 1_700_000_000).await.unwrap();
+        assert_eq!(report.imported, vec!["bored-apes", "bored-apes-1", "bored-apes-2"]);
+        let skipped: Vec<usize> = report.skipped.iter().map(|entry| entry.index).collect();
+        assert_eq!(skipped, vec![2, 3, 4, 5]);
+        assert!(report.skipped[0].reason.contains("token_id"));
+        assert!(report.skipped[2].reason.contains("NFT name"));
+        assert!(report.skipped[3].reason.contains("bytes"));
+
+        let collection = storage.get_collection("bored-apes").await.unwrap().unwrap();
+        assert_eq!(collection.name, "Bored Apes");
+        assert_eq!(collection.creator, creator());
+        assert_eq!(collection.created_at, 1_700_000_000);
+        let nfts = storage.query_nfts(&QueryNfts {
+            collection: Some("bored-apes".to_string()),
+            ..QueryNfts::default()
+        }).await.unwrap();
+        assert_eq!(nfts.total, 2);
+
+        let ape = storage.get_nft("bored-apes-1").await.unwrap().unwrap();
+        assert_eq!(ape.owner, creator());
+        assert_eq!(ape.metadata["image"], "ipfs://ape1");
+        assert!(ape.traits().contains(&("hat".to_string(), "cap".to_string())));
+        assert_eq!(storage.get_nft("bored-apes-2").await.unwrap().unwrap().owner, holder);
+        let history = storage.get_nft_history("bored-apes-2").await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!((history[0].from.as_ref(), &history[0].to), (None, &holder));
+
+        // Someone else cannot import into the collection
+        let err = import_file(&storage, &config, ImportFormat::Erc721, &path, &holder, 1_700_000_000).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Validation(_)));
+        
+        // Nor can a collection name break the limits of create_collection
+        let long_name = serde_json::json!({"name": "a".repeat(MAX_COLLECTION_NAME_LEN + 1), "tokens": []});
+        let err = import_erc721(&storage, &config, &long_name, &creator(), 0).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn test_import_erc20_token_list() {
+        let storage = Storage::from_backend(Arc::new(MemoryBackend::new()));
+        let data = serde_json::json!({"tokens": [
+            {"symbol": "pepe", "name": "Pepe", "decimals": 9, "totalSupply": "420690000000"},
+            {"symbol": "BIG", "name": "Too Big", "totalSupply": "340282366920938463463374607431768211455"},
+            {"name": "No Symbol", "totalSupply": 1},
+            {"symbol": "meme", "name": "Native", "totalSupply": 1000},
+            {"symbol": "DUST", "name": "Dust", "totalSupply": 5},
+            {"symbol": "HUGE", "name": "Over Cap", "totalSupply": "600000000000"},
+        ]});
+        let config = ChainConfig {
+            min_supply: 10,
+            total_token_cap: Some(1_000_000_000_000),
+            ..ChainConfig::default()
+        };
+
+        let report = import_erc20(&storage, &config, &data, &creator(), 1_700_000_000).await.unwrap();
+        assert_eq!(report.imported, vec!["PEPE"]);
+        let reasons: Vec<&str> = report.skipped.iter().map(|entry| entry.reason.as_str()).collect();
+        assert_eq!(reasons.len(), 5);
+        assert!(reasons[2].contains("reserved"));
+        assert!(reasons[3].contains("outside allowed range"));
+        assert!(reasons[4].contains("total token cap"));
+
+        let token = storage.get_token("PEPE").await.unwrap().unwrap();
+        assert_eq!((token.decimals, token.total_supply), (9, 420_690_000_000));
+        let balance = storage.get_balance(&creator(), "PEPE").await.unwrap().unwrap();
+        assert_eq!(balance.amount, 420_690_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_import_refused_after_first_block() {
+        let storage = Storage::from_backend(Arc::new(MemoryBackend::new()));
+        storage.store_block(&crate::types::Block::new(1, vec![], vec![], String::new(), 0)).await.unwrap();
+        let data = serde_json::json!([{"symbol": "PEPE", "name": "Pepe", "totalSupply": 1000}]);
+
+        let err = import_erc20(&storage, &ChainConfig::default(), &data, &creator(), 0).await.unwrap_err();
+        assert!(err.to_string().contains("before the first block"));
+        assert!(storage.get_token("PEPE").await.unwrap().is_none());
+    }
+} 
\ No newline at end of file
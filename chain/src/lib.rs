@@ -1,9 +1,18 @@
 pub mod app;
+pub mod bin_value;
+pub mod clock;
 pub mod cmd;
 pub mod config;
+pub mod consensus;
 pub mod error;
+pub mod interop;
 pub mod modules;
+pub mod network;
+pub mod results;
+pub mod rpc;
 pub mod storage;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
 pub mod types;
 
 pub use app::MemeChainApp;
@@ -11,7 +20,12 @@ pub use error::MemeChainError;
 
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::{info, warn};
+use tracing::{info, info_span, warn, Instrument};
+
+/// Crate version of this build
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Git commit this build was made from (`unknown` outside a checkout)
+pub const GIT_HASH: &str = env!("MEMECHAIN_GIT_HASH");
 
 /// Main blockchain application
 pub struct MemeChain {
@@ -36,6 +50,9 @@ impl MemeChain {
         // Initialize storage
         self.app.read().await.initialize_storage().await?;
         
+        // The native token must have been seeded from genesis
+        self.app.read().await.validate_native_token().await?;
+        
         // Start consensus engine
         self.start_consensus().await?;
         
@@ -46,6 +63,25 @@ impl MemeChain {
         Ok(())
     }
 
+    /// Apply the genesis state if the chain has not been initialized yet,
+    /// otherwise check that `genesis` is the one the chain started from.
+    ///
+    /// The genesis hash is written last, so a genesis interrupted part way
+    /// is applied again. A database from before genesis hashes were recorded
+    /// counts as initialized if it holds the native token.
+    pub async fn init_genesis(&self, genesis: &config::GenesisConfig) -> Result<(), MemeChainError> {
+        let app = self.app.read().await;
+        let initialized = app.storage().get_genesis_hash().await?.is_some()
+            || app.storage().get_token(&app.config().chain.native_symbol).await?.is_some();
+        if initialized {
+            app.verify_genesis(genesis).await?;
+        } else {
+            info!("Applying genesis state for chain {}", genesis.chain_id);
+            app.apply_genesis(genesis).await?;
+        }
+        Ok(())
+    }
+
     /// Start the consensus engine
     async fn start_consensus(&self) -> Result<(), MemeChainError> {
         info!("Starting consensus engine...");
@@ -54,27 +90,42 @@ impl MemeChain {
         // This would typically involve:
         // 1. Starting Tendermint Core
         // 2. Connecting to the ABCI application
-        // 3. Starting block production
+        // Until then, produce blocks locally on a fixed schedule
+        
+        let scheduler = consensus::BlockScheduler::new(
+            self.config.chain.block_time,
+            self.app.read().await.clock().now(),
+            self.config.consensus.max_catchup_blocks,
+            self.config.consensus.block_time_jitter_ms,
+        );
+        let span = self.node_span().await;
+        tokio::spawn(consensus::run_block_production(self.app.clone(), scheduler).instrument(span));
         
         Ok(())
     }
 
     /// Start the API server
     async fn start_api_server(&self) -> Result<(), MemeChainError> {
-        info!("Starting API server on port {}", self.config.api_port);
+        info!("Starting API server on port {}", self.config.api.api_port);
         
         let app = self.app.clone();
-        let port = self.config.api_port;
+        let api = self.config.api.clone();
+        let span = self.node_span().await;
         
         tokio::spawn(async move {
-            if let Err(e) = crate::app::start_api_server(app, port).await {
+            if let Err(e) = crate::app::start_api_server(app, &api).await {
                 warn!("API server error: {}", e);
             }
-        });
+        }.instrument(span));
         
         Ok(())
     }
 
+    /// Span tagging a task's logs with this node's ID
+    async fn node_span(&self) -> tracing::Span {
+        info_span!("node", id = %self.app.read().await.node_id())
+    }
+
     /// Get the application instance
     pub fn app(&self) -> Arc<RwLock<MemeChainApp>> {
         self.app.clone()
@@ -96,4 +147,47 @@ mod tests {
         let chain = MemeChain::new(config).await;
         assert!(chain.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_init_rejects_changed_genesis() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut config = config::Config::default();
+        config.storage.db_path = temp_dir.path().join("db").to_str().unwrap().to_string();
+        let genesis = config::GenesisConfig::new("test-chain".to_string(), "validator".to_string());
+
+        {
+            let chain = MemeChain::new(config.clone()).await.unwrap();
+            chain.init_genesis(&genesis).await.unwrap();
+        }
+
+        let chain = MemeChain::new(config).await.unwrap();
+        chain.init_genesis(&genesis).await.unwrap();
+        let mut changed = genesis.clone();
+        changed.chain_id = "other-chain".to_string();
+        let err = chain.init_genesis(&changed).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Config(error::ConfigError::InvalidGenesis(_))));
+    }
+
+    #[tokio::test]
+    async fn test_init_reapplies_interrupted_genesis() {
+        let mut config = config::Config::default();
+        config.storage.db_type = "memory".to_string();
+        let mut genesis = config::GenesisConfig::new("test-chain".to_string(), "validator".to_string());
+        genesis.accounts.push(config::Account {
+            address: "memechain1alice".to_string(),
+            balance: 100,
+            name: "alice".to_string(),
+        });
+        let chain = MemeChain::new(config).await.unwrap();
+
+        // Only the first chunk of balances made it to disk
+        let alice = types::Address::new("memechain1alice".to_string());
+        let app = chain.app();
+        app.read().await.storage().store_balance(&types::Balance::new(alice, "MEME".to_string(), 100, 0)).await.unwrap();
+
+        chain.init_genesis(&genesis).await.unwrap();
+        let app = app.read().await;
+        assert_eq!(app.storage().get_genesis_hash().await.unwrap(), Some(genesis.hash()));
+        assert_eq!(app.storage().get_token("MEME").await.unwrap().unwrap().total_supply, 100);
+    }
 } 
\ No newline at end of file
@@ -1,16 +1,101 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use memechain::cmd::Signer;
 use memechain::{MemeChain, MemeChainError};
+use std::path::PathBuf;
 use tracing::{error, info, Level};
-use tracing_subscriber;
 
 #[derive(Parser)]
 #[command(name = "memechain")]
 #[command(about = "High-performance Layer 1 blockchain for NFTs and meme tokens")]
 struct Cli {
+    /// Log output format
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Pretty)]
+    log_format: LogFormat,
+    /// Minimum log level (trace, debug, info, warn, error)
+    #[arg(long, global = true, default_value = "info")]
+    log_level: Level,
+    #[command(flatten)]
+    keys: KeyArgs,
     #[command(subcommand)]
     command: Commands,
 }
 
+/// Where transaction-sending commands get their signing key and node
+#[derive(Debug, Clone, Args)]
+struct KeyArgs {
+    /// JSON key file holding the signing key
+    #[arg(long, global = true, conflicts_with = "keyring_dir")]
+    keyfile: Option<PathBuf>,
+    /// Directory of named key files (`<name>.json`)
+    #[arg(long, global = true)]
+    keyring_dir: Option<PathBuf>,
+    /// Name of the key to use from the keyring
+    #[arg(long, global = true, requires = "keyring_dir")]
+    key: Option<String>,
+    /// Node API URL transactions are submitted to
+    #[arg(long, global = true, default_value = "http://127.0.0.1:8080")]
+    node: String,
+    /// Chain ID transactions are signed for; must match the node's
+    #[arg(long = "chain", global = true, default_value = memechain::config::DEFAULT_CHAIN_ID)]
+    chain: String,
+}
+
+impl KeyArgs {
+    /// Load the selected signing key
+    fn signer(&self) -> Result<Signer, MemeChainError> {
+        Signer::load(self.keyfile.as_deref(), self.keyring_dir.as_deref(), self.key.as_deref())
+    }
+
+    /// Sign a transaction with the selected key and submit it to the node
+    async fn send(
+        &self,
+        module: &str,
+        action: &str,
+        to: Option<memechain::types::Address>,
+        data: serde_json::Value,
+    ) -> Result<String, MemeChainError> {
+        let signer = self.signer()?;
+        let tx = signer.transaction(&self.chain, module, action, to, data)?;
+        info!("Submitting {}/{} from {} to {}", module, action, signer.address(), self.node);
+        memechain::cmd::submit_transaction(&self.node, &tx).await
+    }
+}
+
+/// Log output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LogFormat {
+    /// One JSON object per line, for log aggregation
+    Json,
+    /// Human-readable lines
+    Pretty,
+}
+
+/// Subscriber settings derived from the CLI flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LogConfig {
+    format: LogFormat,
+    level: Level,
+}
+
+impl LogConfig {
+    /// Build the logging settings from parsed CLI flags
+    fn from_cli(cli: &Cli) -> Self {
+        Self {
+            format: cli.log_format,
+            level: cli.log_level,
+        }
+    }
+
+    /// Install the global tracing subscriber
+    fn init(self) {
+        let builder = tracing_subscriber::fmt().with_max_level(self.level);
+        match self.format {
+            LogFormat::Json => builder.json().init(),
+            LogFormat::Pretty => builder.init(),
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Start the blockchain node
@@ -18,6 +103,9 @@ enum Commands {
         /// Configuration file path
         #[arg(short, long, default_value = "config.toml")]
         config: String,
+        /// Genesis file path
+        #[arg(short, long, default_value = "genesis.json")]
+        genesis: String,
     },
     /// Initialize a new blockchain
     Init {
@@ -39,22 +127,31 @@ enum Commands {
         /// Total supply
         #[arg(short, long)]
         supply: u64,
-        /// Creator address
-        #[arg(short, long)]
-        creator: String,
     },
-    /// Mint an NFT
+    /// Mint an NFT to the signing key's address
     MintNft {
-        /// Collection name
+        /// Collection ID
         #[arg(short, long)]
         collection: String,
         /// NFT name
         #[arg(short, long)]
         name: String,
-        /// Owner address
-        #[arg(short, long)]
-        owner: String,
     },
+    /// Generate a new keypair
+    Keygen {
+        /// Generate a BIP39 mnemonic and derive the key from it
+        #[arg(long)]
+        mnemonic: bool,
+        /// Recover the key from an existing mnemonic phrase, read from
+        /// `MEMECHAIN_MNEMONIC` or else from stdin (never from the command line)
+        #[arg(long, conflicts_with = "mnemonic")]
+        recover: bool,
+        /// SLIP-0010 derivation path (hardened segments only)
+        #[arg(long, default_value = memechain::modules::common::DEFAULT_DERIVATION_PATH)]
+        path: String,
+    },
+    /// Print the node version and build commit
+    Version,
     /// Transfer tokens
     Transfer {
         /// Recipient address
@@ -67,23 +164,79 @@ enum Commands {
         #[arg(short, long)]
         token: String,
     },
+    /// Import ERC20 token lists or ERC721 collection metadata into local
+    /// state. Imports are part of genesis: run the same import on every node,
+    /// while it is stopped, before the first block.
+    Import {
+        /// Metadata format of the file
+        #[arg(short, long, value_enum)]
+        format: memechain::interop::ImportFormat,
+        /// JSON file to import
+        file: PathBuf,
+        /// Address that creates the imported tokens and collections
+        #[arg(long)]
+        creator: String,
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.toml")]
+        config: String,
+        /// Genesis file; imported records are stamped with its genesis time
+        #[arg(short, long, default_value = "genesis.json")]
+        genesis: String,
+    },
+    /// Write every non-zero balance of a token to a CSV file of
+    /// `address,amount,updated_at` rows (run while the node is stopped)
+    ExportBalances {
+        /// Token symbol
+        #[arg(short, long)]
+        token: String,
+        /// CSV file to write
+        #[arg(short, long)]
+        out: PathBuf,
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.toml")]
+        config: String,
+    },
+}
+
+/// Environment variable `keygen --recover` reads the mnemonic from
+const MNEMONIC_ENV: &str = "MEMECHAIN_MNEMONIC";
+
+/// Mnemonic for `keygen --recover`, from `MEMECHAIN_MNEMONIC` or the first
+/// line of stdin, so it stays out of shell history and the process list
+fn read_mnemonic() -> Result<String, MemeChainError> {
+    let phrase = match std::env::var(MNEMONIC_ENV) {
+        Ok(phrase) => phrase,
+        Err(_) => {
+            eprint!("Mnemonic: ");
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line)?;
+            line
+        }
+    };
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    if phrase.is_empty() {
+        return Err(MemeChainError::Validation(format!(
+            "No mnemonic given; set {} or enter it on stdin", MNEMONIC_ENV
+        )));
+    }
+    Ok(phrase)
 }
 
 #[tokio::main]
 async fn main() -> Result<(), MemeChainError> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_max_level(Level::INFO)
-        .init();
-
     let cli = Cli::parse();
 
+    // Initialize logging
+    LogConfig::from_cli(&cli).init();
+
     match cli.command {
-        Commands::Start { config } => {
+        Commands::Start { config, genesis } => {
             info!("Starting MemeChain node with config: {}", config);
             
             let config = memechain::config::Config::from_file(&config)?;
+            let genesis = memechain::config::GenesisConfig::from_file(&genesis)?;
             let chain = MemeChain::new(config).await?;
+            chain.init_genesis(&genesis).await?;
             
             chain.start().await?;
             
@@ -107,44 +260,92 @@ async fn main() -> Result<(), MemeChainError> {
             config.save("config.toml")?;
             
             info!("Blockchain initialized successfully!");
-            info!("Genesis file: genesis.json");
+            info!("Genesis file: genesis.json (hash {})", genesis.hash());
             info!("Config file: config.toml");
         }
         
-        Commands::CreateToken { name, symbol, supply, creator } => {
+        Commands::CreateToken { name, symbol, supply } => {
             info!("Creating token: {} ({}) with supply: {}", name, symbol, supply);
             
-            // TODO: Implement token creation logic
-            // This would typically involve:
-            // 1. Validating the request
-            // 2. Creating the token in the meme module
-            // 3. Broadcasting the transaction
+            let data = serde_json::json!({"name": name, "symbol": symbol, "supply": supply});
+            let hash = cli.keys.send("meme", "create_token", None, data).await?;
+            
+            println!("Token creation submitted: {} ({}), tx {}", name, symbol, hash);
+        }
+        
+        Commands::MintNft { collection, name } => {
+            info!("Minting NFT: {} in collection: {}", name, collection);
+            
+            let data = serde_json::json!({"collection": collection, "name": name});
+            let hash = cli.keys.send("nft", "mint", None, data).await?;
             
-            println!("Token creation request submitted: {} ({})", name, symbol);
+            println!("NFT mint submitted: {} in {}, tx {}", name, collection, hash);
         }
         
-        Commands::MintNft { collection, name, owner } => {
-            info!("Minting NFT: {} in collection: {} for owner: {}", name, collection, owner);
+        Commands::Keygen { mnemonic, recover, path } => {
+            use memechain::modules::common::{CommonModule, Keypair};
             
-            // TODO: Implement NFT minting logic
-            // This would typically involve:
-            // 1. Validating the request
-            // 2. Minting the NFT in the NFT module
-            // 3. Broadcasting the transaction
+            let phrase = if recover {
+                Some(read_mnemonic()?)
+            } else if mnemonic {
+                Some(CommonModule::generate_mnemonic()?)
+            } else {
+                None
+            };
             
-            println!("NFT minting request submitted: {} in {}", name, collection);
+            let keypair = match &phrase {
+                Some(phrase) => CommonModule::keypair_from_mnemonic(phrase, &path)?,
+                None => Keypair::generate(),
+            };
+            
+            if let Some(phrase) = &phrase {
+                println!("Mnemonic:    {}", phrase);
+                println!("Path:        {}", path);
+            }
+            println!("Address:     {}", keypair.address);
+            println!("Public key:  {}", keypair.public_key);
+            println!("Private key: {}", keypair.private_key);
+        }
+        
+        Commands::Version => {
+            println!("memechain {} ({})", memechain::VERSION, memechain::GIT_HASH);
         }
         
         Commands::Transfer { to, amount, token } => {
             info!("Transferring {} {} to {}", amount, token, to);
             
-            // TODO: Implement transfer logic
-            // This would typically involve:
-            // 1. Validating the request
-            // 2. Executing the transfer
-            // 3. Broadcasting the transaction
+            let to = memechain::types::Address::new(to);
+            let data = serde_json::json!({"token": token, "amount": amount});
+            let hash = cli.keys.send("meme", "transfer", Some(to.clone()), data).await?;
+            
+            println!("Transfer submitted: {} {} to {}, tx {}", amount, token, to, hash);
+        }
+        
+        Commands::Import { format, file, creator, config, genesis } => {
+            let creator = memechain::types::Address::new(creator);
+            if !creator.is_valid() {
+                return Err(MemeChainError::InvalidAddress(creator.to_string()));
+            }
+            
+            let config = memechain::config::Config::from_file(&config)?;
+            let genesis_time = memechain::config::GenesisConfig::from_file(&genesis)?.parsed_genesis_time()?.timestamp();
+            let storage = memechain::storage::Storage::new(&config.storage).await?;
+            let report = memechain::interop::import_file(&storage, &config.chain, format, &file, &creator, genesis_time).await?;
+            storage.flush().await?;
+            
+            println!("Imported {} records from {}", report.imported.len(), file.display());
+            for skipped in &report.skipped {
+                println!("Skipped entry {}: {}", skipped.index, skipped.reason);
+            }
+        }
+        
+        Commands::ExportBalances { token, out, config } => {
+            let config = memechain::config::Config::from_file(&config)?;
+            let storage = memechain::storage::Storage::new(&config.storage).await?;
+            let file = std::io::BufWriter::new(std::fs::File::create(&out)?);
+            let rows = storage.export_balances_csv(&token, file).await?;
             
-            println!("Transfer request submitted: {} {} to {}", amount, token, to);
+            println!("Exported {} {} balances to {}", rows, token, out.display());
         }
     }
 
@@ -161,4 +362,71 @@ mod tests {
         let cli = Cli::try_parse_from(args);
         assert!(cli.is_ok());
     }
+
+    #[test]
+    fn test_keygen_parsing() {
+        let cli = Cli::try_parse_from(vec!["memechain", "keygen", "--mnemonic"]).unwrap();
+        assert!(matches!(cli.command, Commands::Keygen { mnemonic: true, recover: false, .. }));
+        
+        let cli = Cli::try_parse_from(vec!["memechain", "keygen", "--recover"]).unwrap();
+        assert!(matches!(cli.command, Commands::Keygen { mnemonic: false, recover: true, .. }));
+        
+        // The phrase itself is never accepted as an argument
+        assert!(Cli::try_parse_from(vec!["memechain", "keygen", "--recover", "abandon about"]).is_err());
+        assert!(Cli::try_parse_from(vec!["memechain", "keygen", "--mnemonic", "--recover"]).is_err());
+    }
+    
+    #[test]
+    fn test_log_flags() {
+        let cli = Cli::try_parse_from(vec!["memechain", "start"]).unwrap();
+        assert_eq!(LogConfig::from_cli(&cli), LogConfig { format: LogFormat::Pretty, level: Level::INFO });
+        
+        let args = vec!["memechain", "start", "--log-format", "json", "--log-level", "debug"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert_eq!(LogConfig::from_cli(&cli), LogConfig { format: LogFormat::Json, level: Level::DEBUG });
+        
+        // Global flags are accepted after the subcommand's own flags too
+        let args = vec!["memechain", "keygen", "--mnemonic", "--log-level", "warn"];
+        assert_eq!(LogConfig::from_cli(&Cli::try_parse_from(args).unwrap()).level, Level::WARN);
+        
+        assert!(Cli::try_parse_from(vec!["memechain", "start", "--log-format", "xml"]).is_err());
+        assert!(Cli::try_parse_from(vec!["memechain", "start", "--log-level", "loud"]).is_err());
+    }
+    
+    #[test]
+    fn test_import_parsing() {
+        let args = vec!["memechain", "import", "--format", "erc721", "apes.json", "--creator", "memechain1alice"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Import { format: memechain::interop::ImportFormat::Erc721, .. }
+        ));
+        
+        let args = vec!["memechain", "import", "--format", "bep20", "t.json", "--creator", "memechain1alice"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+    
+    #[test]
+    fn test_version_parsing() {
+        let cli = Cli::try_parse_from(vec!["memechain", "version"]).unwrap();
+        assert!(matches!(cli.command, Commands::Version));
+    }
+    
+    #[test]
+    fn test_key_flags() {
+        let args = vec!["memechain", "transfer", "--to", "memechain1bob", "--amount", "5", "--token", "MEME"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert!(cli.keys.keyfile.is_none());
+        assert_eq!(cli.keys.node, "http://127.0.0.1:8080");
+        assert!(cli.keys.signer().is_err());
+        
+        let args = vec!["memechain", "--keyring-dir", "keys", "--key", "alice", "version"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert_eq!(cli.keys.keyring_dir, Some(PathBuf::from("keys")));
+        assert_eq!(cli.keys.key.as_deref(), Some("alice"));
+        
+        // One key source at a time, and --key only names a keyring entry
+        assert!(Cli::try_parse_from(vec!["memechain", "--keyfile", "k.json", "--keyring-dir", "keys", "version"]).is_err());
+        assert!(Cli::try_parse_from(vec!["memechain", "--key", "alice", "version"]).is_err());
+    }
 } 
\ No newline at end of file
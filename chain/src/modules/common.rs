@@ -1,20 +1,90 @@
+use crate::clock::{system_clock, SharedClock};
 use crate::error::{MemeChainError, Result, CommonError};
+use crate::results::{GenerateKeypairResult, HashDataResult, RegisterMultisigResult, ValidateAddressResult};
 use crate::storage::Storage;
-use crate::types::{Address, Transaction, TransactionResult};
-use ed25519_dalek::{PublicKey, SecretKey, Signature, Verifier};
-use sha2::{Digest, Sha256};
+use crate::types::{
+    has_prime_order_points, verify_ed25519, Address, AddressCheck, MultisigAccount, Transaction, TransactionResult,
+    SIG_SCHEME_ED25519, SIG_SCHEME_MULTISIG,
+};
+use bip39::Mnemonic;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use std::collections::HashSet;
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use sha2::{Digest, Sha256, Sha512};
 use tracing::{debug, info};
 
+/// Default SLIP-0010 derivation path for MemeChain keys
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/118'/0'/0'/0'";
+
+/// Offset marking a hardened derivation index
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+/// A hex-encoded ed25519 keypair and its address
+#[derive(Clone, Serialize)]
+pub struct Keypair {
+    /// Public key (hex)
+    pub public_key: String,
+    /// Private key (hex). Sensitive: redacted from `Debug` output
+    pub private_key: String,
+    /// Address derived from the public key
+    pub address: Address,
+}
+
+impl std::fmt::Debug for Keypair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Keypair")
+            .field("public_key", &self.public_key)
+            .field("private_key", &crate::types::REDACTED)
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+impl Keypair {
+    /// Generate a random, unrecoverable keypair
+    pub fn generate() -> Self {
+        Self::from_signing_key(&SigningKey::generate(&mut rand::thread_rng()))
+    }
+
+    /// Rebuild a keypair from a hex-encoded private key
+    pub fn from_private_key(private_key: &str) -> Result<Self> {
+        let key_bytes: [u8; 32] = hex::decode(private_key.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| CommonError::InvalidPrivateKey("Expected 32-byte hex key".to_string()))?;
+        Ok(Self::from_signing_key(&SigningKey::from_bytes(&key_bytes)))
+    }
+
+    fn from_signing_key(signing_key: &SigningKey) -> Self {
+        let public_key = signing_key.verifying_key().to_bytes();
+        Self {
+            public_key: hex::encode(public_key),
+            private_key: hex::encode(signing_key.to_bytes()),
+            address: Address::from_public_key(&public_key),
+        }
+    }
+}
+
 /// Common utilities module for shared functionality
 pub struct CommonModule {
     storage: Storage,
+    clock: SharedClock,
 }
 
 impl CommonModule {
     /// Create a new common module
     pub async fn new(storage: Storage) -> Result<Self> {
         info!("Initializing Common module");
-        Ok(Self { storage })
+        Ok(Self {
+            storage,
+            clock: system_clock(),
+        })
+    }
+
+    /// Replace the time source
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
     }
 
     /// Process common module transactions
@@ -25,6 +95,7 @@ impl CommonModule {
             "validate_address" => self.validate_address_tx(tx).await,
             "generate_keypair" => self.generate_keypair(tx).await,
             "hash_data" => self.hash_data(tx).await,
+            "register_multisig" => self.register_multisig(tx).await,
             _ => Err(CommonError::InvalidAddress(format!("Unknown action: {}", tx.action))),
         }
     }
@@ -39,30 +110,211 @@ impl CommonModule {
         Ok(())
     }
 
-    /// Validate transaction signature
-    pub async fn validate_signature(&self, tx: &Transaction) -> Result<()> {
-        // TODO: Implement proper signature validation
-        // For now, just check if signature is not empty
-        if tx.signature.is_empty() {
-            return Err(CommonError::InvalidSignature("Empty signature".to_string()));
+    /// Check an address string without submitting a transaction
+    pub fn check_address(&self, raw: &str) -> AddressCheck {
+        let address = Address::new(raw.to_string());
+        let checksum_ok = Address::well_formed(raw);
+        let is_valid = address.is_valid() && checksum_ok;
+        AddressCheck {
+            address: raw.to_string(),
+            is_valid,
+            normalized: is_valid.then(|| address.to_string()),
+            checksum_ok,
+        }
+    }
+
+    /// Validate a transaction signature made for `chain_id`, dispatching on the signature scheme
+    pub async fn validate_signature(&self, tx: &Transaction, chain_id: &str) -> Result<()> {
+        if tx.signature.is_empty() && tx.signatures.is_empty() {
+            return Err(CommonError::InvalidSignature("Empty signature".to_string()).into());
         }
+
+        match tx.sig_scheme {
+            SIG_SCHEME_ED25519 => self.validate_ed25519_signature(tx, chain_id),
+            SIG_SCHEME_MULTISIG => self.validate_multisig_signature(tx, chain_id).await,
+            scheme => Err(CommonError::UnsupportedSignatureScheme(scheme).into()),
+        }
+    }
+
+    /// Validate an ed25519 transaction signature against the sender address
+    fn validate_ed25519_signature(&self, tx: &Transaction, chain_id: &str) -> Result<()> {
+        let public_key = hex::decode(&tx.public_key)
+            .map_err(|e| CommonError::InvalidPublicKey(e.to_string()))?;
+        let signature = hex::decode(&tx.signature)
+            .map_err(|e| CommonError::InvalidSignature(e.to_string()))?;
+
+        if self.generate_address(&public_key)? != tx.from {
+            return Err(CommonError::InvalidSignature(format!(
+                "Public key does not match sender {}", tx.from
+            )).into());
+        }
+
+        if !self.verify_signature(&tx.signing_bytes(chain_id), &signature, &public_key)? {
+            return Err(CommonError::InvalidSignature("Signature verification failed".to_string()).into());
+        }
+
         Ok(())
     }
 
+    /// Which of `txs` carry a valid single-key ed25519 signature from their
+    /// sender, checked as one batch. If the batch fails, each signature is
+    /// checked on its own to single out the bad ones. Multisig and malformed
+    /// transactions are reported unverified, as are signatures with a key or
+    /// `R` not of prime order, which the cofactored batch
+    /// equation would accept but `verify_ed25519` rejects.
+    pub fn batch_verify_signatures(&self, txs: &[Transaction], chain_id: &str) -> Vec<bool> {
+        let mut verified = vec![false; txs.len()];
+        let mut candidates = Vec::new();
+        for (index, tx) in txs.iter().enumerate() {
+            if tx.sig_scheme != SIG_SCHEME_ED25519 {
+                continue;
+            }
+            let public_key = hex::decode(&tx.public_key).ok().and_then(|bytes| <[u8; 32]>::try_from(bytes).ok());
+            let signature = hex::decode(&tx.signature).ok().and_then(|bytes| Signature::from_slice(&bytes).ok());
+            let (Some(public_key), Some(signature)) = (public_key, signature) else {
+                continue;
+            };
+            let Ok(verifying_key) = VerifyingKey::from_bytes(&public_key) else {
+                continue;
+            };
+            if Address::from_public_key(&public_key) == tx.from && has_prime_order_points(&verifying_key, &signature) {
+                candidates.push((index, tx.signing_bytes(chain_id), signature, verifying_key));
+            }
+        }
+
+        let messages: Vec<&[u8]> = candidates.iter().map(|(_, message, _, _)| message.as_slice()).collect();
+        let signatures: Vec<Signature> = candidates.iter().map(|(_, _, signature, _)| *signature).collect();
+        let keys: Vec<VerifyingKey> = candidates.iter().map(|(_, _, _, key)| *key).collect();
+        let batch_ok = ed25519_dalek::verify_batch(&messages, &signatures, &keys).is_ok();
+        if !batch_ok {
+            debug!("Signature batch of {} failed; verifying individually", candidates.len());
+        }
+        for (index, message, signature, key) in &candidates {
+            verified[*index] = batch_ok || verify_ed25519(key, message, signature);
+        }
+        verified
+    }
+
+    /// Validate a multisig transaction: at least `threshold` distinct members must have signed
+    async fn validate_multisig_signature(&self, tx: &Transaction, chain_id: &str) -> Result<()> {
+        let account = self.storage.get_multisig(&tx.from).await?
+            .ok_or_else(|| CommonError::InvalidSignature(format!(
+                "{} is not a registered multisig address", tx.from
+            )))?;
+
+        let message = tx.signing_bytes(chain_id);
+        let mut signers = HashSet::new();
+        for entry in &tx.signatures {
+            if !account.public_keys.contains(&entry.public_key) || signers.contains(&entry.public_key) {
+                continue;
+            }
+
+            let public_key = hex::decode(&entry.public_key)
+                .map_err(|e| CommonError::InvalidPublicKey(e.to_string()))?;
+            let signature = hex::decode(&entry.signature)
+                .map_err(|e| CommonError::InvalidSignature(e.to_string()))?;
+
+            if self.verify_signature(&message, &signature, &public_key)? {
+                signers.insert(entry.public_key.clone());
+            }
+        }
+
+        if (signers.len() as u32) < account.threshold {
+            return Err(CommonError::InvalidSignature(format!(
+                "Multisig requires {} valid signatures, got {}", account.threshold, signers.len()
+            )).into());
+        }
+
+        Ok(())
+    }
+
+    /// Register a k-of-n multisig account
+    async fn register_multisig(&self, tx: Transaction) -> Result<TransactionResult> {
+        let threshold = tx.data["threshold"]
+            .as_u64()
+            .and_then(|threshold| u32::try_from(threshold).ok())
+            .ok_or_else(|| CommonError::InvalidAmount("Missing or invalid threshold".to_string()))?;
+
+        let mut public_keys = Vec::new();
+        for key in tx.data["public_keys"].as_array().cloned().unwrap_or_default() {
+            let key = key
+                .as_str()
+                .ok_or_else(|| CommonError::InvalidPublicKey("Public keys must be hex strings".to_string()))?
+                .to_lowercase();
+            let bytes = hex::decode(&key)
+                .map_err(|e| CommonError::InvalidPublicKey(e.to_string()))?;
+            if bytes.len() != 32 {
+                return Err(CommonError::InvalidPublicKey(format!("Expected 32-byte public key: {}", key)).into());
+            }
+            if !public_keys.contains(&key) {
+                public_keys.push(key);
+            }
+        }
+
+        if threshold == 0 || threshold as usize > public_keys.len() {
+            return Err(CommonError::InvalidAmount(format!(
+                "Threshold must be between 1 and {}", public_keys.len()
+            )).into());
+        }
+
+        let address = self.multisig_address(threshold, &public_keys)?;
+        let account = MultisigAccount {
+            address: address.clone(),
+            threshold,
+            public_keys,
+        };
+        self.storage.store_multisig(&account).await?;
+
+        info!("Registered {}-of-{} multisig {}", threshold, account.public_keys.len(), address);
+
+        Ok(RegisterMultisigResult {
+            address,
+            threshold,
+            public_keys: account.public_keys,
+        }.into())
+    }
+
+    /// Derive a multisig address from its threshold and sorted member keys
+    pub fn multisig_address(&self, threshold: u32, public_keys: &[String]) -> Result<Address> {
+        let mut sorted = public_keys.to_vec();
+        sorted.sort();
+        let preimage = format!("multisig:{}:{}", threshold, sorted.join(","));
+        self.generate_address(preimage.as_bytes())
+    }
+
     /// Generate a new keypair
     async fn generate_keypair(&self, tx: Transaction) -> Result<TransactionResult> {
         let mut rng = rand::thread_rng();
-        let secret_key = SecretKey::generate(&mut rng);
-        let public_key = PublicKey::from(&secret_key);
-
-        let keypair_data = serde_json::json!({
-            "public_key": hex::encode(public_key.to_bytes()),
-            "private_key": hex::encode(secret_key.to_bytes()),
-        });
+        let signing_key = SigningKey::generate(&mut rng);
+        let public_key = signing_key.verifying_key();
 
+        // Only the requester is logged; the result itself carries the secret
         info!("Generated new keypair for {}", tx.from);
 
-        Ok(TransactionResult::success(Some(keypair_data)))
+        Ok(GenerateKeypairResult {
+            public_key: hex::encode(public_key.to_bytes()),
+            private_key: hex::encode(signing_key.to_bytes()),
+        }.into())
+    }
+
+    /// Generate a new 24-word BIP39 mnemonic
+    pub fn generate_mnemonic() -> Result<String> {
+        let mnemonic = Mnemonic::generate(24)
+            .map_err(|e| CommonError::InvalidMnemonic(e.to_string()))?;
+        Ok(mnemonic.to_string())
+    }
+
+    /// Deterministically derive an ed25519 keypair from a BIP39 mnemonic.
+    ///
+    /// The mnemonic checksum is validated, the seed is derived with an empty
+    /// passphrase, and the key follows SLIP-0010 along `path`, which must use
+    /// hardened segments only (e.g. `m/44'/118'/0'/0'/0'`).
+    pub fn keypair_from_mnemonic(phrase: &str, path: &str) -> Result<Keypair> {
+        let mnemonic = Mnemonic::parse(phrase)
+            .map_err(|e| CommonError::InvalidMnemonic(e.to_string()))?;
+        let seed = mnemonic.to_seed("");
+        let signing_key = SigningKey::from_bytes(&derive_slip10_ed25519(&seed, path)?);
+        Ok(Keypair::from_signing_key(&signing_key))
     }
 
     /// Hash data
@@ -73,12 +325,10 @@ impl CommonModule {
 
         let hash = self.calculate_hash(data.as_bytes());
 
-        let hash_data = serde_json::json!({
-            "data": data,
-            "hash": hash,
-        });
-
-        Ok(TransactionResult::success(Some(hash_data)))
+        Ok(HashDataResult {
+            data: data.to_string(),
+            hash,
+        }.into())
     }
 
     /// Validate address transaction
@@ -90,12 +340,10 @@ impl CommonModule {
         let address = Address::new(address_str.to_string());
         let is_valid = address.is_valid();
 
-        let result_data = serde_json::json!({
-            "address": address_str,
-            "is_valid": is_valid,
-        });
-
-        Ok(TransactionResult::success(Some(result_data)))
+        Ok(ValidateAddressResult {
+            address: address_str.to_string(),
+            is_valid,
+        }.into())
     }
 
     /// Calculate SHA256 hash
@@ -107,32 +355,32 @@ impl CommonModule {
 
     /// Verify signature
     pub fn verify_signature(&self, message: &[u8], signature: &[u8], public_key: &[u8]) -> Result<bool> {
-        let pub_key = PublicKey::from_bytes(public_key)
+        let key_bytes: [u8; 32] = public_key
+            .try_into()
+            .map_err(|_| CommonError::InvalidPublicKey("Expected 32-byte public key".to_string()))?;
+        let pub_key = VerifyingKey::from_bytes(&key_bytes)
             .map_err(|e| CommonError::InvalidPublicKey(e.to_string()))?;
         
-        let sig = Signature::from_bytes(signature)
+        let sig = Signature::from_slice(signature)
             .map_err(|e| CommonError::InvalidSignature(e.to_string()))?;
 
-        match pub_key.verify(message, &sig) {
-            Ok(_) => Ok(true),
-            Err(_) => Ok(false),
-        }
+        Ok(verify_ed25519(&pub_key, message, &sig))
     }
 
     /// Sign data
     pub fn sign_data(&self, message: &[u8], private_key: &[u8]) -> Result<String> {
-        let secret_key = SecretKey::from_bytes(private_key)
-            .map_err(|e| CommonError::InvalidPrivateKey(e.to_string()))?;
+        let key_bytes: [u8; 32] = private_key
+            .try_into()
+            .map_err(|_| CommonError::InvalidPrivateKey("Expected 32-byte private key".to_string()))?;
+        let signing_key = SigningKey::from_bytes(&key_bytes);
         
-        let signature = secret_key.sign(message);
+        let signature = signing_key.sign(message);
         Ok(hex::encode(signature.to_bytes()))
     }
 
     /// Generate address from public key
     pub fn generate_address(&self, public_key: &[u8]) -> Result<Address> {
-        let hash = self.calculate_hash(public_key);
-        let address = format!("memechain1{}", &hash[..32]);
-        Ok(Address::new(address))
+        Ok(Address::from_public_key(public_key))
     }
 
     /// Encrypt data (placeholder)
@@ -170,13 +418,15 @@ impl CommonModule {
 
     /// Parse amount from string
     pub fn parse_amount(&self, amount_str: &str, decimals: u8) -> Result<u64> {
+        let overflow = || CommonError::InvalidAmount(format!("Amount too large: {}", amount_str));
+        let scale = 10_u64.checked_pow(decimals as u32).ok_or_else(overflow)?;
         let parts: Vec<&str> = amount_str.split('.').collect();
         
         match parts.len() {
             1 => {
                 let whole = parts[0].parse::<u64>()
                     .map_err(|_| CommonError::InvalidAmount("Invalid whole number".to_string()))?;
-                Ok(whole * 10_u64.pow(decimals as u32))
+                whole.checked_mul(scale).ok_or_else(|| overflow().into())
             }
             2 => {
                 let whole = parts[0].parse::<u64>()
@@ -184,22 +434,30 @@ impl CommonModule {
                 let fraction_str = parts[1];
                 
                 if fraction_str.len() > decimals as usize {
-                    return Err(CommonError::InvalidAmount("Too many decimal places".to_string()));
+                    return Err(CommonError::InvalidAmount(format!(
+                        "Too many decimal places: at most {} allowed", decimals
+                    )).into());
+                }
+                if !fraction_str.chars().all(|c| c.is_ascii_digit()) {
+                    return Err(CommonError::InvalidAmount("Invalid fraction".to_string()).into());
                 }
                 
                 let fraction = format!("{:0<width$}", fraction_str, width = decimals as usize)
                     .parse::<u64>()
-                    .map_err(|_| CommonError::InvalidAmount("Invalid fraction".to_string()))?;
+                    .unwrap_or(0);
                 
-                Ok(whole * 10_u64.pow(decimals as u32) + fraction)
+                whole
+                    .checked_mul(scale)
+                    .and_then(|whole| whole.checked_add(fraction))
+                    .ok_or_else(|| overflow().into())
             }
-            _ => Err(CommonError::InvalidAmount("Invalid amount format".to_string())),
+            _ => Err(CommonError::InvalidAmount("Invalid amount format".to_string()).into()),
         }
     }
 
     /// Get current timestamp
     pub fn get_timestamp(&self) -> i64 {
-        chrono::Utc::now().timestamp()
+        self.clock.now()
     }
 
     /// Validate timestamp
@@ -212,10 +470,50 @@ impl CommonModule {
     }
 }
 
+/// SLIP-0010 ed25519 private key derivation
+fn derive_slip10_ed25519(seed: &[u8], path: &str) -> Result<[u8; 32]> {
+    let mut segments = path.split('/');
+    if segments.next() != Some("m") {
+        return Err(CommonError::InvalidDerivationPath(format!("Path must start with m: {}", path)).into());
+    }
+
+    let (mut key, mut chain_code) = hmac_sha512_split(b"ed25519 seed", &[seed]);
+    for segment in segments {
+        let index = segment
+            .strip_suffix('\'')
+            .and_then(|index| index.parse::<u32>().ok())
+            .filter(|index| *index < HARDENED_OFFSET)
+            .ok_or_else(|| CommonError::InvalidDerivationPath(format!(
+                "ed25519 supports hardened segments only: {}", segment
+            )))?;
+        (key, chain_code) = hmac_sha512_split(
+            &chain_code,
+            &[&[0u8], &key, &(index | HARDENED_OFFSET).to_be_bytes()],
+        );
+    }
+
+    Ok(key)
+}
+
+/// HMAC-SHA512 over the concatenated parts, split into two 32-byte halves
+fn hmac_sha512_split(key: &[u8], parts: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for part in parts {
+        mac.update(part);
+    }
+    let output = mac.finalize().into_bytes();
+
+    let mut left = [0u8; 32];
+    let mut right = [0u8; 32];
+    left.copy_from_slice(&output[..32]);
+    right.copy_from_slice(&output[32..]);
+    (left, right)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::StorageConfig;
+    use crate::config::{StorageConfig, DEFAULT_CHAIN_ID};
     use tempfile::tempdir;
 
     async fn create_test_storage() -> Storage {
@@ -226,6 +524,7 @@ mod tests {
             db_path: path.to_str().unwrap().to_string(),
             cache_size: 100,
             enable_compression: false,
+            ..StorageConfig::default()
         };
         
         Storage::new(&config).await.unwrap()
@@ -289,13 +588,251 @@ mod tests {
             Address::new("memechain1alice".to_string()),
             None,
             serde_json::json!({}),
+            0,
         );
         
         let result = module.process_transaction(tx).await.unwrap();
         assert!(result.success);
         
-        let data = result.data.unwrap();
-        assert!(data["public_key"].as_str().is_some());
-        assert!(data["private_key"].as_str().is_some());
+        // The key is returned to the caller but never shown by `Debug`
+        let private_key = result.data.as_ref().unwrap()["private_key"].as_str().unwrap().to_string();
+        assert!(result.data.as_ref().unwrap()["public_key"].as_str().is_some());
+        let debug = format!("{:?}", result);
+        assert!(!debug.contains(&private_key));
+        assert!(debug.contains(crate::types::REDACTED));
+        let typed: GenerateKeypairResult = result.typed().unwrap().unwrap();
+        assert!(!format!("{:?}", typed).contains(&private_key));
+
+        let keypair = Keypair::generate();
+        assert!(!format!("{:?}", keypair).contains(&keypair.private_key));
+    }
+
+    #[tokio::test]
+    async fn test_signature_scheme_dispatch() {
+        let storage = create_test_storage().await;
+        let module = CommonModule::new(storage).await.unwrap();
+        
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let from = module.generate_address(&signing_key.verifying_key().to_bytes()).unwrap();
+        
+        let mut tx = Transaction::new(
+            "common".to_string(),
+            "hash_data".to_string(),
+            from,
+            None,
+            serde_json::json!({"data": "hello"}),
+            0,
+        );
+        tx.sign(DEFAULT_CHAIN_ID, &hex::encode(signing_key.to_bytes())).unwrap();
+        
+        assert_eq!(tx.sig_scheme, SIG_SCHEME_ED25519);
+        assert!(module.validate_signature(&tx, DEFAULT_CHAIN_ID).await.is_ok());
+        
+        tx.sig_scheme = 99;
+        let err = module.validate_signature(&tx, DEFAULT_CHAIN_ID).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MemeChainError::Module(crate::error::ModuleError::Common(CommonError::UnsupportedSignatureScheme(99)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_multisig_two_of_three() {
+        let storage = create_test_storage().await;
+        let module = CommonModule::new(storage).await.unwrap();
+        
+        let keys: Vec<SigningKey> = (1..=3u8).map(|i| SigningKey::from_bytes(&[i; 32])).collect();
+        let public_keys: Vec<String> = keys
+            .iter()
+            .map(|key| hex::encode(key.verifying_key().to_bytes()))
+            .collect();
+        
+        let register_tx = Transaction::new(
+            "common".to_string(),
+            "register_multisig".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({"threshold": 2, "public_keys": public_keys}),
+            0,
+        );
+        let result = module.process_transaction(register_tx).await.unwrap();
+        let multisig = Address::new(result.data.unwrap()["address"].as_str().unwrap().to_string());
+        
+        let unsigned = Transaction::new(
+            "common".to_string(),
+            "hash_data".to_string(),
+            multisig,
+            None,
+            serde_json::json!({"data": "treasury spend"}),
+            0,
+        );
+        
+        let mut two_sigs = unsigned.clone();
+        two_sigs.add_multisig_signature(DEFAULT_CHAIN_ID, &hex::encode(keys[0].to_bytes())).unwrap();
+        two_sigs.add_multisig_signature(DEFAULT_CHAIN_ID, &hex::encode(keys[2].to_bytes())).unwrap();
+        assert!(module.validate_signature(&two_sigs, DEFAULT_CHAIN_ID).await.is_ok());
+        
+        let mut one_sig = unsigned.clone();
+        one_sig.add_multisig_signature(DEFAULT_CHAIN_ID, &hex::encode(keys[0].to_bytes())).unwrap();
+        assert!(module.validate_signature(&one_sig, DEFAULT_CHAIN_ID).await.is_err());
+        
+        // The same signer twice does not count towards the threshold
+        one_sig.add_multisig_signature(DEFAULT_CHAIN_ID, &hex::encode(keys[0].to_bytes())).unwrap();
+        assert!(module.validate_signature(&one_sig, DEFAULT_CHAIN_ID).await.is_err());
+
+        // A threshold that would truncate to 2 as a u32 is rejected, not wrapped
+        let oversized = Transaction::new(
+            "common".to_string(),
+            "register_multisig".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({"threshold": (1u64 << 32) + 2, "public_keys": public_keys}),
+            0,
+        );
+        assert!(module.process_transaction(oversized).await.is_err());
+    }
+
+    const TEST_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_keypair_from_mnemonic_deterministic() {
+        let first = CommonModule::keypair_from_mnemonic(TEST_MNEMONIC, DEFAULT_DERIVATION_PATH).unwrap();
+        let second = CommonModule::keypair_from_mnemonic(TEST_MNEMONIC, DEFAULT_DERIVATION_PATH).unwrap();
+        assert_eq!(first.address, second.address);
+        assert_eq!(first.private_key, second.private_key);
+        assert!(first.address.is_valid());
+
+        // A different account index gives a different key
+        let other = CommonModule::keypair_from_mnemonic(TEST_MNEMONIC, "m/44'/118'/1'/0'/0'").unwrap();
+        assert_ne!(other.address, first.address);
+
+        // Generated mnemonics round-trip
+        let phrase = CommonModule::generate_mnemonic().unwrap();
+        assert_eq!(phrase.split_whitespace().count(), 24);
+        let generated = CommonModule::keypair_from_mnemonic(&phrase, DEFAULT_DERIVATION_PATH).unwrap();
+        assert_eq!(
+            generated.address,
+            CommonModule::keypair_from_mnemonic(&phrase, DEFAULT_DERIVATION_PATH).unwrap().address
+        );
+    }
+
+    #[test]
+    fn test_mnemonic_checksum_and_path_validated() {
+        let bad_checksum = ["abandon"; 12].join(" ");
+        assert!(CommonModule::keypair_from_mnemonic(&bad_checksum, DEFAULT_DERIVATION_PATH).is_err());
+        assert!(CommonModule::keypair_from_mnemonic(TEST_MNEMONIC, "m/44'/118'/0/0").is_err());
+        assert!(CommonModule::keypair_from_mnemonic(TEST_MNEMONIC, "44'/118'").is_err());
+    }
+
+    #[test]
+    fn test_slip10_vector() {
+        // SLIP-0010 ed25519 test vector 1
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        assert_eq!(
+            hex::encode(derive_slip10_ed25519(&seed, "m").unwrap()),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+        assert_eq!(
+            hex::encode(derive_slip10_ed25519(&seed, "m/0'").unwrap()),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_verify_signatures() {
+        let storage = create_test_storage().await;
+        let module = CommonModule::new(storage).await.unwrap();
+
+        let mut txs: Vec<Transaction> = (0..256u32)
+            .map(|i| {
+                let seed: [u8; 32] = Sha256::digest(i.to_be_bytes()).into();
+                let signing_key = SigningKey::from_bytes(&seed);
+                let from = Address::from_public_key(&signing_key.verifying_key().to_bytes());
+                let mut tx = Transaction::new(
+                    "common".to_string(),
+                    "hash_data".to_string(),
+                    from,
+                    None,
+                    serde_json::json!({"data": i}),
+                    0,
+                );
+                tx.sign(DEFAULT_CHAIN_ID, &hex::encode(signing_key.to_bytes())).unwrap();
+                tx
+            })
+            .collect();
+
+        let started = std::time::Instant::now();
+        assert!(module.batch_verify_signatures(&txs, DEFAULT_CHAIN_ID).iter().all(|ok| *ok));
+        debug!("Batch-verified {} signatures in {:?}", txs.len(), started.elapsed());
+        // Signed for another chain, none verify
+        assert!(module.batch_verify_signatures(&txs, "other-chain").iter().all(|ok| !*ok));
+
+        // One tampered transaction is singled out
+        txs[137].data = serde_json::json!({"data": "tampered"});
+        let verified = module.batch_verify_signatures(&txs, DEFAULT_CHAIN_ID);
+        let failed: Vec<usize> = (0..txs.len()).filter(|i| !verified[*i]).collect();
+        assert_eq!(failed, vec![137]);
+
+        // Multisig transactions are left to the regular check
+        txs[3].sig_scheme = SIG_SCHEME_MULTISIG;
+        assert!(!module.batch_verify_signatures(&txs, DEFAULT_CHAIN_ID)[3]);
+    }
+
+    /// Sign `message` with a nonce point offset by a point of order 8, which
+    /// satisfies the cofactored verification equation but not the cofactorless one
+    fn torsion_tainted_signature(signing_key: &SigningKey, message: &[u8]) -> Signature {
+        use curve25519_dalek::constants::{ED25519_BASEPOINT_POINT, EIGHT_TORSION};
+        use curve25519_dalek::scalar::Scalar;
+
+        let nonce = Scalar::from_bytes_mod_order([42u8; 32]);
+        let r = (ED25519_BASEPOINT_POINT * nonce + EIGHT_TORSION[1]).compress();
+        let public_key = signing_key.verifying_key().to_bytes();
+        let challenge: [u8; 64] = Sha512::new()
+            .chain_update(r.as_bytes())
+            .chain_update(public_key)
+            .chain_update(message)
+            .finalize()
+            .into();
+        let s = nonce + Scalar::from_bytes_mod_order_wide(&challenge) * signing_key.to_scalar();
+
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(r.as_bytes());
+        bytes[32..].copy_from_slice(s.as_bytes());
+        Signature::from_bytes(&bytes)
+    }
+
+    #[tokio::test]
+    async fn test_torsion_tainted_signature_rejected_by_both_paths() {
+        let storage = create_test_storage().await;
+        let module = CommonModule::new(storage).await.unwrap();
+        let signing_key = SigningKey::from_bytes(&[5u8; 32]);
+        let verifying_key = signing_key.verifying_key();
+        let from = Address::from_public_key(&verifying_key.to_bytes());
+        let mut txs: Vec<Transaction> = (0..2)
+            .map(|i| {
+                let mut tx = Transaction::new(
+                    "common".to_string(),
+                    "hash_data".to_string(),
+                    from.clone(),
+                    None,
+                    serde_json::json!({"data": i}),
+                    0,
+                );
+                tx.sign(DEFAULT_CHAIN_ID, &hex::encode(signing_key.to_bytes())).unwrap();
+                tx
+            })
+            .collect();
+        let message = txs[0].signing_bytes(DEFAULT_CHAIN_ID);
+        let tainted = torsion_tainted_signature(&signing_key, &message);
+        txs[0].signature = hex::encode(tainted.to_bytes());
+
+        // The raw equations disagree on this signature
+        assert!(ed25519_dalek::verify_batch(&[message.as_slice()], &[tainted], &[verifying_key]).is_ok());
+        assert!(verifying_key.verify_strict(&message, &tainted).is_err());
+
+        // Both of the chain's paths reject it
+        assert!(!module.verify_signature(&message, &tainted.to_bytes(), &verifying_key.to_bytes()).unwrap());
+        assert_eq!(module.batch_verify_signatures(&txs, DEFAULT_CHAIN_ID), vec![false, true]);
+        assert_eq!(module.batch_verify_signatures(&txs[..1], DEFAULT_CHAIN_ID), vec![false]);
     }
 } 
\ No newline at end of file
@@ -0,0 +1,244 @@
+//! Pluggable token transfer behaviour.
+//!
+//! A token may name a hook in its `transfer_hook` setting; `transfer_token`
+//! asks the hook how a transfer's amount is split between the recipient and
+//! any other accounts (tax collectors, holders, ...). Hooks are pure: they
+//! see a snapshot of the token and, if they ask for it, its holders.
+
+use crate::error::{MemeError, Result};
+use crate::types::{Address, Balance, Token, TransferHookConfig};
+
+/// Basis points in one whole
+const BPS_DENOMINATOR: u128 = 10_000;
+
+/// Largest holders a reflection is shared between, bounding the reads and
+/// writes of a single transfer
+pub const MAX_REFLECTION_HOLDERS: usize = 100;
+
+/// State visible to a hook during a transfer
+pub struct TransferContext<'a> {
+    /// Token being transferred
+    pub token: &'a Token,
+    /// Largest non-zero holders other than the sender and recipient, at most
+    /// `MAX_REFLECTION_HOLDERS`; empty unless the hook's `needs_holders`
+    /// returns true
+    pub holders: &'a [Balance],
+}
+
+/// How a transferred amount is split
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adjustments {
+    /// Amount credited to the recipient
+    pub recipient_amount: u64,
+    /// Amounts credited to other accounts
+    pub credits: Vec<(Address, u64)>,
+}
+
+impl Adjustments {
+    /// The whole amount goes to the recipient
+    pub fn passthrough(amount: u64) -> Self {
+        Self {
+            recipient_amount: amount,
+            credits: Vec::new(),
+        }
+    }
+
+    /// Check that the split accounts for exactly `amount`
+    pub fn validate(&self, amount: u64) -> Result<()> {
+        let total = self
+            .credits
+            .iter()
+            .try_fold(self.recipient_amount, |total, (_, credit)| total.checked_add(*credit));
+        if total != Some(amount) {
+            return Err(MemeError::TransferFailed(format!(
+                "Transfer hook split {:?} does not add up to {}", total, amount
+            )).into());
+        }
+        Ok(())
+    }
+}
+
+/// Custom transfer behaviour referenced by name from a token
+pub trait TransferHook: Send + Sync {
+    /// Split a transfer of `amount` from `from` to `to`
+    fn on_transfer(&self, ctx: &TransferContext<'_>, from: &Address, to: &Address, amount: u64) -> Adjustments;
+
+    /// Whether `on_transfer` needs `TransferContext::holders` populated
+    fn needs_holders(&self) -> bool {
+        false
+    }
+}
+
+/// Sends a fixed share of every transfer to one address (treasury, charity, LP)
+pub struct FlatTaxHook {
+    pub rate_bps: u16,
+    pub recipient: Address,
+}
+
+impl TransferHook for FlatTaxHook {
+    fn on_transfer(&self, _ctx: &TransferContext<'_>, _from: &Address, _to: &Address, amount: u64) -> Adjustments {
+        let tax = share(amount, self.rate_bps);
+        if tax == 0 {
+            return Adjustments::passthrough(amount);
+        }
+        Adjustments {
+            recipient_amount: amount - tax,
+            credits: vec![(self.recipient.clone(), tax)],
+        }
+    }
+}
+
+/// Distributes a share of every transfer to the largest existing holders,
+/// pro rata to their balances. No holder is credited past the token's max
+/// wallet; what that holds back, and rounding dust, stays with the recipient.
+pub struct ReflectionHook {
+    pub rate_bps: u16,
+}
+
+impl TransferHook for ReflectionHook {
+    fn on_transfer(&self, ctx: &TransferContext<'_>, _from: &Address, _to: &Address, amount: u64) -> Adjustments {
+        let fee = share(amount, self.rate_bps);
+        let total_held: u128 = ctx.holders.iter().map(|holder| holder.amount as u128).sum();
+        if fee == 0 || total_held == 0 {
+            return Adjustments::passthrough(amount);
+        }
+
+        let max_wallet = ctx.token.total_supply as u128 * ctx.token.anti_rug.max_wallet_percentage as u128 / 100;
+        let credits: Vec<(Address, u64)> = ctx
+            .holders
+            .iter()
+            .map(|holder| {
+                let pro_rata = fee as u128 * holder.amount as u128 / total_held;
+                let headroom = max_wallet.saturating_sub(holder.amount as u128);
+                (holder.address.clone(), pro_rata.min(headroom) as u64)
+            })
+            .filter(|(_, credit)| *credit > 0)
+            .collect();
+        let distributed: u64 = credits.iter().map(|(_, credit)| credit).sum();
+
+        Adjustments {
+            recipient_amount: amount - distributed,
+            credits,
+        }
+    }
+
+    fn needs_holders(&self) -> bool {
+        true
+    }
+}
+
+/// `rate_bps` of `amount`, rounded down
+fn share(amount: u64, rate_bps: u16) -> u64 {
+    (amount as u128 * rate_bps as u128 / BPS_DENOMINATOR) as u64
+}
+
+/// Names of the built-in hooks
+pub const BUILTIN_HOOKS: &[&str] = &["flat_tax", "reflection"];
+
+/// Instantiate the hook a token's settings refer to
+pub fn transfer_hook(config: &TransferHookConfig) -> Result<Box<dyn TransferHook>> {
+    if config.rate_bps as u128 > BPS_DENOMINATOR {
+        return Err(MemeError::InvalidTransferHook(format!(
+            "rate_bps must be at most {}, got {}", BPS_DENOMINATOR, config.rate_bps
+        )).into());
+    }
+
+    match config.name.as_str() {
+        "flat_tax" => {
+            let recipient = config.recipient.clone().ok_or_else(|| {
+                MemeError::InvalidTransferHook("flat_tax requires a recipient".to_string())
+            })?;
+            Ok(Box::new(FlatTaxHook {
+                rate_bps: config.rate_bps,
+                recipient,
+            }))
+        }
+        "reflection" => Ok(Box::new(ReflectionHook {
+            rate_bps: config.rate_bps,
+        })),
+        other => Err(MemeError::InvalidTransferHook(format!(
+            "Unknown transfer hook {}; expected one of {:?}", other, BUILTIN_HOOKS
+        )).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AntiRugSettings;
+
+    fn token() -> Token {
+        Token::new(
+            "TEST".to_string(),
+            "Test Token".to_string(),
+            1_000_000,
+            Address::from("memechain1alice"),
+            AntiRugSettings::default(),
+            0,
+        )
+    }
+
+    #[test]
+    fn test_flat_tax_hook() {
+        let token = token();
+        let ctx = TransferContext { token: &token, holders: &[] };
+        let charity = Address::from("memechain1charity");
+        let hook = FlatTaxHook { rate_bps: 250, recipient: charity.clone() };
+
+        let adjustments = hook.on_transfer(&ctx, &Address::from("memechain1alice"), &Address::from("memechain1bob"), 1000);
+        assert_eq!(adjustments, Adjustments { recipient_amount: 975, credits: vec![(charity, 25)] });
+        adjustments.validate(1000).unwrap();
+    }
+
+    #[test]
+    fn test_reflection_rounding_stays_with_recipient() {
+        let token = token();
+        let holders = vec![
+            Balance::new(Address::from("memechain1carol"), "TEST".to_string(), 1, 0),
+            Balance::new(Address::from("memechain1dave"), "TEST".to_string(), 2, 0),
+        ];
+        let ctx = TransferContext { token: &token, holders: &holders };
+        let hook = ReflectionHook { rate_bps: 1000 };
+
+        let adjustments = hook.on_transfer(&ctx, &Address::from("memechain1alice"), &Address::from("memechain1bob"), 100);
+        assert_eq!(adjustments.credits, vec![
+            (Address::from("memechain1carol"), 3),
+            (Address::from("memechain1dave"), 6),
+        ]);
+        assert_eq!(adjustments.recipient_amount, 91);
+        adjustments.validate(100).unwrap();
+    }
+
+    #[test]
+    fn test_reflection_respects_max_wallet() {
+        // 5% of 1,000,000: carol is 10 below the cap, dave is already at it
+        let token = token();
+        let holders = vec![
+            Balance::new(Address::from("memechain1carol"), "TEST".to_string(), 49_990, 0),
+            Balance::new(Address::from("memechain1dave"), "TEST".to_string(), 50_000, 0),
+        ];
+        let ctx = TransferContext { token: &token, holders: &holders };
+        let hook = ReflectionHook { rate_bps: 1000 };
+
+        let adjustments = hook.on_transfer(&ctx, &Address::from("memechain1alice"), &Address::from("memechain1bob"), 10_000);
+        assert_eq!(adjustments.credits, vec![(Address::from("memechain1carol"), 10)]);
+        assert_eq!(adjustments.recipient_amount, 9_990);
+        adjustments.validate(10_000).unwrap();
+    }
+
+    #[test]
+    fn test_hook_registry() {
+        let config = |name: &str, rate_bps: u16, recipient: Option<&str>| TransferHookConfig {
+            name: name.to_string(),
+            rate_bps,
+            recipient: recipient.map(Address::from),
+        };
+
+        assert!(transfer_hook(&config("reflection", 200, None)).is_ok());
+        assert!(transfer_hook(&config("flat_tax", 200, Some("memechain1charity"))).is_ok());
+        assert!(transfer_hook(&config("flat_tax", 200, None)).is_err());
+        assert!(transfer_hook(&config("reflection", 10_001, None)).is_err());
+        assert!(transfer_hook(&config("auto_lp", 200, None)).is_err());
+        assert!(Adjustments::passthrough(10).validate(11).is_err());
+    }
+}
@@ -1,22 +1,36 @@
+use crate::config::ChainConfig;
+use crate::clock::{system_clock, SharedClock};
 use crate::error::{MemeChainError, Result, MemeError};
+use crate::results::{
+    BurnTokenResult, BuyTokenResult, CreateTokenResult, LaunchAllocation, LaunchTokenResult, LockLiquidityResult, PauseTokenResult, RegisterWrappedResult,
+    SellTokenResult, TransferTokenResult,
+};
+use crate::modules::hooks::{self, Adjustments, TransferContext};
 use crate::storage::Storage;
-use crate::types::{Address, AntiRugSettings, Balance, Token, Transaction, TransactionResult};
+use crate::types::{
+    Address, AntiRugSettings, Balance, Token, TokenSupply, TradeQuote, TradeSide, Transaction, TransactionResult,
+    TransferHookConfig,
+};
 use serde_json::Value;
 use tracing::{debug, info, warn};
 
 /// Meme token module for managing tokens with anti-rug features
 pub struct MemeModule {
     storage: Storage,
+    config: ChainConfig,
     current_block_height: u64,
+    clock: SharedClock,
 }
 
 impl MemeModule {
     /// Create a new meme token module
-    pub async fn new(storage: Storage) -> Result<Self> {
+    pub async fn new(storage: Storage, config: ChainConfig) -> Result<Self> {
         info!("Initializing Meme token module");
         Ok(Self {
             storage,
+            config,
             current_block_height: 0,
+            clock: system_clock(),
         })
     }
 
@@ -26,10 +40,15 @@ impl MemeModule {
 
         match tx.action.as_str() {
             "create_token" => self.create_token(tx).await,
+            "launch_token" => self.launch_token(tx).await,
             "transfer" => self.transfer_token(tx).await,
+            "burn" => self.burn_token(tx).await,
             "buy" => self.buy_token(tx).await,
             "sell" => self.sell_token(tx).await,
             "lock_liquidity" => self.lock_liquidity(tx).await,
+            "pause_token" => self.set_paused(tx, true).await,
+            "unpause_token" => self.set_paused(tx, false).await,
+            "register_wrapped" => self.register_wrapped(tx).await,
             _ => Err(MemeError::InvalidSymbol(format!("Unknown action: {}", tx.action))),
         }
     }
@@ -49,42 +68,157 @@ impl MemeModule {
             .ok_or_else(|| MemeError::InvalidSupply("Missing or invalid supply".to_string()))?;
         
         let creator = tx.from;
+        validate_new_token(&self.config, &self.storage, symbol, supply).await?;
 
-        // Check if token already exists
-        if self.storage.get_token(symbol).await?.is_some() {
-            return Err(MemeError::TokenExists(symbol.to_string()));
+        // Parse anti-rug settings, either explicit or from a named preset
+        let mut anti_rug = match (tx.data.get("anti_rug"), tx.data.get("preset")) {
+            (Some(_), Some(_)) => {
+                return Err(MemeError::InvalidAntiRugSettings(
+                    "Specify either anti_rug or preset, not both".to_string()
+                ).into());
+            }
+            (Some(anti_rug_data), None) => {
+                let anti_rug: AntiRugSettings = serde_json::from_value(anti_rug_data.clone())?;
+                // Locks start at the creation block, through lock_on_create
+                if anti_rug.lock_start_block.is_some() {
+                    return Err(MemeError::InvalidAntiRugSettings(
+                        "lock_start_block cannot be set; use lock_on_create to lock liquidity".to_string()
+                    ).into());
+                }
+                anti_rug
+            }
+            (None, Some(preset)) => {
+                let preset = preset
+                    .as_str()
+                    .ok_or_else(|| MemeError::InvalidAntiRugSettings("Preset must be a string".to_string()))?;
+                AntiRugSettings::preset(preset)?
+            }
+            (None, None) => self.config.default_anti_rug(),
+        };
+        anti_rug.validate(self.config.max_total_tax_percentage)?;
+
+        // Optionally lock liquidity from the creation block onwards
+        let lock_on_create = tx.data.get("lock_on_create").map_or(Ok(false), |value| {
+            value.as_bool().ok_or_else(|| MemeError::InvalidAntiRugSettings("lock_on_create must be a boolean".to_string()))
+        })?;
+        match (lock_on_create, tx.data.get("lock_duration_blocks")) {
+            (true, duration) => {
+                if let Some(duration) = duration {
+                    anti_rug.lock_duration_blocks = duration.as_u64().ok_or_else(|| {
+                        MemeError::InvalidAmount("lock_duration_blocks must be a non-negative integer".to_string())
+                    })?;
+                }
+                validate_lock_duration(anti_rug.lock_duration_blocks)?;
+                anti_rug.lock_start_block = Some(self.current_block_height);
+            }
+            (false, Some(_)) => {
+                return Err(MemeError::InvalidAntiRugSettings(
+                    "lock_duration_blocks requires lock_on_create".to_string()
+                ).into());
+            }
+            (false, None) => {}
         }
 
-        // Parse anti-rug settings
-        let anti_rug = if let Some(anti_rug_data) = tx.data.get("anti_rug") {
-            serde_json::from_value(anti_rug_data.clone())?
-        } else {
-            AntiRugSettings::default()
+        let transfer_hook = match tx.data.get("transfer_hook") {
+            Some(hook) => {
+                let hook: TransferHookConfig = serde_json::from_value(hook.clone())
+                    .map_err(|e| MemeError::InvalidTransferHook(e.to_string()))?;
+                hooks::transfer_hook(&hook)?;
+                Some(hook)
+            }
+            None => None,
         };
+        anti_rug.validate_transfer_charges(transfer_hook.as_ref(), self.config.max_total_tax_percentage)?;
 
-        let token = Token::new(
+        let mut token = Token::new(
             symbol.to_string(),
             name.to_string(),
             supply,
             creator.clone(),
             anti_rug,
+            self.clock.now(),
         );
+        token.transfer_hook = transfer_hook;
 
         // Store token
         self.storage.store_token(&token).await?;
 
         // Create initial balance for creator
-        let initial_balance = Balance::new(creator.clone(), symbol.to_string(), supply);
+        let initial_balance = Balance::new(creator.clone(), symbol.to_string(), supply, token.created_at);
         self.storage.store_balance(&initial_balance).await?;
 
         info!("Created token: {} ({}) with supply: {} by {}", name, symbol, supply, creator);
 
-        Ok(TransactionResult::success(Some(serde_json::json!({
-            "symbol": symbol,
-            "name": name,
-            "supply": supply,
-            "creator": creator.to_string(),
-        }))))
+        Ok(CreateTokenResult {
+            symbol: symbol.to_string(),
+            name: name.to_string(),
+            supply,
+            creator,
+            lock_start_block: token.anti_rug.lock_start_block,
+        }.into())
+    }
+
+    /// Create a token and hand out initial allocations from the creator's
+    /// supply in one step; nothing is written if any allocation is rejected
+    async fn launch_token(&self, tx: Transaction) -> Result<TransactionResult> {
+        let allocations = parse_allocations(&tx.data)?;
+
+        self.storage.begin_batch();
+        match self.create_and_allocate(tx, allocations).await {
+            Ok(result) => {
+                self.storage.commit_batch().await?;
+                Ok(result.into())
+            }
+            Err(e) => {
+                self.storage.discard_batch();
+                Err(e)
+            }
+        }
+    }
+
+    async fn create_and_allocate(&self, tx: Transaction, allocations: Vec<LaunchAllocation>) -> Result<LaunchTokenResult> {
+        let creator = tx.from.clone();
+        let created = self.create_token(tx).await?;
+        let created = created
+            .typed::<CreateTokenResult>()?
+            .ok_or_else(|| MemeError::TransferFailed("Token creation returned no result".to_string()))?;
+        let token = self.storage.get_token(&created.symbol).await?
+            .ok_or_else(|| MemeError::TokenNotFound(created.symbol.clone()))?;
+
+        let total: u128 = allocations.iter().map(|allocation| allocation.amount as u128).sum();
+        if total > token.total_supply as u128 {
+            return Err(MemeError::InsufficientBalance(format!(
+                "Allocations total {} but the supply is {}", total, token.total_supply
+            )).into());
+        }
+
+        let mut creator_balance = self.storage.get_balance(&creator, &token.symbol).await?
+            .ok_or_else(|| MemeError::InsufficientBalance(format!("No balance for {}", creator)))?;
+        let now = self.clock.now();
+        for allocation in &allocations {
+            if allocation.address == creator {
+                return Err(MemeError::TransferFailed("Cannot allocate to the creator".to_string()).into());
+            }
+            if token.anti_rug.exceeds_max_wallet(0, allocation.amount, token.total_supply) {
+                return Err(MemeError::MaxWalletLimitExceeded(format!(
+                    "Allocation of {} to {} exceeds the {}% max wallet",
+                    allocation.amount, allocation.address, token.anti_rug.max_wallet_percentage
+                )).into());
+            }
+            creator_balance.subtract(allocation.amount, now)?;
+            self.storage.update_balance(&allocation.address, &token.symbol, allocation.amount as i64, now).await?;
+        }
+        self.storage.store_balance(&creator_balance).await?;
+
+        info!("Launched {} with {} allocations", token.symbol, allocations.len());
+
+        Ok(LaunchTokenResult {
+            symbol: token.symbol,
+            supply: token.total_supply,
+            creator,
+            allocations,
+            creator_balance: creator_balance.amount,
+        })
     }
 
     /// Transfer tokens
@@ -93,14 +227,21 @@ impl MemeModule {
             .as_str()
             .ok_or_else(|| MemeError::InvalidSymbol("Missing token symbol".to_string()))?;
         
-        let amount = tx.data["amount"]
-            .as_u64()
-            .ok_or_else(|| MemeError::InvalidAmount("Missing or invalid amount".to_string()))?;
+        let amount = parse_amount(&tx.data)?;
         
+        let memo = tx.memo;
         let from_address = tx.from;
         let to_address = tx.to
             .ok_or_else(|| MemeError::TransferFailed("Missing recipient address".to_string()))?;
 
+        if to_address == from_address {
+            return Err(MemeError::TransferFailed("Cannot transfer to self".to_string()).into());
+        }
+
+        let token = self.storage.get_token(token_symbol).await?
+            .ok_or_else(|| MemeError::TokenNotFound(token_symbol.to_string()))?;
+        ensure_not_paused(&token)?;
+
         // Get sender balance
         let mut from_balance = self.storage.get_balance(&from_address, token_symbol).await?
             .ok_or_else(|| MemeError::InsufficientBalance(format!("No balance for {}", from_address)))?;
@@ -112,25 +253,129 @@ impl MemeModule {
             )));
         }
 
+        // The transfer tax comes off the top; any hook splits the remainder
+        let fee = token.anti_rug.calculate_transfer_tax(amount);
+        let mut adjustments = self.transfer_adjustments(&token, &from_address, &to_address, amount - fee).await?;
+        if fee > 0 {
+            adjustments.credits.push((Address::new(self.config.treasury_address.clone()), fee));
+        }
+
         // Update balances
-        from_balance.subtract(amount)?;
-        self.storage.store_balance(&from_balance).await?;
+        let now = self.clock.now();
+        from_balance.subtract(amount, now)?;
+        let dust = self.sweepable_dust(&from_balance);
+        if dust > 0 {
+            self.storage.delete_balance(&from_address, token_symbol).await?;
+        } else {
+            self.storage.store_balance(&from_balance).await?;
+        }
 
         // Get or create recipient balance
-        let mut to_balance = self.storage.get_balance(&to_address, token_symbol).await
-            .unwrap_or_else(|_| Balance::new(to_address.clone(), token_symbol.to_string(), 0));
+        let mut to_balance = self.storage.get_balance(&to_address, token_symbol).await?
+            .unwrap_or_else(|| Balance::new(to_address.clone(), token_symbol.to_string(), 0, now));
         
-        to_balance.add(amount);
+        to_balance.add(adjustments.recipient_amount, now);
         self.storage.store_balance(&to_balance).await?;
 
-        info!("Transferred {} {} from {} to {}", amount, token_symbol, from_address, to_address);
+        for (address, credit) in &adjustments.credits {
+            self.storage.update_balance(address, token_symbol, *credit as i64, now).await?;
+        }
+
+        // Credited last so it adds to any treasury balance written above
+        if dust > 0 {
+            let treasury = Address::new(self.config.treasury_address.clone());
+            self.storage.update_balance(&treasury, token_symbol, dust as i64, now).await?;
+            debug!("Swept {} {} of dust from {} to the treasury", dust, token_symbol, from_address);
+        }
+
+        info!("Transferred {} {} from {} to {} (fee: {})", amount, token_symbol, from_address, to_address, fee);
+
+        Ok(TransferTokenResult {
+            token: token_symbol.to_string(),
+            amount,
+            fee,
+            from: from_address,
+            to: to_address,
+            memo,
+        }.into())
+    }
+
+    /// The remainder of `balance` to sweep to the treasury: all of it when it
+    /// is non-zero but under `dust_threshold`, otherwise nothing
+    fn sweepable_dust(&self, balance: &Balance) -> u64 {
+        let is_dust = balance.amount > 0 && balance.amount < self.config.dust_threshold;
+        if is_dust && balance.address.as_str() != self.config.treasury_address {
+            balance.amount
+        } else {
+            0
+        }
+    }
+
+    /// Burn tokens from the sender's balance, shrinking the supply
+    async fn burn_token(&self, tx: Transaction) -> Result<TransactionResult> {
+        let token_symbol = tx.data["token"]
+            .as_str()
+            .ok_or_else(|| MemeError::InvalidSymbol("Missing token symbol".to_string()))?;
+        
+        let amount = parse_amount(&tx.data)?;
+        let burner = tx.from;
+
+        let mut token = self.storage.get_token(token_symbol).await?
+            .ok_or_else(|| MemeError::TokenNotFound(token_symbol.to_string()))?;
+        ensure_not_paused(&token)?;
+
+        let mut balance = self.storage.get_balance(&burner, token_symbol).await?
+            .ok_or_else(|| MemeError::InsufficientBalance(format!("No balance for {}", burner)))?;
+        if balance.amount < amount {
+            return Err(MemeError::InsufficientBalance(format!(
+                "Insufficient balance: {} < {}", balance.amount, amount
+            )).into());
+        }
+        let now = self.clock.now();
+        balance.subtract(amount, now)?;
+        self.storage.store_balance(&balance).await?;
+
+        token.total_supply = token.total_supply.saturating_sub(amount);
+        token.burned = token.burned.saturating_add(amount);
+        token.updated_at = now;
+        self.storage.store_token(&token).await?;
+
+        info!("{} burned {} {}", burner, amount, token_symbol);
 
-        Ok(TransactionResult::success(Some(serde_json::json!({
-            "token": token_symbol,
-            "amount": amount,
-            "from": from_address.to_string(),
-            "to": to_address.to_string(),
-        }))))
+        Ok(BurnTokenResult {
+            token: token_symbol.to_string(),
+            amount,
+            burned_by: burner,
+            total_supply: token.total_supply,
+        }.into())
+    }
+
+    /// Ask the token's transfer hook, if any, how to split a transfer
+    async fn transfer_adjustments(
+        &self,
+        token: &Token,
+        from: &Address,
+        to: &Address,
+        amount: u64,
+    ) -> Result<Adjustments> {
+        let Some(config) = &token.transfer_hook else {
+            return Ok(Adjustments::passthrough(amount));
+        };
+
+        let hook = hooks::transfer_hook(config)?;
+        let holders = if hook.needs_holders() {
+            self.storage.top_holders(&token.symbol, hooks::MAX_REFLECTION_HOLDERS, &[from.clone(), to.clone()]).await?
+        } else {
+            Vec::new()
+        };
+
+        let ctx = TransferContext { token, holders: &holders };
+        let adjustments = hook.on_transfer(&ctx, from, to, amount);
+        adjustments.validate(amount)?;
+        if adjustments.credits.iter().any(|(address, _)| address == from) {
+            return Err(MemeError::TransferFailed("Transfer hook cannot credit the sender".to_string()).into());
+        }
+        Ok(adjustments)
     }
 
     /// Buy tokens (simulated DEX interaction)
@@ -139,35 +384,47 @@ impl MemeModule {
             .as_str()
             .ok_or_else(|| MemeError::InvalidSymbol("Missing token symbol".to_string()))?;
         
-        let amount = tx.data["amount"]
-            .as_u64()
-            .ok_or_else(|| MemeError::InvalidAmount("Missing or invalid amount".to_string()))?;
+        let amount = parse_amount(&tx.data)?;
         
         let buyer = tx.from;
 
         // Get token
         let token = self.storage.get_token(token_symbol).await?
             .ok_or_else(|| MemeError::TokenNotFound(token_symbol.to_string()))?;
+        ensure_not_paused(&token)?;
 
-        // Calculate buy tax
-        let buy_tax = token.anti_rug.calculate_buy_tax(amount);
-        let tokens_received = amount - buy_tax;
+        // Pay for the tokens in the native currency
+        let native_symbol = self.config.native_symbol.as_str();
+        let mut payment_balance = self.storage.get_balance(&buyer, native_symbol).await?
+            .ok_or_else(|| MemeError::InsufficientBalance(format!("No {} balance for {}", native_symbol, buyer)))?;
+        if payment_balance.amount < amount {
+            return Err(MemeError::InsufficientBalance(format!(
+                "Insufficient {} balance: {} < {}", native_symbol, payment_balance.amount, amount
+            )));
+        }
+        let now = self.clock.now();
+        payment_balance.subtract(amount, now)?;
+        self.storage.store_balance(&payment_balance).await?;
+
+        let quote = quote_trade(&token, TradeSide::Buy, amount);
+        let buy_tax = quote.tax;
+        let tokens_received = quote.amount_out;
 
         // Get or create buyer balance
-        let mut buyer_balance = self.storage.get_balance(&buyer, token_symbol).await
-            .unwrap_or_else(|_| Balance::new(buyer.clone(), token_symbol.to_string(), 0));
+        let mut buyer_balance = self.storage.get_balance(&buyer, token_symbol).await?
+            .unwrap_or_else(|| Balance::new(buyer.clone(), token_symbol.to_string(), 0, now));
         
-        buyer_balance.add(tokens_received);
+        buyer_balance.add(tokens_received, now);
         self.storage.store_balance(&buyer_balance).await?;
 
         info!("Buy: {} received {} {} (tax: {})", buyer, tokens_received, token_symbol, buy_tax);
 
-        Ok(TransactionResult::success(Some(serde_json::json!({
-            "token": token_symbol,
-            "amount": tokens_received,
-            "tax": buy_tax,
-            "buyer": buyer.to_string(),
-        }))))
+        Ok(BuyTokenResult {
+            token: token_symbol.to_string(),
+            amount: tokens_received,
+            tax: buy_tax,
+            buyer,
+        }.into())
     }
 
     /// Sell tokens (simulated DEX interaction)
@@ -176,15 +433,14 @@ impl MemeModule {
             .as_str()
             .ok_or_else(|| MemeError::InvalidSymbol("Missing token symbol".to_string()))?;
         
-        let amount = tx.data["amount"]
-            .as_u64()
-            .ok_or_else(|| MemeError::InvalidAmount("Missing or invalid amount".to_string()))?;
+        let amount = parse_amount(&tx.data)?;
         
         let seller = tx.from;
 
         // Get token
         let token = self.storage.get_token(token_symbol).await?
             .ok_or_else(|| MemeError::TokenNotFound(token_symbol.to_string()))?;
+        ensure_not_paused(&token)?;
 
         // Check if liquidity is locked
         if token.anti_rug.is_liquidity_locked(self.current_block_height) {
@@ -202,22 +458,26 @@ impl MemeModule {
             )));
         }
 
-        // Calculate sell tax
-        let sell_tax = token.anti_rug.calculate_sell_tax(amount);
-        let tokens_sold = amount - sell_tax;
+        let quote = quote_trade(&token, TradeSide::Sell, amount);
+        let sell_tax = quote.tax;
+        let tokens_sold = quote.amount_out;
 
         // Update seller balance
-        seller_balance.subtract(amount)?;
+        let now = self.clock.now();
+        seller_balance.subtract(amount, now)?;
         self.storage.store_balance(&seller_balance).await?;
 
+        // Pay out the proceeds in the native currency
+        self.storage.update_balance(&seller, &self.config.native_symbol, tokens_sold as i64, now).await?;
+
         info!("Sell: {} sold {} {} (tax: {})", seller, tokens_sold, token_symbol, sell_tax);
 
-        Ok(TransactionResult::success(Some(serde_json::json!({
-            "token": token_symbol,
-            "amount": tokens_sold,
-            "tax": sell_tax,
-            "seller": seller.to_string(),
-        }))))
+        Ok(SellTokenResult {
+            token: token_symbol.to_string(),
+            amount: tokens_sold,
+            tax: sell_tax,
+            seller,
+        }.into())
     }
 
     /// Lock liquidity
@@ -229,6 +489,7 @@ impl MemeModule {
         let lock_duration = tx.data["duration_blocks"]
             .as_u64()
             .ok_or_else(|| MemeError::InvalidAmount("Missing lock duration".to_string()))?;
+        validate_lock_duration(lock_duration)?;
         
         let locker = tx.from;
 
@@ -244,19 +505,86 @@ impl MemeModule {
         // Set lock parameters
         token.anti_rug.lock_start_block = Some(self.current_block_height);
         token.anti_rug.lock_duration_blocks = lock_duration;
-        token.updated_at = chrono::Utc::now().timestamp();
+        token.updated_at = self.clock.now();
 
         // Store updated token
         self.storage.store_token(&token).await?;
 
         info!("Liquidity locked for token: {} by {} for {} blocks", token_symbol, locker, lock_duration);
 
-        Ok(TransactionResult::success(Some(serde_json::json!({
-            "token": token_symbol,
-            "lock_start_block": self.current_block_height,
-            "lock_duration_blocks": lock_duration,
-            "locked_by": locker.to_string(),
-        }))))
+        Ok(LockLiquidityResult {
+            token: token_symbol.to_string(),
+            lock_start_block: self.current_block_height,
+            lock_duration_blocks: lock_duration,
+            locked_by: locker,
+        }.into())
+    }
+
+    /// Pause or unpause all transfers and trades of a token (creator only)
+    async fn set_paused(&self, tx: Transaction, paused: bool) -> Result<TransactionResult> {
+        let token_symbol = tx.data["token"]
+            .as_str()
+            .ok_or_else(|| MemeError::InvalidSymbol("Missing token symbol".to_string()))?;
+        
+        let sender = tx.from;
+
+        let mut token = self.storage.get_token(token_symbol).await?
+            .ok_or_else(|| MemeError::TokenNotFound(token_symbol.to_string()))?;
+
+        if token.creator != sender {
+            return Err(MemeError::Unauthorized("Only token creator can pause or unpause".to_string()).into());
+        }
+
+        token.paused = paused;
+        token.updated_at = self.clock.now();
+        self.storage.store_token(&token).await?;
+
+        info!("Token {} {} by {}", token_symbol, if paused { "paused" } else { "unpaused" }, sender);
+
+        Ok(PauseTokenResult {
+            token: token_symbol.to_string(),
+            paused,
+            updated_by: sender,
+        }.into())
+    }
+
+    /// Mark a token as a wrapped representation of an asset on another chain
+    /// (token creator or a configured bridge; the origin cannot be changed)
+    async fn register_wrapped(&self, tx: Transaction) -> Result<TransactionResult> {
+        let token_symbol = tx.data["token"]
+            .as_str()
+            .ok_or_else(|| MemeError::InvalidSymbol("Missing token symbol".to_string()))?;
+        let origin_chain = origin_field(&tx.data, "origin_chain", MAX_ORIGIN_CHAIN_LEN)?;
+        let origin_asset = origin_field(&tx.data, "origin_asset", MAX_ORIGIN_ASSET_LEN)?;
+        
+        let sender = tx.from;
+
+        let mut token = self.storage.get_token(token_symbol).await?
+            .ok_or_else(|| MemeError::TokenNotFound(token_symbol.to_string()))?;
+
+        let is_bridge = self.config.bridge_addresses.iter().any(|bridge| Address::new(bridge.clone()) == sender);
+        if token.creator != sender && !is_bridge {
+            return Err(MemeError::Unauthorized(
+                "Only the token creator or a bridge can register a wrapped token".to_string()
+            ).into());
+        }
+        if token.is_wrapped() {
+            return Err(MemeError::InvalidSymbol(format!("Token {} is already wrapped", token_symbol)).into());
+        }
+
+        token.origin_chain = Some(origin_chain.clone());
+        token.origin_asset = Some(origin_asset.clone());
+        token.updated_at = self.clock.now();
+        self.storage.store_token(&token).await?;
+
+        info!("Registered {} as wrapped {} from {}", token_symbol, origin_asset, origin_chain);
+
+        Ok(RegisterWrappedResult {
+            token: token_symbol.to_string(),
+            origin_chain,
+            origin_asset,
+            registered_by: sender,
+        }.into())
     }
 
     /// Update current block height
@@ -264,11 +592,38 @@ impl MemeModule {
         self.current_block_height = height;
     }
 
+    /// Replace the time source
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
+    }
+
     /// Get token by symbol
     pub async fn get_token(&self, symbol: &str) -> Result<Option<Token>> {
         self.storage.get_token(symbol).await
     }
 
+    /// Supply breakdown of a token, or `None` if it does not exist
+    pub async fn get_supply(&self, symbol: &str) -> Result<Option<TokenSupply>> {
+        let Some(token) = self.storage.get_token(symbol).await? else {
+            return Ok(None);
+        };
+        let held_by = |address: &str| {
+            let address = Address::new(address.to_string());
+            async move {
+                Ok::<_, MemeChainError>(self.storage.get_balance(&address, symbol).await?.map_or(0, |balance| balance.amount))
+            }
+        };
+        let treasury = held_by(&self.config.treasury_address).await?;
+        let burn_address = held_by(&self.config.burn_address).await?;
+
+        Ok(Some(TokenSupply {
+            total_supply: token.total_supply,
+            circulating_supply: token.total_supply.saturating_sub(treasury).saturating_sub(burn_address),
+            burned: token.burned,
+            treasury,
+        }))
+    }
+
     /// Get balance
     pub async fn get_balance(&self, address: &Address, token: &str) -> Result<Option<Balance>> {
         self.storage.get_balance(address, token).await
@@ -284,8 +639,14 @@ impl MemeModule {
                 "symbol": token.symbol,
                 "name": token.name,
                 "total_supply": token.total_supply,
+                "decimals": token.decimals,
                 "creator": token.creator.to_string(),
                 "anti_rug": token.anti_rug,
+                "paused": token.paused,
+                "wrapped": token.is_wrapped(),
+                "origin_chain": token.origin_chain,
+                "origin_asset": token.origin_asset,
+                "transfer_hook": token.transfer_hook,
                 "created_at": token.created_at,
                 "updated_at": token.updated_at,
             }));
@@ -295,10 +656,133 @@ impl MemeModule {
     }
 }
 
+/// Price a buy or sell of `amount` against the current pool state.
+///
+/// Trades settle one-to-one with the native token, so the only cost is the
+/// token's tax and there is no price impact.
+pub fn quote_trade(token: &Token, side: TradeSide, amount: u64) -> TradeQuote {
+    let tax = match side {
+        TradeSide::Buy => token.anti_rug.calculate_buy_tax(amount),
+        TradeSide::Sell => token.anti_rug.calculate_sell_tax(amount),
+    };
+    TradeQuote {
+        side,
+        amount_in: amount,
+        amount_out: amount - tax,
+        tax,
+        price_impact_bps: 0,
+    }
+}
+
+/// Most allocations a single `launch_token` may make
+pub const MAX_LAUNCH_ALLOCATIONS: usize = 100;
+
+/// Parse a `launch_token` allocation list: distinct addresses, non-zero amounts
+fn parse_allocations(data: &Value) -> Result<Vec<LaunchAllocation>> {
+    let allocations: Vec<LaunchAllocation> = match data.get("allocations") {
+        Some(allocations) => serde_json::from_value(allocations.clone())
+            .map_err(|e| MemeError::InvalidAmount(format!("Invalid allocations: {}", e)))?,
+        None => return Err(MemeError::InvalidAmount("Missing allocations".to_string()).into()),
+    };
+    if allocations.len() > MAX_LAUNCH_ALLOCATIONS {
+        return Err(MemeError::InvalidAmount(format!(
+            "{} allocations, at most {} allowed", allocations.len(), MAX_LAUNCH_ALLOCATIONS
+        )).into());
+    }
+    let mut seen = std::collections::HashSet::new();
+    for allocation in &allocations {
+        if allocation.amount == 0 {
+            return Err(MemeError::InvalidAmount(format!("Zero allocation to {}", allocation.address)).into());
+        }
+        if !seen.insert(&allocation.address) {
+            return Err(MemeError::InvalidAmount(format!("Duplicate allocation to {}", allocation.address)).into());
+        }
+    }
+    Ok(allocations)
+}
+
+/// Read a non-zero `amount` from transaction data
+fn parse_amount(data: &Value) -> Result<u64> {
+    let amount = data["amount"]
+        .as_u64()
+        .ok_or_else(|| MemeError::InvalidAmount("Missing or invalid amount".to_string()))?;
+    if amount == 0 {
+        return Err(MemeError::InvalidAmount("Amount cannot be zero".to_string()).into());
+    }
+    Ok(amount)
+}
+
+/// Longest accepted `origin_chain` of a wrapped token
+const MAX_ORIGIN_CHAIN_LEN: usize = 64;
+
+/// Longest liquidity lock, in blocks (about ten years at one block a second)
+pub const MAX_LOCK_DURATION_BLOCKS: u64 = 315_360_000;
+
+/// A liquidity lock must last at least one block and at most `MAX_LOCK_DURATION_BLOCKS`
+fn validate_lock_duration(duration: u64) -> Result<()> {
+    if duration == 0 || duration > MAX_LOCK_DURATION_BLOCKS {
+        return Err(MemeError::InvalidAmount(format!(
+            "Lock duration must be between 1 and {} blocks, got {}", MAX_LOCK_DURATION_BLOCKS, duration
+        )).into());
+    }
+    Ok(())
+}
+/// Longest accepted `origin_asset` of a wrapped token
+const MAX_ORIGIN_ASSET_LEN: usize = 128;
+
+/// Read a required, length-bounded origin field of a wrapped token
+fn origin_field(data: &Value, field: &str, max_len: usize) -> Result<String> {
+    let value = data[field]
+        .as_str()
+        .map(str::trim)
+        .ok_or_else(|| MemeError::InvalidSymbol(format!("Missing {}", field)))?;
+    if value.is_empty() || value.len() > max_len {
+        return Err(MemeError::InvalidSymbol(format!(
+            "{} must be 1 to {} characters", field, max_len
+        )).into());
+    }
+    Ok(value.to_string())
+}
+
+/// Check a token about to be created against the chain's rules: a free,
+/// unreserved symbol and a supply within bounds and under the total cap.
+/// Shared by `create_token` and the ERC20 import.
+pub(crate) async fn validate_new_token(config: &ChainConfig, storage: &Storage, symbol: &str, supply: u64) -> Result<()> {
+    if config.is_reserved_symbol(symbol) {
+        return Err(MemeError::InvalidSymbol(format!("Symbol {} is reserved", symbol)).into());
+    }
+    if supply < config.min_supply || supply > config.max_supply {
+        return Err(MemeError::InvalidSupply(format!(
+            "Supply {} outside allowed range {}..={}", supply, config.min_supply, config.max_supply
+        )).into());
+    }
+    if storage.get_token(symbol).await?.is_some() {
+        return Err(MemeError::TokenExists(symbol.to_string()));
+    }
+    if let Some(cap) = config.total_token_cap {
+        let total = storage.total_token_supply().await?;
+        if total + supply as u128 > cap as u128 {
+            return Err(MemeError::InvalidSupply(format!(
+                "Supply {} would exceed the total token cap {} ({} already issued)", supply, cap, total
+            )).into());
+        }
+    }
+    Ok(())
+}
+
+/// Reject transfers and trades of a paused token
+fn ensure_not_paused(token: &Token) -> Result<()> {
+    if token.paused {
+        return Err(MemeError::TokenPaused(token.symbol.clone()).into());
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::StorageConfig;
+    use crate::error::ModuleError;
     use tempfile::tempdir;
 
     async fn create_test_storage() -> Storage {
@@ -310,6 +794,7 @@ mod tests {
             db_type: "rocksdb".to_string(),
             cache_size: 100,
             enable_compression: false,
+            ..StorageConfig::default()
         };
         
         Storage::new(&config).await.unwrap()
@@ -318,7 +803,7 @@ mod tests {
     #[tokio::test]
     async fn test_create_token() {
         let storage = create_test_storage().await;
-        let module = MemeModule::new(storage).await.unwrap();
+        let module = MemeModule::new(storage, ChainConfig::default()).await.unwrap();
         
         let tx = Transaction::new(
             "meme".to_string(),
@@ -330,6 +815,7 @@ mod tests {
                 "symbol": "TEST",
                 "supply": 1000000
             }),
+            0,
         );
         
         let result = module.process_transaction(tx).await.unwrap();
@@ -339,7 +825,7 @@ mod tests {
     #[tokio::test]
     async fn test_transfer_token() {
         let storage = create_test_storage().await;
-        let module = MemeModule::new(storage).await.unwrap();
+        let module = MemeModule::new(storage, ChainConfig::default()).await.unwrap();
         
         // First create a token
         let create_tx = Transaction::new(
@@ -352,6 +838,7 @@ mod tests {
                 "symbol": "TEST",
                 "supply": 1000000
             }),
+            0,
         );
         
         module.process_transaction(create_tx).await.unwrap();
@@ -366,9 +853,604 @@ mod tests {
                 "token": "TEST",
                 "amount": 1000
             }),
+            0,
         );
         
         let result = module.process_transaction(transfer_tx).await.unwrap();
         assert!(result.success);
     }
+
+    async fn module_with_token() -> MemeModule {
+        let storage = create_test_storage().await;
+        let module = MemeModule::new(storage, ChainConfig::default()).await.unwrap();
+        
+        let create_tx = Transaction::new(
+            "meme".to_string(),
+            "create_token".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({
+                "name": "Test Token",
+                "symbol": "TEST",
+                "supply": 1000000
+            }),
+            0,
+        );
+        module.process_transaction(create_tx).await.unwrap();
+        module
+    }
+
+    fn transfer_tx(to: &str, amount: u64) -> Transaction {
+        Transaction::new(
+            "meme".to_string(),
+            "transfer".to_string(),
+            Address::new("memechain1alice".to_string()),
+            Some(Address::new(to.to_string())),
+            serde_json::json!({
+                "token": "TEST",
+                "amount": amount
+            }),
+            0,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_zero_amount_transfer_rejected() {
+        let module = module_with_token().await;
+        
+        let err = module.process_transaction(transfer_tx("memechain1bob", 0)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::InvalidAmount(_)))));
+    }
+
+    #[tokio::test]
+    async fn test_self_transfer_rejected() {
+        let module = module_with_token().await;
+        
+        let err = module.process_transaction(transfer_tx("MemeChain1Alice", 1000)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::TransferFailed(_)))));
+        
+        let balance = module.get_balance(&Address::from("memechain1alice"), "TEST").await.unwrap().unwrap();
+        assert_eq!(balance.amount, 1000000);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_moves_balance() {
+        let module = module_with_token().await;
+        
+        let result = module.process_transaction(transfer_tx("memechain1bob", 1000)).await.unwrap();
+        assert!(result.success);
+        
+        let alice = module.get_balance(&Address::from("memechain1alice"), "TEST").await.unwrap().unwrap();
+        let bob = module.get_balance(&Address::from("memechain1bob"), "TEST").await.unwrap().unwrap();
+        assert_eq!(alice.amount, 999000);
+        assert_eq!(bob.amount, 1000);
+    }
+
+    #[tokio::test]
+    async fn test_supply_bounds() {
+        let storage = create_test_storage().await;
+        let config = ChainConfig {
+            max_supply: 1_000_000,
+            ..ChainConfig::default()
+        };
+        let module = MemeModule::new(storage, config).await.unwrap();
+        let create = |symbol: &str, supply: u64| Transaction::new(
+            "meme".to_string(),
+            "create_token".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({
+                "name": "Test Token",
+                "symbol": symbol,
+                "supply": supply
+            }),
+            0,
+        );
+        
+        let err = module.process_transaction(create("ZERO", 0)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::InvalidSupply(_)))));
+        
+        assert!(module.process_transaction(create("MAX", 1_000_000)).await.unwrap().success);
+        
+        let err = module.process_transaction(create("OVER", 1_000_001)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::InvalidSupply(_)))));
+    }
+
+    #[tokio::test]
+    async fn test_create_token_with_preset() {
+        let storage = create_test_storage().await;
+        let module = MemeModule::new(storage, ChainConfig::default()).await.unwrap();
+        let create = |symbol: &str, preset: &str| Transaction::new(
+            "meme".to_string(),
+            "create_token".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({
+                "name": "Test Token",
+                "symbol": symbol,
+                "supply": 1000000,
+                "preset": preset
+            }),
+            0,
+        );
+        
+        module.process_transaction(create("SAFE", "safe")).await.unwrap();
+        let token = module.get_token("SAFE").await.unwrap().unwrap();
+        assert_eq!(token.anti_rug.max_wallet_percentage, 2);
+        assert_eq!(token.anti_rug.liquidity_locked_percentage, 100);
+        
+        let err = module.process_transaction(create("BAD", "yolo")).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::InvalidAntiRugSettings(_)))));
+    }
+
+    #[tokio::test]
+    async fn test_create_token_rejects_invalid_anti_rug() {
+        let storage = create_test_storage().await;
+        let module = MemeModule::new(storage, ChainConfig::default()).await.unwrap();
+        let create = |symbol: &str, buy_tax: u8, max_wallet: u8| Transaction::new(
+            "meme".to_string(),
+            "create_token".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({
+                "name": "Test Token",
+                "symbol": symbol,
+                "supply": 1000000,
+                "anti_rug": {
+                    "max_wallet_percentage": max_wallet,
+                    "buy_tax_percentage": buy_tax,
+                    "sell_tax_percentage": 3,
+                    "liquidity_locked_percentage": 80,
+                    "lock_duration_blocks": 1000,
+                    "lock_start_block": null
+                }
+            }),
+            0,
+        );
+        
+        let err = module.process_transaction(create("TAX", 150, 5)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::InvalidAntiRugSettings(_)))));
+        
+        let err = module.process_transaction(create("WALLET", 2, 0)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::InvalidAntiRugSettings(_)))));
+        
+        assert!(module.process_transaction(create("OK", 2, 5)).await.unwrap().success);
+    }
+
+    fn pause_tx(from: &str, action: &str) -> Transaction {
+        Transaction::new(
+            "meme".to_string(),
+            action.to_string(),
+            Address::new(from.to_string()),
+            None,
+            serde_json::json!({"token": "TEST"}),
+            0,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_paused_token_rejects_transfers() {
+        let module = module_with_token().await;
+        
+        module.process_transaction(pause_tx("memechain1alice", "pause_token")).await.unwrap();
+        let err = module.process_transaction(transfer_tx("memechain1bob", 1000)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::TokenPaused(_)))));
+        
+        module.process_transaction(pause_tx("memechain1alice", "unpause_token")).await.unwrap();
+        let result = module.process_transaction(transfer_tx("memechain1bob", 1000)).await.unwrap();
+        assert!(result.success);
+    }
+
+    #[tokio::test]
+    async fn test_only_creator_can_pause() {
+        let module = module_with_token().await;
+        
+        let err = module.process_transaction(pause_tx("memechain1bob", "pause_token")).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::Unauthorized(_)))));
+        
+        module.process_transaction(pause_tx("memechain1alice", "pause_token")).await.unwrap();
+        let err = module.process_transaction(pause_tx("memechain1bob", "unpause_token")).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::Unauthorized(_)))));
+        assert!(module.get_token("TEST").await.unwrap().unwrap().paused);
+    }
+
+    fn wrap_tx(from: &str, origin_chain: &str, origin_asset: &str) -> Transaction {
+        Transaction::new(
+            "meme".to_string(),
+            "register_wrapped".to_string(),
+            Address::new(from.to_string()),
+            None,
+            serde_json::json!({
+                "token": "TEST",
+                "origin_chain": origin_chain,
+                "origin_asset": origin_asset,
+            }),
+            0,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_register_wrapped_token() {
+        let module = module_with_token().await;
+        let usdc = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+        
+        let err = module.process_transaction(wrap_tx("memechain1bob", "ethereum", usdc)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::Unauthorized(_)))));
+        assert!(module.process_transaction(wrap_tx("memechain1alice", "", usdc)).await.is_err());
+        assert!(module.process_transaction(wrap_tx("memechain1alice", "ethereum", &"a".repeat(129))).await.is_err());
+        
+        module.process_transaction(wrap_tx("memechain1alice", "ethereum", usdc)).await.unwrap();
+        let token = module.get_token("TEST").await.unwrap().unwrap();
+        assert!(token.is_wrapped());
+        assert_eq!(token.origin_chain.as_deref(), Some("ethereum"));
+        assert_eq!(token.origin_asset.as_deref(), Some(usdc));
+        
+        let listed = module.list_tokens().await.unwrap();
+        let listed = listed.iter().find(|token| token["symbol"] == "TEST").unwrap();
+        assert_eq!(listed["wrapped"], true);
+        assert_eq!(listed["origin_chain"], "ethereum");
+        assert_eq!(listed["origin_asset"], usdc);
+        
+        // The origin cannot be re-pointed
+        assert!(module.process_transaction(wrap_tx("memechain1alice", "solana", usdc)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bridge_can_register_wrapped() {
+        let storage = create_test_storage().await;
+        let config = ChainConfig {
+            bridge_addresses: vec!["memechain1bridge".to_string()],
+            ..ChainConfig::default()
+        };
+        let module = MemeModule::new(storage, config).await.unwrap();
+        let create_tx = Transaction::new(
+            "meme".to_string(),
+            "create_token".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({"name": "Test Token", "symbol": "TEST", "supply": 1000000}),
+            0,
+        );
+        module.process_transaction(create_tx).await.unwrap();
+        
+        module.process_transaction(wrap_tx("memechain1bridge", "ethereum", "0xdead")).await.unwrap();
+        assert!(module.get_token("TEST").await.unwrap().unwrap().is_wrapped());
+    }
+
+    #[tokio::test]
+    async fn test_reflection_hook_distributes_to_holders() {
+        let storage = create_test_storage().await;
+        let module = MemeModule::new(storage, ChainConfig::default()).await.unwrap();
+        let create_tx = Transaction::new(
+            "meme".to_string(),
+            "create_token".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({
+                "name": "Reflect",
+                "symbol": "TEST",
+                "supply": 1000000,
+                "transfer_hook": {"name": "reflection", "rate_bps": 1000},
+            }),
+            0,
+        );
+        module.process_transaction(create_tx).await.unwrap();
+        let balance = |name: &'static str| {
+            let module = &module;
+            async move {
+                module.get_balance(&Address::from(name), "TEST").await.unwrap().map_or(0, |b| b.amount)
+            }
+        };
+        
+        // No other holders yet: the recipient gets everything
+        module.process_transaction(transfer_tx("memechain1bob", 1000)).await.unwrap();
+        assert_eq!(balance("memechain1bob").await, 1000);
+        
+        // Bob is the only other holder and receives the 10% reflection
+        module.process_transaction(transfer_tx("memechain1carol", 1000)).await.unwrap();
+        assert_eq!(balance("memechain1bob").await, 1100);
+        assert_eq!(balance("memechain1carol").await, 900);
+        
+        // Split pro rata between bob (1100) and carol (900)
+        module.process_transaction(transfer_tx("memechain1dave", 1000)).await.unwrap();
+        assert_eq!(balance("memechain1bob").await, 1155);
+        assert_eq!(balance("memechain1carol").await, 945);
+        assert_eq!(balance("memechain1dave").await, 900);
+        assert_eq!(balance("memechain1alice").await, 1000000 - 3000);
+    }
+
+    #[tokio::test]
+    async fn test_create_token_rejects_unknown_hook() {
+        let storage = create_test_storage().await;
+        let module = MemeModule::new(storage, ChainConfig::default()).await.unwrap();
+        let create_tx = Transaction::new(
+            "meme".to_string(),
+            "create_token".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({
+                "name": "Test Token",
+                "symbol": "TEST",
+                "supply": 1000000,
+                "transfer_hook": {"name": "auto_lp", "rate_bps": 100},
+            }),
+            0,
+        );
+        let err = module.process_transaction(create_tx).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::InvalidTransferHook(_)))));
+    }
+
+    fn create_tx(symbol: &str, supply: u64) -> Transaction {
+        Transaction::new(
+            "meme".to_string(),
+            "create_token".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({"name": symbol, "symbol": symbol, "supply": supply}),
+            0,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_total_token_cap() {
+        let storage = create_test_storage().await;
+        let config = ChainConfig {
+            total_token_cap: Some(1_000_000),
+            ..ChainConfig::default()
+        };
+        let module = MemeModule::new(storage, config).await.unwrap();
+        
+        module.process_transaction(create_tx("PEPE", 600_000)).await.unwrap();
+        module.process_transaction(create_tx("DOGE", 400_000)).await.unwrap();
+        
+        let err = module.process_transaction(create_tx("WOJAK", 1)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::InvalidSupply(_)))));
+        assert!(module.get_token("WOJAK").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_chain_anti_rug_defaults() {
+        let storage = create_test_storage().await;
+        let config = ChainConfig {
+            anti_rug_defaults: Some(crate::config::AntiRugSettings {
+                max_wallet_percentage: 3,
+                buy_tax_percentage: 1,
+                sell_tax_percentage: 1,
+                transfer_tax_percentage: 0,
+                liquidity_locked_percentage: 90,
+                lock_duration_blocks: 5000,
+            }),
+            ..ChainConfig::default()
+        };
+        let module = MemeModule::new(storage, config).await.unwrap();
+
+        module.process_transaction(create_tx("PEPE", 1_000_000)).await.unwrap();
+
+        let anti_rug = module.get_token("PEPE").await.unwrap().unwrap().anti_rug;
+        assert_eq!(anti_rug.max_wallet_percentage, 3);
+        assert_eq!(anti_rug.buy_tax_percentage, 1);
+        assert_eq!(anti_rug.sell_tax_percentage, 1);
+        assert_eq!(anti_rug.liquidity_locked_percentage, 90);
+        assert_eq!(anti_rug.lock_duration_blocks, 5000);
+    }
+
+    #[tokio::test]
+    async fn test_lock_on_create() {
+        let storage = create_test_storage().await;
+        let mut module = MemeModule::new(storage, ChainConfig::default()).await.unwrap();
+        module.update_block_height(5);
+
+        let mut tx = create_tx("PEPE", 1_000_000);
+        tx.data["lock_on_create"] = serde_json::json!(true);
+        tx.data["lock_duration_blocks"] = serde_json::json!(100);
+        let result = module.process_transaction(tx).await.unwrap();
+        let result: CreateTokenResult = result.typed().unwrap().unwrap();
+        assert_eq!(result.lock_start_block, Some(5));
+
+        let token = module.get_token("PEPE").await.unwrap().unwrap();
+        let status = token.anti_rug.lock_status(5, 6);
+        assert!(status.locked);
+        assert_eq!((status.lock_start_block, status.remaining_blocks), (Some(5), 100));
+        assert_eq!(status.remaining_seconds, 600);
+
+        // Without lock_on_create nothing is locked, and a duration alone is rejected
+        module.process_transaction(create_tx("DOGE", 1_000_000)).await.unwrap();
+        assert!(!module.get_token("DOGE").await.unwrap().unwrap().anti_rug.lock_status(5, 6).locked);
+
+        let mut tx = create_tx("WOJAK", 1_000_000);
+        tx.data["lock_duration_blocks"] = serde_json::json!(100);
+        assert!(module.process_transaction(tx).await.is_err());
+
+        for duration in [0, MAX_LOCK_DURATION_BLOCKS + 1] {
+            let mut tx = create_tx("WOJAK", 1_000_000);
+            tx.data["lock_on_create"] = serde_json::json!(true);
+            tx.data["lock_duration_blocks"] = serde_json::json!(duration);
+            let err = module.process_transaction(tx).await.unwrap_err();
+            assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::InvalidAmount(_)))));
+        }
+
+        // A lock cannot be smuggled in through explicit settings
+        let mut tx = create_tx("WOJAK", 1_000_000);
+        let mut anti_rug = AntiRugSettings::default();
+        anti_rug.lock_start_block = Some(0);
+        anti_rug.lock_duration_blocks = u64::MAX;
+        tx.data["anti_rug"] = serde_json::to_value(&anti_rug).unwrap();
+        let err = module.process_transaction(tx).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::InvalidAntiRugSettings(_)))));
+    }
+
+    #[tokio::test]
+    async fn test_reserved_symbols_rejected() {
+        let storage = create_test_storage().await;
+        let config = ChainConfig {
+            reserved_symbols: vec!["ADMIN".to_string()],
+            ..ChainConfig::default()
+        };
+        let module = MemeModule::new(storage, config).await.unwrap();
+
+        for symbol in ["MEME", "meme", "Admin"] {
+            let err = module.process_transaction(create_tx(symbol, 1_000)).await.unwrap_err();
+            assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::InvalidSymbol(_)))));
+        }
+        assert!(module.process_transaction(create_tx("PEPE", 1_000)).await.is_ok());
+    }
+
+    fn launch_tx(symbol: &str, supply: u64, allocations: &[(&str, u64)]) -> Transaction {
+        let allocations: Vec<_> = allocations
+            .iter()
+            .map(|(address, amount)| serde_json::json!({"address": address, "amount": amount}))
+            .collect();
+        Transaction::new(
+            "meme".to_string(),
+            "launch_token".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({"name": symbol, "symbol": symbol, "supply": supply, "allocations": allocations}),
+            0,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_launch_token_with_allocations() {
+        let storage = create_test_storage().await;
+        let module = MemeModule::new(storage, ChainConfig::default()).await.unwrap();
+
+        let allocations = [("memechain1team", 50_000), ("memechain1marketing", 30_000), ("memechain1advisor", 20_000)];
+        let result = module.process_transaction(launch_tx("PEPE", 1_000_000, &allocations)).await.unwrap();
+        assert_eq!(result.data.unwrap()["creator_balance"], 900_000);
+
+        for (address, amount) in allocations {
+            let balance = module.get_balance(&Address::from(address), "PEPE").await.unwrap().unwrap();
+            assert_eq!(balance.amount, amount);
+        }
+        let creator = module.get_balance(&Address::from("memechain1alice"), "PEPE").await.unwrap().unwrap();
+        assert_eq!(creator.amount, 900_000);
+    }
+
+    #[tokio::test]
+    async fn test_launch_token_rolls_back_on_invalid_allocation() {
+        let storage = create_test_storage().await;
+        let module = MemeModule::new(storage, ChainConfig::default()).await.unwrap();
+
+        // More than the supply in total
+        let over_supply = [("memechain1team", 40_000), ("memechain1marketing", 40_000), ("memechain1advisor", 40_000)];
+        let err = module.process_transaction(launch_tx("PEPE", 100_000, &over_supply)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::InsufficientBalance(_)))));
+        assert!(module.get_token("PEPE").await.unwrap().is_none());
+        assert!(module.get_balance(&Address::from("memechain1alice"), "PEPE").await.unwrap().is_none());
+
+        // One allocation above the 5% max wallet
+        let over_wallet = [("memechain1team", 50_000), ("memechain1marketing", 50_001)];
+        let err = module.process_transaction(launch_tx("PEPE", 1_000_000, &over_wallet)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::MaxWalletLimitExceeded(_)))));
+        assert!(module.get_token("PEPE").await.unwrap().is_none());
+        assert!(module.get_balance(&Address::from("memechain1team"), "PEPE").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_transfer_sweeps_dust_to_treasury() {
+        let storage = create_test_storage().await;
+        let config = ChainConfig {
+            dust_threshold: 10,
+            ..ChainConfig::default()
+        };
+        let treasury = Address::new(config.treasury_address.clone());
+        let module = MemeModule::new(storage.clone(), config).await.unwrap();
+        module.process_transaction(Transaction::new(
+            "meme".to_string(),
+            "create_token".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({"name": "Test Token", "symbol": "TEST", "supply": 1000000}),
+            0,
+        )).await.unwrap();
+        module.process_transaction(transfer_tx("memechain1bob", 1000)).await.unwrap();
+
+        // Bob keeps 5, under the threshold
+        let bob = Address::from("memechain1bob");
+        module.process_transaction(Transaction::new(
+            "meme".to_string(),
+            "transfer".to_string(),
+            bob.clone(),
+            Some(Address::from("memechain1carol")),
+            serde_json::json!({"token": "TEST", "amount": 995}),
+            0,
+        )).await.unwrap();
+
+        assert!(module.get_balance(&bob, "TEST").await.unwrap().is_none());
+        assert_eq!(storage.get_balance(&treasury, "TEST").await.unwrap().unwrap().amount, 5);
+        let holders = storage.top_holders("TEST", 10, &[]).await.unwrap();
+        assert!(holders.iter().all(|holder| holder.address != bob));
+
+        // Remainders at or above the threshold stay put
+        let carol = Address::from("memechain1carol");
+        module.process_transaction(Transaction::new(
+            "meme".to_string(),
+            "transfer".to_string(),
+            carol.clone(),
+            Some(bob),
+            serde_json::json!({"token": "TEST", "amount": 985}),
+            0,
+        )).await.unwrap();
+        assert_eq!(module.get_balance(&carol, "TEST").await.unwrap().unwrap().amount, 10);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_tax_goes_to_treasury() {
+        let storage = create_test_storage().await;
+        let config = ChainConfig::default();
+        let treasury = Address::new(config.treasury_address.clone());
+        let module = MemeModule::new(storage.clone(), config).await.unwrap();
+        let mut anti_rug = serde_json::to_value(AntiRugSettings::default()).unwrap();
+        anti_rug["transfer_tax_percentage"] = serde_json::json!(10);
+        module.process_transaction(Transaction::new(
+            "meme".to_string(),
+            "create_token".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({"name": "Test Token", "symbol": "TEST", "supply": 1000000, "anti_rug": anti_rug}),
+            0,
+        )).await.unwrap();
+
+        let result = module.process_transaction(transfer_tx("memechain1bob", 1000)).await.unwrap();
+        let data = result.data.unwrap();
+        assert_eq!((data["amount"].as_u64(), data["fee"].as_u64()), (Some(1000), Some(100)));
+        assert_eq!(module.get_balance(&Address::from("memechain1bob"), "TEST").await.unwrap().unwrap().amount, 900);
+        assert_eq!(storage.get_balance(&treasury, "TEST").await.unwrap().unwrap().amount, 100);
+        let alice = module.get_balance(&Address::from("memechain1alice"), "TEST").await.unwrap().unwrap();
+        assert_eq!(alice.amount, 999_000);
+
+        // Out-of-range transfer tax is rejected at creation
+        anti_rug["transfer_tax_percentage"] = serde_json::json!(101);
+        let err = module.process_transaction(Transaction::new(
+            "meme".to_string(),
+            "create_token".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({"name": "Greedy", "symbol": "GREED", "supply": 1000000, "anti_rug": anti_rug}),
+            0,
+        )).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::InvalidAntiRugSettings(_)))));
+
+        // The transfer tax and a hook's rate share one ceiling
+        anti_rug["transfer_tax_percentage"] = serde_json::json!(10);
+        let with_hook = |symbol: &str, rate_bps: u16| Transaction::new(
+            "meme".to_string(),
+            "create_token".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({
+                "name": "Hooked",
+                "symbol": symbol,
+                "supply": 1000000,
+                "anti_rug": anti_rug,
+                "transfer_hook": {"name": "flat_tax", "rate_bps": rate_bps, "recipient": "memechain1charity"},
+            }),
+            0,
+        );
+        let ceiling_bps = ChainConfig::default().max_total_tax_percentage as u16 * 100;
+        let err = module.process_transaction(with_hook("HOOKA", ceiling_bps - 999)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Meme(MemeError::InvalidAntiRugSettings(_)))));
+        module.process_transaction(with_hook("HOOKB", ceiling_bps - 1000)).await.unwrap();
+    }
 } 
\ No newline at end of file
@@ -1,6 +1,7 @@
 pub mod nft;
 pub mod meme;
 pub mod common;
+pub mod hooks;
 
 pub use nft::NftModule;
 pub use meme::MemeModule;
@@ -1,20 +1,43 @@
+use crate::clock::{system_clock, SharedClock};
+use crate::config::ChainConfig;
 use crate::error::{MemeChainError, Result, NftError};
-use crate::storage::Storage;
-use crate::types::{Address, Collection, Nft, Transaction, TransactionResult};
+use crate::results::{
+    BatchTransferNftResult, BurnNftResult, CreateCollectionResult, MintNftResult, RedeemVoucherResult,
+    RevealCollectionResult, SetMintersResult, TransferNftResult, UpdateMetadataResult,
+};
+use crate::storage::{Page, QueryNfts, Storage};
+use crate::types::{Address, Collection, Nft, OwnershipRecord, Redemption, Transaction, TransactionResult, Voucher};
 use serde_json::Value;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+/// Most NFTs a single `batch_transfer` may move
+pub const MAX_BATCH_TRANSFER: usize = 100;
+
+/// Longest collection name, in characters
+pub const MAX_COLLECTION_NAME_LEN: usize = 64;
+
+/// Longest collection description, in characters
+pub const MAX_COLLECTION_DESCRIPTION_LEN: usize = 1024;
+
 /// NFT module for managing collections and NFTs
 pub struct NftModule {
     storage: Storage,
+    config: ChainConfig,
+    current_block_height: u64,
+    clock: SharedClock,
 }
 
 impl NftModule {
     /// Create a new NFT module
-    pub async fn new(storage: Storage) -> Result<Self> {
+    pub async fn new(storage: Storage, config: ChainConfig) -> Result<Self> {
         info!("Initializing NFT module");
-        Ok(Self { storage })
+        Ok(Self {
+            storage,
+            config,
+            current_block_height: 0,
+            clock: system_clock(),
+        })
     }
 
     /// Process NFT-related transactions
@@ -25,8 +48,12 @@ impl NftModule {
             "create_collection" => self.create_collection(tx).await,
             "mint" => self.mint_nft(tx).await,
             "transfer" => self.transfer_nft(tx).await,
+            "batch_transfer" => self.batch_transfer(tx).await,
             "burn" => self.burn_nft(tx).await,
             "update_metadata" => self.update_metadata(tx).await,
+            "set_minters" => self.set_minters(tx).await,
+            "reveal_collection" => self.reveal_collection(tx).await,
+            "redeem_voucher" => self.redeem_voucher(tx).await,
             _ => Err(NftError::InvalidNftId(format!("Unknown action: {}", tx.action))),
         }
     }
@@ -36,40 +63,62 @@ impl NftModule {
         let name = tx.data["name"]
             .as_str()
             .ok_or_else(|| NftError::InvalidMetadata("Missing collection name".to_string()))?;
+        let name = normalize_name("Collection name", name, MAX_COLLECTION_NAME_LEN)?;
         
         let description = tx.data["description"]
             .as_str()
             .unwrap_or("");
+        validate_collection_description(description)?;
 
         let collection_id = Uuid::new_v4().to_string();
         let creator = tx.from;
+        let minters = parse_minters(&tx.data)?.unwrap_or_default();
+        let reveal_block = match tx.data.get("reveal_block") {
+            None | Some(Value::Null) => None,
+            Some(block) => Some(block.as_u64().ok_or_else(|| {
+                NftError::InvalidMetadata("reveal_block must be a block height".to_string())
+            })?),
+        };
+        let placeholder_metadata = tx.data.get("placeholder_metadata").filter(|metadata| !metadata.is_null()).cloned();
+        if let Some(placeholder) = &placeholder_metadata {
+            validate_metadata_size("Placeholder metadata", placeholder, self.config.max_metadata_size)?;
+        }
+        if placeholder_metadata.is_some() && reveal_block.is_none() {
+            return Err(NftError::InvalidMetadata("placeholder_metadata requires a reveal_block".to_string()).into());
+        }
 
         // Check if collection already exists
         if self.storage.get_collection(&collection_id).await?.is_some() {
             return Err(NftError::CollectionExists(collection_id));
         }
 
-        let collection = Collection::new(
+        let mut collection = Collection::new(
             collection_id.clone(),
-            name.to_string(),
+            name.clone(),
             creator.clone(),
             description.to_string(),
+            self.clock.now(),
         );
+        collection.minters = minters;
+        collection.reveal_block = reveal_block;
+        collection.placeholder_metadata = placeholder_metadata;
+        collection.redeemable = tx.data["redeemable"].as_bool().unwrap_or(false);
 
         // Store collection
         self.storage.store_collection(&collection).await?;
 
         info!("Created collection: {} by {}", name, creator);
 
-        Ok(TransactionResult::success(Some(serde_json::json!({
-            "collection_id": collection_id,
-            "name": name,
-            "creator": creator.to_string(),
-        }))))
+        Ok(CreateCollectionResult {
+            collection_id,
+            name: name.to_string(),
+            creator,
+        }.into())
     }
 
     /// Mint a new NFT
     async fn mint_nft(&self, tx: Transaction) -> Result<TransactionResult> {
+        let tx_hash = tx.hash();
         let collection_id = tx.data["collection"]
             .as_str()
             .ok_or_else(|| NftError::InvalidCollectionId("Missing collection ID".to_string()))?;
@@ -77,41 +126,155 @@ impl NftModule {
         let name = tx.data["name"]
             .as_str()
             .ok_or_else(|| NftError::InvalidMetadata("Missing NFT name".to_string()))?;
+        let name = normalize_name("NFT name", name, self.config.max_nft_name_len)?;
         
         let metadata = tx.data["metadata"].clone();
+        validate_metadata_size("NFT metadata", &metadata, self.config.max_metadata_size)?;
         let owner = tx.from;
 
-        // Verify collection exists
-        let collection = self.storage.get_collection(collection_id).await?
-            .ok_or_else(|| NftError::CollectionNotFound(collection_id.to_string()))?;
+        let collection = self.mintable_collection(collection_id, &metadata, &owner).await?;
+        let nft_id = self.mint_into(&tx_hash, &collection, name.clone(), metadata, &owner).await?;
+
+        info!("Minted NFT: {} in collection: {} for owner: {}", name, collection_id, owner);
+
+        Ok(MintNftResult {
+            nft_id,
+            collection_id: collection_id.to_string(),
+            name,
+            owner,
+        }.into())
+    }
+
+    /// Mint a creator-signed voucher to the sender, who pays the voucher price
+    /// to the creator in the native token. Each voucher is redeemable once.
+    async fn redeem_voucher(&self, tx: Transaction) -> Result<TransactionResult> {
+        let tx_hash = tx.hash();
+        let voucher: Voucher = serde_json::from_value(tx.data["voucher"].clone())
+            .map_err(|e| NftError::InvalidMetadata(format!("Invalid voucher: {}", e)))?;
+        let chain_id = &self.config.chain_id;
+        let creator = voucher.verify(chain_id)?;
+        if let Some(expires_at_height) = voucher.expires_at_height {
+            if self.current_block_height > expires_at_height {
+                return Err(NftError::VoucherExpired(format!(
+                    "Voucher expired at block {}; current block is {}",
+                    expires_at_height, self.current_block_height
+                ))
+                .into());
+            }
+        }
+        let nullifier = voucher.nullifier(chain_id);
+        if self.storage.is_voucher_redeemed(&nullifier).await? {
+            return Err(NftError::VoucherRedeemed(nullifier).into());
+        }
+
+        let name = normalize_name("NFT name", &voucher.name, self.config.max_nft_name_len)?;
+        validate_metadata_size("NFT metadata", &voucher.metadata, self.config.max_metadata_size)?;
+        let collection = self.mintable_collection(&voucher.collection, &voucher.metadata, &creator).await?;
+        let redeemer = tx.from;
+
+        self.storage.begin_batch();
+        match self.pay_and_mint(&tx_hash, &voucher, &collection, name, &creator, &redeemer).await {
+            Ok(nft_id) => {
+                self.storage.commit_batch().await?;
+                info!("{} redeemed a voucher from {} for NFT {}", redeemer, creator, nft_id);
+                Ok(RedeemVoucherResult {
+                    nft_id,
+                    collection_id: collection.id,
+                    owner: redeemer,
+                    creator,
+                    price: voucher.price,
+                }.into())
+            }
+            Err(e) => {
+                self.storage.discard_batch();
+                Err(e)
+            }
+        }
+    }
+
+    async fn pay_and_mint(
+        &self,
+        tx_hash: &str,
+        voucher: &Voucher,
+        collection: &Collection,
+        name: String,
+        creator: &Address,
+        redeemer: &Address,
+    ) -> Result<String> {
+        if voucher.price > 0 && redeemer != creator {
+            let native = &self.config.native_symbol;
+            let available = self.storage.get_balance(redeemer, native).await?.map_or(0, |balance| balance.amount);
+            if available < voucher.price {
+                return Err(MemeChainError::InsufficientBalance(format!(
+                    "Voucher costs {} {} but {} has {}", voucher.price, native, redeemer, available
+                )));
+            }
+            let price = i64::try_from(voucher.price)
+                .map_err(|_| NftError::InvalidMetadata("Voucher price is too large".to_string()))?;
+            let now = self.clock.now();
+            self.storage.update_balance(redeemer, native, -price, now).await?;
+            self.storage.update_balance(creator, native, price, now).await?;
+        }
 
-        // Generate unique NFT ID
-        let nft_id = Uuid::new_v4().to_string();
+        let nft_id = self.mint_into(tx_hash, collection, name, voucher.metadata.clone(), redeemer).await?;
+        self.storage.mark_voucher_redeemed(&voucher.nullifier(&self.config.chain_id), &nft_id).await?;
+        Ok(nft_id)
+    }
 
-        // Check if NFT already exists
-        if self.storage.get_nft(&nft_id).await?.is_some() {
-            return Err(NftError::NftExists(nft_id));
+    /// Load a collection `minter` may mint into with `metadata`
+    async fn mintable_collection(&self, collection_id: &str, metadata: &Value, minter: &Address) -> Result<Collection> {
+        // Metadata may only point at the collection the NFT is minted into
+        if let Some(referenced) = metadata.get("collection") {
+            if referenced.as_str() != Some(collection_id) {
+                return Err(NftError::InvalidMetadata(format!(
+                    "Metadata references collection {} but NFT is minted into {}", referenced, collection_id
+                )).into());
+            }
         }
 
-        let nft = Nft::new(
+        // Verify collection exists; it cannot be created by this transaction
+        let collection = self.storage.get_collection(collection_id).await?
+            .ok_or_else(|| NftError::InvalidCollectionId(format!("Collection does not exist: {}", collection_id)))?;
+
+        // Verify the minter is authorized for it
+        if !collection.can_mint(minter) {
+            return Err(NftError::Unauthorized(format!(
+                "{} is not an authorized minter for collection {}", minter, collection_id
+            )).into());
+        }
+        Ok(collection)
+    }
+
+    /// Store a new NFT in `collection` for `owner`, minted by the
+    /// transaction with `tx_hash`, and return its ID
+    async fn mint_into(&self, tx_hash: &str, collection: &Collection, name: String, metadata: Value, owner: &Address) -> Result<String> {
+        let nft_id = self.next_nft_id(tx_hash).await?;
+
+        // A sealed collection shows its placeholder until the reveal
+        let sealed = collection.is_sealed();
+        let (visible, hidden) = if sealed {
+            (collection.placeholder_metadata.clone().unwrap_or(Value::Null), Some(metadata))
+        } else {
+            (metadata, None)
+        };
+
+        let mut nft = Nft::new(
             nft_id.clone(),
-            collection_id.to_string(),
-            name.to_string(),
+            collection.id.clone(),
+            name,
             owner.clone(),
-            metadata,
+            visible,
+            self.clock.now(),
         );
+        nft.revealed = !sealed;
 
         // Store NFT
         self.storage.store_nft(&nft).await?;
-
-        info!("Minted NFT: {} in collection: {} for owner: {}", name, collection_id, owner);
-
-        Ok(TransactionResult::success(Some(serde_json::json!({
-            "nft_id": nft_id,
-            "collection_id": collection_id,
-            "name": name,
-            "owner": owner.to_string(),
-        }))))
+        if let Some(hidden) = hidden.filter(|metadata| !metadata.is_null()) {
+            self.storage.store_sealed_metadata(&nft_id, &hidden).await?;
+        }
+        self.record_ownership(&nft_id, None, owner).await?;
+        Ok(nft_id)
     }
 
     /// Transfer an NFT
@@ -138,21 +301,93 @@ impl NftModule {
 
         // Update owner
         nft.owner = to_address.clone();
-        nft.updated_at = chrono::Utc::now().timestamp();
+        nft.updated_at = self.clock.now();
 
         // Store updated NFT
         self.storage.store_nft(&nft).await?;
+        self.record_ownership(nft_id, Some(&from_address), &to_address).await?;
 
         info!("Transferred NFT: {} from {} to {}", nft_id, from_address, to_address);
 
-        Ok(TransactionResult::success(Some(serde_json::json!({
-            "nft_id": nft_id,
-            "from": from_address.to_string(),
-            "to": to_address.to_string(),
-        }))))
+        Ok(TransferNftResult {
+            nft_id: nft_id.to_string(),
+            from: from_address,
+            to: to_address,
+        }.into())
+    }
+
+    /// Transfer several NFTs to one recipient; either all move or none do
+    async fn batch_transfer(&self, tx: Transaction) -> Result<TransactionResult> {
+        let nft_ids: Vec<String> = tx.data["nft_ids"]
+            .as_array()
+            .ok_or_else(|| NftError::InvalidNftId("Missing NFT IDs".to_string()))?
+            .iter()
+            .map(|id| {
+                id.as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| NftError::InvalidNftId(format!("Invalid NFT ID: {}", id)))
+            })
+            .collect::<std::result::Result<_, _>>()?;
+
+        if nft_ids.is_empty() || nft_ids.len() > MAX_BATCH_TRANSFER {
+            return Err(NftError::TransferFailed(format!(
+                "Batch must contain between 1 and {} NFTs, got {}", MAX_BATCH_TRANSFER, nft_ids.len()
+            )).into());
+        }
+        let mut seen = std::collections::HashSet::new();
+        if let Some(duplicate) = nft_ids.iter().find(|id| !seen.insert(*id)) {
+            return Err(NftError::TransferFailed(format!("Duplicate NFT in batch: {}", duplicate)).into());
+        }
+
+        let to_address = tx.data["to"]
+            .as_str()
+            .map(|to| Address::new(to.to_string()))
+            .or(tx.to)
+            .ok_or_else(|| NftError::TransferFailed("Missing recipient address".to_string()))?;
+        let from_address = tx.from;
+
+        // Check every NFT before touching storage
+        let mut nfts = Vec::with_capacity(nft_ids.len());
+        for nft_id in &nft_ids {
+            let nft = self.storage.get_nft(nft_id).await?
+                .ok_or_else(|| NftError::NftNotFound(nft_id.clone()))?;
+            if nft.owner != from_address {
+                return Err(NftError::Unauthorized(format!(
+                    "NFT {} is not owned by {}", nft_id, from_address
+                )).into());
+            }
+            nfts.push(nft);
+        }
+
+        self.storage.begin_batch();
+        let now = self.clock.now();
+        for mut nft in nfts {
+            nft.owner = to_address.clone();
+            nft.updated_at = now;
+            let written = match self.storage.store_nft(&nft).await {
+                Ok(()) => self.record_ownership(&nft.id, Some(&from_address), &to_address).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = written {
+                self.storage.discard_batch();
+                return Err(e);
+            }
+        }
+        self.storage.commit_batch().await?;
+
+        info!("Transferred {} NFTs from {} to {}", nft_ids.len(), from_address, to_address);
+
+        Ok(BatchTransferNftResult {
+            nft_ids,
+            from: from_address,
+            to: to_address,
+        }.into())
     }
 
-    /// Burn an NFT
+    /// Burn an NFT. Supplying `redemption_data` claims the collection's
+    /// physical item; the data is stored publicly on-chain, so it should be an
+    /// opaque reference or a hash of the shipping details, never the details
+    /// themselves
     async fn burn_nft(&self, tx: Transaction) -> Result<TransactionResult> {
         let nft_id = tx.data["nft_id"]
             .as_str()
@@ -171,16 +406,50 @@ impl NftModule {
             )));
         }
 
-        // Delete NFT
-        let key = format!("nft:{}", nft_id);
-        self.storage.backend.delete(&key).await?;
+        // Burning with redemption data claims the physical item
+        let redemption_data = tx.data.get("redemption_data").filter(|data| !data.is_null());
+        if let Some(redemption_data) = redemption_data {
+            validate_metadata_size("Redemption data", redemption_data, self.config.max_metadata_size)?;
+            let collection = self.storage.get_collection(&nft.collection_id).await?
+                .ok_or_else(|| NftError::CollectionNotFound(nft.collection_id.clone()))?;
+            if !collection.redeemable {
+                return Err(NftError::InvalidMetadata(format!(
+                    "Collection {} does not offer redemptions", collection.id
+                )).into());
+            }
+            if self.storage.get_redemption(nft_id).await?.is_some() {
+                return Err(NftError::AlreadyRedeemed(nft_id.to_string()).into());
+            }
 
-        info!("Burned NFT: {} by owner: {}", nft_id, owner);
+            self.storage.begin_batch();
+            let redemption = Redemption {
+                nft_id: nft_id.to_string(),
+                collection_id: nft.collection_id.clone(),
+                redeemed_by: owner.clone(),
+                redemption_data: redemption_data.clone(),
+                block_height: self.current_block_height,
+                timestamp: self.clock.now(),
+            };
+            let written = match self.storage.store_redemption(&redemption).await {
+                Ok(()) => self.storage.delete_nft(&nft).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = written {
+                self.storage.discard_batch();
+                return Err(e);
+            }
+            self.storage.commit_batch().await?;
+            info!("Burned NFT: {} by owner: {} for redemption", nft_id, owner);
+        } else {
+            self.storage.delete_nft(&nft).await?;
+            info!("Burned NFT: {} by owner: {}", nft_id, owner);
+        }
 
-        Ok(TransactionResult::success(Some(serde_json::json!({
-            "nft_id": nft_id,
-            "burned_by": owner.to_string(),
-        }))))
+        Ok(BurnNftResult {
+            nft_id: nft_id.to_string(),
+            burned_by: owner,
+            redeemed: redemption_data.is_some(),
+        }.into())
     }
 
     /// Update NFT metadata
@@ -190,6 +459,7 @@ impl NftModule {
             .ok_or_else(|| NftError::InvalidNftId("Missing NFT ID".to_string()))?;
         
         let new_metadata = tx.data["metadata"].clone();
+        validate_metadata_size("NFT metadata", &new_metadata, self.config.max_metadata_size)?;
         let owner = tx.from;
 
         // Get NFT
@@ -203,19 +473,200 @@ impl NftModule {
             )));
         }
 
+        // The placeholder stays until the collection is revealed
+        if !nft.revealed {
+            return Err(NftError::RevealNotReady(format!("NFT {} has not been revealed yet", nft_id)).into());
+        }
+
+        // Throttle updates per NFT
+        let cooldown = self.config.metadata_update_cooldown_blocks;
+        if let Some(last_update) = nft.metadata_updated_height {
+            let next_allowed = last_update.saturating_add(cooldown);
+            if self.current_block_height < next_allowed {
+                return Err(NftError::MetadataCooldown(format!(
+                    "NFT {} metadata was updated at block {}; next update allowed at block {}",
+                    nft_id, last_update, next_allowed
+                )).into());
+            }
+        }
+
         // Update metadata
         nft.metadata = new_metadata;
-        nft.updated_at = chrono::Utc::now().timestamp();
+        nft.metadata_updated_height = Some(self.current_block_height);
+        nft.updated_at = self.clock.now();
 
         // Store updated NFT
         self.storage.store_nft(&nft).await?;
 
         info!("Updated metadata for NFT: {} by owner: {}", nft_id, owner);
 
-        Ok(TransactionResult::success(Some(serde_json::json!({
-            "nft_id": nft_id,
-            "updated_by": owner.to_string(),
-        }))))
+        Ok(UpdateMetadataResult {
+            nft_id: nft_id.to_string(),
+            updated_by: owner,
+        }.into())
+    }
+
+    /// Replace the set of addresses allowed to mint into a collection
+    async fn set_minters(&self, tx: Transaction) -> Result<TransactionResult> {
+        let collection_id = tx.data["collection"]
+            .as_str()
+            .ok_or_else(|| NftError::InvalidCollectionId("Missing collection ID".to_string()))?;
+        
+        let minters = parse_minters(&tx.data)?
+            .ok_or_else(|| NftError::InvalidMetadata("Missing minters".to_string()))?;
+        let sender = tx.from;
+
+        let mut collection = self.storage.get_collection(collection_id).await?
+            .ok_or_else(|| NftError::CollectionNotFound(collection_id.to_string()))?;
+
+        // Only the creator manages minters
+        if collection.creator != sender {
+            return Err(NftError::Unauthorized(format!(
+                "Only the creator of collection {} can set minters", collection_id
+            )).into());
+        }
+
+        collection.minters = minters;
+        collection.updated_at = self.clock.now();
+        self.storage.store_collection(&collection).await?;
+
+        info!("Set {} minters for collection: {}", collection.minters.len(), collection_id);
+
+        Ok(SetMintersResult {
+            collection_id: collection_id.to_string(),
+            minters: collection.minters,
+        }.into())
+    }
+
+    /// Swap the real metadata into a sealed collection's NFTs once its
+    /// `reveal_block` is reached. Metadata given in the transaction
+    /// (`nft_id -> metadata`) wins over what was supplied at mint.
+    async fn reveal_collection(&self, tx: Transaction) -> Result<TransactionResult> {
+        let collection_id = tx.data["collection"]
+            .as_str()
+            .ok_or_else(|| NftError::InvalidCollectionId("Missing collection ID".to_string()))?;
+        let sender = tx.from;
+
+        let mut collection = self.storage.get_collection(collection_id).await?
+            .ok_or_else(|| NftError::CollectionNotFound(collection_id.to_string()))?;
+        if collection.creator != sender {
+            return Err(NftError::Unauthorized(format!(
+                "Only the creator of collection {} can reveal it", collection_id
+            )).into());
+        }
+        let Some(reveal_block) = collection.reveal_block else {
+            return Err(NftError::RevealNotReady(format!("Collection {} is not sealed", collection_id)).into());
+        };
+        if collection.revealed {
+            return Err(NftError::RevealNotReady(format!("Collection {} is already revealed", collection_id)).into());
+        }
+        if self.current_block_height < reveal_block {
+            return Err(NftError::RevealNotReady(format!(
+                "Collection {} reveals at block {}; current block is {}",
+                collection_id, reveal_block, self.current_block_height
+            )).into());
+        }
+
+        let provided = match tx.data.get("metadata") {
+            None | Some(Value::Null) => serde_json::Map::new(),
+            Some(Value::Object(provided)) => provided.clone(),
+            Some(_) => return Err(NftError::InvalidMetadata("metadata must map NFT IDs to metadata".to_string()).into()),
+        };
+        let nfts = self.storage.query_nfts(&QueryNfts {
+            collection: Some(collection_id.to_string()),
+            ..QueryNfts::default()
+        }).await?.items;
+        if let Some(unknown) = provided.keys().find(|id| !nfts.iter().any(|nft| &nft.id == *id)) {
+            return Err(NftError::InvalidNftId(format!("{} is not in collection {}", unknown, collection_id)).into());
+        }
+        for metadata in provided.values() {
+            validate_metadata_size("NFT metadata", metadata, self.config.max_metadata_size)?;
+        }
+
+        self.storage.begin_batch();
+        let mut revealed = 0;
+        let now = self.clock.now();
+        for mut nft in nfts.into_iter().filter(|nft| !nft.revealed) {
+            let sealed = match self.storage.take_sealed_metadata(&nft.id).await {
+                Ok(sealed) => sealed,
+                Err(e) => {
+                    self.storage.discard_batch();
+                    return Err(e);
+                }
+            };
+            nft.metadata = provided.get(&nft.id).cloned().or(sealed).unwrap_or(Value::Null);
+            nft.revealed = true;
+            nft.metadata_updated_height = Some(self.current_block_height);
+            nft.updated_at = now;
+            if let Err(e) = self.storage.store_nft(&nft).await {
+                self.storage.discard_batch();
+                return Err(e);
+            }
+            revealed += 1;
+        }
+        collection.revealed = true;
+        collection.updated_at = now;
+        if let Err(e) = self.storage.store_collection(&collection).await {
+            self.storage.discard_batch();
+            return Err(e);
+        }
+        self.storage.commit_batch().await?;
+
+        info!("Revealed {} NFTs in collection: {}", revealed, collection_id);
+
+        Ok(RevealCollectionResult {
+            collection_id: collection_id.to_string(),
+            revealed,
+            revealed_by: sender,
+        }.into())
+    }
+
+    /// ID for an NFT minted by the transaction with `tx_hash`. IDs are
+    /// derived from the hash rather than drawn at random so every node
+    /// assigns the same one (see `consensus::block_rng`); a repeated
+    /// transaction takes the next derivation not in use.
+    async fn next_nft_id(&self, tx_hash: &str) -> Result<String> {
+        use sha2::{Digest, Sha256};
+        let mut index = 0u64;
+        loop {
+            let digest = Sha256::new()
+                .chain_update(tx_hash.as_bytes())
+                .chain_update(index.to_be_bytes())
+                .finalize();
+            let mut bytes = [0u8; 16];
+            bytes.copy_from_slice(&digest[..16]);
+            let nft_id = uuid::Builder::from_random_bytes(bytes).into_uuid().to_string();
+            if self.storage.get_nft(&nft_id).await?.is_none() {
+                return Ok(nft_id);
+            }
+            index += 1;
+        }
+    }
+
+    /// Append an ownership change to the NFT's history
+    async fn record_ownership(&self, nft_id: &str, from: Option<&Address>, to: &Address) -> Result<()> {
+        let record = OwnershipRecord {
+            from: from.cloned(),
+            to: to.clone(),
+            block_height: self.current_block_height,
+            timestamp: self.clock.now(),
+        };
+        self.storage.append_nft_history(nft_id, &record).await
+    }
+
+    /// Update current block height
+    pub fn update_block_height(&mut self, height: u64) {
+        self.current_block_height = height;
+    }
+
+    /// Replace the time source
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
+    }
+
+    /// Get an NFT's ownership history, oldest first
+    pub async fn get_nft_history(&self, nft_id: &str) -> Result<Vec<OwnershipRecord>> {
+        self.storage.get_nft_history(nft_id).await
     }
 
     /// Get NFT by ID
@@ -259,6 +710,7 @@ impl NftModule {
                 "name": collection.name,
                 "creator": collection.creator.to_string(),
                 "description": collection.description,
+                "minters": collection.minters.iter().map(|minter| minter.to_string()).collect::<Vec<_>>(),
                 "created_at": collection.created_at,
                 "updated_at": collection.updated_at,
             }));
@@ -267,53 +719,128 @@ impl NftModule {
         Ok(result)
     }
 
-    /// Get NFTs by collection
-    pub async fn get_nfts_by_collection(&self, collection_id: &str) -> Result<Vec<Value>> {
-        let nfts = self.storage.get_all_nfts().await?;
+    /// Get a page of a collection's NFTs, ordered by ID
+    pub async fn get_nfts_by_collection(
+        &self,
+        collection_id: &str,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> Result<Page<Value>> {
+        let page = self.storage.query_nfts(&QueryNfts {
+            collection: Some(collection_id.to_string()),
+            limit,
+            offset,
+            ..QueryNfts::default()
+        }).await?;
         let mut result = Vec::new();
         
-        for nft in nfts {
-            if nft.collection_id == collection_id {
-                result.push(serde_json::json!({
-                    "id": nft.id,
-                    "name": nft.name,
-                    "owner": nft.owner.to_string(),
-                    "metadata": nft.metadata,
-                    "created_at": nft.created_at,
-                    "updated_at": nft.updated_at,
-                }));
-            }
+        for nft in page.items {
+            result.push(serde_json::json!({
+                "id": nft.id,
+                "name": nft.name,
+                "owner": nft.owner.to_string(),
+                "metadata": nft.metadata,
+                "created_at": nft.created_at,
+                "updated_at": nft.updated_at,
+            }));
         }
         
-        Ok(result)
+        Ok(Page {
+            items: result,
+            total: page.total,
+        })
     }
 
     /// Get NFTs by owner
     pub async fn get_nfts_by_owner(&self, owner: &Address) -> Result<Vec<Value>> {
-        let nfts = self.storage.get_all_nfts().await?;
+        let page = self.storage.query_nfts(&QueryNfts {
+            owner: Some(owner.clone()),
+            ..QueryNfts::default()
+        }).await?;
         let mut result = Vec::new();
         
-        for nft in nfts {
-            if nft.owner == *owner {
-                result.push(serde_json::json!({
-                    "id": nft.id,
-                    "collection_id": nft.collection_id,
-                    "name": nft.name,
-                    "metadata": nft.metadata,
-                    "created_at": nft.created_at,
-                    "updated_at": nft.updated_at,
-                }));
-            }
+        for nft in page.items {
+            result.push(serde_json::json!({
+                "id": nft.id,
+                "collection_id": nft.collection_id,
+                "name": nft.name,
+                "metadata": nft.metadata,
+                "created_at": nft.created_at,
+                "updated_at": nft.updated_at,
+            }));
         }
         
         Ok(result)
     }
 }
 
+/// Trim a name and check it is 1..=`max_len` characters with no control characters
+pub(crate) fn normalize_name(kind: &str, name: &str, max_len: usize) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(NftError::InvalidMetadata(format!("{} must not be empty", kind)).into());
+    }
+    if name.chars().any(char::is_control) {
+        return Err(NftError::InvalidMetadata(format!("{} must not contain control characters", kind)).into());
+    }
+    let name_len = name.chars().count();
+    if name_len > max_len {
+        return Err(NftError::InvalidMetadata(format!(
+            "{} is {} characters, at most {} allowed", kind, name_len, max_len
+        )).into());
+    }
+    Ok(name.to_string())
+}
+
+/// Check a collection description is within `MAX_COLLECTION_DESCRIPTION_LEN`
+pub(crate) fn validate_collection_description(description: &str) -> Result<()> {
+    let description_len = description.chars().count();
+    if description_len > MAX_COLLECTION_DESCRIPTION_LEN {
+        return Err(NftError::InvalidMetadata(format!(
+            "Collection description is {} characters, at most {} allowed",
+            description_len, MAX_COLLECTION_DESCRIPTION_LEN
+        )).into());
+    }
+    Ok(())
+}
+
+/// Check `metadata` serializes to at most `max_size` bytes
+pub(crate) fn validate_metadata_size(kind: &str, metadata: &Value, max_size: usize) -> Result<()> {
+    let size = serde_json::to_vec(metadata)?.len();
+    if size > max_size {
+        return Err(NftError::InvalidMetadata(format!(
+            "{} is {} bytes, at most {} allowed", kind, size, max_size
+        )).into());
+    }
+    Ok(())
+}
+
+/// Read an optional `minters` address list from transaction data
+fn parse_minters(data: &Value) -> Result<Option<Vec<Address>>> {
+    let Some(minters) = data.get("minters") else {
+        return Ok(None);
+    };
+    let minters = minters
+        .as_array()
+        .ok_or_else(|| NftError::InvalidMetadata("Minters must be a list of addresses".to_string()))?;
+
+    minters
+        .iter()
+        .map(|minter| {
+            minter
+                .as_str()
+                .map(Address::from)
+                .ok_or_else(|| NftError::InvalidMetadata("Minters must be a list of addresses".to_string()).into())
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(Some)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::StorageConfig;
+    use crate::config::{ChainConfig, StorageConfig};
+    use crate::error::ModuleError;
     use tempfile::tempdir;
 
     async fn create_test_storage() -> Storage {
@@ -325,6 +852,7 @@ mod tests {
             db_type: "rocksdb".to_string(),
             cache_size: 100,
             enable_compression: false,
+            ..StorageConfig::default()
         };
         
         Storage::new(&config).await.unwrap()
@@ -333,7 +861,7 @@ mod tests {
     #[tokio::test]
     async fn test_create_collection() {
         let storage = create_test_storage().await;
-        let module = NftModule::new(storage).await.unwrap();
+        let module = NftModule::new(storage, ChainConfig::default()).await.unwrap();
         
         let tx = Transaction::new(
             "nft".to_string(),
@@ -344,6 +872,7 @@ mod tests {
                 "name": "Test Collection",
                 "description": "A test collection"
             }),
+            0,
         );
         
         let result = module.process_transaction(tx).await.unwrap();
@@ -353,7 +882,7 @@ mod tests {
     #[tokio::test]
     async fn test_mint_nft() {
         let storage = create_test_storage().await;
-        let module = NftModule::new(storage).await.unwrap();
+        let module = NftModule::new(storage, ChainConfig::default()).await.unwrap();
         
         // First create a collection
         let collection_tx = Transaction::new(
@@ -365,6 +894,7 @@ mod tests {
                 "name": "Test Collection",
                 "description": "A test collection"
             }),
+            0,
         );
         
         let collection_result = module.process_transaction(collection_tx).await.unwrap();
@@ -381,9 +911,431 @@ mod tests {
                 "name": "Test NFT",
                 "metadata": {"rarity": "legendary"}
             }),
+            0,
         );
         
         let result = module.process_transaction(mint_tx).await.unwrap();
         assert!(result.success);
     }
+
+    fn mint_tx(minter: &str, collection_id: &str) -> Transaction {
+        Transaction::new(
+            "nft".to_string(),
+            "mint".to_string(),
+            Address::new(minter.to_string()),
+            None,
+            serde_json::json!({
+                "collection": collection_id,
+                "name": "Test NFT",
+            }),
+            0,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_minter_authorization() {
+        let storage = create_test_storage().await;
+        let module = NftModule::new(storage, ChainConfig::default()).await.unwrap();
+        
+        let collection_tx = Transaction::new(
+            "nft".to_string(),
+            "create_collection".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({"name": "Test Collection"}),
+            0,
+        );
+        let result = module.process_transaction(collection_tx).await.unwrap();
+        let collection_id = result.data.unwrap()["collection_id"].as_str().unwrap().to_string();
+        
+        // Creator-only by default
+        assert!(module.process_transaction(mint_tx("memechain1alice", &collection_id)).await.is_ok());
+        let err = module.process_transaction(mint_tx("memechain1bob", &collection_id)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::Unauthorized(_)))));
+        
+        // Only the creator can grant minting rights
+        let set_minters = |from: &str| Transaction::new(
+            "nft".to_string(),
+            "set_minters".to_string(),
+            Address::new(from.to_string()),
+            None,
+            serde_json::json!({"collection": collection_id, "minters": ["memechain1bob"]}),
+            0,
+        );
+        assert!(module.process_transaction(set_minters("memechain1bob")).await.is_err());
+        module.process_transaction(set_minters("memechain1alice")).await.unwrap();
+        
+        assert!(module.process_transaction(mint_tx("memechain1bob", &collection_id)).await.is_ok());
+        assert!(module.process_transaction(mint_tx("memechain1alice", &collection_id)).await.is_ok());
+        let err = module.process_transaction(mint_tx("memechain1carol", &collection_id)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::Unauthorized(_)))));
+    }
+
+    #[tokio::test]
+    async fn test_nft_ids_are_deterministic() {
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            // Two nodes replaying the same transactions
+            let storage = create_test_storage().await;
+            let alice = Address::new("memechain1alice".to_string());
+            storage.store_collection(&Collection::new("apes".to_string(), "Apes".to_string(), alice, String::new(), 0)).await.unwrap();
+            let module = NftModule::new(storage, ChainConfig::default()).await.unwrap();
+            let mint = mint_tx("memechain1alice", "apes");
+
+            let first = module.process_transaction(mint.clone()).await.unwrap();
+            let repeat = module.process_transaction(mint).await.unwrap();
+            ids.push((first.data.unwrap()["nft_id"].clone(), repeat.data.unwrap()["nft_id"].clone()));
+        }
+        assert_eq!(ids[0], ids[1]);
+        assert_ne!(ids[0].0, ids[0].1);
+    }
+
+    async fn mint_many(module: &NftModule, owner: &str, count: usize) -> Vec<String> {
+        let collection_tx = Transaction::new(
+            "nft".to_string(),
+            "create_collection".to_string(),
+            Address::new(owner.to_string()),
+            None,
+            serde_json::json!({"name": "Batch Collection", "description": "Batch"}),
+            0,
+        );
+        let result = module.process_transaction(collection_tx).await.unwrap();
+        let collection_id = result.data.unwrap()["collection_id"].as_str().unwrap().to_string();
+
+        let mut ids = Vec::new();
+        for _ in 0..count {
+            let result = module.process_transaction(mint_tx(owner, &collection_id)).await.unwrap();
+            ids.push(result.data.unwrap()["nft_id"].as_str().unwrap().to_string());
+        }
+        ids
+    }
+
+    fn batch_tx(from: &str, nft_ids: &[String], to: &str) -> Transaction {
+        Transaction::new(
+            "nft".to_string(),
+            "batch_transfer".to_string(),
+            Address::new(from.to_string()),
+            None,
+            serde_json::json!({"nft_ids": nft_ids, "to": to}),
+            0,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_batch_transfer() {
+        let storage = create_test_storage().await;
+        let module = NftModule::new(storage, ChainConfig::default()).await.unwrap();
+        let ids = mint_many(&module, "memechain1alice", 3).await;
+
+        let result = module
+            .process_transaction(batch_tx("memechain1alice", &ids, "memechain1bob"))
+            .await
+            .unwrap();
+        let result: BatchTransferNftResult = result.typed().unwrap().unwrap();
+        assert_eq!(result.nft_ids, ids);
+
+        let bob = Address::new("memechain1bob".to_string());
+        for id in &ids {
+            let nft = module.get_nft(id).await.unwrap().unwrap();
+            assert_eq!(nft.owner, bob);
+            let history = module.get_nft_history(id).await.unwrap();
+            assert_eq!(history.last().unwrap().to, bob);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_transfer_is_atomic() {
+        let storage = create_test_storage().await;
+        let module = NftModule::new(storage, ChainConfig::default()).await.unwrap();
+        let mut ids = mint_many(&module, "memechain1alice", 2).await;
+        ids.extend(mint_many(&module, "memechain1carol", 1).await);
+
+        let err = module
+            .process_transaction(batch_tx("memechain1alice", &ids, "memechain1bob"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::Unauthorized(_)))));
+
+        // Nothing moved, including the NFTs alice does own
+        let alice = Address::new("memechain1alice".to_string());
+        for id in &ids[..2] {
+            assert_eq!(module.get_nft(id).await.unwrap().unwrap().owner, alice);
+        }
+
+        let too_many = vec![ids[0].clone(); MAX_BATCH_TRANSFER + 1];
+        assert!(module
+            .process_transaction(batch_tx("memechain1alice", &too_many, "memechain1bob"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_collection_field_bounds() {
+        let storage = create_test_storage().await;
+        let module = NftModule::new(storage, ChainConfig::default()).await.unwrap();
+        let create = |name: String, description: String| Transaction::new(
+            "nft".to_string(),
+            "create_collection".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({"name": name, "description": description}),
+            0,
+        );
+
+        let err = module
+            .process_transaction(create("a".repeat(MAX_COLLECTION_NAME_LEN + 1), String::new()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::InvalidMetadata(_)))));
+
+        let err = module
+            .process_transaction(create("Apes".to_string(), "a".repeat(MAX_COLLECTION_DESCRIPTION_LEN + 1)))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::InvalidMetadata(_)))));
+
+        assert!(module
+            .process_transaction(create("a".repeat(MAX_COLLECTION_NAME_LEN), String::new()))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_mint_into_missing_collection() {
+        let storage = create_test_storage().await;
+        let module = NftModule::new(storage, ChainConfig::default()).await.unwrap();
+
+        let err = module
+            .process_transaction(mint_tx("memechain1alice", "no-such-collection"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::InvalidCollectionId(_)))));
+
+        // Metadata cannot point at a different collection
+        let mut tx = mint_tx("memechain1alice", "no-such-collection");
+        tx.data["metadata"] = serde_json::json!({"collection": "other"});
+        let err = module.process_transaction(tx).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::InvalidMetadata(_)))));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_update_cooldown() {
+        let storage = create_test_storage().await;
+        let config = ChainConfig {
+            metadata_update_cooldown_blocks: 10,
+            ..ChainConfig::default()
+        };
+        let mut module = NftModule::new(storage, config).await.unwrap();
+        let nft_id = mint_many(&module, "memechain1alice", 1).await.remove(0);
+        let update = |rarity: &str| Transaction::new(
+            "nft".to_string(),
+            "update_metadata".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({"nft_id": nft_id, "metadata": {"rarity": rarity}}),
+            0,
+        );
+
+        module.update_block_height(5);
+        module.process_transaction(update("rare")).await.unwrap();
+
+        // A second update inside the cooldown is rejected
+        module.update_block_height(14);
+        let err = module.process_transaction(update("epic")).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::MetadataCooldown(_)))));
+        assert!(err.to_string().contains("next update allowed at block 15"));
+
+        module.update_block_height(15);
+        module.process_transaction(update("epic")).await.unwrap();
+        let nft = module.get_nft(&nft_id).await.unwrap().unwrap();
+        assert_eq!(nft.metadata, serde_json::json!({"rarity": "epic"}));
+        assert_eq!(nft.metadata_updated_height, Some(15));
+    }
+
+    #[tokio::test]
+    async fn test_name_normalization() {
+        let storage = create_test_storage().await;
+        let module = NftModule::new(storage, ChainConfig::default()).await.unwrap();
+        let collection_tx = |name: &str| Transaction::new(
+            "nft".to_string(),
+            "create_collection".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({"name": name}),
+            0,
+        );
+
+        let result = module.process_transaction(collection_tx("  Apes  ")).await.unwrap();
+        let collection_id = result.data.unwrap()["collection_id"].as_str().unwrap().to_string();
+        let collection = module.get_collection(&collection_id).await.unwrap().unwrap();
+        assert_eq!(collection.name, "Apes");
+
+        let err = module.process_transaction(collection_tx("Apes\nClub")).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::InvalidMetadata(_)))));
+
+        let mint = |name: String| {
+            let mut tx = mint_tx("memechain1alice", &collection_id);
+            tx.data["name"] = serde_json::json!(name);
+            tx
+        };
+        let max_len = ChainConfig::default().max_nft_name_len;
+        for name in ["a".repeat(max_len + 1), "Ape\n#1".to_string(), "   ".to_string()] {
+            let err = module.process_transaction(mint(name)).await.unwrap_err();
+            assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::InvalidMetadata(_)))));
+        }
+
+        let result = module.process_transaction(mint(format!(" {} ", "a".repeat(max_len)))).await.unwrap();
+        assert_eq!(result.data.unwrap()["name"], "a".repeat(max_len));
+    }
+
+    #[tokio::test]
+    async fn test_sealed_collection_reveal() {
+        let storage = create_test_storage().await;
+        let mut module = NftModule::new(storage, ChainConfig::default()).await.unwrap();
+        let collection_tx = Transaction::new(
+            "nft".to_string(),
+            "create_collection".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({
+                "name": "Mystery Apes",
+                "reveal_block": 10,
+                "placeholder_metadata": {"image": "mystery.png"},
+            }),
+            0,
+        );
+        let result = module.process_transaction(collection_tx).await.unwrap();
+        let collection_id = result.data.unwrap()["collection_id"].as_str().unwrap().to_string();
+
+        let mut nft_ids = Vec::new();
+        for image in ["ape1.png", "ape2.png"] {
+            let mut tx = mint_tx("memechain1alice", &collection_id);
+            tx.data["metadata"] = serde_json::json!({"image": image});
+            let result = module.process_transaction(tx).await.unwrap();
+            nft_ids.push(result.data.unwrap()["nft_id"].as_str().unwrap().to_string());
+        }
+        let nft = module.get_nft(&nft_ids[0]).await.unwrap().unwrap();
+        assert_eq!(nft.metadata, serde_json::json!({"image": "mystery.png"}));
+        assert!(!nft.revealed);
+
+        let reveal = |from: &str, metadata: Value| Transaction::new(
+            "nft".to_string(),
+            "reveal_collection".to_string(),
+            Address::new(from.to_string()),
+            None,
+            serde_json::json!({"collection": collection_id, "metadata": metadata}),
+            0,
+        );
+
+        module.update_block_height(9);
+        let err = module.process_transaction(reveal("memechain1alice", Value::Null)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::RevealNotReady(_)))));
+
+        module.update_block_height(10);
+        let err = module.process_transaction(reveal("memechain1bob", Value::Null)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::Unauthorized(_)))));
+
+        // The second NFT's metadata is replaced at reveal time
+        let provided = serde_json::json!({nft_ids[1].clone(): {"image": "ape2-final.png"}});
+        let result = module.process_transaction(reveal("memechain1alice", provided)).await.unwrap();
+        assert_eq!(result.data.unwrap()["revealed"], 2);
+
+        let first = module.get_nft(&nft_ids[0]).await.unwrap().unwrap();
+        assert!(first.revealed);
+        assert_eq!(first.metadata, serde_json::json!({"image": "ape1.png"}));
+        let second = module.get_nft(&nft_ids[1]).await.unwrap().unwrap();
+        assert_eq!(second.metadata, serde_json::json!({"image": "ape2-final.png"}));
+
+        // Later mints are revealed immediately, and a second reveal is refused
+        let mut tx = mint_tx("memechain1alice", &collection_id);
+        tx.data["metadata"] = serde_json::json!({"image": "ape3.png"});
+        let result = module.process_transaction(tx).await.unwrap();
+        let third = module.get_nft(result.data.unwrap()["nft_id"].as_str().unwrap()).await.unwrap().unwrap();
+        assert_eq!(third.metadata, serde_json::json!({"image": "ape3.png"}));
+        let err = module.process_transaction(reveal("memechain1alice", Value::Null)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::RevealNotReady(_)))));
+    }
+
+    #[tokio::test]
+    async fn test_redeem_voucher() {
+        let storage = create_test_storage().await;
+        let mut module = NftModule::new(storage.clone(), ChainConfig::default()).await.unwrap();
+        let chain_id = ChainConfig::default().chain_id;
+        let creator_key = hex::encode([7u8; 32]);
+        let creator = Address::from_public_key(
+            &ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]).verifying_key().to_bytes(),
+        );
+        let collection_tx = Transaction::new(
+            "nft".to_string(),
+            "create_collection".to_string(),
+            creator.clone(),
+            None,
+            serde_json::json!({"name": "Lazy Apes"}),
+            0,
+        );
+        let result = module.process_transaction(collection_tx).await.unwrap();
+        let collection_id = result.data.unwrap()["collection_id"].as_str().unwrap().to_string();
+
+        let mut voucher = Voucher {
+            collection: collection_id.clone(),
+            name: "Lazy Ape #1".to_string(),
+            metadata: serde_json::json!({"image": "lazy1.png"}),
+            price: 300,
+            nonce: 1,
+            expires_at_height: None,
+            public_key: String::new(),
+            creator_sig: String::new(),
+        };
+        voucher.sign(&chain_id, &creator_key).unwrap();
+
+        let buyer = Address::from("memechain1bob");
+        storage.update_balance(&buyer, "MEME", 1000, 0).await.unwrap();
+        let redeem = |voucher: &Voucher| Transaction::new(
+            "nft".to_string(),
+            "redeem_voucher".to_string(),
+            buyer.clone(),
+            None,
+            serde_json::json!({"voucher": voucher}),
+            0,
+        );
+
+        let result = module.process_transaction(redeem(&voucher)).await.unwrap();
+        let redeemed: RedeemVoucherResult = result.typed().unwrap().unwrap();
+        assert_eq!(redeemed.creator, creator);
+        let nft = module.get_nft(&redeemed.nft_id).await.unwrap().unwrap();
+        assert_eq!((nft.owner, nft.collection_id), (buyer.clone(), collection_id));
+        assert_eq!(nft.metadata["image"], "lazy1.png");
+        assert_eq!(storage.get_balance(&buyer, "MEME").await.unwrap().unwrap().amount, 700);
+        assert_eq!(storage.get_balance(&creator, "MEME").await.unwrap().unwrap().amount, 300);
+
+        // Replays are rejected and charge nothing
+        let err = module.process_transaction(redeem(&voucher)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::VoucherRedeemed(_)))));
+        assert_eq!(storage.get_balance(&buyer, "MEME").await.unwrap().unwrap().amount, 700);
+
+        // A tampered price no longer matches the creator's signature
+        voucher.nonce = 2;
+        voucher.sign(&chain_id, &creator_key).unwrap();
+        voucher.price = 1;
+        let err = module.process_transaction(redeem(&voucher)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Common(_))));
+
+        // A voucher signed for another chain does not verify here
+        voucher.price = 300;
+        voucher.sign("otherchain-1", &creator_key).unwrap();
+        let err = module.process_transaction(redeem(&voucher)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Common(_))));
+
+        // Vouchers are redeemable up to and including their expiry height
+        voucher.nonce = 3;
+        voucher.expires_at_height = Some(5);
+        voucher.sign(&chain_id, &creator_key).unwrap();
+        module.update_block_height(6);
+        let err = module.process_transaction(redeem(&voucher)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Module(ModuleError::Nft(NftError::VoucherExpired(_)))));
+        assert_eq!(storage.get_balance(&buyer, "MEME").await.unwrap().unwrap().amount, 700);
+        module.update_block_height(5);
+        module.process_transaction(redeem(&voucher)).await.unwrap();
+        assert_eq!(storage.get_balance(&buyer, "MEME").await.unwrap().unwrap().amount, 400);
+    }
 } 
\ No newline at end of file
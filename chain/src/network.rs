@@ -0,0 +1,232 @@
+//! Static peer bookkeeping and the peer handshake.
+//!
+//! There is no gossip yet: the peer book is seeded from the configured
+//! persistent peers and updated as peers are contacted.
+
+use crate::config::NetworkConfig;
+use crate::error::{NetworkError, Result};
+use crate::types::Peer;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Version of the peer protocol this node speaks
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// First message each side of a peer connection sends
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Handshake {
+    pub node_id: String,
+    pub chain_id: String,
+    pub protocol_version: u32,
+    /// Hash identifying the genesis the node's state was built from
+    pub genesis_hash: String,
+}
+
+impl Handshake {
+    /// Handshake for this node at the current protocol version
+    pub fn new(node_id: String, chain_id: String, genesis_hash: String) -> Self {
+        Self {
+            node_id,
+            chain_id,
+            protocol_version: PROTOCOL_VERSION,
+            genesis_hash,
+        }
+    }
+}
+
+/// Check a peer's handshake against our own. Peers must be on the same chain
+/// and genesis and speak at least `min_protocol_version`.
+pub fn verify_handshake(local: &Handshake, remote: &Handshake, min_protocol_version: u32) -> Result<()> {
+    if remote.node_id.is_empty() {
+        return Err(NetworkError::InvalidMessage("Handshake is missing a node ID".to_string()).into());
+    }
+    if remote.node_id == local.node_id {
+        return Err(NetworkError::HandshakeFailed(format!("Peer {} is this node", remote.node_id)).into());
+    }
+    if remote.chain_id != local.chain_id {
+        return Err(NetworkError::HandshakeFailed(format!(
+            "Peer {} is on chain {}, expected {}", remote.node_id, remote.chain_id, local.chain_id
+        )).into());
+    }
+    if remote.genesis_hash != local.genesis_hash {
+        return Err(NetworkError::HandshakeFailed(format!(
+            "Peer {} has genesis {}, expected {}", remote.node_id, remote.genesis_hash, local.genesis_hash
+        )).into());
+    }
+    if remote.protocol_version < min_protocol_version {
+        return Err(NetworkError::ProtocolError(format!(
+            "Peer {} speaks protocol {}, at least {} required",
+            remote.node_id, remote.protocol_version, min_protocol_version
+        )).into());
+    }
+    Ok(())
+}
+
+/// Known peers, bounded by `max_peers`
+#[derive(Debug, Clone)]
+pub struct PeerBook {
+    peers: HashMap<String, Peer>,
+    max_peers: usize,
+}
+
+impl PeerBook {
+    /// Create an empty peer book
+    pub fn new(max_peers: u32) -> Self {
+        Self {
+            peers: HashMap::new(),
+            max_peers: max_peers as usize,
+        }
+    }
+
+    /// Create a peer book seeded with the configured persistent peers
+    pub fn from_config(config: &NetworkConfig) -> Result<Self> {
+        let mut book = Self::new(config.max_peers);
+        for entry in &config.persistent_peers {
+            book.add_peer(parse_peer(entry, true)?)?;
+        }
+        Ok(book)
+    }
+
+    /// Add a peer, or refresh `last_seen` if it is already known
+    pub fn add_peer(&mut self, peer: Peer) -> Result<()> {
+        if let Some(existing) = self.peers.get_mut(&peer.id) {
+            existing.update_last_seen();
+            return Ok(());
+        }
+
+        if self.peers.len() >= self.max_peers {
+            return Err(NetworkError::PeerLimitReached(self.max_peers).into());
+        }
+
+        debug!("Added peer {} at {}:{}", peer.id, peer.address, peer.port);
+        self.peers.insert(peer.id.clone(), peer);
+        Ok(())
+    }
+
+    /// Remove a peer
+    pub fn remove_peer(&mut self, id: &str) -> Option<Peer> {
+        self.peers.remove(id)
+    }
+
+    /// Record contact with a known peer
+    pub fn mark_seen(&mut self, id: &str) -> Result<()> {
+        let peer = self.peers
+            .get_mut(id)
+            .ok_or_else(|| NetworkError::PeerNotFound(id.to_string()))?;
+        peer.update_last_seen();
+        Ok(())
+    }
+
+    /// All known peers, ordered by ID
+    pub fn list_peers(&self) -> Vec<Peer> {
+        let mut peers: Vec<Peer> = self.peers.values().cloned().collect();
+        peers.sort_by(|a, b| a.id.cmp(&b.id));
+        peers
+    }
+
+    /// Number of known peers
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// Whether no peers are known
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+}
+
+/// Parse a peer in `id@host:port` form
+pub fn parse_peer(entry: &str, persistent: bool) -> Result<Peer> {
+    let invalid = || NetworkError::InvalidPeerAddress(entry.to_string());
+
+    let (id, host_port) = entry.split_once('@').ok_or_else(invalid)?;
+    let (host, port) = host_port.rsplit_once(':').ok_or_else(invalid)?;
+    let port = port.parse::<u16>().map_err(|_| invalid())?;
+    if id.is_empty() || host.is_empty() {
+        return Err(invalid().into());
+    }
+
+    Ok(Peer::new(id.to_string(), host.to_string(), port, persistent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_book_respects_max_peers() {
+        let config = NetworkConfig {
+            persistent_peers: vec!["node0@10.0.0.1:26656".to_string()],
+            max_peers: 3,
+            ..NetworkConfig::default()
+        };
+        let mut book = PeerBook::from_config(&config).unwrap();
+        assert_eq!(book.len(), 1);
+        assert!(book.list_peers()[0].persistent);
+
+        book.add_peer(parse_peer("node1@10.0.0.2:26656", false).unwrap()).unwrap();
+        book.add_peer(parse_peer("node2@10.0.0.3:26656", false).unwrap()).unwrap();
+        assert_eq!(book.len(), 3);
+
+        // Re-adding a known peer only refreshes it
+        book.add_peer(parse_peer("node1@10.0.0.2:26656", false).unwrap()).unwrap();
+        assert_eq!(book.len(), 3);
+
+        let err = book.add_peer(parse_peer("node3@10.0.0.4:26656", false).unwrap()).unwrap_err();
+        assert!(matches!(err, crate::error::MemeChainError::Network(NetworkError::PeerLimitReached(3))));
+
+        // Removing a peer frees a slot
+        assert!(book.remove_peer("node2").is_some());
+        book.add_peer(parse_peer("node3@10.0.0.4:26656", false).unwrap()).unwrap();
+        let ids: Vec<String> = book.list_peers().into_iter().map(|peer| peer.id).collect();
+        assert_eq!(ids, vec!["node0", "node1", "node3"]);
+    }
+
+    #[test]
+    fn test_parse_peer() {
+        let peer = parse_peer("abc123@seed.memechain.io:26656", true).unwrap();
+        assert_eq!(peer.id, "abc123");
+        assert_eq!(peer.address, "seed.memechain.io");
+        assert_eq!(peer.port, 26656);
+
+        assert!(parse_peer("seed.memechain.io:26656", false).is_err());
+        assert!(parse_peer("abc@seed.memechain.io", false).is_err());
+        assert!(parse_peer("abc@seed.memechain.io:notaport", false).is_err());
+    }
+
+    fn handshake(node_id: &str, chain_id: &str) -> Handshake {
+        Handshake::new(node_id.to_string(), chain_id.to_string(), "abc123".to_string())
+    }
+
+    #[test]
+    fn test_verify_handshake_matching_chain() {
+        let local = handshake("node0", "memechain-1");
+        verify_handshake(&local, &handshake("node1", "memechain-1"), PROTOCOL_VERSION).unwrap();
+
+        // Connecting to ourselves is refused
+        let err = verify_handshake(&local, &handshake("node0", "memechain-1"), PROTOCOL_VERSION).unwrap_err();
+        assert!(matches!(err, crate::error::MemeChainError::Network(NetworkError::HandshakeFailed(_))));
+    }
+
+    #[test]
+    fn test_verify_handshake_rejects_mismatches() {
+        use crate::error::MemeChainError;
+
+        let local = handshake("node0", "memechain-1");
+        let err = verify_handshake(&local, &handshake("node1", "memechain-2"), PROTOCOL_VERSION).unwrap_err();
+        assert!(matches!(err, MemeChainError::Network(NetworkError::HandshakeFailed(_))));
+        assert!(err.to_string().contains("memechain-2"));
+
+        let mut remote = handshake("node1", "memechain-1");
+        remote.genesis_hash = "def456".to_string();
+        let err = verify_handshake(&local, &remote, PROTOCOL_VERSION).unwrap_err();
+        assert!(matches!(err, MemeChainError::Network(NetworkError::HandshakeFailed(_))));
+
+        let mut remote = handshake("node1", "memechain-1");
+        remote.protocol_version = 0;
+        let err = verify_handshake(&local, &remote, PROTOCOL_VERSION).unwrap_err();
+        assert!(matches!(err, MemeChainError::Network(NetworkError::ProtocolError(_))));
+        verify_handshake(&local, &remote, 0).unwrap();
+    }
+} 
\ No newline at end of file
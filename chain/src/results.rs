@@ -0,0 +1,307 @@
+//! Typed transaction results.
+//!
+//! Module handlers build one of these and convert it into a
+//! `TransactionResult`; the JSON in `TransactionResult::data` is exactly the
+//! serialized struct, so the wire format is unchanged. Callers read it back
+//! with `TransactionResult::typed`.
+
+use crate::types::{Address, TransactionResult};
+use serde::{Deserialize, Serialize};
+
+/// `nft/create_collection`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CreateCollectionResult {
+    pub collection_id: String,
+    pub name: String,
+    pub creator: Address,
+}
+
+/// `nft/mint`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintNftResult {
+    pub nft_id: String,
+    pub collection_id: String,
+    pub name: String,
+    pub owner: Address,
+}
+
+/// `nft/transfer`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferNftResult {
+    pub nft_id: String,
+    pub from: Address,
+    pub to: Address,
+}
+
+/// `nft/batch_transfer`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchTransferNftResult {
+    pub nft_ids: Vec<String>,
+    pub from: Address,
+    pub to: Address,
+}
+
+/// `nft/burn`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BurnNftResult {
+    pub nft_id: String,
+    pub burned_by: Address,
+    /// Whether the burn claimed a physical redemption
+    #[serde(default)]
+    pub redeemed: bool,
+}
+
+/// `nft/update_metadata`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateMetadataResult {
+    pub nft_id: String,
+    pub updated_by: Address,
+}
+
+/// `nft/set_minters`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetMintersResult {
+    pub collection_id: String,
+    pub minters: Vec<Address>,
+}
+
+/// `nft/reveal_collection`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevealCollectionResult {
+    pub collection_id: String,
+    /// Number of NFTs whose metadata was revealed
+    pub revealed: usize,
+    pub revealed_by: Address,
+}
+
+/// `nft/redeem_voucher`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedeemVoucherResult {
+    pub nft_id: String,
+    pub collection_id: String,
+    pub owner: Address,
+    pub creator: Address,
+    /// Native token base units paid to the creator
+    pub price: u64,
+}
+
+/// `meme/create_token`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CreateTokenResult {
+    pub symbol: String,
+    pub name: String,
+    pub supply: u64,
+    pub creator: Address,
+    /// Set when liquidity was locked at creation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_start_block: Option<u64>,
+}
+
+/// One initial allocation of a `meme/launch_token`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaunchAllocation {
+    pub address: Address,
+    pub amount: u64,
+}
+
+/// `meme/launch_token`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaunchTokenResult {
+    pub symbol: String,
+    pub supply: u64,
+    pub creator: Address,
+    pub allocations: Vec<LaunchAllocation>,
+    /// Supply left with the creator after the allocations
+    pub creator_balance: u64,
+}
+
+/// `meme/transfer`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferTokenResult {
+    pub token: String,
+    pub amount: u64,
+    /// Transfer tax sent to the treasury out of `amount`
+    #[serde(default)]
+    pub fee: u64,
+    pub from: Address,
+    pub to: Address,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// `meme/burn`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BurnTokenResult {
+    pub token: String,
+    pub amount: u64,
+    pub burned_by: Address,
+    pub total_supply: u64,
+}
+
+/// `meme/buy`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuyTokenResult {
+    pub token: String,
+    pub amount: u64,
+    pub tax: u64,
+    pub buyer: Address,
+}
+
+/// `meme/sell`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SellTokenResult {
+    pub token: String,
+    pub amount: u64,
+    pub tax: u64,
+    pub seller: Address,
+}
+
+/// `meme/lock_liquidity`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockLiquidityResult {
+    pub token: String,
+    pub lock_start_block: u64,
+    pub lock_duration_blocks: u64,
+    pub locked_by: Address,
+}
+
+/// `meme/pause_token` and `meme/unpause_token`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PauseTokenResult {
+    pub token: String,
+    pub paused: bool,
+    pub updated_by: Address,
+}
+
+/// `meme/register_wrapped`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisterWrappedResult {
+    pub token: String,
+    pub origin_chain: String,
+    pub origin_asset: String,
+    pub registered_by: Address,
+}
+
+/// `common/register_multisig`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisterMultisigResult {
+    pub address: Address,
+    pub threshold: u32,
+    pub public_keys: Vec<String>,
+}
+
+/// `common/generate_keypair`
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenerateKeypairResult {
+    pub public_key: String,
+    /// Sensitive: hex private key, redacted from `Debug` output
+    pub private_key: String,
+}
+
+impl std::fmt::Debug for GenerateKeypairResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GenerateKeypairResult")
+            .field("public_key", &self.public_key)
+            .field("private_key", &crate::types::REDACTED)
+            .finish()
+    }
+}
+
+/// `common/hash_data`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashDataResult {
+    pub data: String,
+    pub hash: String,
+}
+
+/// `common/validate_address`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidateAddressResult {
+    pub address: String,
+    pub is_valid: bool,
+}
+
+macro_rules! impl_into_transaction_result {
+    ($($result:ty),* $(,)?) => {
+        $(
+            impl From<$result> for TransactionResult {
+                fn from(result: $result) -> Self {
+                    TransactionResult::success(serde_json::to_value(result).ok())
+                }
+            }
+        )*
+    };
+}
+
+impl_into_transaction_result!(
+    CreateCollectionResult,
+    MintNftResult,
+    TransferNftResult,
+    BatchTransferNftResult,
+    BurnNftResult,
+    UpdateMetadataResult,
+    SetMintersResult,
+    RevealCollectionResult,
+    RedeemVoucherResult,
+    CreateTokenResult,
+    LaunchTokenResult,
+    TransferTokenResult,
+    BurnTokenResult,
+    BuyTokenResult,
+    SellTokenResult,
+    LockLiquidityResult,
+    PauseTokenResult,
+    RegisterWrappedResult,
+    RegisterMultisigResult,
+    GenerateKeypairResult,
+    HashDataResult,
+    ValidateAddressResult,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_result_wire_format() {
+        let result: TransactionResult = MintNftResult {
+            nft_id: "nft-1".to_string(),
+            collection_id: "apes".to_string(),
+            name: "Ape 1".to_string(),
+            owner: Address::from("memechain1alice"),
+        }
+        .into();
+
+        assert!(result.success);
+        assert_eq!(
+            result.data,
+            Some(serde_json::json!({
+                "nft_id": "nft-1",
+                "collection_id": "apes",
+                "name": "Ape 1",
+                "owner": "memechain1alice",
+            }))
+        );
+
+        let typed: MintNftResult = result.typed().unwrap().unwrap();
+        assert_eq!(typed.owner, Address::from("memechain1alice"));
+    }
+
+    #[test]
+    fn test_typed_result_from_untyped_json() {
+        // Results built before typing existed read back the same way
+        let result = TransactionResult::success(Some(serde_json::json!({
+            "token": "PEPE",
+            "amount": 970,
+            "tax": 30,
+            "seller": "memechain1bob",
+        })));
+
+        let typed: SellTokenResult = result.typed().unwrap().unwrap();
+        assert_eq!(typed.amount, 970);
+        assert_eq!(typed.tax, 30);
+
+        assert!(result.typed::<MintNftResult>().is_err());
+        assert!(TransactionResult::failure("boom".to_string()).typed::<SellTokenResult>().unwrap().is_none());
+    }
+}
@@ -0,0 +1,275 @@
+//! JSON-RPC 2.0 compatibility layer served at `POST /rpc`.
+//!
+//! Methods map onto the same application logic as the REST API. Both single
+//! requests and batches (JSON arrays of requests) are supported.
+
+use crate::app::{AppState, MemeChainApp};
+use crate::types::{Address, Transaction};
+use axum::{body::Bytes, extract::State, http::{HeaderMap, StatusCode}, response::Json};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Invalid JSON was received
+pub const PARSE_ERROR: i64 = -32700;
+/// The JSON sent is not a valid request object
+pub const INVALID_REQUEST: i64 = -32600;
+/// The method does not exist
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// Invalid method parameters
+pub const INVALID_PARAMS: i64 = -32602;
+/// The application rejected the call
+pub const SERVER_ERROR: i64 = -32000;
+
+/// A single JSON-RPC request
+#[derive(Debug, Deserialize)]
+pub struct RpcRequest {
+    /// Protocol version, must be "2.0"
+    pub jsonrpc: String,
+    /// Request identifier echoed in the response
+    #[serde(default)]
+    pub id: Value,
+    /// Method name
+    pub method: String,
+    /// Positional (array) or named (object) parameters
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// A JSON-RPC error object
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcError {
+    /// Error code
+    pub code: i64,
+    /// Error message
+    pub message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<crate::error::MemeChainError> for RpcError {
+    fn from(err: crate::error::MemeChainError) -> Self {
+        Self::new(SERVER_ERROR, err.to_string())
+    }
+}
+
+/// A JSON-RPC response envelope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcResponse {
+    /// Protocol version, always "2.0"
+    pub jsonrpc: String,
+    /// Identifier of the request this answers
+    pub id: Value,
+    /// Call result on success
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// Error on failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    fn new(id: Value, outcome: std::result::Result<Value, RpcError>) -> Self {
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+/// `POST /rpc` handler
+pub(crate) async fn handle_rpc(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Json<Value>) {
+    let payload = match serde_json::from_slice::<Value>(&body) {
+        Ok(payload) => payload,
+        Err(e) => {
+            let response = RpcResponse::new(Value::Null, Err(RpcError::new(PARSE_ERROR, e.to_string())));
+            return (StatusCode::OK, Json(serde_json::to_value(response).unwrap_or_default()));
+        }
+    };
+
+    let app = state.app.read().await;
+    if let Err(message) = crate::app::check_chain_id(&app, &headers) {
+        let response = RpcResponse::new(Value::Null, Err(RpcError::new(SERVER_ERROR, message)));
+        return (StatusCode::CONFLICT, Json(serde_json::to_value(response).unwrap_or_default()));
+    }
+    (StatusCode::OK, Json(dispatch(&app, payload).await))
+}
+
+/// Handle a single request or a batch
+pub async fn dispatch(app: &MemeChainApp, payload: Value) -> Value {
+    let responses = match payload {
+        Value::Array(requests) if requests.is_empty() => {
+            let response = RpcResponse::new(Value::Null, Err(RpcError::new(INVALID_REQUEST, "Empty batch")));
+            return serde_json::to_value(response).unwrap_or_default();
+        }
+        Value::Array(requests) => {
+            let mut responses = Vec::with_capacity(requests.len());
+            for request in requests {
+                responses.push(handle_request(app, request).await);
+            }
+            responses
+        }
+        request => {
+            let response = handle_request(app, request).await;
+            return serde_json::to_value(response).unwrap_or_default();
+        }
+    };
+
+    serde_json::to_value(responses).unwrap_or_default()
+}
+
+/// Handle one request object
+async fn handle_request(app: &MemeChainApp, request: Value) -> RpcResponse {
+    let request: RpcRequest = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(e) => return RpcResponse::new(Value::Null, Err(RpcError::new(INVALID_REQUEST, e.to_string()))),
+    };
+
+    if request.jsonrpc != "2.0" {
+        return RpcResponse::new(
+            request.id,
+            Err(RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\"")),
+        );
+    }
+
+    let outcome = call(app, &request.method, &request.params).await;
+    RpcResponse::new(request.id, outcome)
+}
+
+/// Route a method call to the application
+async fn call(app: &MemeChainApp, method: &str, params: &Value) -> std::result::Result<Value, RpcError> {
+    match method {
+        "chain_getStatus" => Ok(app.status().await),
+        "chain_getBlock" => {
+            let height = param(params, 0, "height")
+                .and_then(Value::as_u64)
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing block height"))?;
+            let block = app.storage().get_block(height).await?;
+            Ok(serde_json::to_value(block).unwrap_or_default())
+        }
+        "tx_submit" => {
+            let tx = param(params, 0, "transaction")
+                .cloned()
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing transaction"))?;
+            let tx: Transaction = serde_json::from_value(tx)
+                .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?;
+            let hash = app.submit_transaction(tx).await?;
+            Ok(serde_json::json!({ "hash": hash }))
+        }
+        "account_getBalance" => {
+            let address = param(params, 0, "address")
+                .and_then(Value::as_str)
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing address"))?;
+            let token = param(params, 1, "token")
+                .and_then(Value::as_str)
+                .unwrap_or(&app.config().chain.native_symbol)
+                .to_string();
+            let address = Address::from(address);
+            let amount = app.storage().get_balance(&address, &token).await?
+                .map(|balance| balance.amount)
+                .unwrap_or(0);
+            Ok(serde_json::json!({
+                "address": address.to_string(),
+                "token": token,
+                "amount": amount,
+            }))
+        }
+        "nft_get" => {
+            let nft_id = param(params, 0, "id")
+                .and_then(Value::as_str)
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing NFT ID"))?;
+            let nft = app.nft_module().get_nft(nft_id).await?;
+            Ok(serde_json::to_value(nft).unwrap_or_default())
+        }
+        _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+    }
+}
+
+/// Look up a parameter by position (array params) or by name (object params)
+fn param<'a>(params: &'a Value, index: usize, name: &str) -> Option<&'a Value> {
+    match params {
+        Value::Array(values) => values.get(index),
+        Value::Object(map) => map.get(name),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TestAccount, TestNode, TEST_ACCOUNT_BALANCE};
+
+    #[tokio::test]
+    async fn test_rpc_get_balance() {
+        let node = TestNode::new().await.unwrap();
+        let alice = TestAccount::from_seed(1);
+        let app = node.app();
+        let app = app.read().await;
+
+        let response = dispatch(&app, serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "account_getBalance",
+            "params": {"address": alice.address().to_string()},
+        })).await;
+
+        assert_eq!(response["jsonrpc"], "2.0");
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["result"]["amount"], TEST_ACCOUNT_BALANCE);
+        assert!(response.get("error").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rpc_batch() {
+        let node = TestNode::new().await.unwrap();
+        let app = node.app();
+        let app = app.read().await;
+
+        let response = dispatch(&app, serde_json::json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "chain_getStatus"},
+            {"jsonrpc": "2.0", "id": 2, "method": "chain_getBlock", "params": [1]},
+            {"jsonrpc": "2.0", "id": 3, "method": "nft_get", "params": []},
+        ])).await;
+
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["result"]["block_height"], 0);
+        assert_eq!(responses[1]["id"], 2);
+        assert!(responses[1]["result"].is_null());
+        assert_eq!(responses[2]["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_method_not_found() {
+        let node = TestNode::new().await.unwrap();
+        let app = node.app();
+        let app = app.read().await;
+
+        let response = dispatch(&app, serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": "abc",
+            "method": "eth_blockNumber",
+        })).await;
+
+        assert_eq!(response["id"], "abc");
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+        assert!(response.get("result").is_none());
+    }
+}
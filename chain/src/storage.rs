@@ -1,10 +1,19 @@
+use crate::bin_value;
 use crate::config::StorageConfig;
 use crate::error::{MemeChainError, Result, StorageError};
-use crate::types::{Address, Balance, Block, Collection, Nft, Token};
-use rocksdb::{DBWithThreadMode, MultiThreaded, Options};
+use crate::types::{Address, Balance, Block, Collection, IdempotencyRecord, MultisigAccount, Nft, OwnershipRecord, PooledTransaction, Receipt, Redemption, Token};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use rocksdb::{BoundColumnFamily, ColumnFamilyDescriptor, DBCompressionType, DBWithThreadMode, MultiThreaded, Options};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
 use tracing::{debug, error, info, warn};
 
 /// Storage trait for different database backends
@@ -16,6 +25,15 @@ pub trait StorageBackend: Send + Sync {
     /// Get a value by key
     async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
     
+    /// Get several values at once, returned in the same order as `keys`
+    async fn multi_get(&self, keys: &[String]) -> Result<Vec<Option<Vec<u8>>>> {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push(self.get(key).await?);
+        }
+        Ok(values)
+    }
+    
     /// Set a key-value pair
     async fn set(&self, key: &str, value: &[u8]) -> Result<()>;
     
@@ -28,32 +46,252 @@ pub trait StorageBackend: Send + Sync {
     /// Get all keys with a prefix
     async fn get_keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>>;
     
+    /// Up to `limit` keys with a prefix that sort after `start_after`, in
+    /// order. The default filters a full prefix scan; backends that iterate
+    /// in key order override it to read only the page.
+    async fn get_keys_with_prefix_page(&self, prefix: &str, start_after: &str, limit: usize) -> Result<Vec<String>> {
+        let mut keys: Vec<String> = self
+            .get_keys_with_prefix(prefix)
+            .await?
+            .into_iter()
+            .filter(|key| key.as_str() > start_after)
+            .collect();
+        keys.sort();
+        keys.truncate(limit);
+        Ok(keys)
+    }
+    
     /// Batch write operations
     async fn batch_write(&self, operations: Vec<(String, Option<Vec<u8>>)>) -> Result<()>;
+    
+    /// Approximate size of the stored data in bytes, if the backend can
+    /// report it cheaply
+    async fn approximate_size(&self) -> Result<Option<u64>> {
+        Ok(None)
+    }
+    
+    /// Make every completed write durable; a no-op for backends that
+    /// already sync on write
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+    
+    /// Scheduled compaction counters, for backends that compact on a timer
+    fn compaction_stats(&self) -> Option<CompactionStats> {
+        None
+    }
+}
+
+/// Column family holding block records
+pub const CF_BLOCKS: &str = "blocks";
+/// Column family holding application state (balances, tokens, NFTs, ...)
+pub const CF_STATE: &str = "state";
+/// Column family holding secondary index entries
+pub const CF_INDEXES: &str = "indexes";
+/// Column family holding transaction receipts
+pub const CF_RECEIPTS: &str = "receipts";
+
+/// Key prefixes routed to a dedicated column family; everything else is state
+const CF_PREFIXES: &[(&str, &str)] = &[
+    ("block:", CF_BLOCKS),
+    ("idx:", CF_INDEXES),
+    ("receipt:", CF_RECEIPTS),
+];
+
+/// Column family a key is stored in
+fn column_family_for(key: &str) -> &'static str {
+    CF_PREFIXES
+        .iter()
+        .find(|(prefix, _)| key.starts_with(prefix))
+        .map(|(_, cf)| *cf)
+        .unwrap_or(CF_STATE)
+}
+
+/// Column families that may hold keys starting with `prefix`
+fn column_families_for_prefix(prefix: &str) -> Vec<&'static str> {
+    if let Some((_, cf)) = CF_PREFIXES.iter().find(|(cf_prefix, _)| prefix.starts_with(cf_prefix)) {
+        return vec![*cf];
+    }
+    
+    let mut families: Vec<&'static str> = CF_PREFIXES
+        .iter()
+        .filter(|(cf_prefix, _)| cf_prefix.starts_with(prefix))
+        .map(|(_, cf)| *cf)
+        .collect();
+    families.push(CF_STATE);
+    families
+}
+
+type RocksDb = DBWithThreadMode<MultiThreaded>;
+
+/// Keys moved per write batch when migrating the default column family
+const DEFAULT_CF_MIGRATION_BATCH: usize = 10_000;
+
+/// Look up an open column family handle
+fn cf_handle<'a>(db: &'a RocksDb, name: &str) -> Result<Arc<BoundColumnFamily<'a>>> {
+    db.cf_handle(name)
+        .ok_or_else(|| StorageError::ConnectionFailed(format!("Missing column family: {}", name)).into())
 }
 
 /// RocksDB storage backend
+///
+/// Keys are routed by prefix into the `blocks`, `state`, `indexes` and
+/// `receipts` column families, so each can be tuned and compacted (or
+/// pruned) independently. Key names are unchanged, so callers still see a
+/// single prefixed keyspace.
 pub struct RocksDBBackend {
-    db: Arc<DBWithThreadMode<MultiThreaded>>,
+    db: Arc<RocksDb>,
+    compaction: Arc<CompactionMetrics>,
+}
+
+/// Counters updated by the scheduled compaction task
+#[derive(Debug, Default)]
+struct CompactionMetrics {
+    runs: AtomicU64,
+    last_duration_ms: AtomicU64,
+    total_duration_ms: AtomicU64,
+}
+
+impl CompactionMetrics {
+    fn record(&self, duration: std::time::Duration) {
+        let millis = duration.as_millis() as u64;
+        self.runs.fetch_add(1, Ordering::Relaxed);
+        self.last_duration_ms.store(millis, Ordering::Relaxed);
+        self.total_duration_ms.fetch_add(millis, Ordering::Relaxed);
+    }
+    
+    fn snapshot(&self) -> CompactionStats {
+        CompactionStats {
+            runs: self.runs.load(Ordering::Relaxed),
+            last_duration_ms: self.last_duration_ms.load(Ordering::Relaxed),
+            total_duration_ms: self.total_duration_ms.load(Ordering::Relaxed),
+        }
+    }
 }
 
 impl RocksDBBackend {
     /// Create a new RocksDB backend
-    pub async fn new(path: &str) -> Result<Self> {
+    pub async fn new(config: &StorageConfig) -> Result<Self> {
+        let path = config.db_path.as_str();
         info!("Initializing RocksDB at path: {}", path);
         
+        if !config.fsync {
+            warn!("RocksDB fsync disabled; recent writes may be lost on crash");
+        }
+        
         let mut opts = Options::default();
         opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
         opts.set_max_open_files(10000);
-        opts.set_use_fsync(true);
-        opts.set_bytes_per_sync(1024 * 1024); // 1MB
+        opts.set_use_fsync(config.fsync);
+        opts.set_bytes_per_sync(config.bytes_per_sync);
         
-        let db = DBWithThreadMode::<MultiThreaded>::open(&opts, path)
-            .map_err(|e| StorageError::ConnectionFailed(e.to_string()))?;
+        let compression = config.effective_compression();
+        match compression.algorithm.as_str() {
+            "lz4" => opts.set_compression_type(DBCompressionType::Lz4),
+            "zstd" => {
+                opts.set_compression_type(DBCompressionType::Zstd);
+                // window_bits and strategy use RocksDB defaults; no dictionary
+                opts.set_compression_options(-14, compression.level, 0, 0);
+            }
+            _ => opts.set_compression_type(DBCompressionType::None),
+        }
         
-        Ok(Self {
-            db: Arc::new(db),
-        })
+        let descriptors = [CF_BLOCKS, CF_STATE, CF_INDEXES, CF_RECEIPTS]
+            .into_iter()
+            .map(|name| ColumnFamilyDescriptor::new(name, Self::cf_options(name, &opts, config)));
+        
+        let db = RocksDb::open_cf_descriptors(&opts, path, descriptors)
+            .map_err(|e| open_error(path, e.to_string()))?;
+        
+        let migrated = Self::migrate_default_cf(&db)?;
+        if migrated > 0 {
+            info!("Moved {} keys from the default column family into their column families", migrated);
+        }
+        
+        let db = Arc::new(db);
+        let compaction = Arc::new(CompactionMetrics::default());
+        if config.compaction_interval_secs > 0 {
+            tokio::spawn(Self::compact_periodically(
+                Arc::downgrade(&db),
+                compaction.clone(),
+                config.compaction_interval_secs,
+            ));
+        }
+        
+        Ok(Self { db, compaction })
+    }
+    
+    /// Move keys written by the single-column-family layout into the column
+    /// family their prefix is now read from. Returns the number moved.
+    fn migrate_default_cf(db: &RocksDb) -> Result<usize> {
+        let mut moved = 0;
+        loop {
+            let mut batch = rocksdb::WriteBatch::default();
+            let mut count = 0;
+            for entry in db.iterator(rocksdb::IteratorMode::Start).take(DEFAULT_CF_MIGRATION_BATCH) {
+                let (key, value) = entry.map_err(|e| StorageError::ReadFailed(e.to_string()))?;
+                let name = std::str::from_utf8(&key).map_err(|_| {
+                    StorageError::CorruptedData(format!(
+                        "Non-UTF-8 key in the default column family: {}", hex::encode(&key)
+                    ))
+                })?;
+                let cf = cf_handle(db, column_family_for(name))?;
+                batch.put_cf(&cf, &key, &value);
+                batch.delete(&key);
+                count += 1;
+            }
+            if count == 0 {
+                return Ok(moved);
+            }
+            db.write(batch).map_err(|e| StorageError::WriteFailed(e.to_string()))?;
+            moved += count;
+        }
+    }
+    
+    /// Compact every column family over its full key range
+    fn compact(db: &RocksDb) -> Result<()> {
+        for name in [CF_BLOCKS, CF_STATE, CF_INDEXES, CF_RECEIPTS] {
+            let cf = cf_handle(db, name)?;
+            db.compact_range_cf(&cf, None::<&[u8]>, None::<&[u8]>);
+        }
+        Ok(())
+    }
+    
+    /// Compact every `interval_secs` until the database is dropped
+    async fn compact_periodically(db: std::sync::Weak<RocksDb>, metrics: Arc<CompactionMetrics>, interval_secs: u64) {
+        let period = std::time::Duration::from_secs(interval_secs);
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        loop {
+            interval.tick().await;
+            let Some(db) = db.upgrade() else {
+                break;
+            };
+            let started = std::time::Instant::now();
+            match tokio::task::spawn_blocking(move || Self::compact(&db)).await {
+                Ok(Ok(())) => {
+                    let duration = started.elapsed();
+                    metrics.record(duration);
+                    info!("RocksDB compaction finished in {:?}", duration);
+                }
+                Ok(Err(e)) => warn!("RocksDB compaction failed: {}", e),
+                Err(e) => warn!("RocksDB compaction task failed: {}", e),
+            }
+        }
+    }
+    
+    /// Options for one column family, derived from the shared options
+    fn cf_options(name: &str, base: &Options, config: &StorageConfig) -> Options {
+        let mut opts = base.clone();
+        match name {
+            // Point lookups dominate state access
+            CF_STATE => opts.optimize_for_point_lookup(config.cache_size as u64),
+            // Index entries have empty values; compression buys nothing
+            CF_INDEXES => opts.set_compression_type(DBCompressionType::None),
+            // Append-only history
+            _ => opts.set_level_compaction_dynamic_level_bytes(true),
+        }
+        opts
     }
 }
 
@@ -69,8 +307,27 @@ impl StorageBackend for RocksDBBackend {
         let key = key.to_string();
         
         tokio::task::spawn_blocking(move || {
-            db.get(key.as_bytes())
-                .map_err(|e| StorageError::ReadFailed(e.to_string()))
+            let cf = cf_handle(&db, column_family_for(&key))?;
+            db.get_cf(&cf, key.as_bytes())
+                .map_err(|e| StorageError::ReadFailed(e.to_string()).into())
+        })
+        .await
+        .map_err(|e| StorageError::ReadFailed(e.to_string()))?
+    }
+    
+    async fn multi_get(&self, keys: &[String]) -> Result<Vec<Option<Vec<u8>>>> {
+        let db = self.db.clone();
+        let keys = keys.to_vec();
+        
+        tokio::task::spawn_blocking(move || {
+            let handles = keys
+                .iter()
+                .map(|key| cf_handle(&db, column_family_for(key)))
+                .collect::<Result<Vec<_>>>()?;
+            db.multi_get_cf(handles.iter().zip(keys.iter().map(|key| key.as_bytes())))
+                .into_iter()
+                .map(|result| result.map_err(|e| StorageError::ReadFailed(e.to_string()).into()))
+                .collect::<Result<Vec<_>>>()
         })
         .await
         .map_err(|e| StorageError::ReadFailed(e.to_string()))?
@@ -82,8 +339,9 @@ impl StorageBackend for RocksDBBackend {
         let value = value.to_vec();
         
         tokio::task::spawn_blocking(move || {
-            db.put(key.as_bytes(), &value)
-                .map_err(|e| StorageError::WriteFailed(e.to_string()))
+            let cf = cf_handle(&db, column_family_for(&key))?;
+            db.put_cf(&cf, key.as_bytes(), &value)
+                .map_err(|e| StorageError::WriteFailed(e.to_string()).into())
         })
         .await
         .map_err(|e| StorageError::WriteFailed(e.to_string()))?
@@ -94,8 +352,9 @@ impl StorageBackend for RocksDBBackend {
         let key = key.to_string();
         
         tokio::task::spawn_blocking(move || {
-            db.delete(key.as_bytes())
-                .map_err(|e| StorageError::WriteFailed(e.to_string()))
+            let cf = cf_handle(&db, column_family_for(&key))?;
+            db.delete_cf(&cf, key.as_bytes())
+                .map_err(|e| StorageError::WriteFailed(e.to_string()).into())
         })
         .await
         .map_err(|e| StorageError::WriteFailed(e.to_string()))?
@@ -111,24 +370,67 @@ impl StorageBackend for RocksDBBackend {
         let prefix = prefix.to_string();
         
         tokio::task::spawn_blocking(move || {
-            let iter = db.iterator(rocksdb::IteratorMode::From(prefix.as_bytes(), rocksdb::Direction::Forward));
             let mut keys = Vec::new();
             
-            for result in iter {
-                match result {
-                    Ok((key, _)) => {
-                        if key.starts_with(prefix.as_bytes()) {
+            for name in column_families_for_prefix(&prefix) {
+                let cf = cf_handle(&db, name)?;
+                let iter = db.iterator_cf(&cf, rocksdb::IteratorMode::From(prefix.as_bytes(), rocksdb::Direction::Forward));
+                
+                for result in iter {
+                    match result {
+                        Ok((key, _)) => {
+                            if !key.starts_with(prefix.as_bytes()) {
+                                break;
+                            }
                             if let Ok(key_str) = String::from_utf8(key.to_vec()) {
                                 keys.push(key_str);
                             }
                         }
+                        Err(e) => {
+                            warn!("Error iterating keys: {}", e);
+                        }
                     }
-                    Err(e) => {
-                        warn!("Error iterating keys: {}", e);
+                }
+            }
+            
+            keys.sort();
+            Ok(keys)
+        })
+        .await
+        .map_err(|e| StorageError::ReadFailed(e.to_string()))?
+    }
+    
+    async fn get_keys_with_prefix_page(&self, prefix: &str, start_after: &str, limit: usize) -> Result<Vec<String>> {
+        let db = self.db.clone();
+        let prefix = prefix.to_string();
+        let start = start_after.max(prefix.as_str()).to_string();
+        let start_after = start_after.to_string();
+        
+        tokio::task::spawn_blocking(move || {
+            let mut keys = Vec::new();
+            
+            // Each family yields its first `limit` keys; the smallest of
+            // those across families are the page
+            for name in column_families_for_prefix(&prefix) {
+                let cf = cf_handle(&db, name)?;
+                let iter = db.iterator_cf(&cf, rocksdb::IteratorMode::From(start.as_bytes(), rocksdb::Direction::Forward));
+                let mut taken = 0;
+                for result in iter {
+                    let (key, _) = result.map_err(|e| StorageError::ReadFailed(e.to_string()))?;
+                    if !key.starts_with(prefix.as_bytes()) || taken == limit {
+                        break;
+                    }
+                    if let Ok(key) = String::from_utf8(key.to_vec()) {
+                        if key > start_after {
+                            keys.push(key);
+                            taken += 1;
+                        }
                     }
                 }
             }
             
+            keys.sort();
+            keys.truncate(limit);
             Ok(keys)
         })
         .await
@@ -142,18 +444,43 @@ impl StorageBackend for RocksDBBackend {
             let mut batch = rocksdb::WriteBatch::default();
             
             for (key, value) in operations {
+                let cf = cf_handle(&db, column_family_for(&key))?;
                 match value {
-                    Some(val) => batch.put(key.as_bytes(), &val),
-                    None => batch.delete(key.as_bytes()),
+                    Some(val) => batch.put_cf(&cf, key.as_bytes(), &val),
+                    None => batch.delete_cf(&cf, key.as_bytes()),
                 }
             }
             
             db.write(batch)
-                .map_err(|e| StorageError::WriteFailed(e.to_string()))
+                .map_err(|e| StorageError::WriteFailed(e.to_string()).into())
         })
         .await
         .map_err(|e| StorageError::WriteFailed(e.to_string()))?
     }
+    
+    fn compaction_stats(&self) -> Option<CompactionStats> {
+        Some(self.compaction.snapshot())
+    }
+    
+    async fn approximate_size(&self) -> Result<Option<u64>> {
+        let db = self.db.clone();
+        
+        tokio::task::spawn_blocking(move || {
+            let mut total = 0;
+            for name in [CF_BLOCKS, CF_STATE, CF_INDEXES, CF_RECEIPTS] {
+                let cf = cf_handle(&db, name)?;
+                for property in ["rocksdb.total-sst-files-size", "rocksdb.size-all-mem-tables"] {
+                    total += db
+                        .property_int_value_cf(&cf, property)
+                        .map_err(|e| StorageError::ReadFailed(e.to_string()))?
+                        .unwrap_or(0);
+                }
+            }
+            Ok(Some(total))
+        })
+        .await
+        .map_err(|e| StorageError::ReadFailed(e.to_string()))?
+    }
 }
 
 /// Sled storage backend
@@ -162,16 +489,38 @@ pub struct SledBackend {
 }
 
 impl SledBackend {
-    /// Create a new Sled backend
-    pub async fn new(path: &str) -> Result<Self> {
+    /// Create a new Sled backend, flushing every `flush_every_ms` milliseconds
+    /// (0 disables the periodic flush)
+    pub async fn new(path: &str, flush_every_ms: u64) -> Result<Self> {
         info!("Initializing Sled at path: {}", path);
         
-        let db = sled::open(path)
-            .map_err(|e| StorageError::ConnectionFailed(e.to_string()))?;
+        // Flushing is driven by our own task instead of Sled's background thread
+        let db = sled::Config::new()
+            .path(path)
+            .flush_every_ms(None)
+            .open()
+            .map_err(|e| open_error(path, e.to_string()))?;
+        let db = Arc::new(db);
         
-        Ok(Self {
-            db: Arc::new(db),
-        })
+        if flush_every_ms > 0 {
+            tokio::spawn(Self::flush_periodically(Arc::downgrade(&db), flush_every_ms));
+        }
+        
+        Ok(Self { db })
+    }
+    
+    /// Flush until the database is dropped
+    async fn flush_periodically(db: std::sync::Weak<sled::Db>, flush_every_ms: u64) {
+        let mut interval = tokio::time::interval(std::time::Duration::from_millis(flush_every_ms));
+        loop {
+            interval.tick().await;
+            let Some(db) = db.upgrade() else {
+                break;
+            };
+            if let Err(e) = db.flush_async().await {
+                warn!("Periodic Sled flush failed: {}", e);
+            }
+        }
     }
 }
 
@@ -254,6 +603,31 @@ impl StorageBackend for SledBackend {
         .map_err(|e| StorageError::ReadFailed(e.to_string()))?
     }
     
+    async fn get_keys_with_prefix_page(&self, prefix: &str, start_after: &str, limit: usize) -> Result<Vec<String>> {
+        let db = self.db.clone();
+        let prefix = prefix.to_string();
+        let start_after = start_after.to_string();
+        
+        tokio::task::spawn_blocking(move || {
+            let start = start_after.as_str().max(prefix.as_str()).as_bytes().to_vec();
+            let mut keys = Vec::new();
+            for result in db.range(start..) {
+                let (key, _) = result.map_err(|e| StorageError::ReadFailed(e.to_string()))?;
+                if !key.starts_with(prefix.as_bytes()) || keys.len() == limit {
+                    break;
+                }
+                if let Ok(key) = String::from_utf8(key.to_vec()) {
+                    if key > start_after {
+                        keys.push(key);
+                    }
+                }
+            }
+            Ok(keys)
+        })
+        .await
+        .map_err(|e| StorageError::ReadFailed(e.to_string()))?
+    }
+    
     async fn batch_write(&self, operations: Vec<(String, Option<Vec<u8>>)>) -> Result<()> {
         let db = self.db.clone();
         
@@ -273,299 +647,2956 @@ impl StorageBackend for SledBackend {
         .await
         .map_err(|e| StorageError::WriteFailed(e.to_string()))?
     }
+    
+    async fn approximate_size(&self) -> Result<Option<u64>> {
+        self.db
+            .size_on_disk()
+            .map(Some)
+            .map_err(|e| StorageError::ReadFailed(e.to_string()).into())
+    }
+    
+    async fn flush(&self) -> Result<()> {
+        self.db
+            .flush_async()
+            .await
+            .map(|_| ())
+            .map_err(|e| StorageError::WriteFailed(e.to_string()).into())
+    }
 }
 
-/// Main storage interface
-pub struct Storage {
-    backend: Box<dyn StorageBackend>,
+/// In-memory storage backend for tests and ephemeral nodes
+#[derive(Default)]
+pub struct MemoryBackend {
+    data: std::sync::RwLock<BTreeMap<String, Vec<u8>>>,
 }
 
-impl Storage {
-    /// Create a new storage instance
-    pub async fn new(config: &StorageConfig) -> Result<Self> {
-        info!("Creating storage with type: {}", config.db_type);
-        
-        let backend: Box<dyn StorageBackend> = match config.db_type.as_str() {
-            "rocksdb" => {
-                let rocks_backend = RocksDBBackend::new(&config.db_path).await?;
-                Box::new(rocks_backend)
-            }
-            "sled" => {
-                let sled_backend = SledBackend::new(&config.db_path).await?;
-                Box::new(sled_backend)
-            }
-            _ => return Err(StorageError::ConnectionFailed(format!("Unknown database type: {}", config.db_type))),
-        };
-        
-        Ok(Self { backend })
+impl MemoryBackend {
+    /// Create a new, empty memory backend
+    pub fn new() -> Self {
+        Self::default()
     }
-    
-    /// Initialize storage
-    pub async fn initialize(&self) -> Result<()> {
-        self.backend.initialize().await
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for MemoryBackend {
+    async fn initialize(&self) -> Result<()> {
+        info!("Memory storage initialized");
+        Ok(())
     }
     
-    /// Store a block
-    pub async fn store_block(&self, block: &Block) -> Result<()> {
-        let key = format!("block:{}", block.height);
-        let value = serde_json::to_vec(block)?;
-        self.backend.set(&key, &value).await
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let data = self.data.read().map_err(|e| StorageError::ReadFailed(e.to_string()))?;
+        Ok(data.get(key).cloned())
     }
     
-    /// Get a block by height
-    pub async fn get_block(&self, height: u64) -> Result<Option<Block>> {
-        let key = format!("block:{}", height);
-        if let Some(data) = self.backend.get(&key).await? {
-            let block: Block = serde_json::from_slice(&data)?;
-            Ok(Some(block))
-        } else {
-            Ok(None)
-        }
+    async fn set(&self, key: &str, value: &[u8]) -> Result<()> {
+        let mut data = self.data.write().map_err(|e| StorageError::WriteFailed(e.to_string()))?;
+        data.insert(key.to_string(), value.to_vec());
+        Ok(())
     }
     
-    /// Store a token
-    pub async fn store_token(&self, token: &Token) -> Result<()> {
-        let key = format!("token:{}", token.symbol);
-        let value = serde_json::to_vec(token)?;
-        self.backend.set(&key, &value).await
+    async fn delete(&self, key: &str) -> Result<()> {
+        let mut data = self.data.write().map_err(|e| StorageError::WriteFailed(e.to_string()))?;
+        data.remove(key);
+        Ok(())
     }
     
-    /// Get a token by symbol
-    pub async fn get_token(&self, symbol: &str) -> Result<Option<Token>> {
-        let key = format!("token:{}", symbol);
-        if let Some(data) = self.backend.get(&key).await? {
-            let token: Token = serde_json::from_slice(&data)?;
-            Ok(Some(token))
-        } else {
-            Ok(None)
-        }
+    async fn exists(&self, key: &str) -> Result<bool> {
+        let data = self.data.read().map_err(|e| StorageError::ReadFailed(e.to_string()))?;
+        Ok(data.contains_key(key))
     }
     
-    /// Store an NFT
-    pub async fn store_nft(&self, nft: &Nft) -> Result<()> {
-        let key = format!("nft:{}", nft.id);
-        let value = serde_json::to_vec(nft)?;
-        self.backend.set(&key, &value).await
+    async fn get_keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>> {
+        let data = self.data.read().map_err(|e| StorageError::ReadFailed(e.to_string()))?;
+        Ok(data
+            .range(prefix.to_string()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, _)| key.clone())
+            .collect())
     }
     
-    /// Get an NFT by ID
-    pub async fn get_nft(&self, id: &str) -> Result<Option<Nft>> {
-        let key = format!("nft:{}", id);
-        if let Some(data) = self.backend.get(&key).await? {
-            let nft: Nft = serde_json::from_slice(&data)?;
-            Ok(Some(nft))
-        } else {
-            Ok(None)
-        }
+    async fn get_keys_with_prefix_page(&self, prefix: &str, start_after: &str, limit: usize) -> Result<Vec<String>> {
+        let data = self.data.read().map_err(|e| StorageError::ReadFailed(e.to_string()))?;
+        Ok(data
+            .range(start_after.max(prefix).to_string()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .filter(|(key, _)| key.as_str() > start_after)
+            .take(limit)
+            .map(|(key, _)| key.clone())
+            .collect())
     }
     
-    /// Store a collection
-    pub async fn store_collection(&self, collection: &Collection) -> Result<()> {
-        let key = format!("collection:{}", collection.id);
-        let value = serde_json::to_vec(collection)?;
-        self.backend.set(&key, &value).await
+    async fn batch_write(&self, operations: Vec<(String, Option<Vec<u8>>)>) -> Result<()> {
+        let mut data = self.data.write().map_err(|e| StorageError::WriteFailed(e.to_string()))?;
+        for (key, value) in operations {
+            match value {
+                Some(val) => data.insert(key, val),
+                None => data.remove(&key),
+            };
+        }
+        Ok(())
     }
-    
-    /// Get a collection by ID
-    pub async fn get_collection(&self, id: &str) -> Result<Option<Collection>> {
-        let key = format!("collection:{}", id);
-        if let Some(data) = self.backend.get(&key).await? {
-            let collection: Collection = serde_json::from_slice(&data)?;
-            Ok(Some(collection))
-        } else {
-            Ok(None)
+}
+
+/// Keys read per page by the entity streams (`iter_tokens`, `iter_nfts`)
+const SCAN_PAGE_SIZE: usize = 100;
+
+/// Key prefixes that are bookkeeping rather than application state. The
+/// `meta:` index and holder snapshots depend on node configuration, so
+/// they must not feed the hash.
+const NON_STATE_PREFIXES: &[&str] = &[
+    "block:",
+    "idempotency:",
+    "mempool:",
+    "meta:",
+    "node:",
+    "receipt:",
+    "snapshot:",
+    "snapshot_marker:",
+];
+
+/// LRU cache of query results, each tagged with the key prefixes it was
+/// read from so writes can evict exactly the results they affect
+struct QueryCache {
+    capacity: usize,
+    /// Bumped on every invalidation; results loaded across one are not cached
+    generation: u64,
+    tick: u64,
+    entries: HashMap<String, CachedQuery>,
+    /// Last-use tick -> cache key, oldest first
+    recency: BTreeMap<u64, String>,
+}
+
+struct CachedQuery {
+    last_used: u64,
+    prefixes: Vec<String>,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+impl QueryCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            generation: 0,
+            tick: 0,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
         }
     }
     
-    /// Store a balance
-    pub async fn store_balance(&self, balance: &Balance) -> Result<()> {
-        let key = format!("balance:{}:{}", balance.address, balance.token);
-        let value = serde_json::to_vec(balance)?;
-        self.backend.set(&key, &value).await
+    fn get<T: Clone + 'static>(&mut self, key: &str) -> Option<T> {
+        self.tick += 1;
+        let entry = self.entries.get_mut(key)?;
+        self.recency.remove(&entry.last_used);
+        entry.last_used = self.tick;
+        self.recency.insert(self.tick, key.to_string());
+        entry.value.downcast_ref::<T>().cloned()
     }
     
-    /// Get a balance
-    pub async fn get_balance(&self, address: &Address, token: &str) -> Result<Option<Balance>> {
-        let key = format!("balance:{}:{}", address, token);
-        if let Some(data) = self.backend.get(&key).await? {
-            let balance: Balance = serde_json::from_slice(&data)?;
-            Ok(Some(balance))
-        } else {
-            Ok(None)
+    fn insert<T: Send + Sync + 'static>(&mut self, key: String, prefixes: &[&str], generation: u64, value: T) {
+        if generation != self.generation {
+            return;
         }
-    }
-    
-    /// Get all tokens
-    pub async fn get_all_tokens(&self) -> Result<Vec<Token>> {
-        let keys = self.backend.get_keys_with_prefix("token:").await?;
-        let mut tokens = Vec::new();
-        
-        for key in keys {
-            if let Some(data) = self.backend.get(&key).await? {
-                if let Ok(token) = serde_json::from_slice::<Token>(&data) {
-                    tokens.push(token);
-                }
-            }
+        if let Some(old) = self.entries.remove(&key) {
+            self.recency.remove(&old.last_used);
         }
-        
-        Ok(tokens)
-    }
-    
-    /// Get all NFTs
-    pub async fn get_all_nfts(&self) -> Result<Vec<Nft>> {
-        let keys = self.backend.get_keys_with_prefix("nft:").await?;
-        let mut nfts = Vec::new();
-        
-        for key in keys {
-            if let Some(data) = self.backend.get(&key).await? {
-                if let Ok(nft) = serde_json::from_slice::<Nft>(&data) {
-                    nfts.push(nft);
-                }
-            }
+        while self.entries.len() >= self.capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else { break };
+            self.entries.remove(&oldest);
         }
-        
-        Ok(nfts)
+        self.tick += 1;
+        self.recency.insert(self.tick, key.clone());
+        self.entries.insert(key, CachedQuery {
+            last_used: self.tick,
+            prefixes: prefixes.iter().map(|prefix| prefix.to_string()).collect(),
+            value: Arc::new(value),
+        });
     }
     
-    /// Get all collections
-    pub async fn get_all_collections(&self) -> Result<Vec<Collection>> {
-        let keys = self.backend.get_keys_with_prefix("collection:").await?;
-        let mut collections = Vec::new();
-        
-        for key in keys {
-            if let Some(data) = self.backend.get(&key).await? {
-                if let Ok(collection) = serde_json::from_slice::<Collection>(&data) {
-                    collections.push(collection);
-                }
+    /// Evict every result read from a prefix of one of `keys`
+    fn invalidate(&mut self, keys: &BTreeSet<String>) {
+        self.generation += 1;
+        let touches = |prefix: &String| {
+            keys.range(prefix.clone()..).next().is_some_and(|key| key.starts_with(prefix.as_str()))
+        };
+        let stale: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.prefixes.iter().any(touches))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in stale {
+            if let Some(entry) = self.entries.remove(&key) {
+                self.recency.remove(&entry.last_used);
             }
         }
+    }
+}
+
+/// Buffered writes for one batch level (`None` marks a delete)
+type Layer = BTreeMap<String, Option<Vec<u8>>>;
+
+/// Index entry holding the sum of all token supplies
+const TOKEN_SUPPLY_TOTAL_KEY: &str = "idx:token_supply_total";
+
+/// Metadata keys the `meta:` index was last built for. Node configuration,
+/// like the index itself, so it is kept out of the app hash.
+const METADATA_INDEX_KEYS_KEY: &str = "node:metadata_index_keys";
+
+/// Name of the codec the database was written with
+const CODEC_KEY: &str = "node:codec";
+
+/// Whether the database may hold values written before checksums:
+/// `legacy` if so, `required` if every value must be sealed
+const CHECKSUMS_KEY: &str = "node:checksums";
+
+/// Trait index entry: number of NFTs in a collection with a trait value
+fn trait_key(collection_id: &str, trait_type: &str, value: &str) -> String {
+    format!(
+        "trait:{}:{}:{}",
+        escape_key_part(collection_id),
+        escape_key_part(trait_type),
+        escape_key_part(value)
+    )
+}
+
+/// Indexed form of metadata `key`: strings as is, other values by their JSON
+/// text, and nothing for a missing or null value
+fn metadata_index_value(metadata: &serde_json::Value, key: &str) -> Option<String> {
+    match metadata.get(key)? {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(value) => Some(value.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+/// Prefix of the `meta:` index entries for one metadata value in a collection
+fn metadata_index_prefix(collection_id: &str, key: &str, value: &str) -> String {
+    format!(
+        "meta:{}:{}:{}:",
+        escape_key_part(collection_id),
+        escape_key_part(key),
+        escape_key_part(value)
+    )
+}
+
+/// Prefix of the entries of the holder snapshot at `height`, which sit
+/// under `{prefix}{token}:`. The height is zero-padded so snapshots sort by
+/// height.
+fn snapshot_key(height: u64) -> String {
+    format!("snapshot:{:020}:", height)
+}
+
+/// Marker of a taken holder snapshot. Markers have a prefix of their own so
+/// pruning finds the snapshots without scanning their entries.
+fn snapshot_marker_key(height: u64) -> String {
+    format!("snapshot_marker:{:020}", height)
+}
+
+/// Escape `%` and `:` so a user-supplied string can sit between key separators
+fn escape_key_part(part: &str) -> String {
+    part.replace('%', "%25").replace(':', "%3A")
+}
+
+/// Reverse `escape_key_part`
+fn unescape_key_part(part: &str) -> String {
+    part.replace("%3A", ":").replace("%25", "%")
+}
+
+/// Address that receives block rewards
+const PROPOSER_KEY: &str = "chain:proposer";
+
+/// Hash of the genesis the database was initialized from
+const GENESIS_HASH_KEY: &str = "chain:genesis_hash";
+
+/// Height of the highest stored block; kept under `block:` so it stays out
+/// of the app hash like the blocks themselves
+const LATEST_BLOCK_KEY: &str = "block:latest";
+
+/// Native tokens minted as block rewards so far
+const INFLATION_TOTAL_KEY: &str = "chain:inflation_total";
+
+/// Serialization format for stored values.
+///
+/// Blobs stay JSON under either codec so their content addresses and the
+/// bytes served by `/blobs/:hash` do not depend on node configuration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StorageCodec {
+    /// `serde_json`, readable and compatible with older databases
+    #[default]
+    Json,
+    /// `bincode`, smaller and faster to parse
+    Bincode,
+}
+
+impl StorageCodec {
+    /// Parse a `StorageConfig::codec` name
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "json" => Ok(Self::Json),
+            "bincode" => Ok(Self::Bincode),
+            other => Err(crate::error::ConfigError::Invalid(format!("Unknown storage codec: {}", other)).into()),
+        }
+    }
+    
+    /// The `StorageConfig::codec` name of this codec
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Bincode => "bincode",
+        }
+    }
+    
+    /// Encode a value for storage
+    pub fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>> {
+        match self {
+            Self::Json => Ok(serde_json::to_vec(value)?),
+            Self::Bincode => bin_value::to_bytes(value)
+                .map_err(|e| StorageError::WriteFailed(format!("bincode encoding failed: {}", e)).into()),
+        }
+    }
+    
+    /// Decode a stored value
+    pub fn decode<T: DeserializeOwned>(&self, data: &[u8]) -> Result<T> {
+        match self {
+            Self::Json => Ok(serde_json::from_slice(data)?),
+            Self::Bincode => bin_value::from_slice(data)
+                .map_err(|e| StorageError::CorruptedData(format!("bincode decoding failed: {}", e)).into()),
+        }
+    }
+    
+    /// The bytes of a stored value that go into the app hash.
+    ///
+    /// Encoded values are hashed as canonical JSON (object keys sorted), so
+    /// nodes agree on the app hash whatever codec they store with. Values
+    /// written raw, like index markers, decimal counters and addresses, are
+    /// hashed as they are.
+    fn canonical(&self, data: Vec<u8>) -> Vec<u8> {
+        if data.is_empty() {
+            return data;
+        }
+        self.decode::<serde_json::Value>(&data)
+            .or_else(|_| Self::Json.decode::<serde_json::Value>(&data))
+            .and_then(|value| Ok(serde_json::to_vec(&value)?))
+            .unwrap_or(data)
+    }
+}
+
+/// NFT metadata larger than this many bytes is moved to the blob store
+pub const BLOB_THRESHOLD_BYTES: usize = 1024;
+
+/// Content address of a blob: hex SHA-256 of its bytes
+pub fn blob_hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Length of the truncated SHA-256 checksum prefixed to every stored value
+const CHECKSUM_LEN: usize = 4;
+
+/// Marks a sealed value: 0xFF never starts UTF-8, JSON or a bincode enum
+/// tag, so values written before checksums existed cannot carry it. The
+/// second byte is the seal format version.
+const SEAL_MAGIC: [u8; 2] = [0xFF, 0x01];
+
+/// Holder rank index key: sorts by amount descending, then by address
+fn holder_rank_key(token: &str, address: &Address, amount: u64) -> String {
+    format!("idx:holder_rank:{}:{:020}:{}", token, u64::MAX - amount, address)
+}
+
+/// Prefix a value with the seal marker and its checksum
+fn seal(value: &[u8]) -> Vec<u8> {
+    let mut sealed = Vec::with_capacity(SEAL_MAGIC.len() + CHECKSUM_LEN + value.len());
+    sealed.extend_from_slice(&SEAL_MAGIC);
+    sealed.extend_from_slice(&Sha256::digest(value)[..CHECKSUM_LEN]);
+    sealed.extend_from_slice(value);
+    sealed
+}
+
+/// Verify and strip the checksum written by `seal`. Values without the seal
+/// marker are returned unchanged if `accept_unsealed` (a database from
+/// before checksums), and rejected otherwise.
+fn unseal(key: &str, sealed: Vec<u8>, accept_unsealed: bool) -> Result<Vec<u8>> {
+    let Some(rest) = sealed.strip_prefix(&SEAL_MAGIC[..]) else {
+        if accept_unsealed {
+            return Ok(sealed);
+        }
+        return Err(StorageError::CorruptedData(format!("Missing checksum for key {}", key)).into());
+    };
+    if rest.len() < CHECKSUM_LEN || Sha256::digest(&rest[CHECKSUM_LEN..])[..CHECKSUM_LEN] != rest[..CHECKSUM_LEN] {
+        return Err(StorageError::CorruptedData(format!("Checksum mismatch for key {}", key)).into());
+    }
+    Ok(rest[CHECKSUM_LEN..].to_vec())
+}
+
+/// Check a database path before opening it: it must be a directory if it
+/// exists, and its parent is created if missing
+fn prepare_db_path(path: &Path) -> Result<()> {
+    if path.exists() && !path.is_dir() {
+        return Err(StorageError::InvalidPath(format!(
+            "{} exists and is not a directory; point db_path at a directory", path.display()
+        )).into());
+    }
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => StorageError::PermissionDenied(format!(
+                "cannot create {}; check the directory's owner and mode", parent.display()
+            )),
+            _ => StorageError::InvalidPath(format!("cannot create {}: {}", parent.display(), e)),
+        })?;
+    }
+    Ok(())
+}
+
+/// Phrases drivers use when another process (or handle) holds the database lock
+const LOCK_ERROR_PHRASES: [&str; 5] = [
+    "lock file",
+    "resource temporarily unavailable",
+    "could not acquire lock",
+    "lock hold by current process",
+    "no locks available",
+];
+
+/// Turn a driver's open failure into an actionable error
+fn open_error(path: &str, message: String) -> MemeChainError {
+    let lower = message.to_lowercase();
+    let error = if LOCK_ERROR_PHRASES.iter().any(|phrase| lower.contains(phrase)) {
+        StorageError::Locked(format!("{} is in use; is another node running on it? ({})", path, message))
+    } else if lower.contains("permission denied") {
+        StorageError::PermissionDenied(format!("cannot open {}; check the directory's owner and mode ({})", path, message))
+    } else if lower.contains("not a directory") {
+        StorageError::InvalidPath(format!("{} is not a directory ({})", path, message))
+    } else {
+        StorageError::ConnectionFailed(message)
+    };
+    error.into()
+}
+
+/// Main storage interface
+#[derive(Clone)]
+pub struct Storage {
+    backend: Arc<dyn StorageBackend>,
+    layers: Arc<Mutex<Vec<Layer>>>,
+    codec: StorageCodec,
+    /// Longest a backend request may take
+    timeout: Option<Duration>,
+    /// Slots for in-flight backend requests
+    slots: Option<Arc<Semaphore>>,
+    /// Cached query results
+    cache: Option<Arc<Mutex<QueryCache>>>,
+    /// NFT metadata keys kept in the `meta:` index
+    indexed_metadata_keys: Arc<BTreeSet<String>>,
+    /// Values without a checksum are read as is (see `CHECKSUMS_KEY`)
+    accept_unsealed: bool,
+}
+
+impl Storage {
+    /// Create a new storage instance
+    pub async fn new(config: &StorageConfig) -> Result<Self> {
+        info!("Creating storage with type: {}", config.db_type);
+        
+        if config.db_type != "memory" {
+            prepare_db_path(Path::new(&config.db_path))?;
+        }
+        
+        let backend: Arc<dyn StorageBackend> = match config.db_type.as_str() {
+            "rocksdb" => {
+                let rocks_backend = RocksDBBackend::new(config).await?;
+                Arc::new(rocks_backend)
+            }
+            "sled" => {
+                let sled_backend = SledBackend::new(&config.db_path, config.flush_every_ms).await?;
+                Arc::new(sled_backend)
+            }
+            "memory" => Arc::new(MemoryBackend::new()),
+            _ => return Err(StorageError::ConnectionFailed(format!("Unknown database type: {}", config.db_type))),
+        };
+        
+        let mut storage = Self::from_backend(backend)
+            .with_codec(StorageCodec::from_name(&config.codec)?)
+            .with_limits(config.request_timeout_ms, config.max_concurrent_requests)
+            .with_query_cache(config.query_cache_size)
+            .with_indexed_metadata_keys(&config.indexed_metadata_keys);
+        storage.accept_unsealed = storage.check_checksums().await?;
+        storage.check_codec().await?;
+        Ok(storage)
+    }
+    
+    /// Whether the database may hold values written before checksums. A new
+    /// database is recorded as requiring them; an existing one without a
+    /// record predates checksums and keeps accepting unsealed values.
+    async fn check_checksums(&self) -> Result<bool> {
+        match self.read(CHECKSUMS_KEY).await?.as_deref() {
+            Some(b"legacy") => Ok(true),
+            Some(b"required") => Ok(false),
+            Some(other) => Err(StorageError::CorruptedData(format!(
+                "Invalid value for {}: {}",
+                CHECKSUMS_KEY,
+                String::from_utf8_lossy(other)
+            ))
+            .into()),
+            None => {
+                let (keys, _) = self.keys_with_prefix_page("", "", 1).await?;
+                let legacy = !keys.is_empty();
+                self.put(CHECKSUMS_KEY, if legacy { b"legacy" } else { b"required" }).await?;
+                Ok(legacy)
+            }
+        }
+    }
+    
+    /// Fail if the database was written with a different codec than the
+    /// configured one, rather than failing later on the first value read.
+    /// A database without a record (new, or written before codecs were
+    /// recorded) adopts the configured codec.
+    async fn check_codec(&self) -> Result<()> {
+        match self.read(CODEC_KEY).await? {
+            Some(data) if data != self.codec.name().as_bytes() => Err(crate::error::ConfigError::Invalid(format!(
+                "Database was written with the {} codec, but storage.codec is {}",
+                String::from_utf8_lossy(&data),
+                self.codec.name()
+            ))
+            .into()),
+            Some(_) => Ok(()),
+            None => self.put(CODEC_KEY, self.codec.name().as_bytes()).await,
+        }
+    }
+    
+    /// Create a storage instance over an existing backend
+    pub fn from_backend(backend: Arc<dyn StorageBackend>) -> Self {
+        Self {
+            backend,
+            layers: Arc::new(Mutex::new(Vec::new())),
+            codec: StorageCodec::default(),
+            timeout: None,
+            slots: None,
+            cache: None,
+            indexed_metadata_keys: Arc::new(BTreeSet::new()),
+            accept_unsealed: false,
+        }
+    }
+    
+    /// Cache up to `capacity` query results (0 disables caching)
+    pub fn with_query_cache(mut self, capacity: usize) -> Self {
+        self.cache = (capacity > 0).then(|| Arc::new(Mutex::new(QueryCache::new(capacity))));
+        self
+    }
+    
+    /// Index NFTs by the values of these top-level metadata keys
+    pub fn with_indexed_metadata_keys(mut self, keys: &[String]) -> Self {
+        self.indexed_metadata_keys = Arc::new(keys.iter().cloned().collect());
+        self
+    }
+    
+    /// Whether NFTs are indexed by metadata `key`
+    pub fn is_metadata_indexed(&self, key: &str) -> bool {
+        self.indexed_metadata_keys.contains(key)
+    }
+    
+    fn lock_cache(cache: &Mutex<QueryCache>) -> std::sync::MutexGuard<'_, QueryCache> {
+        cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    /// Serve `key` from the query cache, or run `load` and cache its result
+    /// as depending on `prefixes`. Inside a batch the cache is bypassed so
+    /// buffered writes stay visible.
+    async fn cached<T, F, Fut>(&self, key: String, prefixes: &[&str], load: F) -> Result<T>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let Some(cache) = self.cache.as_ref().filter(|_| self.lock_layers().is_empty()) else {
+            return load().await;
+        };
+        let generation = {
+            let mut cache = Self::lock_cache(cache);
+            if let Some(value) = cache.get::<T>(&key) {
+                return Ok(value);
+            }
+            cache.generation
+        };
+        
+        let value = load().await?;
+        Self::lock_cache(cache).insert(key, prefixes, generation, value.clone());
+        Ok(value)
+    }
+    
+    /// Keys about to be written, when there is a cache to invalidate
+    fn written_keys(&self, operations: &[(String, Option<Vec<u8>>)]) -> Option<BTreeSet<String>> {
+        self.cache.as_ref()?;
+        Some(operations.iter().map(|(key, _)| key.clone()).collect())
+    }
+    
+    /// Evict cached results that depend on any of `keys`
+    fn invalidate_cache(&self, keys: Option<BTreeSet<String>>) {
+        if let (Some(cache), Some(keys)) = (&self.cache, keys) {
+            Self::lock_cache(cache).invalidate(&keys);
+        }
+    }
+    
+    /// Time out backend requests after `timeout_ms` and allow at most
+    /// `max_concurrent` in flight; 0 disables either limit
+    pub fn with_limits(mut self, timeout_ms: u64, max_concurrent: usize) -> Self {
+        self.timeout = (timeout_ms > 0).then(|| Duration::from_millis(timeout_ms));
+        self.slots = (max_concurrent > 0).then(|| Arc::new(Semaphore::new(max_concurrent)));
+        self
+    }
+    
+    /// Run a backend request within the configured limits, so a stalled
+    /// database surfaces as `StorageError::Timeout` instead of hanging the
+    /// caller (and any lock it holds).
+    ///
+    /// Requests made while a batch is open are not timed out. Batches carry
+    /// genesis and block execution, including the app hash, and a timeout
+    /// there would fail a block, or fork the node, over a slow disk rather
+    /// than over its transactions.
+    async fn call<T, Fut>(&self, operation: &str, request: impl FnOnce(Arc<dyn StorageBackend>) -> Fut) -> Result<T>
+    where
+        T: Send + 'static,
+        Fut: std::future::Future<Output = Result<T>> + Send + 'static,
+    {
+        let timeout = if self.lock_layers().is_empty() { self.timeout } else { None };
+        self.call_within(operation, timeout, request).await
+    }
+    
+    /// Like `call`, with an explicit timeout. The request runs as its own
+    /// task holding its slot, so a request abandoned by a timeout keeps
+    /// counting against `max_concurrent` until the backend is done with it.
+    async fn call_within<T, Fut>(
+        &self,
+        operation: &str,
+        timeout: Option<Duration>,
+        request: impl FnOnce(Arc<dyn StorageBackend>) -> Fut,
+    ) -> Result<T>
+    where
+        T: Send + 'static,
+        Fut: std::future::Future<Output = Result<T>> + Send + 'static,
+    {
+        let limited = async {
+            let slot = match &self.slots {
+                Some(slots) => Some(slots.clone().acquire_owned().await.map_err(|e| StorageError::ReadFailed(e.to_string()))?),
+                None => None,
+            };
+            let request = request(self.backend.clone());
+            tokio::spawn(async move {
+                let result = request.await;
+                drop(slot);
+                result
+            })
+            .await
+            .map_err(|e| StorageError::ReadFailed(e.to_string()))?
+        };
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, limited).await.map_err(|_| {
+                StorageError::Timeout(format!("{} took longer than {}ms", operation, timeout.as_millis()))
+            })?,
+            None => limited.await,
+        }
+    }
+    
+    /// Use `codec` for stored values
+    pub fn with_codec(mut self, codec: StorageCodec) -> Self {
+        self.codec = codec;
+        self
+    }
+    
+    /// Encode a value with the configured codec
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>> {
+        self.codec.encode(value)
+    }
+    
+    /// Decode a value with the configured codec
+    fn decode<T: DeserializeOwned>(&self, data: &[u8]) -> Result<T> {
+        self.codec.decode(data)
+    }
+    
+    /// Initialize storage
+    pub async fn initialize(&self) -> Result<()> {
+        self.backend.initialize().await
+    }
+    
+    /// Make all committed writes durable
+    pub async fn flush(&self) -> Result<()> {
+        self.backend.flush().await
+    }
+    
+    /// Start buffering writes in memory instead of sending them to the backend.
+    ///
+    /// Batches nest: committing an inner batch merges it into the outer one,
+    /// and only committing the outermost batch writes to the backend, as a
+    /// single atomic `batch_write`. Reads see buffered writes.
+    pub fn begin_batch(&self) {
+        self.lock_layers().push(Layer::new());
+    }
+    
+    /// Commit the innermost batch
+    pub async fn commit_batch(&self) -> Result<()> {
+        let operations = {
+            let mut layers = self.lock_layers();
+            let top = layers.pop().ok_or_else(|| {
+                StorageError::TransactionFailed("No batch in progress".to_string())
+            })?;
+            match layers.last_mut() {
+                Some(parent) => {
+                    parent.extend(top);
+                    return Ok(());
+                }
+                None => top.into_iter().collect::<Vec<_>>(),
+            }
+        };
+        
+        // Not timed out: a block's commit that outlived its timeout would still
+        // land, leaving state the caller believes was never written
+        let written = self.written_keys(&operations);
+        let result = self
+            .call_within("batch commit", None, |backend| async move { backend.batch_write(operations).await })
+            .await;
+        self.invalidate_cache(written);
+        result
+    }
+    
+    /// Number of keys buffered in the innermost batch, or 0 outside a batch
+    pub fn batch_len(&self) -> usize {
+        self.lock_layers().last().map_or(0, |layer| layer.len())
+    }
+    
+    /// Drop the innermost batch without writing anything
+    pub fn discard_batch(&self) {
+        self.lock_layers().pop();
+    }
+    
+    fn lock_layers(&self) -> std::sync::MutexGuard<'_, Vec<Layer>> {
+        self.layers.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    /// Look up a key in the buffered batches, newest first
+    fn buffered(&self, key: &str) -> Option<Option<Vec<u8>>> {
+        self.lock_layers()
+            .iter()
+            .rev()
+            .find_map(|layer| layer.get(key).cloned())
+    }
+    
+    /// Read a key, seeing buffered writes and verifying its checksum
+    async fn read(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let sealed = match self.buffered(key) {
+            Some(value) => value,
+            None => {
+                let key = key.to_string();
+                self.call("read", |backend| async move { backend.get(&key).await }).await?
+            }
+        };
+        sealed.map(|sealed| unseal(key, sealed, self.accept_unsealed)).transpose()
+    }
+    
+    /// Read several keys, seeing buffered writes
+    async fn read_many(&self, keys: &[String]) -> Result<Vec<Option<Vec<u8>>>> {
+        let buffered: Vec<_> = keys.iter().map(|key| self.buffered(key)).collect();
+        let missing: Vec<String> = keys
+            .iter()
+            .zip(&buffered)
+            .filter(|(_, value)| value.is_none())
+            .map(|(key, _)| key.clone())
+            .collect();
+        
+        let mut fetched = self
+            .call("read", |backend| async move { backend.multi_get(&missing).await })
+            .await?
+            .into_iter();
+        keys.iter()
+            .zip(buffered)
+            .map(|(key, value)| {
+                let sealed = match value {
+                    Some(value) => value,
+                    None => fetched.next().flatten(),
+                };
+                sealed.map(|sealed| unseal(key, sealed, self.accept_unsealed)).transpose()
+            })
+            .collect()
+    }
+    
+    /// List keys under a prefix, seeing buffered writes
+    async fn keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>> {
+        let owned_prefix = prefix.to_string();
+        let mut keys: BTreeSet<String> = self
+            .call("key scan", |backend| async move { backend.get_keys_with_prefix(&owned_prefix).await })
+            .await?
+            .into_iter()
+            .collect();
+        
+        for layer in self.lock_layers().iter() {
+            for (key, value) in layer.range(prefix.to_string()..).take_while(|(key, _)| key.starts_with(prefix)) {
+                match value {
+                    Some(_) => keys.insert(key.clone()),
+                    None => keys.remove(key),
+                };
+            }
+        }
+        
+        Ok(keys.into_iter().collect())
+    }
+    
+    /// One page of keys with `prefix` after `start_after`, seeing buffered
+    /// writes. Also returns the cursor for the next page, `None` at the end.
+    async fn keys_with_prefix_page(&self, prefix: &str, start_after: &str, limit: usize) -> Result<(Vec<String>, Option<String>)> {
+        let (owned_prefix, owned_start) = (prefix.to_string(), start_after.to_string());
+        let page = self
+            .call("key scan", |backend| async move {
+                backend.get_keys_with_prefix_page(&owned_prefix, &owned_start, limit).await
+            })
+            .await?;
+        // A full page only covers keys up to its last one
+        let end = (page.len() == limit).then(|| page.last().cloned()).flatten();
+        let mut keys: BTreeSet<String> = page.into_iter().collect();
+        
+        for layer in self.lock_layers().iter() {
+            let buffered = layer
+                .range(start_after.max(prefix).to_string()..)
+                .skip_while(|(key, _)| key.as_str() == start_after)
+                .take_while(|(key, _)| key.starts_with(prefix) && end.as_ref().map_or(true, |end| *key <= end));
+            for (key, value) in buffered {
+                match value {
+                    Some(_) => keys.insert(key.clone()),
+                    None => keys.remove(key),
+                };
+            }
+        }
+        
+        Ok((keys.into_iter().collect(), end))
+    }
+    
+    /// Stream the keys with `prefix` in key order, `SCAN_PAGE_SIZE` at a time
+    fn scan_key_pages(&self, prefix: String) -> impl Stream<Item = Result<Vec<String>>> + '_ {
+        stream::try_unfold(Some(String::new()), move |cursor| {
+            let prefix = prefix.clone();
+            async move {
+                let Some(cursor) = cursor else {
+                    return Ok(None);
+                };
+                let (keys, next) = self.keys_with_prefix_page(&prefix, &cursor, SCAN_PAGE_SIZE).await?;
+                Ok(Some((keys, next)))
+            }
+        })
+    }
+    
+    /// Stream the values of every key with `prefix`, in key order, reading
+    /// `SCAN_PAGE_SIZE` keys at a time
+    fn scan_values(&self, prefix: &str) -> impl Stream<Item = Result<Vec<u8>>> + '_ {
+        self.scan_key_pages(prefix.to_string())
+            .and_then(move |keys| async move { self.read_many(&keys).await })
+            .map_ok(|values| stream::iter(values.into_iter().flatten().map(Ok)))
+            .try_flatten()
+    }
+    
+    /// Stream every token without loading them all at once
+    pub fn iter_tokens(&self) -> impl Stream<Item = Result<Token>> + '_ {
+        self.scan_values("token:").map(move |data| self.decode::<Token>(&data?))
+    }
+    
+    /// Stream every NFT without loading them all at once
+    pub fn iter_nfts(&self) -> impl Stream<Item = Result<Nft>> + '_ {
+        self.scan_values("nft:").then(move |data| async move { self.decode_nft(&data?).await })
+    }
+    
+    /// Write operations, buffering them if a batch is in progress.
+    /// Values are sealed with a checksum that `read` verifies.
+    async fn write(&self, operations: Vec<(String, Option<Vec<u8>>)>) -> Result<()> {
+        let operations: Vec<_> = operations
+            .into_iter()
+            .map(|(key, value)| (key, value.map(|value| seal(&value))))
+            .collect();
+        {
+            let mut layers = self.lock_layers();
+            if let Some(top) = layers.last_mut() {
+                top.extend(operations);
+                return Ok(());
+            }
+        }
+        
+        let written = self.written_keys(&operations);
+        let result = self.call("write", |backend| async move { backend.batch_write(operations).await }).await;
+        self.invalidate_cache(written);
+        result
+    }
+    
+    /// Set a single key
+    async fn put(&self, key: &str, value: &[u8]) -> Result<()> {
+        self.write(vec![(key.to_string(), Some(value.to_vec()))]).await
+    }
+    
+    /// Store a block and record it as the chain tip
+    pub async fn store_block(&self, block: &Block) -> Result<()> {
+        let key = format!("block:{}", block.height);
+        let value = self.encode(block)?;
+        self.write(vec![
+            (key, Some(value)),
+            (LATEST_BLOCK_KEY.to_string(), Some(block.height.to_string().into_bytes())),
+        ]).await
+    }
+    
+    /// The highest stored block. Databases from before the tip was recorded
+    /// fall back to scanning block keys.
+    pub async fn get_latest_block(&self) -> Result<Option<Block>> {
+        let height = match self.read(LATEST_BLOCK_KEY).await? {
+            Some(data) => Some(String::from_utf8_lossy(&data).parse::<u64>().map_err(|e| {
+                StorageError::CorruptedData(format!("Invalid {}: {}", LATEST_BLOCK_KEY, e))
+            })?),
+            None => self
+                .keys_with_prefix("block:")
+                .await?
+                .iter()
+                .filter_map(|key| key.strip_prefix("block:")?.parse::<u64>().ok())
+                .max(),
+        };
+        match height {
+            Some(height) => self.get_block(height).await,
+            None => Ok(None),
+        }
+    }
+    
+    /// Get a block by height
+    pub async fn get_block(&self, height: u64) -> Result<Option<Block>> {
+        let key = format!("block:{}", height);
+        if let Some(data) = self.read(&key).await? {
+            let block: Block = self.decode(&data)?;
+            Ok(Some(block))
+        } else {
+            Ok(None)
+        }
+    }
+    
+    /// Store a token
+    pub async fn store_token(&self, token: &Token) -> Result<()> {
+        let key = format!("token:{}", token.symbol);
+        
+        // Keep the aggregate supply in step with mints and burns
+        let previous_supply = match self.read(&key).await? {
+            Some(data) => self.decode::<Token>(&data)?.total_supply,
+            None => 0,
+        };
+        let total_supply = self
+            .total_token_supply()
+            .await?
+            .checked_sub(previous_supply as u128)
+            .and_then(|total| total.checked_add(token.total_supply as u128))
+            .ok_or_else(|| StorageError::CorruptedData(format!(
+                "{} is inconsistent with the supply of {}", TOKEN_SUPPLY_TOTAL_KEY, token.symbol
+            )))?;
+        
+        let value = self.encode(token)?;
+        let index_key = format!("idx:token_creator:{}:{}", token.creator, token.symbol);
+        self.write(vec![
+            (key, Some(value)),
+            (index_key, Some(Vec::new())),
+            (TOKEN_SUPPLY_TOTAL_KEY.to_string(), Some(total_supply.to_string().into_bytes())),
+        ]).await
+    }
+    
+    /// Sum of the total supplies of every token, including the native token
+    pub async fn total_token_supply(&self) -> Result<u128> {
+        match self.read(TOKEN_SUPPLY_TOTAL_KEY).await? {
+            Some(data) => String::from_utf8_lossy(&data).parse().map_err(|_| {
+                StorageError::CorruptedData(format!("Invalid value for {}", TOKEN_SUPPLY_TOTAL_KEY)).into()
+            }),
+            None => Ok(0),
+        }
+    }
+    
+    /// Compute the aggregate supply from the `token:` records for databases
+    /// written before it was tracked. Returns the backfilled total, if any.
+    pub async fn backfill_token_supply_total(&self) -> Result<Option<u128>> {
+        if self.read(TOKEN_SUPPLY_TOTAL_KEY).await?.is_some() {
+            return Ok(None);
+        }
+        let total = self
+            .iter_tokens()
+            .try_fold(0u128, |total, token| async move {
+                total.checked_add(token.total_supply as u128).ok_or_else(|| {
+                    MemeChainError::from(StorageError::CorruptedData("Sum of token supplies overflows".to_string()))
+                })
+            })
+            .await?;
+        if total == 0 {
+            return Ok(None);
+        }
+        self.put(TOKEN_SUPPLY_TOTAL_KEY, total.to_string().as_bytes()).await?;
+        Ok(Some(total))
+    }
+    
+    /// Set the address that receives block rewards
+    pub async fn store_proposer(&self, address: &Address) -> Result<()> {
+        self.put(PROPOSER_KEY, address.to_string().as_bytes()).await
+    }
+    
+    /// Address that receives block rewards, if genesis named one
+    pub async fn get_proposer(&self) -> Result<Option<Address>> {
+        Ok(self
+            .read(PROPOSER_KEY)
+            .await?
+            .map(|data| Address::new(String::from_utf8_lossy(&data).into_owned())))
+    }
+    
+    /// Record the hash of the genesis this database was initialized from
+    pub async fn set_genesis_hash(&self, hash: &str) -> Result<()> {
+        self.put(GENESIS_HASH_KEY, hash.as_bytes()).await
+    }
+    
+    /// Hash of the genesis this database was initialized from, if recorded
+    pub async fn get_genesis_hash(&self) -> Result<Option<String>> {
+        Ok(self
+            .read(GENESIS_HASH_KEY)
+            .await?
+            .map(|data| String::from_utf8_lossy(&data).into_owned()))
+    }
+    
+    /// Add newly minted block rewards to the cumulative inflation total
+    pub async fn record_inflation(&self, amount: u64) -> Result<()> {
+        let total = self.total_inflation().await? + amount as u128;
+        self.put(INFLATION_TOTAL_KEY, total.to_string().as_bytes()).await
+    }
+    
+    /// Native tokens minted as block rewards since genesis
+    pub async fn total_inflation(&self) -> Result<u128> {
+        match self.read(INFLATION_TOTAL_KEY).await? {
+            Some(data) => String::from_utf8_lossy(&data).parse().map_err(|_| {
+                StorageError::CorruptedData(format!("Invalid value for {}", INFLATION_TOTAL_KEY)).into()
+            }),
+            None => Ok(0),
+        }
+    }
+    
+    /// Get a token by symbol
+    pub async fn get_token(&self, symbol: &str) -> Result<Option<Token>> {
+        let key = format!("token:{}", symbol);
+        let prefix = key.clone();
+        self.cached(key.clone(), &[prefix.as_str()], move || async move {
+            match self.read(&key).await? {
+                Some(data) => Ok(Some(self.decode::<Token>(&data)?)),
+                None => Ok(None),
+            }
+        }).await
+    }
+    
+    /// Store an NFT, keeping the owner and collection indexes in sync.
+    ///
+    /// Metadata over `BLOB_THRESHOLD_BYTES` is stored once under
+    /// `blob:{hash}` and the record keeps only the hash.
+    pub async fn store_nft(&self, nft: &Nft) -> Result<()> {
+        let key = format!("nft:{}", nft.id);
+        let mut operations = Vec::new();
+        
+        // Drop the index entry for the previous owner on transfer
+        let mut previous_traits = BTreeSet::new();
+        let mut previous_meta_keys = BTreeSet::new();
+        if let Some(data) = self.read(&key).await? {
+            let previous = self.decode_nft(&data).await?;
+            if previous.owner != nft.owner {
+                operations.push((format!("idx:nft_owner:{}:{}", previous.owner, nft.id), None));
+            }
+            previous_traits = previous.traits();
+            previous_meta_keys = self.metadata_index_keys(&previous);
+        }
+        
+        let meta_keys = self.metadata_index_keys(nft);
+        operations.extend(previous_meta_keys.difference(&meta_keys).map(|key| (key.clone(), None)));
+        operations.extend(meta_keys.difference(&previous_meta_keys).map(|key| (key.clone(), Some(Vec::new()))));
+        
+        let traits = nft.traits();
+        let removed = previous_traits.difference(&traits).map(|t| (t, -1));
+        let added = traits.difference(&previous_traits).map(|t| (t, 1));
+        let changes: Vec<_> = removed.chain(added).collect();
+        operations.extend(self.trait_count_updates(&nft.collection_id, changes).await?);
+        
+        let metadata = serde_json::to_vec(&nft.metadata)?;
+        let value = if metadata.len() > BLOB_THRESHOLD_BYTES {
+            let hash = blob_hash(&metadata);
+            let blob_key = format!("blob:{}", hash);
+            if self.read(&blob_key).await?.is_none() {
+                operations.push((blob_key, Some(metadata)));
+            }
+            self.encode(&Nft {
+                metadata: serde_json::Value::Null,
+                metadata_hash: Some(hash),
+                ..nft.clone()
+            })?
+        } else {
+            self.encode(&Nft {
+                metadata_hash: None,
+                ..nft.clone()
+            })?
+        };
+        
+        operations.push((key, Some(value)));
+        operations.push((format!("idx:nft_owner:{}:{}", nft.owner, nft.id), Some(Vec::new())));
+        operations.push((format!("idx:nft_collection:{}:{}", nft.collection_id, nft.id), Some(Vec::new())));
+        self.write(operations).await
+    }
+    
+    /// Delete an NFT and its index entries
+    pub async fn delete_nft(&self, nft: &Nft) -> Result<()> {
+        let traits = nft.traits();
+        let mut operations = self
+            .trait_count_updates(&nft.collection_id, traits.iter().map(|t| (t, -1)).collect())
+            .await?;
+        operations.extend([
+            (format!("nft:{}", nft.id), None),
+            (format!("idx:nft_owner:{}:{}", nft.owner, nft.id), None),
+            (format!("idx:nft_collection:{}:{}", nft.collection_id, nft.id), None),
+        ]);
+        operations.extend(self.metadata_index_keys(nft).into_iter().map(|key| (key, None)));
+        self.write(operations).await
+    }
+    
+    /// `meta:` index entries for an NFT's indexed metadata values
+    fn metadata_index_keys(&self, nft: &Nft) -> BTreeSet<String> {
+        self.indexed_metadata_keys
+            .iter()
+            .filter_map(|key| {
+                let value = metadata_index_value(&nft.metadata, key)?;
+                Some(format!("{}{}", metadata_index_prefix(&nft.collection_id, key, &value), nft.id))
+            })
+            .collect()
+    }
+    
+    /// NFTs in a collection whose metadata `key` has `value`, ordered by ID.
+    /// `key` must be one of the indexed metadata keys. Each hit is checked
+    /// against the NFT itself, so entries left stale by a change of indexed
+    /// keys are never returned.
+    pub async fn search_nfts_by_metadata(&self, collection_id: &str, key: &str, value: &str) -> Result<Vec<Nft>> {
+        let prefix = metadata_index_prefix(collection_id, key, value);
+        let mut nfts = Vec::new();
+        for index_key in self.keys_with_prefix(&prefix).await? {
+            if let Some(nft) = self.get_nft(&index_key[prefix.len()..]).await? {
+                if metadata_index_value(&nft.metadata, key).as_deref() == Some(value) {
+                    nfts.push(nft);
+                }
+            }
+        }
+        Ok(nfts)
+    }
+    
+    /// Rebuild the `meta:` index if the indexed metadata keys differ from
+    /// the ones it was built for, so NFTs stored before a key was added are
+    /// found too. Returns the number of entries written, if rebuilt.
+    pub async fn rebuild_metadata_index(&self) -> Result<Option<usize>> {
+        let built_for = match self.read(METADATA_INDEX_KEYS_KEY).await? {
+            Some(data) => Some(self.decode::<BTreeSet<String>>(&data)?),
+            None => None,
+        };
+        if built_for.as_ref() == Some(self.indexed_metadata_keys.as_ref()) {
+            return Ok(None);
+        }
+        
+        let pages: Vec<Vec<String>> = self.scan_key_pages("meta:".to_string()).try_collect().await?;
+        for keys in pages {
+            self.write(keys.into_iter().map(|key| (key, None)).collect()).await?;
+        }
+        let mut written = 0;
+        let mut nfts = std::pin::pin!(self.iter_nfts());
+        while let Some(nft) = nfts.try_next().await? {
+            let operations: Vec<_> = self
+                .metadata_index_keys(&nft)
+                .into_iter()
+                .map(|key| (key, Some(Vec::new())))
+                .collect();
+            written += operations.len();
+            if !operations.is_empty() {
+                self.write(operations).await?;
+            }
+        }
+        let keys = self.encode(self.indexed_metadata_keys.as_ref())?;
+        self.put(METADATA_INDEX_KEYS_KEY, &keys).await?;
+        Ok(Some(written))
+    }
+    
+    /// Writes that apply `changes` to a collection's trait counts; counts
+    /// that reach zero are removed
+    async fn trait_count_updates(
+        &self,
+        collection_id: &str,
+        changes: Vec<(&(String, String), i64)>,
+    ) -> Result<Vec<(String, Option<Vec<u8>>)>> {
+        let mut operations = Vec::with_capacity(changes.len());
+        for ((trait_type, value), delta) in changes {
+            let key = trait_key(collection_id, trait_type, value);
+            let count = match self.read(&key).await? {
+                Some(data) => String::from_utf8_lossy(&data).parse::<i64>().map_err(|_| {
+                    StorageError::CorruptedData(format!("Invalid value for {}", key))
+                })?,
+                None => 0,
+            };
+            let count = (count + delta).max(0);
+            operations.push((key, (count > 0).then(|| count.to_string().into_bytes())));
+        }
+        Ok(operations)
+    }
+    
+    /// Number of NFTs per trait value in a collection: trait -> value -> count
+    pub async fn trait_distribution(&self, collection_id: &str) -> Result<BTreeMap<String, BTreeMap<String, u64>>> {
+        let prefix = format!("trait:{}:", escape_key_part(collection_id));
+        let keys = self.keys_with_prefix(&prefix).await?;
+        let values = self.read_many(&keys).await?;
+        
+        let mut distribution: BTreeMap<String, BTreeMap<String, u64>> = BTreeMap::new();
+        for (key, value) in keys.iter().zip(values) {
+            let corrupted = || StorageError::CorruptedData(format!("Invalid trait index entry {}", key));
+            let (trait_type, trait_value) = key[prefix.len()..].split_once(':').ok_or_else(corrupted)?;
+            let count = value
+                .and_then(|data| String::from_utf8_lossy(&data).parse().ok())
+                .ok_or_else(corrupted)?;
+            distribution
+                .entry(unescape_key_part(trait_type))
+                .or_default()
+                .insert(unescape_key_part(trait_value), count);
+        }
+        Ok(distribution)
+    }
+    
+    /// Build the `trait:` index from the stored NFTs for databases written
+    /// before it existed. Returns the number of entries written.
+    pub async fn backfill_trait_index(&self) -> Result<usize> {
+        if !self.keys_with_prefix_page("trait:", "", 1).await?.0.is_empty()
+            || self.keys_with_prefix_page("nft:", "", 1).await?.0.is_empty()
+        {
+            return Ok(0);
+        }
+        
+        let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+        let mut nfts = std::pin::pin!(self.iter_nfts());
+        while let Some(nft) = nfts.try_next().await? {
+            for (trait_type, value) in nft.traits() {
+                *counts.entry(trait_key(&nft.collection_id, &trait_type, &value)).or_default() += 1;
+            }
+        }
+        
+        let written = counts.len();
+        let mut operations = counts.into_iter().map(|(key, count)| (key, Some(count.to_string().into_bytes())));
+        loop {
+            let page: Vec<_> = operations.by_ref().take(SCAN_PAGE_SIZE).collect();
+            if page.is_empty() {
+                break;
+            }
+            self.write(page).await?;
+        }
+        Ok(written)
+    }
+    
+    /// Get an NFT by ID
+    pub async fn get_nft(&self, id: &str) -> Result<Option<Nft>> {
+        let key = format!("nft:{}", id);
+        if let Some(data) = self.read(&key).await? {
+            Ok(Some(self.decode_nft(&data).await?))
+        } else {
+            Ok(None)
+        }
+    }
+    
+    /// Decode a stored NFT record, resolving metadata kept in the blob store
+    async fn decode_nft(&self, data: &[u8]) -> Result<Nft> {
+        let mut nft: Nft = self.decode(data)?;
+        if let Some(hash) = &nft.metadata_hash {
+            let blob = self.get_blob(hash).await?.ok_or_else(|| {
+                StorageError::CorruptedData(format!("Missing metadata blob {} for NFT {}", hash, nft.id))
+            })?;
+            nft.metadata = serde_json::from_slice(&blob)?;
+        }
+        Ok(nft)
+    }
+    
+    /// Get a content-addressed blob by its hash
+    pub async fn get_blob(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        self.read(&format!("blob:{}", hash)).await
+    }
+    
+    /// Append an entry to an NFT's ownership history (append-only; kept after burn)
+    pub async fn append_nft_history(&self, nft_id: &str, record: &OwnershipRecord) -> Result<()> {
+        let mut history = self.get_nft_history(nft_id).await?;
+        history.push(record.clone());
+        let key = format!("nft_history:{}", nft_id);
+        let value = self.encode(&history)?;
+        self.put(&key, &value).await
+    }
+    
+    /// Get an NFT's ownership history, oldest first
+    pub async fn get_nft_history(&self, nft_id: &str) -> Result<Vec<OwnershipRecord>> {
+        let key = format!("nft_history:{}", nft_id);
+        if let Some(data) = self.read(&key).await? {
+            Ok(self.decode(&data)?)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+    
+    /// Persist an admitted pool transaction under its hash
+    pub async fn store_pooled_transaction(&self, hash: &str, entry: &PooledTransaction) -> Result<()> {
+        let key = format!("mempool:{}", hash);
+        let value = self.encode(entry)?;
+        self.put(&key, &value).await
+    }
+    
+    /// Forget persisted pool transactions
+    pub async fn remove_pooled_transactions(&self, hashes: &[String]) -> Result<()> {
+        let operations = hashes.iter().map(|hash| (format!("mempool:{}", hash), None)).collect();
+        self.write(operations).await
+    }
+    
+    /// Every persisted pool transaction with its hash; undecodable entries are skipped
+    pub async fn pooled_transactions(&self) -> Result<Vec<(String, PooledTransaction)>> {
+        let keys = self.keys_with_prefix("mempool:").await?;
+        let values = self.read_many(&keys).await?;
+        let mut entries = Vec::new();
+        for (key, data) in keys.iter().zip(values) {
+            let Some(data) = data else { continue };
+            match self.decode::<PooledTransaction>(&data) {
+                Ok(entry) => entries.push((key["mempool:".len()..].to_string(), entry)),
+                Err(e) => warn!("Skipping undecodable pool entry {}: {}", key, e),
+            }
+        }
+        Ok(entries)
+    }
+    
+    /// Remember the result of a write under a client idempotency key
+    pub async fn store_idempotency_record(&self, key: &str, record: &IdempotencyRecord) -> Result<()> {
+        let key = format!("idempotency:{}", key);
+        let value = self.encode(record)?;
+        self.put(&key, &value).await
+    }
+    
+    /// Store a transaction receipt under its hash
+    pub async fn store_receipt(&self, receipt: &Receipt) -> Result<()> {
+        let key = format!("receipt:{}", receipt.tx_hash);
+        let value = self.encode(receipt)?;
+        self.put(&key, &value).await
+    }
+    
+    /// Get this node's persisted identity
+    pub async fn get_node_id(&self) -> Result<Option<String>> {
+        match self.read("node:id").await? {
+            Some(data) => Ok(Some(String::from_utf8_lossy(&data).into_owned())),
+            None => Ok(None),
+        }
+    }
+    
+    /// Persist this node's identity
+    pub async fn set_node_id(&self, node_id: &str) -> Result<()> {
+        self.put("node:id", node_id.as_bytes()).await
+    }
+    
+    /// Get the receipt of an included transaction
+    pub async fn get_receipt(&self, tx_hash: &str) -> Result<Option<Receipt>> {
+        let key = format!("receipt:{}", tx_hash);
+        if let Some(data) = self.read(&key).await? {
+            Ok(Some(self.decode(&data)?))
+        } else {
+            Ok(None)
+        }
+    }
+    
+    /// Delete idempotency records that expired at or before `now`. Returns
+    /// the number deleted.
+    pub async fn prune_idempotency_records(&self, now: i64) -> Result<usize> {
+        let pages: Vec<Vec<String>> = self.scan_key_pages("idempotency:".to_string()).try_collect().await?;
+        let mut expired = Vec::new();
+        for keys in pages {
+            let values = self.read_many(&keys).await?;
+            for (key, value) in keys.into_iter().zip(values) {
+                let Some(data) = value else { continue };
+                let record: IdempotencyRecord = self.decode(&data)?;
+                if record.expires_at <= now {
+                    expired.push((key, None));
+                }
+            }
+        }
+        let pruned = expired.len();
+        if pruned > 0 {
+            self.write(expired).await?;
+        }
+        Ok(pruned)
+    }
+    
+    /// Get the remembered result for an idempotency key, expired or not
+    pub async fn get_idempotency_record(&self, key: &str) -> Result<Option<IdempotencyRecord>> {
+        let key = format!("idempotency:{}", key);
+        if let Some(data) = self.read(&key).await? {
+            Ok(Some(self.decode(&data)?))
+        } else {
+            Ok(None)
+        }
+    }
+    
+    /// Keep an unrevealed NFT's real metadata out of its visible record
+    pub async fn store_sealed_metadata(&self, nft_id: &str, metadata: &serde_json::Value) -> Result<()> {
+        let key = format!("sealed:{}", nft_id);
+        let value = self.encode(metadata)?;
+        self.put(&key, &value).await
+    }
+    
+    /// Get and remove an NFT's sealed metadata
+    pub async fn take_sealed_metadata(&self, nft_id: &str) -> Result<Option<serde_json::Value>> {
+        let key = format!("sealed:{}", nft_id);
+        let Some(data) = self.read(&key).await? else {
+            return Ok(None);
+        };
+        self.write(vec![(key, None)]).await?;
+        Ok(Some(self.decode(&data)?))
+    }
+    
+    /// Record the physical redemption claimed by burning an NFT
+    pub async fn store_redemption(&self, redemption: &Redemption) -> Result<()> {
+        let key = format!("redemption:{}", redemption.nft_id);
+        let value = self.encode(redemption)?;
+        self.put(&key, &value).await
+    }
+    
+    /// Get the redemption recorded for a burned NFT
+    pub async fn get_redemption(&self, nft_id: &str) -> Result<Option<Redemption>> {
+        let key = format!("redemption:{}", nft_id);
+        match self.read(&key).await? {
+            Some(data) => Ok(Some(self.decode(&data)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Whether a voucher with this nullifier has been redeemed
+    pub async fn is_voucher_redeemed(&self, nullifier: &str) -> Result<bool> {
+        Ok(self.read(&format!("voucher:{}", nullifier)).await?.is_some())
+    }
+    
+    /// Record a voucher as redeemed for the NFT it minted
+    pub async fn mark_voucher_redeemed(&self, nullifier: &str, nft_id: &str) -> Result<()> {
+        self.put(&format!("voucher:{}", nullifier), nft_id.as_bytes()).await
+    }
+    
+    /// Store a collection
+    pub async fn store_collection(&self, collection: &Collection) -> Result<()> {
+        let key = format!("collection:{}", collection.id);
+        let value = self.encode(collection)?;
+        self.put(&key, &value).await
+    }
+    
+    /// Get a collection by ID
+    pub async fn get_collection(&self, id: &str) -> Result<Option<Collection>> {
+        let key = format!("collection:{}", id);
+        if let Some(data) = self.read(&key).await? {
+            let collection: Collection = self.decode(&data)?;
+            Ok(Some(collection))
+        } else {
+            Ok(None)
+        }
+    }
+    
+    /// Store a balance
+    pub async fn store_balance(&self, balance: &Balance) -> Result<()> {
+        let key = format!("balance:{}:{}", balance.address, balance.token);
+        let previous = self.previous_amount(&key).await?;
+        let value = self.encode(balance)?;
+        
+        // Only non-zero balances count as holders
+        let index_key = format!("idx:token_holder:{}:{}", balance.token, balance.address);
+        let index_value = (balance.amount > 0).then(Vec::new);
+        let mut operations = vec![
+            (key, Some(value)),
+            (index_key, index_value),
+        ];
+        if previous != balance.amount {
+            if previous > 0 {
+                operations.push((holder_rank_key(&balance.token, &balance.address, previous), None));
+            }
+            if balance.amount > 0 {
+                operations.push((holder_rank_key(&balance.token, &balance.address, balance.amount), Some(Vec::new())));
+            }
+        }
+        self.write(operations).await
+    }
+    
+    /// Delete a balance and its holder index entries
+    pub async fn delete_balance(&self, address: &Address, token: &str) -> Result<()> {
+        let key = format!("balance:{}:{}", address, token);
+        let mut operations = vec![(format!("idx:token_holder:{}:{}", token, address), None)];
+        let previous = self.previous_amount(&key).await?;
+        if previous > 0 {
+            operations.push((holder_rank_key(token, address, previous), None));
+        }
+        operations.push((key, None));
+        self.write(operations).await
+    }
+    
+    /// Amount currently stored under a balance key, zero if there is none
+    async fn previous_amount(&self, key: &str) -> Result<u64> {
+        match self.read(key).await? {
+            Some(data) => Ok(self.decode::<Balance>(&data)?.amount),
+            None => Ok(0),
+        }
+    }
+    
+    /// Build the holder rank index from the holder index for databases
+    /// written before it existed. Returns the number of entries written.
+    pub async fn backfill_holder_ranks(&self) -> Result<usize> {
+        if !self.keys_with_prefix_page("idx:holder_rank:", "", 1).await?.0.is_empty()
+            || self.keys_with_prefix_page("idx:token_holder:", "", 1).await?.0.is_empty()
+        {
+            return Ok(0);
+        }
+        
+        let prefix = "idx:token_holder:";
+        let pages: Vec<Vec<String>> = self.scan_key_pages(prefix.to_string()).try_collect().await?;
+        let mut written = 0;
+        for index_keys in pages {
+            let keys: Vec<String> = index_keys
+                .iter()
+                .filter_map(|key| key[prefix.len()..].split_once(':'))
+                .map(|(token, address)| format!("balance:{}:{}", address, token))
+                .collect();
+            let mut operations = Vec::new();
+            for data in self.read_many(&keys).await?.into_iter().flatten() {
+                let balance: Balance = self.decode(&data)?;
+                if balance.amount > 0 {
+                    operations.push((holder_rank_key(&balance.token, &balance.address, balance.amount), Some(Vec::new())));
+                }
+            }
+            written += operations.len();
+            if !operations.is_empty() {
+                self.write(operations).await?;
+            }
+        }
+        Ok(written)
+    }
+    
+    /// Stream the non-zero balances of a token, ordered by address, reading
+    /// the holder index a page at a time
+    pub fn iter_balances<'a>(&'a self, token: &'a str) -> impl Stream<Item = Result<Balance>> + 'a {
+        let prefix = format!("idx:token_holder:{}:", token);
+        self.scan_key_pages(prefix.clone())
+            .and_then(move |index_keys| {
+                let keys: Vec<String> = index_keys
+                    .iter()
+                    .map(|key| format!("balance:{}:{}", &key[prefix.len()..], token))
+                    .collect();
+                async move { self.read_many(&keys).await }
+            })
+            .map_ok(|values| stream::iter(values.into_iter().flatten().map(Ok)))
+            .try_flatten()
+            .map(move |data| self.decode::<Balance>(&data?))
+    }
+    
+    /// Write the non-zero balances of `token` to `writer` as CSV rows of
+    /// `address,amount,updated_at` under a header row, without holding them
+    /// all in memory. Returns the number of balances written.
+    pub async fn export_balances_csv<W: std::io::Write>(&self, token: &str, mut writer: W) -> Result<usize> {
+        writeln!(writer, "address,amount,updated_at")?;
+        let mut balances = std::pin::pin!(self.iter_balances(token));
+        let mut rows = 0;
+        while let Some(balance) = balances.try_next().await? {
+            writeln!(writer, "{},{},{}", balance.address, balance.amount, balance.updated_at)?;
+            rows += 1;
+        }
+        writer.flush()?;
+        Ok(rows)
+    }
+    
+    /// Record the current non-zero balances of `tokens` as the holder
+    /// snapshot for `height`, then delete all but the `retention` most
+    /// recent snapshots
+    pub async fn store_holder_snapshot(&self, height: u64, tokens: &[String], retention: usize) -> Result<()> {
+        let prefix = snapshot_key(height);
+        let mut operations = vec![(snapshot_marker_key(height), Some(Vec::new()))];
+        for token in tokens {
+            let mut balances = std::pin::pin!(self.iter_balances(token));
+            while let Some(balance) = balances.try_next().await? {
+                let key = format!("{}{}:{}", prefix, token, balance.address);
+                operations.push((key, Some(self.encode(&balance)?)));
+            }
+        }
+        self.write(operations).await?;
+        self.prune_holder_snapshots(retention).await
+    }
+    
+    /// Delete every holder snapshot older than the `retention` most recent
+    async fn prune_holder_snapshots(&self, retention: usize) -> Result<()> {
+        let pages: Vec<Vec<String>> = self.scan_key_pages("snapshot_marker:".to_string()).try_collect().await?;
+        let markers: Vec<String> = pages.into_iter().flatten().collect();
+        // Markers sort by height, so the oldest come first
+        let stale = markers.len().saturating_sub(retention);
+        for marker in &markers[..stale] {
+            let prefix = format!("snapshot:{}:", &marker["snapshot_marker:".len()..]);
+            let pages: Vec<Vec<String>> = self.scan_key_pages(prefix).try_collect().await?;
+            let mut operations: Vec<_> = pages.into_iter().flatten().map(|key| (key, None)).collect();
+            operations.push((marker.clone(), None));
+            self.write(operations).await?;
+        }
+        Ok(())
+    }
+    
+    /// Balances of `token` recorded by the snapshot at `height`, ordered by
+    /// address; `None` if no snapshot was taken at that height
+    pub async fn get_holder_snapshot(&self, token: &str, height: u64) -> Result<Option<Vec<Balance>>> {
+        if self.read(&snapshot_marker_key(height)).await?.is_none() {
+            return Ok(None);
+        }
+        let keys = self.keys_with_prefix(&format!("{}{}:", snapshot_key(height), token)).await?;
+        let mut balances = Vec::with_capacity(keys.len());
+        for data in self.read_many(&keys).await?.into_iter().flatten() {
+            balances.push(self.decode::<Balance>(&data)?);
+        }
+        Ok(Some(balances))
+    }
+    
+    /// Largest holders of a token by balance, descending, skipping `exclude`.
+    /// Reads the holder rank index a page at a time, so only about `limit`
+    /// balances are loaded however many holders the token has.
+    pub async fn top_holders(&self, token: &str, limit: usize, exclude: &[Address]) -> Result<Vec<Balance>> {
+        let prefix = format!("idx:holder_rank:{}:", token);
+        let mut holders = Vec::new();
+        let mut cursor = String::new();
+        while holders.len() < limit {
+            let page_size = (limit - holders.len()).saturating_add(exclude.len()).min(SCAN_PAGE_SIZE);
+            let (ranked, next) = self.keys_with_prefix_page(&prefix, &cursor, page_size).await?;
+            let keys: Vec<String> = ranked
+                .iter()
+                .filter_map(|key| key[prefix.len()..].split_once(':'))
+                .map(|(_, address)| Address::from(address))
+                .filter(|address| !exclude.contains(address))
+                .map(|address| format!("balance:{}:{}", address, token))
+                .collect();
+            for data in self.read_many(&keys).await?.into_iter().flatten() {
+                holders.push(self.decode::<Balance>(&data)?);
+            }
+            match next {
+                Some(next) => cursor = next,
+                None => break,
+            }
+        }
+        holders.truncate(limit);
+        Ok(holders)
+    }
+    
+    /// Get a balance
+    pub async fn get_balance(&self, address: &Address, token: &str) -> Result<Option<Balance>> {
+        let key = format!("balance:{}:{}", address, token);
+        if let Some(data) = self.read(&key).await? {
+            let balance: Balance = self.decode(&data)?;
+            Ok(Some(balance))
+        } else {
+            Ok(None)
+        }
+    }
+    
+    /// Store a multisig account
+    pub async fn store_multisig(&self, account: &MultisigAccount) -> Result<()> {
+        let key = format!("multisig:{}", account.address);
+        let value = self.encode(account)?;
+        self.put(&key, &value).await
+    }
+    
+    /// Get a multisig account by address
+    pub async fn get_multisig(&self, address: &Address) -> Result<Option<MultisigAccount>> {
+        let key = format!("multisig:{}", address);
+        if let Some(data) = self.read(&key).await? {
+            let account: MultisigAccount = self.decode(&data)?;
+            Ok(Some(account))
+        } else {
+            Ok(None)
+        }
+    }
+    
+    /// Get all tokens
+    pub async fn get_all_tokens(&self) -> Result<Vec<Token>> {
+        self.cached("all_tokens".to_string(), &["token:"], move || self.load_all_tokens()).await
+    }
+    
+    async fn load_all_tokens(&self) -> Result<Vec<Token>> {
+        let keys = self.keys_with_prefix("token:").await?;
+        let mut tokens = Vec::new();
+        
+        for data in self.read_many(&keys).await?.into_iter().flatten() {
+            if let Ok(token) = self.decode::<Token>(&data) {
+                tokens.push(token);
+            }
+        }
+        
+        Ok(tokens)
+    }
+    
+    /// Get all NFTs
+    pub async fn get_all_nfts(&self) -> Result<Vec<Nft>> {
+        self.cached("all_nfts".to_string(), &["nft:"], move || self.load_all_nfts()).await
+    }
+    
+    async fn load_all_nfts(&self) -> Result<Vec<Nft>> {
+        let keys = self.keys_with_prefix("nft:").await?;
+        let mut nfts = Vec::new();
+        
+        for data in self.read_many(&keys).await?.into_iter().flatten() {
+            if let Ok(nft) = self.decode_nft(&data).await {
+                nfts.push(nft);
+            }
+        }
+        
+        Ok(nfts)
+    }
+    
+    /// Get all collections
+    pub async fn get_all_collections(&self) -> Result<Vec<Collection>> {
+        self.cached("all_collections".to_string(), &["collection:"], move || self.load_all_collections()).await
+    }
+    
+    async fn load_all_collections(&self) -> Result<Vec<Collection>> {
+        let keys = self.keys_with_prefix("collection:").await?;
+        let mut collections = Vec::new();
+        
+        for data in self.read_many(&keys).await?.into_iter().flatten() {
+            if let Ok(collection) = self.decode::<Collection>(&data) {
+                collections.push(collection);
+            }
+        }
+        
+        Ok(collections)
+    }
+    
+    /// Compute a deterministic hash over the full application state.
+    ///
+    /// This is a flat SHA-256 over every state key/value in key order, each
+    /// length-prefixed; it can be swapped for a Merkle tree later without
+    /// changing callers. Keys under `NON_STATE_PREFIXES` are excluded, and
+    /// values are hashed in their codec-independent form (see
+    /// `StorageCodec::canonical`).
+    pub async fn app_hash(&self) -> Result<String> {
+        let keys: Vec<String> = self
+            .keys_with_prefix("")
+            .await?
+            .into_iter()
+            .filter(|key| !NON_STATE_PREFIXES.iter().any(|prefix| key.starts_with(prefix)))
+            .collect();
+        let values = self.read_many(&keys).await?;
+        
+        let mut hasher = Sha256::new();
+        for (key, value) in keys.iter().zip(values) {
+            let value = self.codec.canonical(value.unwrap_or_default());
+            hasher.update((key.len() as u64).to_be_bytes());
+            hasher.update(key.as_bytes());
+            hasher.update((value.len() as u64).to_be_bytes());
+            hasher.update(&value);
+        }
+        
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+    
+    /// Key counts per prefix and approximate on-disk size.
+    ///
+    /// A key's prefix is everything before its first `:`. The size comes from
+    /// the backend when it can report one (RocksDB properties, sled's
+    /// on-disk size); otherwise it is the sum of key and value lengths.
+    pub async fn stats(&self) -> Result<StorageStats> {
+        let keys = self.backend.get_keys_with_prefix("").await?;
+        
+        let mut keys_by_prefix = BTreeMap::new();
+        for key in &keys {
+            let prefix = key.split(':').next().unwrap_or_default();
+            *keys_by_prefix.entry(prefix.to_string()).or_insert(0) += 1;
+        }
+        
+        let approx_size_bytes = match self.backend.approximate_size().await? {
+            Some(size) => size,
+            None => {
+                let values = self.backend.multi_get(&keys).await?;
+                keys.iter()
+                    .zip(values)
+                    .map(|(key, value)| (key.len() + value.map_or(0, |value| value.len())) as u64)
+                    .sum()
+            }
+        };
+        
+        Ok(StorageStats {
+            total_keys: keys.len() as u64,
+            keys_by_prefix,
+            approx_size_bytes,
+            compaction: self.backend.compaction_stats(),
+        })
+    }
+    
+    /// Query NFTs by owner and/or collection with pagination
+    pub async fn query_nfts(&self, query: &QueryNfts) -> Result<Page<Nft>> {
+        let prefixes = ["nft:", "idx:nft_owner:", "idx:nft_collection:"];
+        self.cached(format!("{:?}", query), &prefixes, move || self.load_nfts(query)).await
+    }
+    
+    async fn load_nfts(&self, query: &QueryNfts) -> Result<Page<Nft>> {
+        let owner_ids = match &query.owner {
+            Some(owner) => Some(self.index_ids(&format!("idx:nft_owner:{}:", owner)).await?),
+            None => None,
+        };
+        let collection_ids = match &query.collection {
+            Some(collection) => Some(self.index_ids(&format!("idx:nft_collection:{}:", collection)).await?),
+            None => None,
+        };
+        
+        let mut ids = match (owner_ids, collection_ids) {
+            (Some(owner_ids), Some(collection_ids)) => {
+                let collection_ids: HashSet<String> = collection_ids.into_iter().collect();
+                owner_ids.into_iter().filter(|id| collection_ids.contains(id)).collect()
+            }
+            (Some(ids), None) | (None, Some(ids)) => ids,
+            (None, None) => self.index_ids("nft:").await?,
+        };
+        ids.sort();
+        
+        let total = ids.len();
+        let keys: Vec<String> = paginate(ids, query.offset, query.limit)
+            .into_iter()
+            .map(|id| format!("nft:{}", id))
+            .collect();
+        
+        let mut items = Vec::with_capacity(keys.len());
+        for data in self.read_many(&keys).await?.into_iter().flatten() {
+            items.push(self.decode_nft(&data).await?);
+        }
+        
+        Ok(Page { items, total })
+    }
+    
+    /// Query tokens, optionally by creator, with pagination
+    pub async fn query_tokens(&self, query: &QueryTokens) -> Result<Page<Token>> {
+        let prefixes = ["token:", "idx:token_creator:"];
+        self.cached(format!("{:?}", query), &prefixes, move || self.load_tokens(query)).await
+    }
+    
+    async fn load_tokens(&self, query: &QueryTokens) -> Result<Page<Token>> {
+        let mut symbols = match &query.creator {
+            Some(creator) => self.index_ids(&format!("idx:token_creator:{}:", creator)).await?,
+            None => self.index_ids("token:").await?,
+        };
+        symbols.sort();
+        
+        let total = symbols.len();
+        let keys: Vec<String> = paginate(symbols, query.offset, query.limit)
+            .into_iter()
+            .map(|symbol| format!("token:{}", symbol))
+            .collect();
+        
+        let mut items = Vec::with_capacity(keys.len());
+        for data in self.read_many(&keys).await?.into_iter().flatten() {
+            items.push(self.decode::<Token>(&data)?);
+        }
+        
+        Ok(Page { items, total })
+    }
+    
+    /// List the trailing ID segment of every key under a prefix
+    async fn index_ids(&self, prefix: &str) -> Result<Vec<String>> {
+        let keys = self.keys_with_prefix(prefix).await?;
+        Ok(keys
+            .into_iter()
+            .map(|key| key[prefix.len()..].to_string())
+            .collect())
+    }
+    
+    /// Next nonce expected from `address`
+    pub async fn get_nonce(&self, address: &Address) -> Result<u64> {
+        let key = format!("nonce:{}", address);
+        match self.read(&key).await? {
+            Some(data) => String::from_utf8_lossy(&data).parse().map_err(|_| {
+                StorageError::CorruptedData(format!("Invalid value for {}", key)).into()
+            }),
+            None => Ok(0),
+        }
+    }
+    
+    /// Set the next nonce expected from `address`
+    pub async fn set_nonce(&self, address: &Address, next: u64) -> Result<()> {
+        self.put(&format!("nonce:{}", address), next.to_string().as_bytes()).await
+    }
+    
+    /// Update balance atomically, stamping it with `now` (unix seconds)
+    pub async fn update_balance(&self, address: &Address, token: &str, amount: i64, now: i64) -> Result<()> {
+        let current_balance = self.get_balance(address, token).await?;
+        let new_amount = match current_balance {
+            Some(mut balance) => {
+                if amount > 0 {
+                    balance.add(amount as u64, now);
+                } else {
+                    balance.subtract((-amount) as u64, now)?;
+                }
+                balance.amount
+            }
+            None => {
+                if amount < 0 {
+                    return Err(StorageError::WriteFailed("Cannot create negative balance".to_string()));
+                }
+                amount as u64
+            }
+        };
+        
+        let new_balance = Balance::new(address.clone(), token.to_string(), new_amount, now);
+        self.store_balance(&new_balance).await
+    }
+}
+
+/// Database size and key counts, as reported by `Storage::stats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageStats {
+    /// Number of committed keys
+    pub total_keys: u64,
+    /// Committed keys per prefix (the part before the first `:`)
+    pub keys_by_prefix: BTreeMap<String, u64>,
+    /// Approximate size of the stored data in bytes
+    pub approx_size_bytes: u64,
+    /// Scheduled compaction counters, when the backend compacts on a timer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compaction: Option<CompactionStats>,
+}
+
+/// Scheduled compaction runs and their durations
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionStats {
+    /// Completed compactions
+    pub runs: u64,
+    /// Duration of the latest compaction in milliseconds
+    pub last_duration_ms: u64,
+    /// Time spent compacting since startup in milliseconds
+    pub total_duration_ms: u64,
+}
+
+/// NFT query filters
+#[derive(Debug, Clone, Default)]
+pub struct QueryNfts {
+    /// Only NFTs owned by this address
+    pub owner: Option<Address>,
+    /// Only NFTs in this collection
+    pub collection: Option<String>,
+    /// Maximum number of results
+    pub limit: Option<usize>,
+    /// Number of results to skip
+    pub offset: usize,
+}
+
+/// Token query filters
+#[derive(Debug, Clone, Default)]
+pub struct QueryTokens {
+    /// Only tokens created by this address
+    pub creator: Option<Address>,
+    /// Maximum number of results
+    pub limit: Option<usize>,
+    /// Number of results to skip
+    pub offset: usize,
+}
+
+/// A page of query results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page<T> {
+    /// Items in this page
+    pub items: Vec<T>,
+    /// Total number of matching items
+    pub total: usize,
+}
+
+/// Apply offset/limit to a sorted list of IDs
+fn paginate(ids: Vec<String>, offset: usize, limit: Option<usize>) -> Vec<String> {
+    let iter = ids.into_iter().skip(offset);
+    match limit {
+        Some(limit) => iter.take(limit).collect(),
+        None => iter.collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CompressionConfig;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_rocksdb_storage() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("test_db");
+        
+        let config = StorageConfig {
+            db_path: path.to_str().unwrap().to_string(),
+            ..StorageConfig::default()
+        };
+        let backend = RocksDBBackend::new(&config).await.unwrap();
+        backend.initialize().await.unwrap();
+        
+        // Test set and get
+        backend.set("test_key", b"test_value").await.unwrap();
+        let value = backend.get("test_key").await.unwrap();
+        assert_eq!(value, Some(b"test_value".to_vec()));
+        
+        // Test exists
+        assert!(backend.exists("test_key").await.unwrap());
+        assert!(!backend.exists("nonexistent").await.unwrap());
+        
+        // Test delete
+        backend.delete("test_key").await.unwrap();
+        assert!(!backend.exists("test_key").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_storage_operations() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("test_storage");
+        
+        let config = StorageConfig {
+            db_path: path.to_str().unwrap().to_string(),
+            db_type: "rocksdb".to_string(),
+            cache_size: 100,
+            enable_compression: false,
+            ..StorageConfig::default()
+        };
+        
+        let storage = Storage::new(&config).await.unwrap();
+        storage.initialize().await.unwrap();
+        
+        // Test token storage
+        let token = Token::new(
+            "TEST".to_string(),
+            "Test Token".to_string(),
+            1000000,
+            Address::new("memechain1alice".to_string()),
+            crate::types::AntiRugSettings::default(),
+            0,
+        );
+        
+        storage.store_token(&token).await.unwrap();
+        let retrieved = storage.get_token("TEST").await.unwrap();
+        assert!(retrieved.is_some());
+        assert_eq!(retrieved.unwrap().symbol, "TEST");
+    }
+
+    #[tokio::test]
+    async fn test_rocksdb_without_fsync() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("test_db_no_fsync");
+        
+        let config = StorageConfig {
+            db_path: path.to_str().unwrap().to_string(),
+            fsync: false,
+            bytes_per_sync: 0,
+            ..StorageConfig::default()
+        };
+        let backend = RocksDBBackend::new(&config).await.unwrap();
+        backend.initialize().await.unwrap();
+        
+        backend.set("test_key", b"test_value").await.unwrap();
+        let value = backend.get("test_key").await.unwrap();
+        assert_eq!(value, Some(b"test_value".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_multi_get_preserves_order() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("test_multi_get");
+        
+        let config = StorageConfig {
+            db_path: path.to_str().unwrap().to_string(),
+            ..StorageConfig::default()
+        };
+        let backend = RocksDBBackend::new(&config).await.unwrap();
+        
+        backend.set("a", b"1").await.unwrap();
+        backend.set("c", b"3").await.unwrap();
+        
+        let keys = vec!["c".to_string(), "b".to_string(), "a".to_string()];
+        let values = backend.multi_get(&keys).await.unwrap();
+        assert_eq!(values, vec![Some(b"3".to_vec()), None, Some(b"1".to_vec())]);
+    }
+
+    #[tokio::test]
+    async fn test_query_nfts_by_owner_and_collection() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("test_query_nfts");
+        
+        let config = StorageConfig {
+            db_path: path.to_str().unwrap().to_string(),
+            ..StorageConfig::default()
+        };
+        let storage = Storage::new(&config).await.unwrap();
+        
+        let alice = Address::new("memechain1alice".to_string());
+        let bob = Address::new("memechain1bob".to_string());
+        let nfts = vec![
+            Nft::new("nft-1".to_string(), "apes".to_string(), "Ape 1".to_string(), alice.clone(), serde_json::json!({}), 0),
+            Nft::new("nft-2".to_string(), "apes".to_string(), "Ape 2".to_string(), bob.clone(), serde_json::json!({}), 0),
+            Nft::new("nft-3".to_string(), "cats".to_string(), "Cat 1".to_string(), alice.clone(), serde_json::json!({}), 0),
+            Nft::new("nft-4".to_string(), "apes".to_string(), "Ape 3".to_string(), alice.clone(), serde_json::json!({}), 0),
+        ];
+        for nft in &nfts {
+            storage.store_nft(nft).await.unwrap();
+        }
+        
+        let page = storage.query_nfts(&QueryNfts {
+            owner: Some(alice.clone()),
+            collection: Some("apes".to_string()),
+            ..QueryNfts::default()
+        }).await.unwrap();
+        
+        assert_eq!(page.total, 2);
+        let ids: Vec<&str> = page.items.iter().map(|nft| nft.id.as_str()).collect();
+        assert_eq!(ids, vec!["nft-1", "nft-4"]);
+        
+        // Transferring updates the owner index
+        let mut transferred = nfts[0].clone();
+        transferred.owner = bob.clone();
+        storage.store_nft(&transferred).await.unwrap();
+        
+        let page = storage.query_nfts(&QueryNfts {
+            owner: Some(alice),
+            collection: Some("apes".to_string()),
+            limit: Some(10),
+            offset: 0,
+        }).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].id, "nft-4");
+    }
+
+    #[tokio::test]
+    async fn test_rocksdb_zstd_compression() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("test_zstd");
+        
+        let config = StorageConfig {
+            db_path: path.to_str().unwrap().to_string(),
+            compression: Some(CompressionConfig {
+                algorithm: "zstd".to_string(),
+                level: 3,
+            }),
+            ..StorageConfig::default()
+        };
+        let backend = RocksDBBackend::new(&config).await.unwrap();
+        
+        let large_value = "nft metadata ".repeat(100_000).into_bytes();
+        backend.set("large", &large_value).await.unwrap();
+        assert_eq!(backend.get("large").await.unwrap(), Some(large_value));
+    }
+
+    #[tokio::test]
+    async fn test_rocksdb_column_families() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("test_column_families");
+        
+        let config = StorageConfig {
+            db_path: path.to_str().unwrap().to_string(),
+            ..StorageConfig::default()
+        };
+        let backend = RocksDBBackend::new(&config).await.unwrap();
+        
+        let entries = [
+            ("block:1", CF_BLOCKS),
+            ("balance:memechain1alice:MEME", CF_STATE),
+            ("idx:nft_owner:memechain1alice:nft-1", CF_INDEXES),
+            ("receipt:abc", CF_RECEIPTS),
+        ];
+        backend.batch_write(
+            entries
+                .iter()
+                .map(|(key, _)| (key.to_string(), Some(key.as_bytes().to_vec())))
+                .collect(),
+        ).await.unwrap();
+        
+        for (key, cf) in entries {
+            assert_eq!(backend.get(key).await.unwrap(), Some(key.as_bytes().to_vec()));
+            
+            // The key physically lives in its column family only
+            let handle = cf_handle(&backend.db, cf).unwrap();
+            assert!(backend.db.get_cf(&handle, key.as_bytes()).unwrap().is_some());
+            let state = cf_handle(&backend.db, CF_STATE).unwrap();
+            assert_eq!(backend.db.get_cf(&state, key.as_bytes()).unwrap().is_some(), cf == CF_STATE);
+        }
+        
+        assert_eq!(backend.get_keys_with_prefix("idx:").await.unwrap(), vec!["idx:nft_owner:memechain1alice:nft-1"]);
+        assert_eq!(backend.get_keys_with_prefix("").await.unwrap().len(), entries.len());
+    }
+
+    #[tokio::test]
+    async fn test_rocksdb_migrates_default_column_family() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("test_default_cf");
+        
+        // A database from before column families kept everything in `default`
+        {
+            let db = rocksdb::DB::open_default(&path).unwrap();
+            db.put(b"block:1", b"block").unwrap();
+            db.put(b"token:TEST", b"token").unwrap();
+            db.put(b"idx:token_creator:memechain1alice:TEST", b"").unwrap();
+        }
+        
+        let config = StorageConfig {
+            db_path: path.to_str().unwrap().to_string(),
+            ..StorageConfig::default()
+        };
+        let backend = RocksDBBackend::new(&config).await.unwrap();
+        assert_eq!(backend.get("block:1").await.unwrap(), Some(b"block".to_vec()));
+        assert_eq!(backend.get("token:TEST").await.unwrap(), Some(b"token".to_vec()));
+        assert_eq!(backend.get_keys_with_prefix("idx:").await.unwrap().len(), 1);
+        assert!(backend.db.iterator(rocksdb::IteratorMode::Start).next().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_value_detected() {
+        let backend = Arc::new(MemoryBackend::new());
+        let storage = Storage::from_backend(backend.clone());
+        
+        let token = Token::new(
+            "TEST".to_string(),
+            "Test Token".to_string(),
+            1000000,
+            Address::new("memechain1alice".to_string()),
+            crate::types::AntiRugSettings::default(),
+            0,
+        );
+        storage.store_token(&token).await.unwrap();
+        assert!(storage.get_token("TEST").await.unwrap().is_some());
+        
+        // Flip a byte in the stored JSON behind the storage layer's back
+        let mut raw = backend.get("token:TEST").await.unwrap().unwrap();
+        let last = raw.len() - 2;
+        raw[last] ^= 0x01;
+        backend.set("token:TEST", &raw).await.unwrap();
+        
+        let err = storage.get_token("TEST").await.unwrap_err();
+        match err {
+            MemeChainError::Storage(StorageError::CorruptedData(message)) => assert!(message.contains("token:TEST")),
+            other => panic!("expected corrupted data error, got {:?}", other),
+        }
+        assert!(storage.get_all_tokens().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reads_values_written_before_checksums() {
+        let temp_dir = tempdir().unwrap();
+        let config = StorageConfig {
+            db_path: temp_dir.path().join("pre_checksum").to_str().unwrap().to_string(),
+            ..StorageConfig::default()
+        };
+        let token = Token::new(
+            "TEST".to_string(),
+            "Test Token".to_string(),
+            1000000,
+            Address::new("memechain1alice".to_string()),
+            crate::types::AntiRugSettings::default(),
+            0,
+        );
         
-        Ok(collections)
+        // A database written by a node that stored plain encoded values
+        {
+            let backend = RocksDBBackend::new(&config).await.unwrap();
+            backend.set("token:TEST", &serde_json::to_vec(&token).unwrap()).await.unwrap();
+        }
+        
+        let storage = Storage::new(&config).await.unwrap();
+        assert_eq!(storage.get_token("TEST").await.unwrap().unwrap().name, "Test Token");
+        
+        // Rewritten values are sealed and still read back
+        storage.store_token(&token).await.unwrap();
+        assert_eq!(storage.get_token("TEST").await.unwrap().unwrap().symbol, "TEST");
     }
-    
-    /// Update balance atomically
-    pub async fn update_balance(&self, address: &Address, token: &str, amount: i64) -> Result<()> {
-        let current_balance = self.get_balance(address, token).await?;
-        let new_amount = match current_balance {
-            Some(mut balance) => {
-                if amount > 0 {
-                    balance.add(amount as u64);
-                } else {
-                    balance.subtract((-amount) as u64)?;
-                }
-                balance.amount
-            }
-            None => {
-                if amount < 0 {
-                    return Err(StorageError::WriteFailed("Cannot create negative balance".to_string()));
-                }
-                amount as u64
-            }
+
+    #[tokio::test]
+    async fn test_new_database_requires_checksums() {
+        let temp_dir = tempdir().unwrap();
+        let config = StorageConfig {
+            db_path: temp_dir.path().join("checksummed").to_str().unwrap().to_string(),
+            ..StorageConfig::default()
         };
+        let token = Token::new(
+            "TEST".to_string(),
+            "Test Token".to_string(),
+            1000000,
+            Address::new("memechain1alice".to_string()),
+            crate::types::AntiRugSettings::default(),
+            0,
+        );
+        drop(Storage::new(&config).await.unwrap());
         
-        let new_balance = Balance::new(address.clone(), token.to_string(), new_amount);
-        self.store_balance(&new_balance).await
+        // A value that lost its seal is not mistaken for a pre-checksum one
+        {
+            let backend = RocksDBBackend::new(&config).await.unwrap();
+            backend.set("token:TEST", &serde_json::to_vec(&token).unwrap()).await.unwrap();
+        }
+        
+        let storage = Storage::new(&config).await.unwrap();
+        let err = storage.get_token("TEST").await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Storage(StorageError::CorruptedData(_))));
     }
-}
 
-impl Clone for Storage {
-    fn clone(&self) -> Self {
-        // This is a simplified clone - in a real implementation,
-        // you'd want to properly clone the backend or use Arc
-        Self {
-            backend: Box::new(DummyBackend {}),
+    #[tokio::test]
+    async fn test_top_holders() {
+        let storage = Storage::from_backend(Arc::new(MemoryBackend::new()));
+        for (name, amount) in [("alice", 500), ("bob", 2000), ("carol", 1000), ("dave", 0), ("treasury", 9000)] {
+            let address = Address::new(format!("memechain1{}", name));
+            storage.store_balance(&Balance::new(address, "MEME".to_string(), amount, 0)).await.unwrap();
         }
+        storage.store_balance(&Balance::new(Address::from("memechain1erin"), "PEPE".to_string(), 5000, 0)).await.unwrap();
+        
+        let treasury = Address::from("memechain1treasury");
+        let holders = storage.top_holders("MEME", 10, &[treasury.clone()]).await.unwrap();
+        let ranked: Vec<(&str, u64)> = holders.iter().map(|b| (b.address.as_str(), b.amount)).collect();
+        assert_eq!(ranked, vec![
+            ("memechain1bob", 2000),
+            ("memechain1carol", 1000),
+            ("memechain1alice", 500),
+        ]);
+        
+        let holders = storage.top_holders("MEME", 2, &[]).await.unwrap();
+        assert_eq!(holders.len(), 2);
+        assert_eq!(holders[0].address, treasury);
+        
+        // Emptying a balance drops it from the index
+        storage.store_balance(&Balance::new(Address::from("memechain1bob"), "MEME".to_string(), 0, 0)).await.unwrap();
+        let holders = storage.top_holders("MEME", 10, &[treasury.clone()]).await.unwrap();
+        assert_eq!(holders[0].address, Address::from("memechain1carol"));
+        
+        // A changed balance moves to its new rank
+        storage.update_balance(&Address::from("memechain1alice"), "MEME", 1500, 0).await.unwrap();
+        let holders = storage.top_holders("MEME", 1, &[treasury]).await.unwrap();
+        assert_eq!((holders[0].address.as_str(), holders[0].amount), ("memechain1alice", 2000));
+        assert_eq!(storage.keys_with_prefix("idx:holder_rank:MEME:").await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_holder_ranks() {
+        let backend = Arc::new(MemoryBackend::new());
+        let storage = Storage::from_backend(backend.clone());
+        for (name, amount) in [("alice", 500), ("bob", 2000)] {
+            let address = Address::new(format!("memechain1{}", name));
+            storage.store_balance(&Balance::new(address, "MEME".to_string(), amount, 0)).await.unwrap();
+        }
+        // A database from before the rank index has holder entries only
+        for key in storage.keys_with_prefix("idx:holder_rank:").await.unwrap() {
+            backend.delete(&key).await.unwrap();
+        }
+        assert!(storage.top_holders("MEME", 10, &[]).await.unwrap().is_empty());
+        
+        assert_eq!(storage.backfill_holder_ranks().await.unwrap(), 2);
+        let holders = storage.top_holders("MEME", 10, &[]).await.unwrap();
+        assert_eq!(holders[0].address, Address::from("memechain1bob"));
+        assert_eq!(storage.backfill_holder_ranks().await.unwrap(), 0);
     }
-}
 
-/// Dummy backend for cloning (not used in production)
-struct DummyBackend {}
+    #[tokio::test]
+    async fn test_backfill_trait_index() {
+        let backend = Arc::new(MemoryBackend::new());
+        let storage = Storage::from_backend(backend.clone());
+        let alice = Address::new("memechain1alice".to_string());
+        for (id, hat) in [("nft-1", "red"), ("nft-2", "red"), ("nft-3", "blue")] {
+            let metadata = serde_json::json!({"traits": [{"trait_type": "hat", "value": hat}]});
+            let nft = Nft::new(id.to_string(), "apes".to_string(), id.to_string(), alice.clone(), metadata, 0);
+            storage.store_nft(&nft).await.unwrap();
+        }
+        // A database from before the trait index has the NFTs only
+        for key in storage.keys_with_prefix("trait:").await.unwrap() {
+            backend.delete(&key).await.unwrap();
+        }
+        assert!(storage.trait_distribution("apes").await.unwrap().is_empty());
+        
+        assert_eq!(storage.backfill_trait_index().await.unwrap(), 2);
+        let distribution = storage.trait_distribution("apes").await.unwrap();
+        assert_eq!(distribution["hat"]["red"], 2);
+        assert_eq!(distribution["hat"]["blue"], 1);
+        assert_eq!(storage.backfill_trait_index().await.unwrap(), 0);
+    }
 
-#[async_trait::async_trait]
-impl StorageBackend for DummyBackend {
-    async fn initialize(&self) -> Result<()> {
-        Ok(())
+    #[tokio::test]
+    async fn test_app_hash_deterministic() {
+        let token = Token::new(
+            "TEST".to_string(),
+            "Test Token".to_string(),
+            1000000,
+            Address::new("memechain1alice".to_string()),
+            crate::types::AntiRugSettings::default(),
+            0,
+        );
+        let balance = Balance::new(Address::new("memechain1alice".to_string()), "TEST".to_string(), 1000000, 0);
+        
+        let mut hashes = Vec::new();
+        for _ in 0..2 {
+            let storage = Storage::from_backend(Arc::new(MemoryBackend::new()));
+            storage.store_token(&token).await.unwrap();
+            storage.store_balance(&balance).await.unwrap();
+            hashes.push(storage.app_hash().await.unwrap());
+        }
+        assert_eq!(hashes[0], hashes[1]);
+        
+        // Diverging state produces a different hash
+        let storage = Storage::from_backend(Arc::new(MemoryBackend::new()));
+        storage.store_token(&token).await.unwrap();
+        assert_ne!(storage.app_hash().await.unwrap(), hashes[0]);
+        
+        // Block records are not part of the state
+        storage.store_balance(&balance).await.unwrap();
+        storage.store_block(&Block::new(1, vec![], vec![], String::new(), 0)).await.unwrap();
+        assert_eq!(storage.app_hash().await.unwrap(), hashes[0]);
     }
     
-    async fn get(&self, _key: &str) -> Result<Option<Vec<u8>>> {
-        Ok(None)
+    #[tokio::test]
+    async fn test_large_metadata_stored_as_blob() {
+        let storage = Storage::from_backend(Arc::new(MemoryBackend::new()));
+        let owner = Address::from("memechain1alice");
+        let metadata = serde_json::json!({"image": "x".repeat(BLOB_THRESHOLD_BYTES)});
+        let hash = blob_hash(&serde_json::to_vec(&metadata).unwrap());
+        
+        for id in ["nft-1", "nft-2"] {
+            let nft = Nft::new(id.to_string(), "apes".to_string(), id.to_string(), owner.clone(), metadata.clone(), 0);
+            storage.store_nft(&nft).await.unwrap();
+        }
+        
+        // The record keeps only the hash; both NFTs share one blob
+        let raw: Nft = serde_json::from_slice(&storage.read("nft:nft-1").await.unwrap().unwrap()).unwrap();
+        assert!(raw.metadata.is_null());
+        assert_eq!(raw.metadata_hash.as_deref(), Some(hash.as_str()));
+        assert_eq!(storage.keys_with_prefix("blob:").await.unwrap(), vec![format!("blob:{}", hash)]);
+        
+        let blob = storage.get_blob(&hash).await.unwrap().unwrap();
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&blob).unwrap(), metadata);
+        assert_eq!(storage.get_nft("nft-2").await.unwrap().unwrap().metadata, metadata);
+        
+        // Small metadata stays inline
+        let mut nft = storage.get_nft("nft-1").await.unwrap().unwrap();
+        nft.metadata = serde_json::json!({"rarity": "common"});
+        storage.store_nft(&nft).await.unwrap();
+        let raw: Nft = serde_json::from_slice(&storage.read("nft:nft-1").await.unwrap().unwrap()).unwrap();
+        assert_eq!(raw.metadata, serde_json::json!({"rarity": "common"}));
+        assert!(raw.metadata_hash.is_none());
     }
     
-    async fn set(&self, _key: &str, _value: &[u8]) -> Result<()> {
-        Ok(())
+    #[tokio::test]
+    async fn test_metadata_index_follows_indexed_keys() {
+        let backend = Arc::new(MemoryBackend::new());
+        let keys = |keys: &[&str]| keys.iter().map(|key| key.to_string()).collect::<Vec<_>>();
+        let ids = |nfts: Vec<Nft>| nfts.into_iter().map(|nft| nft.id).collect::<Vec<_>>();
+        let by_rarity = Storage::from_backend(backend.clone()).with_indexed_metadata_keys(&keys(&["rarity"]));
+        let app_hash = by_rarity.app_hash().await.unwrap();
+        assert_eq!(by_rarity.rebuild_metadata_index().await.unwrap(), Some(0));
+        assert_eq!(by_rarity.rebuild_metadata_index().await.unwrap(), None);
+        assert_eq!(by_rarity.app_hash().await.unwrap(), app_hash);
+        let owner = Address::from("memechain1alice");
+        let metadata = serde_json::json!({"rarity": "rare", "level": 2});
+        let mut nft = Nft::new("nft-1".to_string(), "apes".to_string(), "Ape".to_string(), owner, metadata, 0);
+        by_rarity.store_nft(&nft).await.unwrap();
+        
+        // A node restarted with `level` indexed instead indexes existing NFTs
+        let by_level = Storage::from_backend(backend.clone()).with_indexed_metadata_keys(&keys(&["level"]));
+        assert!(by_level.search_nfts_by_metadata("apes", "level", "2").await.unwrap().is_empty());
+        assert_eq!(by_level.rebuild_metadata_index().await.unwrap(), Some(1));
+        assert_eq!(ids(by_level.search_nfts_by_metadata("apes", "level", "2").await.unwrap()), vec!["nft-1"]);
+        assert!(by_level.keys_with_prefix("meta:apes:rarity:").await.unwrap().is_empty());
+        
+        // Entries the current keys no longer maintain are checked on read
+        by_rarity.store_nft(&nft).await.unwrap();
+        nft.metadata = serde_json::json!({"rarity": "common", "level": 2});
+        by_level.store_nft(&nft).await.unwrap();
+        assert_eq!(by_rarity.keys_with_prefix("meta:apes:rarity:rare:").await.unwrap().len(), 1);
+        assert!(by_rarity.search_nfts_by_metadata("apes", "rarity", "rare").await.unwrap().is_empty());
     }
     
-    async fn delete(&self, _key: &str) -> Result<()> {
-        Ok(())
+    #[tokio::test]
+    async fn test_holder_snapshots_pruned_to_retention() {
+        let storage = Storage::from_backend(Arc::new(MemoryBackend::new()));
+        let alice = Address::from("memechain1alice");
+        for token in ["PEPE", "DOGE"] {
+            storage.update_balance(&alice, token, 100, 0).await.unwrap();
+        }
+        let tokens = vec!["PEPE".to_string()];
+        for height in [2, 4, 6] {
+            storage.store_holder_snapshot(height, &tokens, 2).await.unwrap();
+        }
+        
+        assert!(storage.get_holder_snapshot("PEPE", 2).await.unwrap().is_none());
+        assert!(storage.keys_with_prefix(&snapshot_key(2)).await.unwrap().is_empty());
+        assert_eq!(storage.keys_with_prefix("snapshot_marker:").await.unwrap().len(), 2);
+        for height in [4, 6] {
+            let holders = storage.get_holder_snapshot("PEPE", height).await.unwrap().unwrap();
+            assert_eq!(holders.len(), 1);
+        }
+        // Tokens not configured are left out
+        assert!(storage.get_holder_snapshot("DOGE", 6).await.unwrap().unwrap().is_empty());
     }
     
-    async fn exists(&self, _key: &str) -> Result<bool> {
-        Ok(false)
+    #[tokio::test]
+    async fn test_total_token_supply_tracks_mints_and_burns() {
+        let storage = Storage::from_backend(Arc::new(MemoryBackend::new()));
+        let creator = Address::from("memechain1alice");
+        let token = |symbol: &str, supply: u64| {
+            Token::new(symbol.to_string(), symbol.to_string(), supply, creator.clone(), crate::types::AntiRugSettings::default(), 0)
+        };
+        
+        storage.store_token(&token("PEPE", 1000)).await.unwrap();
+        storage.store_token(&token("DOGE", 500)).await.unwrap();
+        assert_eq!(storage.total_token_supply().await.unwrap(), 1500);
+        
+        // Burning from a supply lowers the aggregate; rewriting leaves it alone
+        storage.store_token(&token("PEPE", 900)).await.unwrap();
+        storage.store_token(&token("PEPE", 900)).await.unwrap();
+        assert_eq!(storage.total_token_supply().await.unwrap(), 1400);
+        
+        // Sums beyond u64 are tracked exactly
+        storage.store_token(&token("BIG", u64::MAX)).await.unwrap();
+        assert_eq!(storage.total_token_supply().await.unwrap(), 1400 + u64::MAX as u128);
     }
     
-    async fn get_keys_with_prefix(&self, _prefix: &str) -> Result<Vec<String>> {
-        Ok(Vec::new())
+    #[tokio::test]
+    async fn test_token_supply_total_backfilled() {
+        let backend = Arc::new(MemoryBackend::new());
+        let storage = Storage::from_backend(backend.clone());
+        let creator = Address::from("memechain1alice");
+        let token = |symbol: &str, supply: u64| {
+            Token::new(symbol.to_string(), symbol.to_string(), supply, creator.clone(), crate::types::AntiRugSettings::default(), 0)
+        };
+        storage.store_token(&token("PEPE", 1000)).await.unwrap();
+        storage.store_token(&token("DOGE", 500)).await.unwrap();
+        
+        // A database from before the aggregate existed
+        backend.delete(TOKEN_SUPPLY_TOTAL_KEY).await.unwrap();
+        let err = storage.store_token(&token("PEPE", 900)).await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Storage(StorageError::CorruptedData(_))));
+        
+        assert_eq!(storage.backfill_token_supply_total().await.unwrap(), Some(1500));
+        assert_eq!(storage.backfill_token_supply_total().await.unwrap(), None);
+        storage.store_token(&token("PEPE", 900)).await.unwrap();
+        assert_eq!(storage.total_token_supply().await.unwrap(), 1400);
     }
     
-    async fn batch_write(&self, _operations: Vec<(String, Option<Vec<u8>>)>) -> Result<()> {
-        Ok(())
+    #[tokio::test]
+    async fn test_storage_stats() {
+        let temp_dir = tempdir().unwrap();
+        let config = StorageConfig {
+            db_path: temp_dir.path().join("test_stats").to_str().unwrap().to_string(),
+            ..StorageConfig::default()
+        };
+        let rocks = Storage::new(&config).await.unwrap();
+        let memory = Storage::from_backend(Arc::new(MemoryBackend::new()));
+        let creator = Address::from("memechain1alice");
+        
+        for storage in [&rocks, &memory] {
+            for symbol in ["PEPE", "DOGE"] {
+                let token = Token::new(symbol.to_string(), symbol.to_string(), 1000, creator.clone(), crate::types::AntiRugSettings::default(), 0);
+                storage.store_token(&token).await.unwrap();
+            }
+            for id in ["nft-1", "nft-2", "nft-3"] {
+                let nft = Nft::new(id.to_string(), "apes".to_string(), id.to_string(), creator.clone(), serde_json::json!({}), 0);
+                storage.store_nft(&nft).await.unwrap();
+            }
+            
+            let stats = storage.stats().await.unwrap();
+            assert_eq!(stats.keys_by_prefix["token"], 2);
+            assert_eq!(stats.keys_by_prefix["nft"], 3);
+            assert_eq!(stats.total_keys, stats.keys_by_prefix.values().sum::<u64>());
+            assert!(stats.approx_size_bytes > 0);
+        }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::tempdir;
-
+    
     #[tokio::test]
-    async fn test_rocksdb_storage() {
+    async fn test_sled_flush_survives_reopen() {
         let temp_dir = tempdir().unwrap();
-        let path = temp_dir.path().join("test_db");
+        let config = StorageConfig {
+            db_path: temp_dir.path().join("test_sled").to_str().unwrap().to_string(),
+            db_type: "sled".to_string(),
+            // Only the explicit flush below persists anything
+            flush_every_ms: 0,
+            ..StorageConfig::default()
+        };
+        let creator = Address::from("memechain1alice");
         
-        let backend = RocksDBBackend::new(path.to_str().unwrap()).await.unwrap();
-        backend.initialize().await.unwrap();
+        {
+            let storage = Storage::new(&config).await.unwrap();
+            let token = Token::new("PEPE".to_string(), "Pepe".to_string(), 1000, creator.clone(), crate::types::AntiRugSettings::default(), 0);
+            storage.store_token(&token).await.unwrap();
+            storage.flush().await.unwrap();
+        }
         
-        // Test set and get
-        backend.set("test_key", b"test_value").await.unwrap();
-        let value = backend.get("test_key").await.unwrap();
-        assert_eq!(value, Some(b"test_value".to_vec()));
+        let storage = Storage::new(&config).await.unwrap();
+        let token = storage.get_token("PEPE").await.unwrap().unwrap();
+        assert_eq!((token.total_supply, token.creator), (1000, creator));
+    }
+    
+    #[tokio::test]
+    async fn test_rocksdb_scheduled_compaction() {
+        let temp_dir = tempdir().unwrap();
+        let config = StorageConfig {
+            db_path: temp_dir.path().join("test_db").to_str().unwrap().to_string(),
+            compaction_interval_secs: 1,
+            ..StorageConfig::default()
+        };
+        let backend = RocksDBBackend::new(&config).await.unwrap();
+        for i in 0..1000 {
+            backend.set(&format!("balance:{}", i), &[i as u8; 64]).await.unwrap();
+        }
+        for i in 0..500 {
+            backend.delete(&format!("balance:{}", i)).await.unwrap();
+        }
         
-        // Test exists
-        assert!(backend.exists("test_key").await.unwrap());
-        assert!(!backend.exists("nonexistent").await.unwrap());
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(10);
+        while backend.compaction_stats().unwrap().runs == 0 {
+            assert!(tokio::time::Instant::now() < deadline, "compaction never ran");
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
         
-        // Test delete
-        backend.delete("test_key").await.unwrap();
-        assert!(!backend.exists("test_key").await.unwrap());
+        assert_eq!(backend.get("balance:0").await.unwrap(), None);
+        assert_eq!(backend.get("balance:999").await.unwrap(), Some(vec![231; 64]));
     }
-
+    
     #[tokio::test]
-    async fn test_storage_operations() {
+    async fn test_open_errors_are_actionable() {
         let temp_dir = tempdir().unwrap();
-        let path = temp_dir.path().join("test_storage");
+        let file = temp_dir.path().join("not_a_dir");
+        std::fs::write(&file, b"").unwrap();
         
+        for db_type in ["rocksdb", "sled"] {
+            let config = StorageConfig {
+                db_path: file.to_str().unwrap().to_string(),
+                db_type: db_type.to_string(),
+                ..StorageConfig::default()
+            };
+            let err = Storage::new(&config).await.err().unwrap();
+            assert!(matches!(err, MemeChainError::Storage(StorageError::InvalidPath(_))));
+            assert!(err.to_string().contains("not a directory"));
+        }
+        
+        // A fresh path, parent directories included, opens fine
         let config = StorageConfig {
-            db_path: path.to_str().unwrap().to_string(),
-            db_type: "rocksdb".to_string(),
-            cache_size: 100,
-            enable_compression: false,
+            db_path: temp_dir.path().join("nested/fresh/db").to_str().unwrap().to_string(),
+            ..StorageConfig::default()
         };
-        
         let storage = Storage::new(&config).await.unwrap();
-        storage.initialize().await.unwrap();
         
-        // Test token storage
+        // A second open of the same RocksDB path reports the lock
+        let err = Storage::new(&config).await.err().unwrap();
+        assert!(matches!(err, MemeChainError::Storage(StorageError::Locked(_))));
+        drop(storage);
+        
+        // "lock" inside a path or another word is not a lock failure
+        let err = open_error("/var/lib/blockchain", "Corruption: bad block in /var/lib/blockchain/000012.sst".to_string());
+        assert!(matches!(err, MemeChainError::Storage(StorageError::ConnectionFailed(_))));
+        let err = open_error("/data", "IO error: While lock file: /data/LOCK: Resource temporarily unavailable".to_string());
+        assert!(matches!(err, MemeChainError::Storage(StorageError::Locked(_))));
+    }
+
+    #[tokio::test]
+    async fn test_bincode_codec_round_trip() {
+        let storage = Storage::from_backend(Arc::new(MemoryBackend::new())).with_codec(StorageCodec::Bincode);
+        let alice = Address::new("memechain1alice".to_string());
+        
         let token = Token::new(
             "TEST".to_string(),
             "Test Token".to_string(),
             1000000,
-            Address::new("memechain1alice".to_string()),
+            alice.clone(),
             crate::types::AntiRugSettings::default(),
+            0,
         );
-        
         storage.store_token(&token).await.unwrap();
-        let retrieved = storage.get_token("TEST").await.unwrap();
-        assert!(retrieved.is_some());
-        assert_eq!(retrieved.unwrap().symbol, "TEST");
+        let raw = storage.read("token:TEST").await.unwrap().unwrap();
+        assert!(serde_json::from_slice::<serde_json::Value>(&raw).is_err());
+        let retrieved = storage.get_token("TEST").await.unwrap().unwrap();
+        assert_eq!(serde_json::to_value(&retrieved).unwrap(), serde_json::to_value(&token).unwrap());
+        
+        // Arbitrary metadata and skipped `None` fields survive the trip
+        let mut nft = Nft::new(
+            "nft-1".to_string(),
+            "apes".to_string(),
+            "Ape 1".to_string(),
+            alice,
+            serde_json::json!({"attributes": [{"trait_type": "hat", "value": "cap"}], "level": -3, "score": 1.5, "extra": null}),
+            0,
+        );
+        nft.metadata_updated_height = Some(7);
+        storage.store_nft(&nft).await.unwrap();
+        let retrieved = storage.get_nft("nft-1").await.unwrap().unwrap();
+        assert_eq!(serde_json::to_value(&retrieved).unwrap(), serde_json::to_value(&nft).unwrap());
+        
+        assert!(StorageCodec::from_name("json").is_ok());
+        assert!(StorageCodec::from_name("cbor").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_app_hash_independent_of_codec() {
+        let alice = Address::new("memechain1alice".to_string());
+        let mut hashes = Vec::new();
+        for codec in [StorageCodec::Json, StorageCodec::Bincode] {
+            let storage = Storage::from_backend(Arc::new(MemoryBackend::new())).with_codec(codec);
+            let token = Token::new(
+                "TEST".to_string(),
+                "Test Token".to_string(),
+                1000,
+                alice.clone(),
+                crate::types::AntiRugSettings::default(),
+                0,
+            );
+            storage.store_token(&token).await.unwrap();
+            let nft = Nft::new(
+                "nft-1".to_string(),
+                "apes".to_string(),
+                "Ape 1".to_string(),
+                alice.clone(),
+                serde_json::json!({"score": 1.5, "level": -3, "attributes": [{"trait_type": "hat", "value": "cap"}]}),
+                0,
+            );
+            storage.store_nft(&nft).await.unwrap();
+            storage.set_nonce(&alice, 3).await.unwrap();
+            hashes.push(storage.app_hash().await.unwrap());
+        }
+        assert_eq!(hashes[0], hashes[1]);
+    }
+
+    #[tokio::test]
+    async fn test_open_with_other_codec_fails() {
+        let temp_dir = tempdir().unwrap();
+        let mut config = StorageConfig {
+            db_path: temp_dir.path().join("test_codec").to_str().unwrap().to_string(),
+            ..StorageConfig::default()
+        };
+        drop(Storage::new(&config).await.unwrap());
+        
+        config.codec = "bincode".to_string();
+        let err = Storage::new(&config).await.unwrap_err();
+        assert!(err.to_string().contains("written with the json codec"), "{}", err);
+        
+        config.codec = "json".to_string();
+        assert!(Storage::new(&config).await.is_ok());
+    }
+
+    /// Memory backend that counts reads reaching it
+    #[derive(Default)]
+    struct CountingBackend {
+        inner: MemoryBackend,
+        reads: AtomicU64,
+    }
+
+    #[async_trait::async_trait]
+    impl StorageBackend for CountingBackend {
+        async fn initialize(&self) -> Result<()> {
+            self.inner.initialize().await
+        }
+        
+        async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            self.inner.get(key).await
+        }
+        
+        async fn set(&self, key: &str, value: &[u8]) -> Result<()> {
+            self.inner.set(key, value).await
+        }
+        
+        async fn delete(&self, key: &str) -> Result<()> {
+            self.inner.delete(key).await
+        }
+        
+        async fn exists(&self, key: &str) -> Result<bool> {
+            self.inner.exists(key).await
+        }
+        
+        async fn get_keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            self.inner.get_keys_with_prefix(prefix).await
+        }
+        
+        async fn batch_write(&self, operations: Vec<(String, Option<Vec<u8>>)>) -> Result<()> {
+            self.inner.batch_write(operations).await
+        }
+    }
+
+    /// Memory backend whose requests take `delay`, tracking how many run at once
+    struct SlowBackend {
+        inner: MemoryBackend,
+        delay: Duration,
+        running: AtomicU64,
+        peak: AtomicU64,
+    }
+
+    impl SlowBackend {
+        fn new(delay: Duration) -> Self {
+            Self { inner: MemoryBackend::new(), delay, running: AtomicU64::new(0), peak: AtomicU64::new(0) }
+        }
+
+        async fn slowly<T>(&self, request: impl std::future::Future<Output = T>) -> T {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            let result = request.await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            result
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl StorageBackend for SlowBackend {
+        async fn initialize(&self) -> Result<()> {
+            self.inner.initialize().await
+        }
+        
+        async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+            self.slowly(self.inner.get(key)).await
+        }
+        
+        async fn set(&self, key: &str, value: &[u8]) -> Result<()> {
+            self.slowly(self.inner.set(key, value)).await
+        }
+        
+        async fn delete(&self, key: &str) -> Result<()> {
+            self.slowly(self.inner.delete(key)).await
+        }
+        
+        async fn exists(&self, key: &str) -> Result<bool> {
+            self.slowly(self.inner.exists(key)).await
+        }
+        
+        async fn get_keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>> {
+            self.slowly(self.inner.get_keys_with_prefix(prefix)).await
+        }
+        
+        async fn batch_write(&self, operations: Vec<(String, Option<Vec<u8>>)>) -> Result<()> {
+            self.slowly(self.inner.batch_write(operations)).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batched_requests_are_not_timed_out() {
+        let backend = Arc::new(SlowBackend::new(Duration::from_millis(200)));
+        let storage = Storage::from_backend(backend.clone()).with_limits(50, 4);
+        
+        // A plain write gives up after the timeout
+        let err = storage.put("meta:plain", b"1").await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Storage(StorageError::Timeout(_))));
+        
+        // A committed batch waits for the backend however long it takes
+        storage.begin_batch();
+        storage.put("meta:batched", b"1").await.unwrap();
+        storage.commit_batch().await.unwrap();
+        assert!(backend.inner.get("meta:batched").await.unwrap().is_some());
+        
+        // So do reads and the app hash while a block's batch is open
+        storage.begin_batch();
+        assert!(storage.read("meta:plain").await.is_ok());
+        assert!(storage.app_hash().await.is_ok());
+        storage.discard_batch();
+        let err = storage.read("meta:plain").await.unwrap_err();
+        assert!(matches!(err, MemeChainError::Storage(StorageError::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_requests_keep_their_slot() {
+        let backend = Arc::new(SlowBackend::new(Duration::from_millis(200)));
+        let storage = Storage::from_backend(backend.clone()).with_limits(50, 1);
+        
+        // Each read gives up while the first is still running in the backend,
+        // which keeps the only slot until it finishes
+        for i in 0..3 {
+            let err = storage.read(&format!("meta:{}", i)).await.unwrap_err();
+            assert!(matches!(err, MemeChainError::Storage(StorageError::Timeout(_))));
+        }
+        
+        // Abandoned requests never ran alongside each other
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(backend.peak.load(Ordering::SeqCst), 1);
+        assert_eq!(backend.running.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_query_cache_invalidated_by_writes() {
+        let backend = Arc::new(CountingBackend::default());
+        let storage = Storage::from_backend(backend.clone()).with_query_cache(16);
+        let alice = Address::new("memechain1alice".to_string());
+        let token = |symbol: &str| {
+            Token::new(symbol.to_string(), symbol.to_string(), 1000, alice.clone(), crate::types::AntiRugSettings::default(), 0)
+        };
+        storage.store_token(&token("PEPE")).await.unwrap();
+        
+        assert_eq!(storage.get_all_tokens().await.unwrap().len(), 1);
+        assert!(storage.get_token("PEPE").await.unwrap().is_some());
+        let reads = backend.reads.load(Ordering::SeqCst);
+        
+        // Served from the cache
+        assert_eq!(storage.get_all_tokens().await.unwrap().len(), 1);
+        assert!(storage.get_token("PEPE").await.unwrap().is_some());
+        assert_eq!(backend.reads.load(Ordering::SeqCst), reads);
+        
+        // Unrelated writes keep the entries; a new token evicts the list
+        storage.store_balance(&Balance::new(alice.clone(), "PEPE".to_string(), 5, 0)).await.unwrap();
+        assert_eq!(storage.get_all_tokens().await.unwrap().len(), 1);
+        assert_eq!(backend.reads.load(Ordering::SeqCst), reads);
+        storage.store_token(&token("DOGE")).await.unwrap();
+        assert_eq!(storage.get_all_tokens().await.unwrap().len(), 2);
+        assert!(backend.reads.load(Ordering::SeqCst) > reads);
+        
+        // Buffered writes are visible inside a batch and evict on commit
+        storage.begin_batch();
+        let mut updated = token("PEPE");
+        updated.paused = true;
+        storage.store_token(&updated).await.unwrap();
+        assert!(storage.get_token("PEPE").await.unwrap().unwrap().paused);
+        storage.commit_batch().await.unwrap();
+        assert!(storage.get_token("PEPE").await.unwrap().unwrap().paused);
+        
+        // Least recently used entries go first
+        let storage = Storage::from_backend(backend.clone()).with_query_cache(1);
+        storage.get_token("PEPE").await.unwrap();
+        storage.get_token("DOGE").await.unwrap();
+        let reads = backend.reads.load(Ordering::SeqCst);
+        storage.get_token("DOGE").await.unwrap();
+        assert_eq!(backend.reads.load(Ordering::SeqCst), reads);
+        storage.get_token("PEPE").await.unwrap();
+        assert_eq!(backend.reads.load(Ordering::SeqCst), reads + 1);
+    }
+
+    #[tokio::test]
+    async fn test_iter_tokens_and_nfts_stream_in_pages() {
+        use futures::TryStreamExt;
+
+        let storage = Storage::from_backend(Arc::new(MemoryBackend::new()));
+        let alice = Address::new("memechain1alice".to_string());
+        for i in 0..350 {
+            let symbol = format!("T{:03}", i);
+            let token = Token::new(symbol.clone(), symbol, 1000, alice.clone(), crate::types::AntiRugSettings::default(), 0);
+            storage.store_token(&token).await.unwrap();
+        }
+        for i in 0..120 {
+            let nft = Nft::new(format!("nft-{:03}", i), "apes".to_string(), format!("Ape {}", i), alice.clone(), serde_json::json!({}), 0);
+            storage.store_nft(&nft).await.unwrap();
+        }
+
+        let tokens: Vec<Token> = storage.iter_tokens().try_collect().await.unwrap();
+        assert_eq!(tokens.len(), 350);
+        assert!(tokens.windows(2).all(|pair| pair[0].symbol < pair[1].symbol));
+        assert_eq!(storage.iter_nfts().try_collect::<Vec<_>>().await.unwrap().len(), 120);
+
+        // Buffered writes are seen across page boundaries
+        storage.begin_batch();
+        let extra = Token::new("T100A".to_string(), "Extra".to_string(), 1, alice.clone(), crate::types::AntiRugSettings::default(), 0);
+        storage.store_token(&extra).await.unwrap();
+        let count = storage.iter_tokens().try_fold(0, |count, _| async move { Ok::<_, MemeChainError>(count + 1) }).await.unwrap();
+        assert_eq!(count, 351);
+        storage.discard_batch();
+    }
+
+    #[tokio::test]
+    async fn test_export_balances_csv() {
+        let storage = Storage::from_backend(Arc::new(MemoryBackend::new()));
+        for i in 0..150u64 {
+            let address = Address::new(format!("memechain1holder{:03}", i));
+            storage.store_balance(&Balance::new(address.clone(), "MEME".to_string(), 1000 + i, 1_700_000_000)).await.unwrap();
+            storage.store_balance(&Balance::new(address, "PEPE".to_string(), 1, 0)).await.unwrap();
+        }
+        // Zero balances are not exported
+        let empty = Address::new("memechain1empty".to_string());
+        storage.store_balance(&Balance::new(empty, "MEME".to_string(), 0, 0)).await.unwrap();
+
+        let mut out = Vec::new();
+        let rows = storage.export_balances_csv("MEME", &mut out).await.unwrap();
+        assert_eq!(rows, 150);
+
+        let csv = String::from_utf8(out).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("address,amount,updated_at"));
+        let parsed: Vec<(String, u64, i64)> = lines
+            .map(|line| {
+                let fields: Vec<&str> = line.split(',').collect();
+                assert_eq!(fields.len(), 3);
+                (fields[0].to_string(), fields[1].parse().unwrap(), fields[2].parse().unwrap())
+            })
+            .collect();
+        assert_eq!(parsed.len(), 150);
+        for (i, (address, amount, updated_at)) in parsed.iter().enumerate() {
+            assert_eq!(address, &format!("memechain1holder{:03}", i));
+            assert_eq!(*amount, 1000 + i as u64);
+            assert_eq!(*updated_at, 1_700_000_000);
+        }
     }
 } 
\ No newline at end of file
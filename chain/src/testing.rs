@@ -0,0 +1,256 @@
+//! In-process test harness for driving a full node without networking.
+//!
+//! Enabled for the crate's own tests and, for downstream users, through the
+//! `test-util` feature.
+
+use crate::app::{self, MemeChainApp};
+use crate::config::{Account, Config, GenesisConfig, DEFAULT_CHAIN_ID};
+use crate::error::Result;
+use crate::types::{Address, Block, Transaction, TransactionResult};
+use ed25519_dalek::SigningKey;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Native balance given to every funded test account at genesis
+pub const TEST_ACCOUNT_BALANCE: u64 = 1_000_000_000;
+
+/// A deterministic test keypair and its address
+#[derive(Clone)]
+pub struct TestAccount {
+    signing_key: SigningKey,
+    address: Address,
+}
+
+impl TestAccount {
+    /// Derive an account from a one-byte seed
+    pub fn from_seed(seed: u8) -> Self {
+        let signing_key = SigningKey::from_bytes(&[seed; 32]);
+        let address = Address::from_public_key(&signing_key.verifying_key().to_bytes());
+        Self { signing_key, address }
+    }
+
+    /// Account address
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Hex-encoded private key
+    pub fn private_key_hex(&self) -> String {
+        hex::encode(self.signing_key.to_bytes())
+    }
+
+    /// Build and sign a transaction from this account for the default chain ID
+    pub fn transaction(
+        &self,
+        module: &str,
+        action: &str,
+        to: Option<Address>,
+        data: serde_json::Value,
+    ) -> Result<Transaction> {
+        self.transaction_on(DEFAULT_CHAIN_ID, module, action, to, data)
+    }
+
+    /// Build and sign a transaction from this account for `chain_id`
+    pub fn transaction_on(
+        &self,
+        chain_id: &str,
+        module: &str,
+        action: &str,
+        to: Option<Address>,
+        data: serde_json::Value,
+    ) -> Result<Transaction> {
+        let mut tx = Transaction::new(
+            module.to_string(),
+            action.to_string(),
+            self.address.clone(),
+            to,
+            data,
+            chrono::Utc::now().timestamp(),
+        );
+        tx.sign(chain_id, &self.private_key_hex())?;
+        Ok(tx)
+    }
+}
+
+/// A full node backed by in-memory storage
+pub struct TestNode {
+    app: Arc<RwLock<MemeChainApp>>,
+}
+
+impl TestNode {
+    /// Create a node with default configuration and funded accounts for seeds 1..=3
+    pub async fn new() -> Result<Self> {
+        Self::with_config(Config::default()).await
+    }
+
+    /// Create a node from a configuration; storage is always forced to memory
+    pub async fn with_config(mut config: Config) -> Result<Self> {
+        config.storage.db_type = "memory".to_string();
+
+        let mut genesis = GenesisConfig::new(config.chain.chain_id.clone(), "test-validator".to_string());
+        genesis.accounts = (1..=3)
+            .map(|seed| Account {
+                address: TestAccount::from_seed(seed).address().to_string(),
+                balance: TEST_ACCOUNT_BALANCE,
+                name: format!("account-{}", seed),
+            })
+            .collect();
+
+        let app = MemeChainApp::new(config).await?;
+        app.initialize_storage().await?;
+        app.apply_genesis(&genesis).await?;
+
+        Ok(Self {
+            app: Arc::new(RwLock::new(app)),
+        })
+    }
+
+    /// Shared application handle
+    pub fn app(&self) -> Arc<RwLock<MemeChainApp>> {
+        self.app.clone()
+    }
+
+    /// Sign a transaction as `account` and submit it to the pool
+    pub async fn submit(
+        &self,
+        account: &TestAccount,
+        module: &str,
+        action: &str,
+        to: Option<Address>,
+        data: serde_json::Value,
+    ) -> Result<String> {
+        let app = self.app.read().await;
+        let tx = account.transaction_on(&app.config().chain.chain_id, module, action, to, data)?;
+        app.submit_transaction(tx).await
+    }
+
+    /// Sign a transaction as `account` and execute it immediately, outside a block
+    pub async fn execute(
+        &self,
+        account: &TestAccount,
+        module: &str,
+        action: &str,
+        to: Option<Address>,
+        data: serde_json::Value,
+    ) -> Result<TransactionResult> {
+        let mut app = self.app.write().await;
+        let tx = account.transaction_on(&app.config().chain.chain_id, module, action, to, data)?;
+        app.process_transaction(tx).await
+    }
+
+    /// Produce a block from the current pool
+    pub async fn produce_block(&self) -> Result<Block> {
+        self.app.write().await.create_block().await
+    }
+
+    /// Serve the API on an ephemeral local port and return the port
+    pub async fn spawn_api(&self) -> Result<u16> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        let api = self.app.read().await.config().api.clone();
+        let router = app::api_router(self.app.clone(), &api);
+
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, router).await;
+        });
+
+        Ok(port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mint_nft_end_to_end() {
+        let node = TestNode::new().await.unwrap();
+        let alice = TestAccount::from_seed(1);
+
+        node.submit(
+            &alice,
+            "nft",
+            "create_collection",
+            None,
+            serde_json::json!({"name": "Test Collection", "description": "A test collection"}),
+        ).await.unwrap();
+        let block = node.produce_block().await.unwrap();
+        assert!(block.results[0].success);
+        let collection_id = block.results[0].data.as_ref().unwrap()["collection_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        node.submit(
+            &alice,
+            "nft",
+            "mint",
+            None,
+            serde_json::json!({"collection": collection_id, "name": "Test NFT"}),
+        ).await.unwrap();
+        let block = node.produce_block().await.unwrap();
+        assert_eq!(block.height, 2);
+        let nft_id = block.results[0].data.as_ref().unwrap()["nft_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let app = node.app();
+        let app = app.read().await;
+        let nft = app.nft_module().get_nft(&nft_id).await.unwrap().unwrap();
+        assert_eq!(&nft.owner, alice.address());
+        assert_eq!(nft.collection_id, collection_id);
+    }
+
+    #[tokio::test]
+    async fn test_nft_history_trail() {
+        let node = TestNode::new().await.unwrap();
+        let alice = TestAccount::from_seed(1);
+        let bob = TestAccount::from_seed(2);
+        let carol = TestAccount::from_seed(3);
+
+        let result = node.execute(
+            &alice,
+            "nft",
+            "create_collection",
+            None,
+            serde_json::json!({"name": "Test Collection"}),
+        ).await.unwrap();
+        let collection_id = result.data.unwrap()["collection_id"].as_str().unwrap().to_string();
+
+        node.submit(
+            &alice,
+            "nft",
+            "mint",
+            None,
+            serde_json::json!({"collection": collection_id, "name": "Test NFT"}),
+        ).await.unwrap();
+        let block = node.produce_block().await.unwrap();
+        let nft_id = block.results[0].data.as_ref().unwrap()["nft_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        node.submit(&alice, "nft", "transfer", Some(bob.address().clone()), serde_json::json!({"nft_id": nft_id}))
+            .await
+            .unwrap();
+        node.produce_block().await.unwrap();
+        node.submit(&bob, "nft", "transfer", Some(carol.address().clone()), serde_json::json!({"nft_id": nft_id}))
+            .await
+            .unwrap();
+        node.produce_block().await.unwrap();
+
+        let app = node.app();
+        let app = app.read().await;
+        let history = app.nft_module().get_nft_history(&nft_id).await.unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].from, None);
+        assert_eq!(&history[0].to, alice.address());
+        assert_eq!(history[1].from.as_ref(), Some(alice.address()));
+        assert_eq!(&history[1].to, bob.address());
+        assert_eq!(history[2].from.as_ref(), Some(bob.address()));
+        assert_eq!(&history[2].to, carol.address());
+        let heights: Vec<u64> = history.iter().map(|record| record.block_height).collect();
+        assert_eq!(heights, vec![1, 2, 3]);
+    }
+}
@@ -1,14 +1,33 @@
+use crate::error::CommonError;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Blockchain address type
+///
+/// Addresses are case-insensitive (bech32-style), so they are stored in
+/// canonical lowercase form; equality, hashing and `Display` all use it.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String")]
 pub struct Address(String);
 
 impl Address {
     /// Create a new address
     pub fn new(addr: String) -> Self {
-        Self(addr)
+        Self(Self::normalize(&addr))
+    }
+
+    /// Canonical form of an address string
+    fn normalize(addr: &str) -> String {
+        addr.to_lowercase()
+    }
+
+    /// Derive an address from a public key (or any key preimage)
+    pub fn from_public_key(public_key: &[u8]) -> Self {
+        use sha2::{Digest, Sha256};
+        let hash = format!("{:x}", Sha256::digest(public_key));
+        Self(format!("memechain1{}", &hash[..32]))
     }
 
     /// Get the address string
@@ -18,11 +37,35 @@ impl Address {
 
     /// Validate address format
     pub fn is_valid(&self) -> bool {
-        // Basic validation - should start with memechain1 and be 39 characters
-        self.0.starts_with("memechain1") && self.0.len() == 39
+        // Basic validation - memechain1 followed by 32 characters of key hash
+        self.0.starts_with("memechain1") && self.0.len() == 42
+    }
+
+    /// Whether `raw` is well-formed as written: a single case throughout
+    /// (as bech32 requires) and a hex key hash after the prefix. Addresses
+    /// carry no checksum, so a mistyped hex digit still passes.
+    pub fn well_formed(raw: &str) -> bool {
+        let single_case = raw == raw.to_lowercase() || raw == raw.to_uppercase();
+        let data = raw.get("memechain1".len()..).unwrap_or_default();
+        single_case && !data.is_empty() && data.chars().all(|c| c.is_ascii_hexdigit())
     }
 }
 
+/// Result of checking an address string, as served by `/address/:addr/validate`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressCheck {
+    /// The address as given
+    pub address: String,
+    /// Whether the address is valid and well-formed
+    pub is_valid: bool,
+    /// Canonical form, when valid
+    pub normalized: Option<String>,
+    /// Whether the address is intact as written (see `Address::well_formed`).
+    /// Addresses carry no checksum, so this is the integrity check there
+    /// is: a mistyped hex digit still passes, mixed case does not.
+    pub checksum_ok: bool,
+}
+
 impl fmt::Display for Address {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -31,16 +74,37 @@ impl fmt::Display for Address {
 
 impl From<String> for Address {
     fn from(s: String) -> Self {
-        Self(s)
+        Self::new(s)
     }
 }
 
 impl From<&str> for Address {
     fn from(s: &str) -> Self {
-        Self(s.to_string())
+        Self(Self::normalize(s))
     }
 }
 
+/// Signature scheme identifier for ed25519 signatures
+pub const SIG_SCHEME_ED25519: u8 = 0;
+
+/// Signature scheme identifier for k-of-n ed25519 multisig
+pub const SIG_SCHEME_MULTISIG: u8 = 1;
+
+/// Domain tag prefixed to transaction signing bytes, ahead of the chain ID
+pub const TX_SIGNING_DOMAIN: &str = "memechain/tx/v1";
+
+/// Domain tag prefixed to NFT voucher signing bytes, ahead of the chain ID
+pub const VOUCHER_SIGNING_DOMAIN: &str = "memechain/nft-voucher/v1";
+
+/// A single signer's signature in a multisig transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureEntry {
+    /// Signer public key (hex)
+    pub public_key: String,
+    /// Signature (hex)
+    pub signature: String,
+}
+
 /// Transaction type
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transaction {
@@ -56,18 +120,42 @@ pub struct Transaction {
     pub data: serde_json::Value,
     /// Transaction timestamp
     pub timestamp: i64,
+    /// Last block height this transaction may be included in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_height: Option<u64>,
+    /// Sender's sequence number; when set, the sender's transactions execute
+    /// strictly in nonce order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    /// Free-form note for the recipient, e.g. an exchange deposit reference
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    /// Fee the sender offers, in native token base units; the chain charges
+    /// the larger of this and `chain.tx_fee`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee: Option<u64>,
+    /// Signature scheme used to produce `signature`
+    #[serde(default)]
+    pub sig_scheme: u8,
+    /// Signer public key (hex)
+    #[serde(default)]
+    pub public_key: String,
     /// Transaction signature
     pub signature: String,
+    /// Signer signatures for multisig transactions
+    #[serde(default)]
+    pub signatures: Vec<SignatureEntry>,
 }
 
 impl Transaction {
-    /// Create a new transaction
+    /// Create a new transaction stamped with `timestamp` (unix seconds)
     pub fn new(
         module: String,
         action: String,
         from: Address,
         to: Option<Address>,
         data: serde_json::Value,
+        timestamp: i64,
     ) -> Self {
         Self {
             module,
@@ -75,15 +163,70 @@ impl Transaction {
             from,
             to,
             data,
-            timestamp: chrono::Utc::now().timestamp(),
+            timestamp,
+            timeout_height: None,
+            nonce: None,
+            memo: None,
+            fee: None,
+            sig_scheme: SIG_SCHEME_ED25519,
+            public_key: String::new(),
             signature: String::new(),
+            signatures: Vec::new(),
+        }
+    }
+
+    /// Bytes covered by the signature: the domain tag and `chain_id`, followed
+    /// by every field except the signature itself
+    pub fn signing_bytes(&self, chain_id: &str) -> Vec<u8> {
+        let mut payload = serde_json::json!({
+            "sig_scheme": self.sig_scheme,
+            "module": self.module,
+            "action": self.action,
+            "from": self.from,
+            "to": self.to,
+            "data": self.data,
+            "timestamp": self.timestamp,
+            "public_key": self.public_key,
+        });
+        // Only present when set, so transactions without a timeout sign as before
+        if let Some(timeout_height) = self.timeout_height {
+            payload["timeout_height"] = timeout_height.into();
+        }
+        if let Some(nonce) = self.nonce {
+            payload["nonce"] = nonce.into();
         }
+        if let Some(memo) = &self.memo {
+            payload["memo"] = memo.clone().into();
+        }
+        if let Some(fee) = self.fee {
+            payload["fee"] = fee.into();
+        }
+        // The prefix binds the signature to one chain, so it cannot be replayed on another
+        let mut bytes = format!("{}\0{}\0", TX_SIGNING_DOMAIN, chain_id).into_bytes();
+        bytes.extend(payload.to_string().into_bytes());
+        bytes
+    }
+
+    /// Sign the transaction for `chain_id` with a hex-encoded ed25519 private key
+    pub fn sign(&mut self, chain_id: &str, private_key: &str) -> crate::error::Result<()> {
+        let signing_key = parse_signing_key(private_key)?;
+
+        self.sig_scheme = SIG_SCHEME_ED25519;
+        self.public_key = hex::encode(signing_key.verifying_key().to_bytes());
+        self.signature = hex::encode(signing_key.sign(&self.signing_bytes(chain_id)).to_bytes());
+        Ok(())
     }
 
-    /// Sign the transaction
-    pub fn sign(&mut self, private_key: &str) -> crate::error::Result<()> {
-        // TODO: Implement proper signature generation
-        self.signature = format!("signed_{}", private_key);
+    /// Add one signer's signature, for `chain_id`, to a multisig transaction
+    pub fn add_multisig_signature(&mut self, chain_id: &str, private_key: &str) -> crate::error::Result<()> {
+        let signing_key = parse_signing_key(private_key)?;
+
+        self.sig_scheme = SIG_SCHEME_MULTISIG;
+        let signature = signing_key.sign(&self.signing_bytes(chain_id));
+        self.signatures.push(SignatureEntry {
+            public_key: hex::encode(signing_key.verifying_key().to_bytes()),
+            signature: hex::encode(signature.to_bytes()),
+        });
         Ok(())
     }
 
@@ -94,10 +237,75 @@ impl Transaction {
         hasher.update(format!("{:?}", self).as_bytes());
         format!("{:x}", hasher.finalize())
     }
+
+    /// Hash of what the transaction asks for, ignoring when and how it was
+    /// signed, so a client's retry of the same request hashes the same
+    pub fn request_hash(&self) -> String {
+        use sha2::{Digest, Sha256};
+        let request = serde_json::json!({
+            "module": self.module,
+            "action": self.action,
+            "from": self.from,
+            "to": self.to,
+            "data": self.data,
+            "memo": self.memo,
+        });
+        format!("{:x}", Sha256::digest(request.to_string().as_bytes()))
+    }
 }
 
-/// Transaction result
+/// Parse a hex-encoded ed25519 private key
+fn parse_signing_key(private_key: &str) -> crate::error::Result<SigningKey> {
+    let key_bytes: [u8; 32] = hex::decode(private_key)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| CommonError::InvalidPrivateKey("Expected 32-byte hex key".to_string()))?;
+    Ok(SigningKey::from_bytes(&key_bytes))
+}
+
+/// Whether `bytes` canonically encode a point of prime order: in the
+/// prime-order subgroup and not the identity
+fn is_prime_order_point(bytes: &[u8; 32]) -> bool {
+    CompressedEdwardsY(*bytes).decompress().is_some_and(|point| {
+        point.is_torsion_free() && !point.is_small_order() && point.compress().as_bytes() == bytes
+    })
+}
+
+/// Whether a signature's key and `R` both have prime order. Only for such
+/// signatures do the cofactored batch equation and the cofactorless single
+/// one agree, so anything else is rejected before either is checked.
+pub fn has_prime_order_points(public_key: &VerifyingKey, signature: &Signature) -> bool {
+    is_prime_order_point(public_key.as_bytes()) && is_prime_order_point(signature.r_bytes())
+}
+
+/// The chain's single rule for accepting an ed25519 signature: prime-order
+/// key and `R`, then `verify_strict`. Batch verification applies the same
+/// point check up front, so both paths accept exactly the same signatures.
+pub fn verify_ed25519(public_key: &VerifyingKey, message: &[u8], signature: &Signature) -> bool {
+    has_prime_order_points(public_key, signature) && public_key.verify_strict(message, signature).is_ok()
+}
+
+/// Multisig account controlled by a k-of-n set of ed25519 keys
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultisigAccount {
+    /// Multisig address
+    pub address: Address,
+    /// Number of distinct valid signatures required
+    pub threshold: u32,
+    /// Member public keys (hex)
+    pub public_keys: Vec<String>,
+}
+
+/// Result data fields holding secrets; `Debug` output of a
+/// `TransactionResult` shows them as `REDACTED`, and they are stripped before
+/// a result is stored or published
+pub const SENSITIVE_RESULT_FIELDS: &[&str] = &["private_key", "mnemonic"];
+
+/// Printed in place of a secret in `Debug` output
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Transaction result
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TransactionResult {
     /// Whether the transaction was successful
     pub success: bool,
@@ -125,8 +333,95 @@ impl TransactionResult {
             data: None,
         }
     }
+
+    /// The result without `SENSITIVE_RESULT_FIELDS`, for blocks, receipts and
+    /// anything else that outlives the response to the sender
+    pub fn without_secrets(mut self) -> Self {
+        if let Some(serde_json::Value::Object(fields)) = &mut self.data {
+            fields.retain(|key, _| !SENSITIVE_RESULT_FIELDS.contains(&key.as_str()));
+        }
+        self
+    }
+
+    /// Read the result data back as a typed result (see `crate::results`)
+    pub fn typed<T: serde::de::DeserializeOwned>(&self) -> crate::error::Result<Option<T>> {
+        match &self.data {
+            Some(data) => Ok(Some(serde_json::from_value(data.clone())?)),
+            None => Ok(None),
+        }
+    }
+}
+
+// Results reach logs through `Debug` (directly or inside blocks and
+// receipts), so secrets in the data are masked there
+impl fmt::Debug for TransactionResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = self.data.as_ref().map(|data| match data {
+            serde_json::Value::Object(fields) => serde_json::Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, value)| match SENSITIVE_RESULT_FIELDS.contains(&key.as_str()) {
+                        true => (key.clone(), REDACTED.into()),
+                        false => (key.clone(), value.clone()),
+                    })
+                    .collect(),
+            ),
+            other => other.clone(),
+        });
+        f.debug_struct("TransactionResult")
+            .field("success", &self.success)
+            .field("error", &self.error)
+            .field("data", &data)
+            .finish()
+    }
+}
+
+/// Outcome of an included transaction, stored by transaction hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Receipt {
+    /// Transaction hash
+    pub tx_hash: String,
+    /// Height of the block that included the transaction
+    pub height: u64,
+    /// Position of the transaction in the block
+    pub index: usize,
+    /// Execution result
+    pub result: TransactionResult,
+    /// Memo attached by the sender
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// Block header fields, as listed by `/blocks`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockSummary {
+    /// Block height
+    pub height: u64,
+    /// Block hash
+    pub hash: String,
+    /// Number of transactions in the block
+    pub tx_count: usize,
+    /// Block timestamp
+    pub timestamp: i64,
+}
+
+impl From<&Block> for BlockSummary {
+    fn from(block: &Block) -> Self {
+        Self {
+            height: block.height,
+            hash: block.hash.clone(),
+            tx_count: block.transactions.len(),
+            timestamp: block.timestamp,
+        }
+    }
 }
 
+/// Version of the block hashing scheme in `Block::calculate_hash`.
+///
+/// Changing what the hash covers or how it is encoded forks the chain:
+/// bump this and gate the new scheme on a height instead of editing it.
+pub const HASH_VERSION: u32 = 1;
+
 /// Block type
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Block {
@@ -142,34 +437,67 @@ pub struct Block {
     pub hash: String,
     /// Previous block hash
     pub previous_hash: String,
+    /// Hash of the application state after applying this block
+    #[serde(default)]
+    pub app_hash: String,
 }
 
 impl Block {
-    /// Create a new block
+    /// Create a new block with the slot `timestamp` (unix seconds)
     pub fn new(
         height: u64,
         transactions: Vec<Transaction>,
         results: Vec<TransactionResult>,
         previous_hash: String,
+        timestamp: i64,
     ) -> Self {
         Self {
             height,
-            timestamp: chrono::Utc::now().timestamp(),
+            timestamp,
             transactions,
             results,
             hash: String::new(),
             previous_hash,
+            app_hash: String::new(),
         }
     }
 
-    /// Calculate block hash
+    /// Calculate block hash: SHA-256 over `HASH_VERSION` (4 bytes, big
+    /// endian) followed by height, timestamp, previous hash, transaction
+    /// root and app hash, each prefixed with its length as a big-endian u64.
+    /// Integers are encoded as 8 big-endian bytes, hashes as their hex text.
     pub fn calculate_hash(&mut self) -> String {
         use sha2::{Digest, Sha256};
+        let tx_root = self.tx_root();
+        let fields: [&[u8]; 5] = [
+            &self.height.to_be_bytes(),
+            &self.timestamp.to_be_bytes(),
+            self.previous_hash.as_bytes(),
+            tx_root.as_bytes(),
+            self.app_hash.as_bytes(),
+        ];
+
         let mut hasher = Sha256::new();
-        hasher.update(format!("{}{}{}", self.height, self.timestamp, self.previous_hash).as_bytes());
+        hasher.update(HASH_VERSION.to_be_bytes());
+        for field in fields {
+            hasher.update((field.len() as u64).to_be_bytes());
+            hasher.update(field);
+        }
         self.hash = format!("{:x}", hasher.finalize());
         self.hash.clone()
     }
+
+    /// SHA-256 over the block's transaction hashes in order, each length-prefixed
+    pub fn tx_root(&self) -> String {
+        use sha2::{Digest, Sha256};
+        let mut hasher = Sha256::new();
+        for tx in &self.transactions {
+            let hash = tx.hash();
+            hasher.update((hash.len() as u64).to_be_bytes());
+            hasher.update(hash.as_bytes());
+        }
+        format!("{:x}", hasher.finalize())
+    }
 }
 
 /// NFT Collection
@@ -183,6 +511,22 @@ pub struct Collection {
     pub creator: Address,
     /// Description
     pub description: String,
+    /// Addresses allowed to mint besides the creator (empty = creator only)
+    #[serde(default)]
+    pub minters: Vec<Address>,
+    /// Block from which the creator may reveal a sealed collection; NFTs
+    /// minted before the reveal show `placeholder_metadata`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reveal_block: Option<u64>,
+    /// Metadata shown for NFTs that are not revealed yet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder_metadata: Option<serde_json::Value>,
+    /// Set once a sealed collection has been revealed
+    #[serde(default)]
+    pub revealed: bool,
+    /// Whether owners may burn NFTs to redeem a physical item
+    #[serde(default)]
+    pub redeemable: bool,
     /// Created timestamp
     pub created_at: i64,
     /// Updated timestamp
@@ -190,18 +534,32 @@ pub struct Collection {
 }
 
 impl Collection {
-    /// Create a new collection
-    pub fn new(id: String, name: String, creator: Address, description: String) -> Self {
-        let now = chrono::Utc::now().timestamp();
+    /// Create a new collection created at `now` (unix seconds)
+    pub fn new(id: String, name: String, creator: Address, description: String, now: i64) -> Self {
         Self {
             id,
             name,
             creator,
             description,
+            minters: Vec::new(),
+            reveal_block: None,
+            placeholder_metadata: None,
+            revealed: false,
+            redeemable: false,
             created_at: now,
             updated_at: now,
         }
     }
+
+    /// Whether `address` may mint into this collection
+    pub fn can_mint(&self, address: &Address) -> bool {
+        *address == self.creator || self.minters.contains(address)
+    }
+
+    /// Whether new mints get placeholder metadata until a reveal
+    pub fn is_sealed(&self) -> bool {
+        self.reveal_block.is_some() && !self.revealed
+    }
 }
 
 /// NFT Token
@@ -217,32 +575,223 @@ pub struct Nft {
     pub owner: Address,
     /// Metadata
     pub metadata: serde_json::Value,
+    /// Content hash of the metadata when it is kept in the blob store
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_hash: Option<String>,
+    /// Block height of the last metadata update
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_updated_height: Option<u64>,
+    /// False while `metadata` is a sealed collection's placeholder
+    #[serde(default = "default_revealed")]
+    pub revealed: bool,
     /// Created timestamp
     pub created_at: i64,
     /// Updated timestamp
     pub updated_at: i64,
 }
 
+fn default_revealed() -> bool {
+    true
+}
+
 impl Nft {
-    /// Create a new NFT
+    /// Create a new NFT created at `now` (unix seconds)
     pub fn new(
         id: String,
         collection_id: String,
         name: String,
         owner: Address,
         metadata: serde_json::Value,
+        now: i64,
     ) -> Self {
-        let now = chrono::Utc::now().timestamp();
         Self {
             id,
             collection_id,
             name,
             owner,
             metadata,
+            metadata_hash: None,
+            metadata_updated_height: None,
+            revealed: true,
             created_at: now,
             updated_at: now,
         }
     }
+
+    /// Distinct `(trait_type, value)` pairs from the metadata's `traits`
+    /// array. Metadata without one, and malformed entries, yield nothing.
+    pub fn traits(&self) -> std::collections::BTreeSet<(String, String)> {
+        let Some(traits) = self.metadata.get("traits").and_then(serde_json::Value::as_array) else {
+            return Default::default();
+        };
+        traits
+            .iter()
+            .filter_map(|entry| {
+                let trait_type = entry.get("trait_type")?.as_str()?;
+                let value = match entry.get("value")? {
+                    serde_json::Value::String(value) => value.clone(),
+                    value @ (serde_json::Value::Number(_) | serde_json::Value::Bool(_)) => value.to_string(),
+                    _ => return None,
+                };
+                Some((trait_type.to_string(), value))
+            })
+            .collect()
+    }
+}
+
+/// A transaction admitted to the pool, published on the `mempool` feed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MempoolEvent {
+    /// Transaction hash
+    pub hash: String,
+    /// Target module
+    pub module: String,
+    /// Module action
+    pub action: String,
+    /// Sender address
+    pub from: Address,
+}
+
+/// A pool transaction as persisted under `mempool:{hash}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PooledTransaction {
+    /// Unix time the transaction was admitted to the pool
+    pub admitted_at: i64,
+    /// The transaction itself
+    pub tx: Transaction,
+}
+
+/// A transaction waiting in the mempool, as listed by `/admin/mempool`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MempoolEntry {
+    /// Transaction hash
+    pub hash: String,
+    /// Held back until the sender's earlier nonce arrives
+    pub queued: bool,
+    /// The transaction itself
+    pub tx: Transaction,
+}
+
+/// Outcome of a write remembered under a client `Idempotency-Key`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotencyRecord {
+    /// Result returned by the original execution
+    pub result: TransactionResult,
+    /// `Transaction::request_hash` of the original request
+    #[serde(default)]
+    pub request_hash: String,
+    /// Unix timestamp after which the key may be reused
+    pub expires_at: i64,
+}
+
+/// Claim recorded when an NFT is burned to redeem a physical item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Redemption {
+    pub nft_id: String,
+    pub collection_id: String,
+    pub redeemed_by: Address,
+    /// Claim details supplied by the owner. This is public, so it should be
+    /// an opaque reference or hash, e.g. an order number, not an address
+    pub redemption_data: serde_json::Value,
+    /// Block height of the burn
+    pub block_height: u64,
+    pub timestamp: i64,
+}
+
+/// One entry in an NFT's ownership history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnershipRecord {
+    /// Previous owner (`None` on mint)
+    pub from: Option<Address>,
+    /// New owner
+    pub to: Address,
+    /// Block height of the change
+    pub block_height: u64,
+    /// Timestamp of the change
+    pub timestamp: i64,
+}
+
+/// A creator's signed offer to mint an NFT to whoever redeems it for `price`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Voucher {
+    pub collection: String,
+    pub name: String,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+    /// Native token base units the redeemer pays the creator
+    pub price: u64,
+    /// Distinguishes otherwise identical vouchers
+    #[serde(default)]
+    pub nonce: u64,
+    /// Last block height at which the voucher may be redeemed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at_height: Option<u64>,
+    /// Hex-encoded ed25519 public key of the creator
+    pub public_key: String,
+    /// Hex-encoded creator signature over `signing_bytes`
+    pub creator_sig: String,
+}
+
+impl Voucher {
+    /// Bytes the creator signs: the domain tag and `chain_id`, followed by
+    /// every field except the signature itself
+    pub fn signing_bytes(&self, chain_id: &str) -> Vec<u8> {
+        let payload = serde_json::json!({
+            "collection": self.collection,
+            "name": self.name,
+            "metadata": self.metadata,
+            "price": self.price,
+            "nonce": self.nonce,
+            "expires_at_height": self.expires_at_height,
+            "public_key": self.public_key,
+        });
+        // The prefix keeps the signature from being valid for anything but a
+        // voucher on this chain
+        let mut bytes = format!("{}\0{}\0", VOUCHER_SIGNING_DOMAIN, chain_id).into_bytes();
+        bytes.extend(payload.to_string().into_bytes());
+        bytes
+    }
+
+    /// Sign the voucher for `chain_id` with a hex-encoded ed25519 private key
+    pub fn sign(&mut self, chain_id: &str, private_key: &str) -> crate::error::Result<()> {
+        let signing_key = parse_signing_key(private_key)?;
+
+        self.public_key = hex::encode(signing_key.verifying_key().to_bytes());
+        self.creator_sig = hex::encode(signing_key.sign(&self.signing_bytes(chain_id)).to_bytes());
+        Ok(())
+    }
+
+    /// Check the signature made for `chain_id` and return the creator's address
+    pub fn verify(&self, chain_id: &str) -> crate::error::Result<Address> {
+        let public_key: [u8; 32] = hex::decode(&self.public_key)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| CommonError::InvalidPublicKey("Expected 32-byte hex key".to_string()))?;
+        let verifying_key = VerifyingKey::from_bytes(&public_key)
+            .map_err(|e| CommonError::InvalidPublicKey(e.to_string()))?;
+        let signature = hex::decode(&self.creator_sig)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .ok_or_else(|| CommonError::InvalidSignature("Malformed voucher signature".to_string()))?;
+
+        if !verify_ed25519(&verifying_key, &self.signing_bytes(chain_id), &signature) {
+            return Err(CommonError::InvalidSignature("Voucher signature does not match".to_string()).into());
+        }
+        Ok(Address::from_public_key(&public_key))
+    }
+
+    /// Identifier recorded once the voucher is redeemed on `chain_id`
+    pub fn nullifier(&self, chain_id: &str) -> String {
+        use sha2::{Digest, Sha256};
+        format!("{:x}", Sha256::digest(self.signing_bytes(chain_id)))
+    }
+}
+
+/// Decimal places of tokens created without an explicit precision
+pub const DEFAULT_TOKEN_DECIMALS: u8 = 6;
+
+fn default_token_decimals() -> u8 {
+    DEFAULT_TOKEN_DECIMALS
 }
 
 /// Meme Token
@@ -254,10 +803,28 @@ pub struct Token {
     pub name: String,
     /// Total supply
     pub total_supply: u64,
+    /// Amount burned over the token's lifetime; already deducted from `total_supply`
+    #[serde(default)]
+    pub burned: u64,
+    /// Decimal places used when amounts are written as decimal strings
+    #[serde(default = "default_token_decimals")]
+    pub decimals: u8,
     /// Creator address
     pub creator: Address,
     /// Anti-rug settings
     pub anti_rug: AntiRugSettings,
+    /// Transfers and trades are rejected while set
+    #[serde(default)]
+    pub paused: bool,
+    /// Chain the wrapped asset originates from (wrapped tokens only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin_chain: Option<String>,
+    /// Asset identifier on the origin chain (wrapped tokens only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin_asset: Option<String>,
+    /// Named hook that customises how transfers are split
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer_hook: Option<TransferHookConfig>,
     /// Created timestamp
     pub created_at: i64,
     /// Updated timestamp
@@ -265,25 +832,84 @@ pub struct Token {
 }
 
 impl Token {
-    /// Create a new token
+    /// Create a new token created at `now` (unix seconds)
     pub fn new(
         symbol: String,
         name: String,
         total_supply: u64,
         creator: Address,
         anti_rug: AntiRugSettings,
+        now: i64,
     ) -> Self {
-        let now = chrono::Utc::now().timestamp();
         Self {
             symbol,
             name,
             total_supply,
+            burned: 0,
+            decimals: DEFAULT_TOKEN_DECIMALS,
             creator,
             anti_rug,
+            paused: false,
+            origin_chain: None,
+            origin_asset: None,
+            transfer_hook: None,
             created_at: now,
             updated_at: now,
         }
     }
+
+    /// Whether this token represents an asset from another chain
+    pub fn is_wrapped(&self) -> bool {
+        self.origin_chain.is_some()
+    }
+}
+
+/// Supply breakdown of a token, as returned by `/tokens/:symbol/supply`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenSupply {
+    /// Current supply, net of burns
+    pub total_supply: u64,
+    /// Supply outside the treasury and the burn address
+    pub circulating_supply: u64,
+    /// Amount burned over the token's lifetime
+    pub burned: u64,
+    /// Balance held by the treasury
+    pub treasury: u64,
+}
+
+/// Direction of a trade against the native token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+/// Expected fill of a trade at the current pool state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeQuote {
+    pub side: TradeSide,
+    /// Amount paid in (native for buys, the token for sells)
+    pub amount_in: u64,
+    /// Amount received after tax
+    pub amount_out: u64,
+    /// Tax withheld from the trade
+    pub tax: u64,
+    /// Price movement caused by the trade, in basis points
+    pub price_impact_bps: u64,
+}
+
+/// A token's reference to a built-in transfer hook (see `modules::hooks`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferHookConfig {
+    /// Hook name, e.g. `flat_tax` or `reflection`
+    pub name: String,
+    /// Share of each transfer the hook redirects, in basis points
+    #[serde(default)]
+    pub rate_bps: u16,
+    /// Destination of the redirected share, for hooks that need one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<Address>,
 }
 
 /// Anti-rug protection settings
@@ -295,6 +921,9 @@ pub struct AntiRugSettings {
     pub buy_tax_percentage: u8,
     /// Sell tax percentage
     pub sell_tax_percentage: u8,
+    /// Percentage of every transfer sent to the treasury
+    #[serde(default)]
+    pub transfer_tax_percentage: u8,
     /// Liquidity locked percentage
     pub liquidity_locked_percentage: u8,
     /// Lock duration in blocks
@@ -303,6 +932,21 @@ pub struct AntiRugSettings {
     pub lock_start_block: Option<u64>,
 }
 
+/// Liquidity lock state of a token at a given height
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockStatus {
+    /// Whether liquidity is currently locked
+    pub locked: bool,
+    /// Block the lock started at; `None` if liquidity was never locked
+    pub lock_start_block: Option<u64>,
+    /// First block at which liquidity is unlocked
+    pub unlock_block: Option<u64>,
+    /// Blocks left until unlock; zero once unlocked
+    pub remaining_blocks: u64,
+    /// Estimated seconds left until unlock at the configured block time
+    pub remaining_seconds: u64,
+}
+
 impl AntiRugSettings {
     /// Create default anti-rug settings
     pub fn default() -> Self {
@@ -310,21 +954,138 @@ impl AntiRugSettings {
             max_wallet_percentage: 5,
             buy_tax_percentage: 2,
             sell_tax_percentage: 3,
+            transfer_tax_percentage: 0,
             liquidity_locked_percentage: 80,
             lock_duration_blocks: 1000,
             lock_start_block: None,
         }
     }
 
+    /// Named anti-rug preset.
+    ///
+    /// | preset     | max wallet | buy tax | sell tax | locked | lock blocks |
+    /// |------------|-----------:|--------:|---------:|-------:|------------:|
+    /// | safe       | 2%         | 1%      | 1%       | 100%   | 100000      |
+    /// | standard   | 5%         | 2%      | 3%       | 80%    | 1000        |
+    /// | aggressive | 10%        | 5%      | 10%      | 50%    | 500         |
+    pub fn preset(name: &str) -> crate::error::Result<Self> {
+        let (max_wallet, buy_tax, sell_tax, locked, lock_blocks) = match name {
+            "safe" => (2, 1, 1, 100, 100_000),
+            "standard" => (5, 2, 3, 80, 1000),
+            "aggressive" => (10, 5, 10, 50, 500),
+            other => {
+                return Err(crate::error::MemeError::InvalidAntiRugSettings(format!(
+                    "Unknown preset: {} (expected safe, standard or aggressive)", other
+                )).into())
+            }
+        };
+
+        Ok(Self {
+            max_wallet_percentage: max_wallet,
+            buy_tax_percentage: buy_tax,
+            sell_tax_percentage: sell_tax,
+            transfer_tax_percentage: 0,
+            liquidity_locked_percentage: locked,
+            lock_duration_blocks: lock_blocks,
+            lock_start_block: None,
+        })
+    }
+
+    /// Check that percentages are in range and that combined tax, and the
+    /// transfer tax on its own, stay within `max_total_tax`
+    pub fn validate(&self, max_total_tax: u8) -> crate::error::Result<()> {
+        let invalid = |message: String| -> crate::error::Result<()> {
+            Err(crate::error::MemeError::InvalidAntiRugSettings(message).into())
+        };
+
+        for (name, value) in [
+            ("max_wallet_percentage", self.max_wallet_percentage),
+            ("buy_tax_percentage", self.buy_tax_percentage),
+            ("sell_tax_percentage", self.sell_tax_percentage),
+            ("transfer_tax_percentage", self.transfer_tax_percentage),
+            ("liquidity_locked_percentage", self.liquidity_locked_percentage),
+        ] {
+            if value > 100 {
+                return invalid(format!("{} must be at most 100, got {}", name, value));
+            }
+        }
+
+        if self.max_wallet_percentage < 1 {
+            return invalid("max_wallet_percentage must be at least 1".to_string());
+        }
+
+        let total_tax = self.buy_tax_percentage as u16 + self.sell_tax_percentage as u16;
+        if total_tax > max_total_tax as u16 {
+            return invalid(format!(
+                "Combined buy and sell tax {}% exceeds the {}% ceiling", total_tax, max_total_tax
+            ));
+        }
+        if self.transfer_tax_percentage > max_total_tax {
+            return invalid(format!(
+                "Transfer tax {}% exceeds the {}% ceiling", self.transfer_tax_percentage, max_total_tax
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check that the transfer tax and `hook`'s rate, both taken from every
+    /// transfer, together stay within `max_total_tax`
+    pub fn validate_transfer_charges(
+        &self,
+        hook: Option<&TransferHookConfig>,
+        max_total_tax: u8,
+    ) -> crate::error::Result<()> {
+        let hook_bps = hook.map_or(0, |hook| hook.rate_bps as u32);
+        let total_bps = self.transfer_tax_percentage as u32 * 100 + hook_bps;
+        if total_bps > max_total_tax as u32 * 100 {
+            return Err(crate::error::MemeError::InvalidAntiRugSettings(format!(
+                "Transfer tax {}% plus transfer hook {} bps exceeds the {}% ceiling",
+                self.transfer_tax_percentage, hook_bps, max_total_tax
+            )).into());
+        }
+        Ok(())
+    }
+
     /// Check if liquidity is locked
     pub fn is_liquidity_locked(&self, current_block: u64) -> bool {
         if let Some(start_block) = self.lock_start_block {
-            current_block < start_block + self.lock_duration_blocks
+            current_block < start_block.saturating_add(self.lock_duration_blocks)
         } else {
             false
         }
     }
 
+    /// Length of the lock in seconds, at `block_time` seconds per block
+    pub fn lock_duration_seconds(&self, block_time: u64) -> u64 {
+        self.lock_duration_blocks.saturating_mul(block_time)
+    }
+
+    /// Liquidity lock state as seen at `current_block`, with times estimated
+    /// at `block_time` seconds per block
+    pub fn lock_status(&self, current_block: u64, block_time: u64) -> LockStatus {
+        match self.lock_start_block {
+            Some(start_block) => {
+                let unlock_block = start_block.saturating_add(self.lock_duration_blocks);
+                let remaining_blocks = unlock_block.saturating_sub(current_block);
+                LockStatus {
+                    locked: self.is_liquidity_locked(current_block),
+                    lock_start_block: Some(start_block),
+                    unlock_block: Some(unlock_block),
+                    remaining_blocks,
+                    remaining_seconds: remaining_blocks.saturating_mul(block_time),
+                }
+            }
+            None => LockStatus {
+                locked: false,
+                lock_start_block: None,
+                unlock_block: None,
+                remaining_blocks: 0,
+                remaining_seconds: 0,
+            },
+        }
+    }
+
     /// Calculate buy tax
     pub fn calculate_buy_tax(&self, amount: u64) -> u64 {
         (amount * self.buy_tax_percentage as u64) / 100
@@ -335,6 +1096,11 @@ impl AntiRugSettings {
         (amount * self.sell_tax_percentage as u64) / 100
     }
 
+    /// Calculate transfer tax
+    pub fn calculate_transfer_tax(&self, amount: u64) -> u64 {
+        (amount as u128 * self.transfer_tax_percentage as u128 / 100) as u64
+    }
+
     /// Check if transfer exceeds max wallet limit
     pub fn exceeds_max_wallet(&self, current_balance: u64, transfer_amount: u64, total_supply: u64) -> bool {
         let max_wallet_amount = (total_supply * self.max_wallet_percentage as u64) / 100;
@@ -356,31 +1122,31 @@ pub struct Balance {
 }
 
 impl Balance {
-    /// Create a new balance
-    pub fn new(address: Address, token: String, amount: u64) -> Self {
+    /// Create a new balance updated at `now` (unix seconds)
+    pub fn new(address: Address, token: String, amount: u64, now: i64) -> Self {
         Self {
             address,
             token,
             amount,
-            updated_at: chrono::Utc::now().timestamp(),
+            updated_at: now,
         }
     }
 
-    /// Add to balance
-    pub fn add(&mut self, amount: u64) {
+    /// Add to balance at `now`
+    pub fn add(&mut self, amount: u64, now: i64) {
         self.amount += amount;
-        self.updated_at = chrono::Utc::now().timestamp();
+        self.updated_at = now;
     }
 
-    /// Subtract from balance
-    pub fn subtract(&mut self, amount: u64) -> crate::error::Result<()> {
+    /// Subtract from balance at `now`
+    pub fn subtract(&mut self, amount: u64, now: i64) -> crate::error::Result<()> {
         if self.amount < amount {
             return Err(crate::error::MemeChainError::InsufficientBalance(
                 format!("Insufficient balance: {} < {}", self.amount, amount)
             ));
         }
         self.amount -= amount;
-        self.updated_at = chrono::Utc::now().timestamp();
+        self.updated_at = now;
         Ok(())
     }
 }
@@ -431,6 +1197,27 @@ mod tests {
         assert!(!invalid_addr.is_valid());
     }
 
+    #[test]
+    fn test_address_case_insensitive() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let mixed = Address::new("MemeChain1Alice".to_string());
+        let lower = Address::from("memechain1alice");
+        assert_eq!(mixed, lower);
+        assert_eq!(mixed.to_string(), "memechain1alice");
+
+        let hash = |address: &Address| {
+            let mut hasher = DefaultHasher::new();
+            address.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&mixed), hash(&lower));
+
+        let deserialized: Address = serde_json::from_str("\"MEMECHAIN1ALICE\"").unwrap();
+        assert_eq!(deserialized, lower);
+    }
+
     #[test]
     fn test_transaction_creation() {
         let from = Address::new("memechain1alice".to_string());
@@ -443,10 +1230,12 @@ mod tests {
             from,
             Some(to),
             data,
+            1_700_000_000,
         );
 
         assert_eq!(tx.module, "meme");
         assert_eq!(tx.action, "transfer");
+        assert_eq!(tx.timestamp, 1_700_000_000);
     }
 
     #[test]
@@ -457,21 +1246,128 @@ mod tests {
         assert_eq!(settings.calculate_sell_tax(1000), 30);
     }
 
+    #[test]
+    fn test_anti_rug_presets() {
+        let safe = AntiRugSettings::preset("safe").unwrap();
+        assert_eq!(
+            (safe.max_wallet_percentage, safe.buy_tax_percentage, safe.sell_tax_percentage),
+            (2, 1, 1)
+        );
+        assert_eq!((safe.liquidity_locked_percentage, safe.lock_duration_blocks), (100, 100_000));
+
+        let standard = AntiRugSettings::preset("standard").unwrap();
+        let default = AntiRugSettings::default();
+        assert_eq!(
+            (standard.max_wallet_percentage, standard.buy_tax_percentage, standard.sell_tax_percentage),
+            (default.max_wallet_percentage, default.buy_tax_percentage, default.sell_tax_percentage)
+        );
+        assert_eq!(
+            (standard.liquidity_locked_percentage, standard.lock_duration_blocks),
+            (default.liquidity_locked_percentage, default.lock_duration_blocks)
+        );
+
+        let aggressive = AntiRugSettings::preset("aggressive").unwrap();
+        assert_eq!(
+            (aggressive.max_wallet_percentage, aggressive.buy_tax_percentage, aggressive.sell_tax_percentage),
+            (10, 5, 10)
+        );
+        assert_eq!((aggressive.liquidity_locked_percentage, aggressive.lock_duration_blocks), (50, 500));
+
+        assert!(AntiRugSettings::preset("yolo").is_err());
+    }
+
+    #[test]
+    fn test_anti_rug_validation() {
+        assert!(AntiRugSettings::default().validate(25).is_ok());
+
+        let mut excessive_tax = AntiRugSettings::default();
+        excessive_tax.buy_tax_percentage = 150;
+        assert!(excessive_tax.validate(25).is_err());
+
+        let mut no_wallet = AntiRugSettings::default();
+        no_wallet.max_wallet_percentage = 0;
+        assert!(no_wallet.validate(25).is_err());
+
+        let mut over_ceiling = AntiRugSettings::default();
+        over_ceiling.buy_tax_percentage = 15;
+        over_ceiling.sell_tax_percentage = 15;
+        assert!(over_ceiling.validate(25).is_err());
+        assert!(over_ceiling.validate(30).is_ok());
+
+        let mut transfer_tax = AntiRugSettings::default();
+        transfer_tax.transfer_tax_percentage = 10;
+        assert!(transfer_tax.validate(25).is_ok());
+        assert_eq!(transfer_tax.calculate_transfer_tax(1000), 100);
+        transfer_tax.transfer_tax_percentage = 30;
+        assert!(transfer_tax.validate(25).is_err());
+    }
+
     #[test]
     fn test_balance_operations() {
         let mut balance = Balance::new(
             Address::new("memechain1alice".to_string()),
             "MEME".to_string(),
             1000,
+            100,
         );
 
-        balance.add(500);
-        assert_eq!(balance.amount, 1500);
+        balance.add(500, 200);
+        assert_eq!((balance.amount, balance.updated_at), (1500, 200));
 
-        balance.subtract(300).unwrap();
-        assert_eq!(balance.amount, 1200);
+        balance.subtract(300, 300).unwrap();
+        assert_eq!((balance.amount, balance.updated_at), (1200, 300));
 
-        let result = balance.subtract(2000);
+        let result = balance.subtract(2000, 400);
         assert!(result.is_err());
+        assert_eq!(balance.updated_at, 300);
+    }
+
+    #[test]
+    fn test_block_hash_is_pinned() {
+        let mut block = Block::new(7, Vec::new(), Vec::new(), "ab".repeat(32), 1_700_000_000);
+        block.app_hash = "cd".repeat(32);
+
+        // Changing this value forks the chain; see `HASH_VERSION`
+        assert_eq!(
+            block.calculate_hash(),
+            "85c3edb662472c2c63d2fa296bf60c20e558cd7be4a81855a2d16d5a222be7c8"
+        );
+
+        // Every covered field changes the hash
+        let pinned = block.hash.clone();
+        block.app_hash = "ce".repeat(32);
+        assert_ne!(block.calculate_hash(), pinned);
+        block.app_hash = "cd".repeat(32);
+        block.transactions.push(Transaction::new(
+            "common".to_string(),
+            "hash_data".to_string(),
+            Address::new("memechain1alice".to_string()),
+            None,
+            serde_json::json!({"data": "hello"}),
+            1_700_000_000,
+        ));
+        assert_ne!(block.calculate_hash(), pinned);
+    }
+
+    #[test]
+    fn test_lock_duration_in_seconds() {
+        let mut settings = AntiRugSettings::default();
+        settings.lock_duration_blocks = 1000;
+        assert_eq!(settings.lock_duration_seconds(6), 6000);
+
+        // Not locked yet: nothing remains
+        let status = settings.lock_status(0, 6);
+        assert!(!status.locked);
+        assert_eq!((status.remaining_blocks, status.remaining_seconds), (0, 0));
+
+        settings.lock_start_block = Some(100);
+        let status = settings.lock_status(600, 6);
+        assert!(status.locked);
+        assert_eq!((status.remaining_blocks, status.remaining_seconds), (500, 3000));
+        assert_eq!(settings.lock_status(1100, 6).remaining_seconds, 0);
+
+        // A lock running past the end of the chain does not overflow
+        settings.lock_duration_blocks = u64::MAX;
+        assert!(settings.is_liquidity_locked(u64::MAX - 1));
     }
 } 
\ No newline at end of file